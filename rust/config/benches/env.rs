@@ -0,0 +1,39 @@
+//! Turning env vars into typed config values.
+
+use std::collections::{HashMap, HashSet};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use smooai_config::env_config::find_and_process_env_config_with_env;
+
+const TYPES: [(&str, &str); 4] = [
+    ("string", "value"),
+    ("number", "8080"),
+    ("boolean", "true"),
+    ("json", r#"{"host":"db.internal","port":5432}"#),
+];
+
+fn env_coercion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("env_coercion");
+    for vars in [50, 500] {
+        let mut env = HashMap::from([("SMOOAI_CONFIG_ENV".to_string(), "test".to_string())]);
+        let mut schema_keys = HashSet::new();
+        let mut schema_types = HashMap::new();
+        for i in 0..vars {
+            let (kind, raw) = TYPES[i % TYPES.len()];
+            let key = format!("KEY_{}", i);
+            env.insert(format!("APP_{}", key), raw.to_string());
+            schema_types.insert(key.clone(), kind.to_string());
+            schema_keys.insert(key);
+        }
+        // Unrelated process env that has to be skipped.
+        env.extend((0..vars).map(|i| (format!("UNRELATED_{}", i), "x".to_string())));
+
+        group.bench_with_input(BenchmarkId::from_parameter(vars), &env, |b, env| {
+            b.iter(|| find_and_process_env_config_with_env(&schema_keys, "APP_", Some(&schema_types), env))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, env_coercion);
+criterion_main!(benches);
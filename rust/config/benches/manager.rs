@@ -0,0 +1,58 @@
+//! `ConfigManager` cold initialization and cached reads.
+
+mod support;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use smooai_config::ConfigManager;
+
+/// A config dir with `default.json` and `test.json`, each `keys` keys, and
+/// the env pointing a manager at it.
+fn config_dir(root: &Path, keys: usize) -> HashMap<String, String> {
+    let dir = root.join(".smooai-config");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("default.json"), support::layer(keys, 0).to_string()).unwrap();
+    std::fs::write(dir.join("test.json"), support::layer(keys, 1).to_string()).unwrap();
+    HashMap::from([
+        ("SMOOAI_ENV_CONFIG_DIR".to_string(), dir.display().to_string()),
+        ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+    ])
+}
+
+fn cold_init(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cold_init");
+    for keys in [100, 1_000, 10_000] {
+        let root = tempfile::tempdir().unwrap();
+        let env = config_dir(root.path(), keys);
+        group.bench_with_input(BenchmarkId::from_parameter(keys), &env, |b, env| {
+            b.iter(|| ConfigManager::new().with_env(env.clone()).init().unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn cached_reads(c: &mut Criterion) {
+    let root = tempfile::tempdir().unwrap();
+    let manager = ConfigManager::new().with_env(config_dir(root.path(), 1_000));
+    manager.prefetch(&["KEY_0", "KEY_1"]).unwrap();
+
+    let mut group = c.benchmark_group("cache_hit");
+    group.bench_function("get_public_config/scalar", |b| {
+        b.iter(|| manager.get_public_config("KEY_1").unwrap())
+    });
+    group.bench_function("get_public_config/object", |b| {
+        b.iter(|| manager.get_public_config("KEY_0").unwrap())
+    });
+    group.bench_function("get_public_config_arc/object", |b| {
+        b.iter(|| manager.get_public_config_arc("KEY_0").unwrap())
+    });
+    group.bench_function("get_path", |b| {
+        b.iter(|| manager.get_path("KEY_0.options.pool_size").unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cold_init, cached_reads);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+//! Merging N config layers with each built-in strategy.
+
+mod support;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::Value;
+use smooai_config::merge::{MergeRules, MergeStrategy};
+
+const KEYS_PER_LAYER: usize = 200;
+
+fn merge_layers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merge_layers");
+    for layers in [2, 8, 32] {
+        let values: Vec<Value> = (0..layers).map(|v| support::layer(KEYS_PER_LAYER, v)).collect();
+        group.throughput(Throughput::Elements(layers as u64));
+        for strategy in [
+            MergeStrategy::ReplaceArrays,
+            MergeStrategy::JsonMergePatch,
+            MergeStrategy::Concat,
+        ] {
+            let rules = MergeRules::new(strategy);
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", strategy), layers),
+                &values,
+                |b, values| b.iter(|| rules.merge_many(values).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, merge_layers);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+//! Config shapes shared by the benchmarks.
+
+use serde_json::{json, Map, Value};
+
+/// A layer with `keys` top-level keys, every fourth one a nested object, and
+/// values varying with `variant` so successive layers override each other.
+pub fn layer(keys: usize, variant: usize) -> Value {
+    let mut map = Map::new();
+    for i in 0..keys {
+        let value = match i % 4 {
+            0 => json!({
+                "host": format!("db-{}-{}.internal", i, variant),
+                "port": 5432 + variant,
+                "options": {"pool_size": 10 + variant, "tls": variant.is_multiple_of(2)},
+                "replicas": [format!("r{}", variant), "r-static"],
+            }),
+            1 => json!(format!("value-{}-{}", i, variant)),
+            2 => json!(i * variant),
+            _ => json!(variant.is_multiple_of(2)),
+        };
+        map.insert(format!("KEY_{}", i), value);
+    }
+    Value::Object(map)
+}
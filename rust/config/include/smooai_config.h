@@ -0,0 +1,81 @@
+#ifndef SMOOAI_CONFIG_H
+#define SMOOAI_CONFIG_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Which tier [`smooai_config_get`] reads from. Passed as a plain integer,
+ * since C can hand over any value for an enum parameter.
+ */
+typedef enum SmooaiConfigTier {
+  SMOOAI_CONFIG_TIER_PUBLIC = 0,
+  SMOOAI_CONFIG_TIER_SECRET = 1,
+  SMOOAI_CONFIG_TIER_FEATURE_FLAG = 2,
+} SmooaiConfigTier;
+
+/**
+ * Opaque handle to a [`ConfigManager`].
+ */
+typedef struct SmooaiConfig SmooaiConfig;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a config handle configured from the process environment, like
+ * `ConfigManager::new()`. Free it with [`smooai_config_free`].
+ */
+struct SmooaiConfig *smooai_config_new(void);
+
+/**
+ * Look up `key` in `tier` (a [`SmooaiConfigTier`] value) and return its
+ * value as JSON text.
+ *
+ * Returns NULL when the key has no value or on error (including an unknown
+ * `tier`); in the error case
+ * [`smooai_config_last_error`] describes it. Free the result with
+ * [`smooai_config_string_free`].
+ *
+ * # Safety
+ *
+ * `config` must come from [`smooai_config_new`] and not yet be freed, and
+ * `key` must be a NUL-terminated string.
+ */
+char *smooai_config_get(const struct SmooaiConfig *config, uint32_t tier, const char *key);
+
+/**
+ * The error from the last failed call on this thread, or NULL if it
+ * succeeded. Valid until the next `smooai_config_*` call on this thread;
+ * do not free it.
+ */
+const char *smooai_config_last_error(void);
+
+/**
+ * Free a string returned by [`smooai_config_get`]. NULL is a no-op.
+ *
+ * # Safety
+ *
+ * `value` must come from [`smooai_config_get`] and not already be freed.
+ */
+void smooai_config_string_free(char *value);
+
+/**
+ * Free a handle from [`smooai_config_new`]. NULL is a no-op.
+ *
+ * # Safety
+ *
+ * `config` must come from [`smooai_config_new`] and not already be freed.
+ */
+void smooai_config_free(struct SmooaiConfig *config);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SMOOAI_CONFIG_H */
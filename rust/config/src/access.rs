@@ -0,0 +1,32 @@
+//! Read recording for test assertions.
+//!
+//! A manager built with
+//! [`with_access_recording`](crate::ConfigManager::with_access_recording)
+//! logs every getter call as a [`KeyAccess`], so tests can pin down what a
+//! code path reads and spot config keys nothing reads:
+//!
+//! ```rust,ignore
+//! let manager = fixture.manager().with_access_recording();
+//! checkout(&manager)?;
+//! assert_eq!(manager.accessed_keys(), BTreeSet::from(["CHECKOUT_URL".to_string()]));
+//! assert!(manager.unread_keys()?.is_empty(), "dead config keys");
+//! ```
+//!
+//! Recording is off by default and costs one mutex push per read when on,
+//! so it's meant for tests rather than production.
+
+use serde::Serialize;
+
+use crate::schema::ConfigTier;
+
+/// One getter call on a recording manager.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyAccess {
+    pub tier: ConfigTier,
+    pub key: String,
+    /// Answered from the per-key cache (or an active `push_overrides` guard)
+    /// rather than the merged config.
+    pub cache_hit: bool,
+    /// A value was returned.
+    pub found: bool,
+}
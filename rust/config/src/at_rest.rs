@@ -0,0 +1,282 @@
+//! Encryption at rest for the secret portion of on-disk caches.
+//!
+//! [`LastKnownGood`](crate::LastKnownGood) snapshots and, with the `snapshot`
+//! feature, [`ConfigSnapshot`](crate::snapshot)s store config in plaintext by
+//! default. With
+//! [`ConfigManager::with_disk_encryption`](crate::ConfigManager::with_disk_encryption)
+//! the values of secret keys are instead sealed with envelope encryption: each
+//! save encrypts them under a fresh AES-256-GCM data key, and that data key
+//! is wrapped by a long-lived [`KeyEncryptionKey`]. Public values stay in
+//! plaintext so the files remain useful for debugging.
+//!
+//! [`LocalKeyFile`] keeps the wrapping key in a `0600` file next to the
+//! cache. To use an OS keychain or a KMS instead, implement
+//! [`KeyEncryptionKey`]: `wrap` and `unwrap` are the only operations it needs.
+//!
+//! Which keys are secret comes from the schema passed to
+//! [`with_definition`](crate::ConfigManager::with_definition). Without one,
+//! every value is treated as secret.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use crate::last_known_good::{io_error, write_private};
+use crate::schema::property_key;
+use crate::utils::SmooaiConfigError;
+
+const NONCE_LEN: usize = 12;
+
+/// The long-lived key that wraps per-save data keys.
+pub trait KeyEncryptionKey: Send + Sync {
+    /// Identifies the key, so a cache sealed under another key is recognized
+    /// and ignored instead of failing to decrypt.
+    fn key_id(&self) -> String;
+
+    /// Encrypt a 32-byte data key for storage next to the ciphertext.
+    fn wrap(&self, data_key: &[u8]) -> Result<Vec<u8>, SmooaiConfigError>;
+
+    /// Recover a data key returned by [`wrap`](Self::wrap).
+    fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, SmooaiConfigError>;
+}
+
+/// A 256-bit wrapping key stored base64-encoded in a local file.
+pub struct LocalKeyFile {
+    path: PathBuf,
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl LocalKeyFile {
+    /// Read the key at `path`, generating one (mode `0600` on Unix) if the
+    /// file doesn't exist yet.
+    pub fn open_or_create(path: impl Into<PathBuf>) -> Result<Self, SmooaiConfigError> {
+        let path = path.into();
+        match std::fs::read_to_string(&path) {
+            Ok(encoded) => {
+                let decoded = Zeroizing::new(STANDARD.decode(encoded.trim()).map_err(|_| invalid_key_file(&path))?);
+                let key: [u8; 32] = decoded.as_slice().try_into().map_err(|_| invalid_key_file(&path))?;
+                Ok(Self {
+                    path,
+                    key: Zeroizing::new(key),
+                })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = Zeroizing::new(<[u8; 32]>::from(Aes256Gcm::generate_key(&mut OsRng)));
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+                }
+                let encoded = Zeroizing::new(STANDARD.encode(key.as_slice()));
+                write_private(&path, encoded.as_bytes()).map_err(|e| io_error(&path, e))?;
+                Ok(Self { path, key })
+            }
+            Err(e) => Err(io_error(&path, e)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for LocalKeyFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalKeyFile")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyEncryptionKey for LocalKeyFile {
+    fn key_id(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+
+    fn wrap(&self, data_key: &[u8]) -> Result<Vec<u8>, SmooaiConfigError> {
+        encrypt(self.key.as_slice(), data_key)
+    }
+
+    fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, SmooaiConfigError> {
+        decrypt(self.key.as_slice(), wrapped)
+    }
+}
+
+/// Secret values sealed under a wrapped data key. Binary fields are base64.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedSecrets {
+    pub key_id: String,
+    pub wrapped_key: String,
+    /// Nonce followed by the AES-GCM ciphertext and tag of the JSON-encoded values.
+    pub ciphertext: String,
+}
+
+/// How an on-disk cache seals secrets: the wrapping key plus which keys are
+/// secret.
+#[derive(Clone)]
+pub struct AtRestEncryption {
+    key: Arc<dyn KeyEncryptionKey>,
+    secret_keys: HashSet<String>,
+}
+
+impl AtRestEncryption {
+    /// Seal the values of `secret_keys`, as returned by
+    /// [`ConfigDefinition::secret_keys`](crate::schema::ConfigDefinition::secret_keys);
+    /// all values when it's empty.
+    pub fn new(key: Arc<dyn KeyEncryptionKey>, secret_keys: HashSet<String>) -> Self {
+        Self { key, secret_keys }
+    }
+
+    pub fn key_id(&self) -> String {
+        self.key.key_id()
+    }
+
+    fn is_secret(&self, key: &str) -> bool {
+        self.secret_keys.is_empty() || self.secret_keys.contains(key) || self.secret_keys.contains(&property_key(key))
+    }
+
+    /// Split `values` into the plaintext public part and the sealed secrets.
+    pub fn seal(
+        &self,
+        values: &HashMap<String, Value>,
+    ) -> Result<(HashMap<String, Value>, SealedSecrets), SmooaiConfigError> {
+        let (secret, public): (HashMap<_, _>, HashMap<_, _>) = values
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .partition(|(k, _)| self.is_secret(k));
+        let plaintext = Zeroizing::new(
+            serde_json::to_vec(&secret)
+                .map_err(|e| SmooaiConfigError::new(&format!("Failed to serialize secrets: {}", e)))?,
+        );
+        let data_key = Zeroizing::new(<[u8; 32]>::from(Aes256Gcm::generate_key(&mut OsRng)));
+        let sealed = SealedSecrets {
+            key_id: self.key.key_id(),
+            wrapped_key: STANDARD.encode(self.key.wrap(data_key.as_slice())?),
+            ciphertext: STANDARD.encode(encrypt(data_key.as_slice(), &plaintext)?),
+        };
+        Ok((public, sealed))
+    }
+
+    /// Decrypt secrets sealed by [`seal`](Self::seal).
+    pub fn open(&self, sealed: &SealedSecrets) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        if sealed.key_id != self.key.key_id() {
+            return Err(SmooaiConfigError::new(&format!(
+                "Secrets were sealed with key {}, not {}",
+                sealed.key_id,
+                self.key.key_id()
+            )));
+        }
+        let wrapped = STANDARD
+            .decode(&sealed.wrapped_key)
+            .map_err(|_| SmooaiConfigError::new("Sealed secrets have an invalid wrapped key"))?;
+        let data_key = Zeroizing::new(self.key.unwrap(&wrapped)?);
+        let ciphertext = STANDARD
+            .decode(&sealed.ciphertext)
+            .map_err(|_| SmooaiConfigError::new("Sealed secrets have invalid ciphertext"))?;
+        let plaintext = Zeroizing::new(decrypt(&data_key, &ciphertext)?);
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| SmooaiConfigError::new(&format!("Failed to parse sealed secrets: {}", e)))
+    }
+}
+
+impl fmt::Debug for AtRestEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtRestEncryption")
+            .field("key_id", &self.key.key_id())
+            .field("secret_keys", &self.secret_keys)
+            .finish()
+    }
+}
+
+/// AES-256-GCM with a random nonce, returned as `nonce || ciphertext || tag`.
+fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, SmooaiConfigError> {
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|_| SmooaiConfigError::new("Encryption key must be 32 bytes"))?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| SmooaiConfigError::new("Failed to encrypt secrets"))?;
+    let mut out = nonce.to_vec();
+    out.extend(ciphertext);
+    Ok(out)
+}
+
+fn decrypt(key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, SmooaiConfigError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(SmooaiConfigError::new("Sealed data is truncated"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|_| SmooaiConfigError::new("Encryption key must be 32 bytes"))?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| SmooaiConfigError::new("Failed to decrypt secrets: wrong key or corrupted data"))
+}
+
+fn invalid_key_file(path: &Path) -> SmooaiConfigError {
+    SmooaiConfigError::new(&format!(
+        "Key file {} must hold a base64-encoded 32-byte key",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_seal_and_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = Arc::new(LocalKeyFile::open_or_create(dir.path().join("keys/cache.key")).unwrap());
+        let encryption = AtRestEncryption::new(key.clone(), HashSet::from(["DB_PASSWORD".to_string()]));
+        let values = HashMap::from([
+            ("API_URL".to_string(), json!("https://api")),
+            ("DB_PASSWORD".to_string(), json!("hunter2")),
+        ]);
+
+        let (public, sealed) = encryption.seal(&values).unwrap();
+        assert_eq!(public, HashMap::from([("API_URL".to_string(), json!("https://api"))]));
+        assert!(!sealed.ciphertext.contains("hunter2"));
+        assert_eq!(
+            encryption.open(&sealed).unwrap(),
+            HashMap::from([("DB_PASSWORD".to_string(), json!("hunter2"))])
+        );
+
+        // The key file is reused on the next start.
+        let reopened = LocalKeyFile::open_or_create(key.path()).unwrap();
+        let encryption = AtRestEncryption::new(Arc::new(reopened), HashSet::new());
+        assert_eq!(encryption.open(&sealed).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_open_rejects_other_keys_and_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let ours = AtRestEncryption::new(
+            Arc::new(LocalKeyFile::open_or_create(dir.path().join("a.key")).unwrap()),
+            HashSet::new(),
+        );
+        let theirs = AtRestEncryption::new(
+            Arc::new(LocalKeyFile::open_or_create(dir.path().join("b.key")).unwrap()),
+            HashSet::new(),
+        );
+        let (public, mut sealed) = ours.seal(&HashMap::from([("TOKEN".to_string(), json!("t"))])).unwrap();
+        assert!(public.is_empty(), "everything is secret without a schema");
+        assert!(theirs.open(&sealed).is_err());
+
+        let mut bytes = STANDARD.decode(&sealed.ciphertext).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        sealed.ciphertext = STANDARD.encode(bytes);
+        assert!(ours.open(&sealed).is_err());
+
+        std::fs::write(dir.path().join("bad.key"), "not a key").unwrap();
+        assert!(LocalKeyFile::open_or_create(dir.path().join("bad.key")).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+//! `smooai-config` command-line tool; see [`smooai_config::cli`].
+
+fn main() -> std::process::ExitCode {
+    smooai_config::cli::main()
+}
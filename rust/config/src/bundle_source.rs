@@ -0,0 +1,490 @@
+//! Object-storage config bundle source (feature `bundle`).
+//!
+//! Downloads a config bundle from a bucket so accounts that can't reach the
+//! config API still get centrally managed values. Supported locations:
+//!
+//! ```text
+//! s3://bucket/path/config.json          SigV4 with the default AWS credential chain
+//! gs://bucket/path/config.tar.gz        bearer token (GOOGLE_OAUTH_ACCESS_TOKEN or GCE metadata)
+//! az://account/container/config.tgz     SAS token from AZURE_STORAGE_SAS_TOKEN
+//! https://host/path/config.json         plain GET (pre-signed URLs, SAS URLs, CDNs)
+//! ```
+//!
+//! A `.json` bundle holds the config values directly. A `.tar`, `.tar.gz` or
+//! `.tgz` bundle holds a config directory (`default.json`, `production.json`,
+//! `production.aws.json`, ...) and is merged in the same order as local
+//! config files. Files are matched by name wherever they sit in the archive,
+//! so each name may appear only once.
+//!
+//! Refreshes send the last `ETag` as `If-None-Match`, so an unchanged bundle
+//! costs a `304` and no download. Values refresh every minute by default.
+//!
+//! ```rust,ignore
+//! use smooai_config::{BundleSource, ConfigManager};
+//!
+//! let manager = ConfigManager::new().with_source(BundleSource::new("s3://acme-config/myapp/bundle.tar.gz"));
+//! ```
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::aws::{resolve_region, sign_request, AwsCredentials};
+use crate::file_config::config_file_names;
+use crate::merge::MergeRules;
+use crate::source::ConfigSource;
+use crate::utils::SmooaiConfigError;
+
+/// Default refresh interval for bundles.
+pub const DEFAULT_BUNDLE_TTL: Duration = Duration::from_secs(60);
+const AZURE_API_VERSION: &str = "2021-08-06";
+const METADATA_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Cached {
+    etag: String,
+    values: HashMap<String, Value>,
+}
+
+/// Config source backed by a bundle in S3, GCS, Azure Blob Storage or any HTTPS URL.
+pub struct BundleSource {
+    url: String,
+    endpoint: Option<String>,
+    region: Option<String>,
+    credentials: Option<AwsCredentials>,
+    bearer_token: Option<String>,
+    ttl: Duration,
+    cached: Mutex<Option<Cached>>,
+}
+
+impl BundleSource {
+    /// Download the bundle at `url` (`s3://`, `gs://`, `az://` or `http(s)://`).
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            endpoint: None,
+            region: None,
+            credentials: None,
+            bearer_token: None,
+            ttl: DEFAULT_BUNDLE_TTL,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Override the storage endpoint (MinIO, LocalStack, fake-gcs-server, tests).
+    /// Buckets are then addressed path-style: `{endpoint}/{bucket}/{key}`.
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// S3 region (default: `AWS_REGION` / `AWS_DEFAULT_REGION`).
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Explicit S3 credentials instead of the default chain.
+    pub fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Bearer token for GCS instead of `GOOGLE_OAUTH_ACCESS_TOKEN` / the metadata server.
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    /// How often the bundle is re-checked (default 1 minute).
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Object key (or URL path) used to pick the bundle format.
+    fn object_name(&self) -> &str {
+        self.url.split(['?', '#']).next().unwrap_or(&self.url)
+    }
+
+    fn build_request(
+        &self,
+        client: &reqwest::blocking::Client,
+        env: &HashMap<String, String>,
+    ) -> Result<reqwest::blocking::RequestBuilder, SmooaiConfigError> {
+        let (scheme, rest) = self.url.split_once("://").unwrap_or(("", &self.url));
+        let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+        match scheme {
+            "s3" => {
+                let region = resolve_region(self.region.as_deref(), env)?;
+                let credentials = AwsCredentials::resolve(self.credentials.as_ref(), env)?;
+                let url = match self.endpoint {
+                    Some(ref endpoint) => object_url(endpoint, &[bucket], key)?,
+                    None => object_url(&format!("https://{}.s3.{}.amazonaws.com", bucket, region), &[], key)?,
+                };
+                let headers = vec![("x-amz-content-sha256".to_string(), hex::encode(Sha256::digest(b"")))];
+                let signed = sign_request(
+                    "GET",
+                    &url,
+                    &headers,
+                    b"",
+                    &region,
+                    "s3",
+                    &credentials,
+                    SystemTime::now(),
+                );
+                let mut request = client.get(url);
+                for (name, value) in headers.iter().chain(signed.iter()) {
+                    request = request.header(name, value);
+                }
+                Ok(request)
+            }
+            "gs" => {
+                let endpoint = self
+                    .endpoint
+                    .clone()
+                    .unwrap_or_else(|| "https://storage.googleapis.com".to_string());
+                let mut request = client.get(object_url(&endpoint, &[bucket], key)?);
+                if let Some(token) = self.gcs_token(client, env) {
+                    request = request.bearer_auth(token);
+                }
+                Ok(request)
+            }
+            "az" => {
+                let (container, blob) = key.split_once('/').unwrap_or((key, ""));
+                let mut url = match self.endpoint {
+                    Some(ref endpoint) => object_url(endpoint, &[bucket, container], blob)?,
+                    None => object_url(&format!("https://{}.blob.core.windows.net", bucket), &[container], blob)?,
+                };
+                if let Some(sas) = env.get("AZURE_STORAGE_SAS_TOKEN").filter(|s| !s.is_empty()) {
+                    url.set_query(Some(sas.trim_start_matches('?')));
+                }
+                Ok(client.get(url).header("x-ms-version", AZURE_API_VERSION))
+            }
+            "http" | "https" => Ok(client.get(&self.url)),
+            _ => Err(SmooaiConfigError::new(&format!(
+                "Unsupported bundle URL scheme '{}' (expected s3://, gs://, az:// or https://)",
+                scheme
+            ))),
+        }
+    }
+
+    /// Explicit token, then `GOOGLE_OAUTH_ACCESS_TOKEN`, then the GCE metadata
+    /// server. `None` falls back to anonymous access (public buckets).
+    fn gcs_token(&self, client: &reqwest::blocking::Client, env: &HashMap<String, String>) -> Option<String> {
+        if let Some(ref token) = self.bearer_token {
+            return Some(token.clone());
+        }
+        if let Some(token) = env.get("GOOGLE_OAUTH_ACCESS_TOKEN").filter(|t| !t.is_empty()) {
+            return Some(token.clone());
+        }
+        let host = env
+            .get("GCE_METADATA_HOST")
+            .map(|h| h.as_str())
+            .unwrap_or("metadata.google.internal");
+        let body: Value = client
+            .get(format!(
+                "http://{}/computeMetadata/v1/instance/service-accounts/default/token",
+                host
+            ))
+            .header("Metadata-Flavor", "Google")
+            .timeout(METADATA_TIMEOUT)
+            .send()
+            .ok()
+            .filter(|r| r.status().is_success())?
+            .json()
+            .ok()?;
+        body.get("access_token").and_then(|t| t.as_str()).map(|t| t.to_string())
+    }
+
+    /// Turn a downloaded bundle into config values.
+    fn parse(&self, bytes: &[u8], env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        let name = self.object_name();
+        let files = if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            read_tar(flate2::read::GzDecoder::new(bytes))?
+        } else if name.ends_with(".tar") {
+            read_tar(bytes)?
+        } else {
+            return match serde_json::from_slice::<Value>(bytes) {
+                Ok(Value::Object(map)) => Ok(map.into_iter().collect()),
+                Ok(_) => Err(SmooaiConfigError::new("Config bundle must be a JSON object")),
+                Err(e) => Err(SmooaiConfigError::file_parse("config bundle", e)),
+            };
+        };
+
+        let mut merged = Value::Object(Default::default());
+        for file_name in config_file_names(env) {
+            match files.get(&file_name) {
+                Some(content) => {
+                    let values: Value = serde_json::from_slice(content)
+                        .map_err(|e| SmooaiConfigError::file_parse(&format!("{} in config bundle", file_name), e))?;
+                    MergeRules::default().merge_into(&mut merged, &values);
+                }
+                None if file_name == "default.json" => {
+                    return Err(SmooaiConfigError::new(
+                        "Required default.json not found in config bundle",
+                    ));
+                }
+                None => {}
+            }
+        }
+        match merged {
+            Value::Object(map) => Ok(map.into_iter().collect()),
+            _ => Ok(HashMap::new()),
+        }
+    }
+}
+
+/// `base` plus URL-encoded `segments` and `/`-separated `key`.
+fn object_url(base: &str, segments: &[&str], key: &str) -> Result<reqwest::Url, SmooaiConfigError> {
+    let mut url = reqwest::Url::parse(base)
+        .map_err(|e| SmooaiConfigError::new(&format!("Invalid bundle URL {}: {}", base, e)))?;
+    url.path_segments_mut()
+        .map_err(|_| SmooaiConfigError::new(&format!("Invalid bundle URL {}", base)))?
+        .pop_if_empty()
+        .extend(segments.iter().copied())
+        .extend(key.split('/'));
+    Ok(url)
+}
+
+/// Every `.json` file in a tar archive, keyed by file name (directories
+/// dropped). Two files with the same name in different directories are an
+/// error, since either could be the one meant.
+fn read_tar<R: Read>(reader: R) -> Result<HashMap<String, Vec<u8>>, SmooaiConfigError> {
+    let archive_error =
+        |e: std::io::Error| SmooaiConfigError::new(&format!("Error reading config bundle archive: {}", e));
+    let mut archive = tar::Archive::new(reader);
+    let mut files = HashMap::new();
+    for entry in archive.entries().map_err(archive_error)? {
+        let mut entry = entry.map_err(archive_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(archive_error)?;
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if !name.ends_with(".json") {
+            continue;
+        }
+        if files.contains_key(&name) {
+            return Err(SmooaiConfigError::new(&format!(
+                "Config bundle archive contains more than one {} (another at {})",
+                name,
+                path.display()
+            )));
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(archive_error)?;
+        files.insert(name, content);
+    }
+    Ok(files)
+}
+
+/// A failed download, with the URL stripped: it can carry a SAS token.
+fn download_error(e: reqwest::Error) -> SmooaiConfigError {
+    let e = e.without_url();
+    SmooaiConfigError::remote_fetch(None, &format!("Config bundle download failed: {}", e)).with_source(e)
+}
+
+impl ConfigSource for BundleSource {
+    fn name(&self) -> String {
+        format!("bundle:{}", self.object_name())
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        let client = reqwest::blocking::Client::new();
+        let mut request = self.build_request(&client, env)?;
+        let mut cached = self
+            .cached
+            .lock()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Bundle cache poisoned"))?;
+        if let Some(ref current) = *cached {
+            request = request.header("If-None-Match", &current.etag);
+        }
+
+        let response = request.send().map_err(download_error)?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(ref current) = *cached {
+                return Ok(current.values.clone());
+            }
+        }
+        if !status.is_success() {
+            return Err(SmooaiConfigError::remote_fetch(
+                Some(status.as_u16()),
+                &format!("Config bundle download returned HTTP {}", status),
+            ));
+        }
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let bytes = response.bytes().map_err(download_error)?;
+        let values = self.parse(&bytes, env)?;
+        *cached = etag.map(|etag| Cached {
+            etag,
+            values: values.clone(),
+        });
+        Ok(values)
+    }
+
+    fn ttl(&self) -> Option<Duration> {
+        Some(self.ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, header_exists, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn tar_gz(files: &[(&str, &str)]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_s3_json_bundle_is_signed_and_uses_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/acme/app/bundle.json"))
+            .and(header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/acme/app/bundle.json"))
+            .and(header_exists("authorization"))
+            .and(header_exists("x-amz-content-sha256"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_json(json!({"API_URL": "https://api"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let (first, second) = tokio::task::spawn_blocking(move || {
+            let source = BundleSource::new("s3://acme/app/bundle.json")
+                .with_endpoint(&uri)
+                .with_region("us-east-1")
+                .with_credentials(AwsCredentials::new("AKID", "secret", None));
+            (source.load(&HashMap::new()), source.load(&HashMap::new()))
+        })
+        .await
+        .unwrap();
+        assert_eq!(first.unwrap()["API_URL"], json!("https://api"));
+        assert_eq!(second.unwrap()["API_URL"], json!("https://api"));
+    }
+
+    #[tokio::test]
+    async fn test_gcs_archive_merges_like_config_dir() {
+        let server = MockServer::start().await;
+        let archive = tar_gz(&[
+            (
+                "bundle/default.json",
+                r#"{"API_URL":"https://default","DATABASE":{"host":"a","port":1}}"#,
+            ),
+            ("bundle/production.json", r#"{"DATABASE":{"host":"prod"}}"#),
+            ("bundle/staging.json", r#"{"API_URL":"https://staging"}"#),
+        ]);
+        Mock::given(method("GET"))
+            .and(path("/acme/app/bundle.tar.gz"))
+            .and(header("Authorization", "Bearer tok"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(archive))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let env = HashMap::from([("SMOOAI_CONFIG_ENV".to_string(), "production".to_string())]);
+        let values = tokio::task::spawn_blocking(move || {
+            BundleSource::new("gs://acme/app/bundle.tar.gz")
+                .with_endpoint(&uri)
+                .with_bearer_token("tok")
+                .load(&env)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(values["API_URL"], json!("https://default"));
+        assert_eq!(values["DATABASE"], json!({"host": "prod", "port": 1}));
+    }
+
+    #[test]
+    fn test_archive_rejects_duplicate_file_names() {
+        let archive = tar_gz(&[
+            ("bundle/default.json", r#"{"API_URL":"https://a"}"#),
+            ("old/default.json", r#"{"API_URL":"https://b"}"#),
+        ]);
+        let err = read_tar(flate2::read::GzDecoder::new(archive.as_slice())).unwrap_err();
+        assert!(err.message.contains("more than one default.json"));
+        assert!(err.message.contains("old/default.json"));
+    }
+
+    #[tokio::test]
+    async fn test_azure_appends_sas_and_requires_default_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/acct/configs/app.tgz"))
+            .and(query_param("sig", "abc"))
+            .and(header("x-ms-version", AZURE_API_VERSION))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(tar_gz(&[("production.json", "{}")])))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let env = HashMap::from([("AZURE_STORAGE_SAS_TOKEN".to_string(), "?sv=1&sig=abc".to_string())]);
+        let err = tokio::task::spawn_blocking(move || {
+            BundleSource::new("az://acct/configs/app.tgz")
+                .with_endpoint(&uri)
+                .load(&env)
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+        assert!(err.message.contains("default.json"));
+    }
+
+    #[test]
+    fn test_download_error_hides_sas_token() {
+        let env = HashMap::from([(
+            "AZURE_STORAGE_SAS_TOKEN".to_string(),
+            "?sv=1&sig=s3cr3t-signature".to_string(),
+        )]);
+        let err = BundleSource::new("az://acct/configs/app.tgz")
+            .with_endpoint("http://127.0.0.1:1")
+            .load(&env)
+            .unwrap_err();
+        assert!(err.message.contains("download failed"), "{}", err.message);
+        assert!(!err.message.contains("s3cr3t"), "{}", err.message);
+        let source = std::error::Error::source(&err).unwrap().to_string();
+        assert!(!source.contains("s3cr3t"), "{}", source);
+    }
+
+    #[test]
+    fn test_unsupported_scheme() {
+        let err = BundleSource::new("ftp://host/bundle.json")
+            .load(&HashMap::new())
+            .unwrap_err();
+        assert!(err.message.contains("ftp"));
+    }
+}
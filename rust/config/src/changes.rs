@@ -0,0 +1,78 @@
+//! Change events for reloaded config.
+//!
+//! [`ConfigManager::subscribe`](crate::ConfigManager::subscribe) hands out a
+//! `tokio::sync::broadcast` receiver. Whenever the manager reloads (after
+//! `invalidate`, a source TTL lapsing, a watch firing, or a single-key
+//! refetch) it compares the new merged config with the previous one and
+//! sends one [`ConfigChange`] per key that was added, removed or modified:
+//!
+//! ```rust,ignore
+//! let mut changes = manager.subscribe();
+//! tokio::spawn(async move {
+//!     while let Ok(change) = changes.recv().await {
+//!         if change.key == "DATABASE_URL" {
+//!             pool.reconnect();
+//!         }
+//!     }
+//! });
+//! ```
+//!
+//! Nothing is sent for the initial load, and temporary `push_overrides`
+//! values don't count as changes. Values of the schema's secret keys are
+//! replaced by [`REDACTED`](crate::REDACTED) (the change is still reported;
+//! re-read the key for its new value). A receiver that falls more than the
+//! channel capacity behind gets `RecvError::Lagged` and should re-read what
+//! it cares about.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::explain::ValueSource;
+
+/// Events buffered per receiver before slow receivers start lagging.
+pub const CHANGE_CHANNEL_CAPACITY: usize = 256;
+
+/// One key whose merged value changed on reload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigChange {
+    pub key: String,
+    /// `None` when the key is new.
+    pub old: Option<Value>,
+    /// `None` when the key was removed.
+    pub new: Option<Value>,
+    /// The layer the new value came from, as [`explain`](crate::ConfigManager::explain)
+    /// would report it. `None` for removals.
+    pub source: Option<ValueSource>,
+}
+
+/// Keys whose values differ between `old` and `new`, sorted.
+pub(crate) fn changed_keys(old: &HashMap<String, Value>, new: &HashMap<String, Value>) -> Vec<String> {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_changed_keys() {
+        let old = HashMap::from([
+            ("SAME".to_string(), json!(1)),
+            ("CHANGED".to_string(), json!({"a": 1})),
+            ("REMOVED".to_string(), json!(true)),
+        ]);
+        let new = HashMap::from([
+            ("SAME".to_string(), json!(1)),
+            ("CHANGED".to_string(), json!({"a": 2})),
+            ("ADDED".to_string(), json!("x")),
+        ]);
+        assert_eq!(changed_keys(&old, &new), ["ADDED", "CHANGED", "REMOVED"]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Config-backed defaults for `clap` commands (feature `clap`).
+//!
+//! [`with_config_defaults`] sets each argument's default from the merged
+//! config key matching its id (`--api-url` / `api_url` reads `API_URL`), so
+//! a value given on the command line wins, then one from the argument's
+//! `env`, then the merged config (remote over files), then the default
+//! written in code:
+//!
+//! ```rust,ignore
+//! #[derive(Parser)]
+//! struct Cli {
+//!     #[arg(long, env = "API_URL")]
+//!     api_url: String,
+//! }
+//!
+//! let command = with_config_defaults(Cli::command(), &ConfigManager::new())?;
+//! let cli = Cli::from_arg_matches(&command.get_matches())?;
+//! ```
+//!
+//! Arrays become multiple defaults, other non-string values their JSON text.
+//! Subcommands are covered too; help and version flags are left alone.
+//! Defaults read from secret keys are hidden from `--help`.
+
+use clap::{ArgAction, Command};
+use serde_json::Value;
+
+use crate::config_manager::ConfigManager;
+use crate::schema::property_key;
+use crate::utils::SmooaiConfigError;
+
+/// Default every argument of `command` (and its subcommands) whose id matches a merged config key.
+///
+/// Secret values are used unredacted, but not shown as `[default: …]` in
+/// help output. Secret keys are those [`ConfigManager::get_all`] redacts.
+/// Arguments with no matching key, or whose key is `null`, keep their own
+/// defaults.
+pub fn with_config_defaults(command: Command, manager: &ConfigManager) -> Result<Command, SmooaiConfigError> {
+    let values = manager.get_all_exposed()?;
+    Ok(apply(command, &|id: &str| {
+        let key = property_key(id);
+        let value = values.get(&key)?.clone();
+        Some((value, manager.is_secret(&key)))
+    }))
+}
+
+/// `lookup` returns the config value for an argument id and whether it's secret.
+fn apply(mut command: Command, lookup: &impl Fn(&str) -> Option<(Value, bool)>) -> Command {
+    let defaults: Vec<(String, Vec<String>, bool)> = command
+        .get_arguments()
+        .filter(|arg| {
+            !matches!(
+                arg.get_action(),
+                ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version
+            )
+        })
+        .filter_map(|arg| {
+            let id = arg.get_id().as_str().to_string();
+            let (value, secret) = lookup(&id)?;
+            Some((id, default_values(value)?, secret))
+        })
+        .collect();
+    for (id, values, secret) in defaults {
+        command = command.mut_arg(id, |arg| {
+            let arg = arg.default_values(values);
+            if secret {
+                arg.hide_default_value(true)
+            } else {
+                arg
+            }
+        });
+    }
+
+    let subcommands: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in subcommands {
+        command = command.mut_subcommand(name, |sub| apply(sub, lookup));
+    }
+    command
+}
+
+fn default_values(value: Value) -> Option<Vec<String>> {
+    match value {
+        Value::Null => None,
+        Value::Array(items) => Some(items.into_iter().map(scalar_text).collect()),
+        other => Some(vec![scalar_text(other)]),
+    }
+}
+
+fn scalar_text(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigFixture;
+    use clap::Arg;
+    use serde_json::json;
+
+    fn manager(values: Value) -> (ConfigFixture, ConfigManager) {
+        let fixture = ConfigFixture::new().with_file("default.json", values);
+        let manager = fixture.manager();
+        (fixture, manager)
+    }
+
+    fn command() -> Command {
+        Command::new("tool")
+            .arg(Arg::new("api_url").long("api-url"))
+            .arg(Arg::new("port").long("port").value_parser(clap::value_parser!(u16)))
+            .arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue))
+            .arg(Arg::new("tags").long("tag").action(ArgAction::Append))
+            .arg(Arg::new("unset").long("unset").default_value("builtin"))
+            .subcommand(Command::new("serve").arg(Arg::new("max_connections").long("max-connections")))
+    }
+
+    #[test]
+    fn test_config_supplies_defaults() {
+        let (_fixture, manager) = manager(json!({
+            "API_URL": "https://api.example.com",
+            "PORT": 8080,
+            "VERBOSE": true,
+            "TAGS": ["a", "b"],
+            "MAX_CONNECTIONS": 50
+        }));
+        let matches = with_config_defaults(command(), &manager)
+            .unwrap()
+            .get_matches_from(["tool", "serve"]);
+        assert_eq!(
+            matches.get_one::<String>("api_url").map(String::as_str),
+            Some("https://api.example.com")
+        );
+        assert_eq!(matches.get_one::<u16>("port"), Some(&8080));
+        assert!(matches.get_flag("verbose"));
+        let tags: Vec<&String> = matches.get_many("tags").unwrap().collect();
+        assert_eq!(tags, ["a", "b"]);
+        assert_eq!(matches.get_one::<String>("unset").map(String::as_str), Some("builtin"));
+        let serve = matches.subcommand_matches("serve").unwrap();
+        assert_eq!(
+            serve.get_one::<String>("max_connections").map(String::as_str),
+            Some("50")
+        );
+    }
+
+    #[test]
+    fn test_secret_defaults_hidden_from_help() {
+        let fixture = ConfigFixture::new().with_file(
+            "default.json",
+            json!({"API_URL": "https://api.example.com", "API_TOKEN": "tok-s3cr3t"}),
+        );
+        let definition = crate::schema::define_config(
+            Some(json!({"type": "object", "properties": {"apiUrl": {"type": "string"}}})),
+            Some(json!({"type": "object", "properties": {"apiToken": {"type": "string"}}})),
+            None,
+        );
+        let manager = fixture.manager().with_definition(definition);
+        let mut command =
+            with_config_defaults(command().arg(Arg::new("api_token").long("api-token")), &manager).unwrap();
+
+        let help = command.render_help().to_string();
+        assert!(help.contains("[default: https://api.example.com]"), "{help}");
+        assert!(!help.contains("tok-s3cr3t"), "{help}");
+
+        let matches = command.get_matches_from(["tool"]);
+        assert_eq!(
+            matches.get_one::<String>("api_token").map(String::as_str),
+            Some("tok-s3cr3t")
+        );
+    }
+
+    #[test]
+    fn test_flags_and_env_win() {
+        let (_fixture, manager) = manager(json!({"API_URL": "https://file.example.com", "PORT": 8080}));
+        std::env::set_var("SMOOAI_CLAP_TEST_PORT", "9090");
+        let command = command()
+            .mut_arg("port", |arg| arg.env("SMOOAI_CLAP_TEST_PORT"))
+            .mut_arg("api_url", |arg| arg.env("SMOOAI_CLAP_TEST_UNSET_URL"));
+        let matches = with_config_defaults(command, &manager).unwrap().get_matches_from([
+            "tool",
+            "--api-url",
+            "https://flag.example.com",
+        ]);
+        std::env::remove_var("SMOOAI_CLAP_TEST_PORT");
+        assert_eq!(
+            matches.get_one::<String>("api_url").map(String::as_str),
+            Some("https://flag.example.com")
+        );
+        assert_eq!(matches.get_one::<u16>("port"), Some(&9090));
+    }
+}
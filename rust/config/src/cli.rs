@@ -0,0 +1,1259 @@
+//! The `smooai-config` command-line tool (feature `cli`).
+//!
+//! Builds a [`ConfigManager`] from the current directory and environment the
+//! same way an application would, so the effective config can be inspected
+//! without writing Rust:
+//!
+//! ```text
+//! smooai-config get API_URL
+//! smooai-config list --tier public
+//! smooai-config validate
+//! smooai-config export --format dotenv --env production
+//! smooai-config diff production staging
+//! smooai-config doctor
+//! smooai-config exec -- ./legacy-service --port 8080
+//! smooai-config init --schema schema.json
+//! smooai-config watch --env production NEW_CHECKOUT
+//! ```
+//!
+//! The schema is read from `--schema` or, if present, `schema.json` in the
+//! config directory (see [`crate::schema_files`]). Without one, tiers are
+//! unknown and nothing is redacted.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::Value;
+use tokio::sync::broadcast::error::TryRecvError;
+
+use crate::config_manager::ConfigManager;
+use crate::file_config::{config_file_names, find_config_directory_with_env};
+use crate::schema::{define_config, property_key, ConfigDefinition, ConfigTier};
+use crate::status::SourceState;
+use crate::utils::{SmooaiConfigError, SmooaiConfigErrorKind};
+
+/// Inspect the effective Smoo AI config for this directory and environment.
+#[derive(Debug, Parser)]
+#[command(name = "smooai-config", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+    /// Environment name (defaults to SMOOAI_CONFIG_ENV).
+    #[arg(long, global = true)]
+    pub env: Option<String>,
+    /// Combined JSON Schema file (defaults to schema.json in the config directory).
+    #[arg(long, global = true)]
+    pub schema: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print one value: strings as-is, anything else as JSON.
+    Get {
+        key: String,
+        /// Tier to read from (defaults to the tier the schema declares).
+        #[arg(long, value_enum)]
+        tier: Option<Tier>,
+    },
+    /// Print every key as KEY=value, secrets redacted.
+    List {
+        /// Only keys the schema declares in this tier.
+        #[arg(long, value_enum)]
+        tier: Option<Tier>,
+    },
+    /// Load every source and check required keys (and, with feature
+    /// `jsonschema`, values) against the schema.
+    Validate,
+    /// Print the fully merged config as JSON, YAML or dotenv, secrets
+    /// redacted unless `--show-secrets`.
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Include real secret values instead of `<redacted>`.
+        #[arg(long)]
+        show_secrets: bool,
+    },
+    /// Compare the merged config of two environments key by key, showing
+    /// which layer each differing value came from.
+    Diff {
+        left: String,
+        right: String,
+        /// Print real secret values instead of `<redacted>` (secrets are
+        /// compared by value either way).
+        #[arg(long)]
+        show_secrets: bool,
+        #[arg(long, value_enum, default_value_t = ColorMode::Auto)]
+        color: ColorMode,
+    },
+    /// Check config-directory discovery, every config file, the schema and
+    /// API credentials, and print what to fix.
+    Doctor,
+    /// Run a program with the merged config exported as environment
+    /// variables, nested objects flattened to `PARENT_CHILD` keys.
+    Exec {
+        /// The program and its arguments, after `--`.
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Scaffold a `.smooai-config/` directory: default, development and
+    /// production files, schema files and a `.gitignore` for `local.json`.
+    /// With `--schema`, `default.json` gets an example value for every
+    /// non-secret key.
+    Init {
+        /// Directory to create `.smooai-config/` in.
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+        /// Overwrite files that already exist.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Reload config every `--interval` seconds and print each change with
+    /// a timestamp and its old and new value, until interrupted.
+    Watch {
+        /// Only report these keys (default: every key).
+        keys: Vec<String>,
+        /// Seconds between reloads.
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+}
+
+/// When to color `diff` output. `auto` colors only a terminal and honors `NO_COLOR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    Dotenv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Tier {
+    Public,
+    Secret,
+    FeatureFlag,
+}
+
+impl From<Tier> for ConfigTier {
+    fn from(tier: Tier) -> Self {
+        match tier {
+            Tier::Public => ConfigTier::Public,
+            Tier::Secret => ConfigTier::Secret,
+            Tier::FeatureFlag => ConfigTier::FeatureFlag,
+        }
+    }
+}
+
+/// Entry point for the binary: parse arguments, run, and report errors on stderr.
+pub fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let env: HashMap<String, String> = std::env::vars().collect();
+    match run(&cli, env, &mut std::io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run `cli` against `env`, writing results to `out`.
+pub fn run(cli: &Cli, env: HashMap<String, String>, out: &mut dyn Write) -> Result<(), SmooaiConfigError> {
+    if let Command::Init { ref dir, force } = cli.command {
+        return init(cli, dir, force, out);
+    }
+    let definition = load_definition(cli, &env)?;
+    let mut manager = manager_for(&env, cli.env.as_deref(), &definition);
+
+    match cli.command {
+        Command::Get { ref key, tier } => {
+            let tier = tier
+                .map(ConfigTier::from)
+                .or_else(|| definition.as_ref().and_then(|d| d.declared_tier(key)))
+                .unwrap_or(ConfigTier::Public);
+            let value = match tier {
+                ConfigTier::Public => manager.get_public_config(key)?,
+                ConfigTier::Secret => manager.get_secret_config(key)?.map(|v| v.into_exposed()),
+                ConfigTier::FeatureFlag => manager.get_feature_flag(key)?,
+            };
+            match value {
+                Some(value) if !value.is_null() => write_line(out, &display(&value)),
+                _ => Err(SmooaiConfigError::new(&format!("Config key {} is not set", key))),
+            }
+        }
+        Command::List { tier } => {
+            let declared = match (tier, &definition) {
+                (None, _) => None,
+                (Some(tier), Some(definition)) => Some((ConfigTier::from(tier), definition)),
+                (Some(_), None) => {
+                    return Err(SmooaiConfigError::new(
+                        "--tier needs a schema (pass --schema or add schema.json to the config directory)",
+                    ))
+                }
+            };
+            for (key, value) in manager.get_all()? {
+                if let Some((tier, definition)) = declared {
+                    if !definition.declares(tier, &key) {
+                        continue;
+                    }
+                }
+                write_line(out, &format!("{}={}", key, display(&value)))?;
+            }
+            Ok(())
+        }
+        Command::Validate => {
+            if let Some(ref definition) = definition {
+                manager = manager.with_required_keys(definition.required_keys());
+                #[cfg(feature = "jsonschema")]
+                {
+                    manager = manager.with_value_validation();
+                }
+            }
+            manager.init()?;
+            let count = manager.get_all()?.len();
+            write_line(out, &format!("Config is valid ({} keys)", count))
+        }
+        Command::Export { format, show_secrets } => {
+            let all = if show_secrets {
+                manager.get_all_exposed()?
+            } else {
+                manager.get_all()?
+            };
+            match format {
+                ExportFormat::Json => {
+                    let json = serde_json::to_string_pretty(&all)
+                        .map_err(|e| SmooaiConfigError::new(&format!("Failed to serialize config: {}", e)))?;
+                    write_line(out, &json)
+                }
+                ExportFormat::Yaml => {
+                    let mut yaml = String::new();
+                    for (key, value) in &all {
+                        write_yaml_entry(&mut yaml, 0, &yaml_key(key), value);
+                    }
+                    write!(out, "{}", yaml)
+                        .map_err(|e| SmooaiConfigError::new(&format!("Failed to write output: {}", e)))
+                }
+                ExportFormat::Dotenv => {
+                    for (key, value) in &all {
+                        write_line(out, &format!("{}={}", key, dotenv_quote(&display(value))))?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        Command::Diff {
+            ref left,
+            ref right,
+            show_secrets,
+            color,
+        } => {
+            let color = match color {
+                ColorMode::Always => true,
+                ColorMode::Never => false,
+                ColorMode::Auto => !env.contains_key("NO_COLOR") && std::io::stdout().is_terminal(),
+            };
+            let left_manager = manager_for(&env, Some(left), &definition);
+            let right_manager = manager_for(&env, Some(right), &definition);
+            let left_values = left_manager.get_all_exposed()?;
+            let right_values = right_manager.get_all_exposed()?;
+            let (left_shown, right_shown) = if show_secrets {
+                (left_values.clone(), right_values.clone())
+            } else {
+                (left_manager.get_all()?, right_manager.get_all()?)
+            };
+            let keys: BTreeSet<&String> = left_values.keys().chain(right_values.keys()).collect();
+
+            let mut differences = 0;
+            for key in keys {
+                let (marker, ansi) = match (left_values.get(key), right_values.get(key)) {
+                    (Some(a), Some(b)) if a == b => continue,
+                    (Some(_), Some(_)) => ('~', "33"),
+                    (Some(_), None) => ('-', "31"),
+                    (None, Some(_)) => ('+', "32"),
+                    (None, None) => continue,
+                };
+                differences += 1;
+                write_line(out, &paint(&format!("{} {}", marker, key), ansi, color))?;
+                let width = left.len().max(right.len());
+                for (name, manager, values) in [
+                    (left, &left_manager, &left_shown),
+                    (right, &right_manager, &right_shown),
+                ] {
+                    let Some(shown) = values.get(key) else {
+                        continue;
+                    };
+                    let source = manager
+                        .explain(key)?
+                        .source
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    write_line(
+                        out,
+                        &format!("    {:<width$}  {} ({})", name, shown, source, width = width),
+                    )?;
+                }
+            }
+            if differences == 0 {
+                write_line(out, &format!("No differences between {} and {}", left, right))?;
+            }
+            Ok(())
+        }
+        Command::Doctor => doctor(cli, &env, out),
+        Command::Init { .. } => unreachable!("handled before loading config"),
+        Command::Watch { ref keys, interval } => watch(&manager, keys, Duration::from_secs(interval), out, || true),
+        Command::Exec { ref command } => {
+            let mut child = build_exec(&manager, &env, cli.env.as_deref(), command)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::CommandExt;
+                // Only returns on failure; on success the child replaces this process.
+                let e = child.exec();
+                Err(SmooaiConfigError::new(&format!("Failed to exec {}: {}", command[0], e)).with_source(e))
+            }
+            #[cfg(not(unix))]
+            {
+                let status = child.status().map_err(|e| {
+                    SmooaiConfigError::new(&format!("Failed to run {}: {}", command[0], e)).with_source(e)
+                })?;
+                std::process::exit(status.code().unwrap_or(1));
+            }
+        }
+    }
+}
+
+/// Print the changes each reload publishes while `keep_going` returns true.
+fn watch(
+    manager: &ConfigManager,
+    keys: &[String],
+    interval: Duration,
+    out: &mut dyn Write,
+    mut keep_going: impl FnMut() -> bool,
+) -> Result<(), SmooaiConfigError> {
+    let keys: HashSet<String> = keys.iter().map(|k| property_key(k)).collect();
+    manager.init()?;
+    let mut changes = manager.subscribe();
+    let watching = if keys.is_empty() {
+        "all keys".to_string()
+    } else {
+        let mut names: Vec<&str> = keys.iter().map(String::as_str).collect();
+        names.sort_unstable();
+        names.join(", ")
+    };
+    write_line(
+        out,
+        &format!("Watching {} every {}s (Ctrl-C to stop)", watching, interval.as_secs()),
+    )?;
+
+    while keep_going() {
+        std::thread::sleep(interval);
+        manager.invalidate();
+        if let Err(e) = manager.init() {
+            eprintln!("{}  reload failed: {}", timestamp(), e);
+            continue;
+        }
+        loop {
+            let change = match changes.try_recv() {
+                Ok(change) => change,
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            };
+            if !keys.is_empty() && !keys.contains(&change.key) {
+                continue;
+            }
+            let show = |value: &Option<Value>| value.as_ref().map_or("(unset)".to_string(), Value::to_string);
+            let source = change.source.map(|s| format!("  ({})", s)).unwrap_or_default();
+            write_line(
+                out,
+                &format!(
+                    "{}  {}  {} → {}{}",
+                    timestamp(),
+                    change.key,
+                    show(&change.old),
+                    show(&change.new),
+                    source
+                ),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Write the scaffold, skipping files that exist unless `force`.
+fn init(cli: &Cli, dir: &Path, force: bool, out: &mut dyn Write) -> Result<(), SmooaiConfigError> {
+    let definition = match cli.schema {
+        Some(_) => load_definition(cli, &HashMap::new())?,
+        None => None,
+    };
+    let config_dir = dir.join(".smooai-config");
+    let io = |e: std::io::Error, path: &Path| {
+        SmooaiConfigError::new(&format!("Failed to write {}: {}", path.display(), e))
+            .with_kind(SmooaiConfigErrorKind::Io {
+                path: path.display().to_string(),
+            })
+            .with_source(e)
+    };
+    std::fs::create_dir_all(&config_dir).map_err(|e| io(e, &config_dir))?;
+
+    let mut defaults = definition
+        .as_ref()
+        .map(|d| d.generate_example())
+        .unwrap_or_else(|| Value::Object(Default::default()));
+    let secrets: BTreeSet<String> = definition
+        .as_ref()
+        .map(|d| d.secret_keys())
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    if let Value::Object(ref mut map) = defaults {
+        // Secrets belong in local.json or the config API, not in a committed file.
+        map.retain(|key, _| !secrets.contains(key));
+    }
+    let empty = Value::Object(Default::default());
+    let pretty = |value: &Value| format!("{}\n", serde_json::to_string_pretty(value).unwrap_or_default());
+    let mut files = vec![
+        ("default.json".to_string(), pretty(&defaults)),
+        ("development.json".to_string(), pretty(&empty)),
+        ("production.json".to_string(), pretty(&empty)),
+    ];
+    let schema_definition = definition.clone().unwrap_or_else(|| define_config(None, None, None));
+    files.extend(
+        schema_definition
+            .schema_files()
+            .into_iter()
+            .map(|(name, contents)| (name.to_string(), contents)),
+    );
+
+    for (name, contents) in files {
+        let path = config_dir.join(&name);
+        if path.exists() && !force {
+            write_line(out, &format!("exists   {}", path.display()))?;
+            continue;
+        }
+        std::fs::write(&path, contents).map_err(|e| io(e, &path))?;
+        write_line(out, &format!("created  {}", path.display()))?;
+    }
+
+    let gitignore = config_dir.join(".gitignore");
+    let existing = std::fs::read_to_string(&gitignore).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == "local.json") {
+        write_line(out, &format!("exists   {}", gitignore.display()))?;
+    } else {
+        let separator = if existing.is_empty() || existing.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        std::fs::write(&gitignore, format!("{}{}local.json\n", existing, separator)).map_err(|e| io(e, &gitignore))?;
+        write_line(out, &format!("updated  {}", gitignore.display()))?;
+    }
+
+    if !secrets.is_empty() {
+        let names: Vec<&str> = secrets.iter().map(String::as_str).collect();
+        write_line(
+            out,
+            &format!("Set secrets in local.json or the config API: {}", names.join(", ")),
+        )?;
+    }
+    Ok(())
+}
+
+/// The child process for `exec`: `env` (with `environment` applied, as for
+/// the manager) plus every merged key, secrets included.
+fn build_exec(
+    manager: &ConfigManager,
+    env: &HashMap<String, String>,
+    environment: Option<&str>,
+    command: &[String],
+) -> Result<std::process::Command, SmooaiConfigError> {
+    let mut vars = BTreeMap::new();
+    for (key, value) in manager.get_all_exposed()? {
+        flatten_env(&key, &value, &mut vars);
+    }
+    let mut child = std::process::Command::new(&command[0]);
+    // The child sees the env the manager loaded from, so `--env` carries over.
+    child
+        .args(&command[1..])
+        .env_clear()
+        .envs(env_for(env, environment))
+        .envs(vars);
+    Ok(child)
+}
+
+/// Flatten `value` into `vars`: object fields become `KEY_FIELD` (fields in
+/// UPPER_SNAKE_CASE), arrays stay JSON, other scalars print as [`display`] does.
+fn flatten_env(key: &str, value: &Value, vars: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (field, nested) in map {
+                flatten_env(&format!("{}_{}", key, property_key(field)), nested, vars);
+            }
+        }
+        Value::Null => {}
+        other => {
+            vars.insert(key.to_string(), display(other));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Ok,
+    Warn,
+    Fail,
+}
+
+struct Finding {
+    level: Level,
+    message: String,
+    hint: Option<String>,
+}
+
+impl Finding {
+    fn new(level: Level, message: impl Into<String>) -> Self {
+        Finding {
+            level,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+const API_VARS: [&str; 3] = ["SMOOAI_CONFIG_API_URL", "SMOOAI_CONFIG_ORG_ID", "SMOOAI_CONFIG_API_KEY"];
+
+/// Run every check, print one line per finding, and fail if any check failed.
+fn doctor(cli: &Cli, env: &HashMap<String, String>, out: &mut dyn Write) -> Result<(), SmooaiConfigError> {
+    let env = env_for(env, cli.env.as_deref());
+    let mut findings = Vec::new();
+    let environment = env
+        .get("SMOOAI_CONFIG_ENV")
+        .map(String::as_str)
+        .unwrap_or("development");
+
+    // 1. Config directory and files.
+    match find_config_directory_with_env(true, &env) {
+        Ok(dir) => {
+            findings.push(Finding::new(Level::Ok, format!("Config directory: {}", dir)));
+            check_files(Path::new(&dir), &env, environment, &mut findings);
+        }
+        Err(e) => findings.push(
+            Finding::new(Level::Fail, e.message)
+                .hint("Create .smooai-config/default.json in your project root, or set SMOOAI_ENV_CONFIG_DIR"),
+        ),
+    }
+
+    // 2. Schema, required keys and values.
+    let definition = match load_definition(cli, &env) {
+        Ok(Some(definition)) => {
+            findings.push(Finding::new(Level::Ok, "Schema loaded"));
+            Some(definition)
+        }
+        Ok(None) => {
+            findings.push(
+                Finding::new(
+                    Level::Warn,
+                    "No schema.json in the config directory: tiers are unknown, nothing is redacted",
+                )
+                .hint("Write it with ConfigDefinition::write_schemas, or pass --schema"),
+            );
+            None
+        }
+        Err(e) => {
+            findings
+                .push(Finding::new(Level::Fail, e.message).hint("Regenerate it with ConfigDefinition::write_schemas"));
+            None
+        }
+    };
+    let mut manager = manager_for(&env, None, &definition);
+    if let Some(ref definition) = definition {
+        manager = manager.with_required_keys(definition.required_keys());
+        #[cfg(feature = "jsonschema")]
+        {
+            manager = manager.with_value_validation();
+        }
+    }
+    let loaded = manager.init();
+    match loaded {
+        Ok(()) => findings.push(Finding::new(
+            Level::Ok,
+            format!(
+                "Config for `{}` loads ({} keys)",
+                environment,
+                manager.status().merged_keys
+            ),
+        )),
+        Err(ref e) => {
+            let hint = match e.kind {
+                SmooaiConfigErrorKind::MissingRequiredKeys { .. } => {
+                    "Set them in a config file, the environment, or the config API"
+                }
+                SmooaiConfigErrorKind::InvalidValues { .. } => "Fix the values or update the schema",
+                _ => "Fix the problem above and rerun",
+            };
+            findings.push(Finding::new(Level::Fail, e.message.clone()).hint(hint));
+        }
+    }
+
+    // 3. API credentials: the load above made the test request.
+    let missing: Vec<&str> = API_VARS.iter().copied().filter(|v| !env.contains_key(*v)).collect();
+    if missing.len() == API_VARS.len() {
+        findings.push(
+            Finding::new(
+                Level::Warn,
+                "Config API not configured: using local files and environment only",
+            )
+            .hint(format!("Set {} to load remote values", API_VARS.join(", "))),
+        );
+    } else if !missing.is_empty() {
+        findings.push(
+            Finding::new(
+                Level::Fail,
+                format!("Incomplete config API credentials: {} unset", missing.join(", ")),
+            )
+            .hint("Set all of them, or none to run from local files only"),
+        );
+    } else {
+        match manager.status().sources.iter().find(|s| s.name == "remote") {
+            Some(status) if status.state == SourceState::Loaded => findings.push(Finding::new(
+                Level::Ok,
+                format!("Config API reachable ({} keys)", status.key_count),
+            )),
+            Some(status) => {
+                let error = status.last_error.clone().unwrap_or_default();
+                let hint = if error.contains("401") || error.contains("403") {
+                    "Check SMOOAI_CONFIG_API_KEY and SMOOAI_CONFIG_ORG_ID"
+                } else {
+                    "Check that SMOOAI_CONFIG_API_URL is correct and reachable"
+                };
+                findings.push(Finding::new(Level::Fail, format!("Config API request failed: {}", error)).hint(hint));
+            }
+            None => findings.push(Finding::new(
+                Level::Warn,
+                "Config API not checked: local config failed to load first",
+            )),
+        }
+    }
+
+    let failures = findings.iter().filter(|f| f.level == Level::Fail).count();
+    for finding in &findings {
+        let label = match finding.level {
+            Level::Ok => "ok  ",
+            Level::Warn => "warn",
+            Level::Fail => "FAIL",
+        };
+        write_line(out, &format!("[{}] {}", label, finding.message))?;
+        if let Some(ref hint) = finding.hint {
+            write_line(out, &format!("       {}", hint))?;
+        }
+    }
+    if failures > 0 {
+        return Err(SmooaiConfigError::new(&format!("doctor found {} problem(s)", failures)));
+    }
+    Ok(())
+}
+
+/// Parse every JSON file in `dir` and report which ones `environment` loads.
+fn check_files(dir: &Path, env: &HashMap<String, String>, environment: &str, findings: &mut Vec<Finding>) {
+    let mut names: Vec<String> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".json"))
+            .collect(),
+        Err(e) => {
+            findings.push(Finding::new(
+                Level::Fail,
+                format!("Failed to read {}: {}", dir.display(), e),
+            ));
+            return;
+        }
+    };
+    names.sort();
+
+    let mut parsed = 0;
+    for name in &names {
+        let path = dir.join(name);
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str::<Value>(&text).map_err(|e| e.to_string()));
+        match result {
+            Ok(Value::Object(_)) => parsed += 1,
+            Ok(_) => findings.push(
+                Finding::new(Level::Fail, format!("{} is not a JSON object", name))
+                    .hint("Config files hold one object of KEY: value pairs"),
+            ),
+            Err(e) => findings.push(Finding::new(Level::Fail, format!("{}: {}", name, e)).hint("Fix the JSON syntax")),
+        }
+    }
+    findings.push(Finding::new(
+        Level::Ok,
+        format!("Parsed {} of {} JSON files", parsed, names.len()),
+    ));
+
+    if !names.iter().any(|n| n == "default.json") {
+        findings.push(
+            Finding::new(Level::Fail, "default.json is missing").hint("Every config directory needs a default.json"),
+        );
+    }
+    let loads: Vec<String> = config_file_names(env)
+        .into_iter()
+        .filter(|n| names.contains(n))
+        .collect();
+    findings.push(Finding::new(
+        Level::Ok,
+        format!("Environment `{}` loads: {}", environment, loads.join(", ")),
+    ));
+}
+
+fn manager_for(
+    env: &HashMap<String, String>,
+    environment: Option<&str>,
+    definition: &Option<ConfigDefinition>,
+) -> ConfigManager {
+    let mut manager = ConfigManager::new().with_env(env_for(env, environment));
+    if let Some(name) = environment {
+        manager = manager.with_environment(name);
+    }
+    if let Some(ref definition) = definition {
+        manager = manager.with_definition(definition.clone());
+    }
+    manager
+}
+
+fn env_for(env: &HashMap<String, String>, environment: Option<&str>) -> HashMap<String, String> {
+    let mut env = env.clone();
+    if let Some(name) = environment {
+        // File layers follow SMOOAI_CONFIG_ENV; `with_environment` covers the remote fetch.
+        env.insert("SMOOAI_CONFIG_ENV".to_string(), name.to_string());
+    }
+    env
+}
+
+fn load_definition(cli: &Cli, env: &HashMap<String, String>) -> Result<Option<ConfigDefinition>, SmooaiConfigError> {
+    let path = match cli.schema {
+        Some(ref path) => path.clone(),
+        None => match find_config_directory_with_env(true, env) {
+            Ok(dir) => Path::new(&dir).join("schema.json"),
+            Err(_) => return Ok(None),
+        },
+    };
+    if cli.schema.is_none() && !path.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| SmooaiConfigError::new(&format!("Failed to read {}: {}", path.display(), e)).with_source(e))?;
+    let schema: Value =
+        serde_json::from_str(&text).map_err(|e| SmooaiConfigError::file_parse(&path.display().to_string(), e))?;
+    Ok(Some(ConfigDefinition::from_json_schema(schema)))
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Append `key: value` at `indent`, nesting objects and arrays in block
+/// style. Scalars are written as JSON, which YAML reads back unchanged.
+fn write_yaml_entry(yaml: &mut String, indent: usize, key: &str, value: &Value) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            yaml.push_str(&format!("{}{}:\n", pad, key));
+            for (k, v) in map {
+                write_yaml_entry(yaml, indent + 2, &yaml_key(k), v);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            yaml.push_str(&format!("{}{}:\n", pad, key));
+            for item in items {
+                write_yaml_item(yaml, indent + 2, item);
+            }
+        }
+        scalar => yaml.push_str(&format!("{}{}: {}\n", pad, key, scalar)),
+    }
+}
+
+fn write_yaml_item(yaml: &mut String, indent: usize, value: &Value) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            let mut first = true;
+            for (k, v) in map {
+                let mut entry = String::new();
+                write_yaml_entry(&mut entry, indent + 2, &yaml_key(k), v);
+                if first {
+                    // Replace the first entry's indentation with the "- " marker.
+                    yaml.push_str(&format!("{}- {}", pad, &entry[indent + 2..]));
+                    first = false;
+                } else {
+                    yaml.push_str(&entry);
+                }
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            yaml.push_str(&format!("{}-\n", pad));
+            for item in items {
+                write_yaml_item(yaml, indent + 2, item);
+            }
+        }
+        scalar => yaml.push_str(&format!("{}- {}\n", pad, scalar)),
+    }
+}
+
+/// Keys made only of word characters go out bare; anything else is quoted.
+fn yaml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+/// Quote a dotenv value only when needed: single quotes (no escapes or
+/// `$` expansion) unless the value itself contains one or a newline.
+fn dotenv_quote(value: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-./:@+,".contains(c);
+    if value.chars().all(plain) {
+        value.to_string()
+    } else if !value.contains('\'') && !value.contains('\n') {
+        format!("'{}'", value)
+    } else {
+        let mut quoted = String::from("\"");
+        for c in value.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '$' => quoted.push_str("\\$"),
+                '\n' => quoted.push_str("\\n"),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+}
+
+fn paint(text: &str, ansi: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", ansi, text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_line(out: &mut dyn Write, line: &str) -> Result<(), SmooaiConfigError> {
+    writeln!(out, "{}", line).map_err(|e| SmooaiConfigError::new(&format!("Failed to write output: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::define_config;
+    use serde_json::json;
+
+    fn setup(files: &[(&str, &str)]) -> (tempfile::TempDir, HashMap<String, String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".smooai-config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        for (name, content) in files {
+            std::fs::write(config_dir.join(name), content).unwrap();
+        }
+        let definition = define_config(
+            Some(json!({"type": "object", "properties": {"apiUrl": {"type": "string"}, "port": {"type": "number"}}})),
+            Some(
+                json!({"type": "object", "properties": {"dbPassword": {"type": "string"}}, "required": ["dbPassword"]}),
+            ),
+            Some(json!({"type": "object", "properties": {"newUi": {"type": "boolean"}}})),
+        );
+        definition.write_schemas(&config_dir).unwrap();
+        let env = HashMap::from([
+            (
+                "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                config_dir.to_string_lossy().to_string(),
+            ),
+            ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+        ]);
+        (dir, env)
+    }
+
+    fn run_args(args: &[&str], env: &HashMap<String, String>) -> Result<String, SmooaiConfigError> {
+        let cli = Cli::try_parse_from(std::iter::once("smooai-config").chain(args.iter().copied())).unwrap();
+        let mut out = Vec::new();
+        run(&cli, env.clone(), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    const DEFAULT_JSON: &str = r#"{"API_URL":"https://api","PORT":8080,"DB_PASSWORD":"hunter2","NEW_UI":true}"#;
+
+    #[test]
+    fn test_get() {
+        let (_dir, env) = setup(&[("default.json", DEFAULT_JSON)]);
+        assert_eq!(run_args(&["get", "API_URL"], &env).unwrap(), "https://api\n");
+        assert_eq!(run_args(&["get", "PORT"], &env).unwrap(), "8080\n");
+        assert_eq!(run_args(&["get", "DB_PASSWORD"], &env).unwrap(), "hunter2\n");
+        assert_eq!(
+            run_args(&["get", "NEW_UI", "--tier", "feature-flag"], &env).unwrap(),
+            "true\n"
+        );
+        assert!(run_args(&["get", "MISSING"], &env).is_err());
+    }
+
+    #[test]
+    fn test_list_redacts_and_filters_by_tier() {
+        let (_dir, env) = setup(&[("default.json", DEFAULT_JSON)]);
+        let all = run_args(&["list"], &env).unwrap();
+        assert!(all.contains("API_URL=https://api\n"));
+        assert!(all.contains("DB_PASSWORD=<redacted>\n"));
+        assert!(!all.contains("hunter2"));
+
+        let public = run_args(&["list", "--tier", "public"], &env).unwrap();
+        assert_eq!(public, "API_URL=https://api\nPORT=8080\n");
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let (_dir, env) = setup(&[(
+            "default.json",
+            r#"{"API_URL":"https://api","PORT":8080,"DB_PASSWORD":"hunter2","NEW_UI":true,"DB":{"HOSTS":["a","b"],"NAME":"it's here"}}"#,
+        )]);
+        let json: Value = serde_json::from_str(&run_args(&["export"], &env).unwrap()).unwrap();
+        assert_eq!(json["API_URL"], "https://api");
+        assert_eq!(json["DB_PASSWORD"], "<redacted>");
+        assert_eq!(json["DB"]["HOSTS"], json!(["a", "b"]));
+
+        let yaml = run_args(&["export", "--format", "yaml"], &env).unwrap();
+        assert!(yaml.starts_with("API_URL: \"https://api\"\n"), "{}", yaml);
+        assert!(
+            yaml.contains(
+                "DB:\n  HOSTS:\n    - \"a\"\n    - \"b\"\n  NAME: \"it's here\"\nDB_PASSWORD: \"<redacted>\"\n"
+            ),
+            "{}",
+            yaml
+        );
+        assert!(yaml.contains("PORT: 8080\n"));
+
+        let dotenv = run_args(&["export", "--format", "dotenv"], &env).unwrap();
+        assert!(dotenv.contains("API_URL=https://api\n"));
+        assert!(dotenv.contains("DB_PASSWORD='<redacted>'\n"));
+        assert!(dotenv.contains(r#"DB="{\"HOSTS\":[\"a\",\"b\"],\"NAME\":\"it's here\"}""#));
+        assert!(dotenv.contains("PORT=8080\n"));
+    }
+
+    #[test]
+    fn test_export_show_secrets() {
+        let (_dir, env) = setup(&[("default.json", DEFAULT_JSON)]);
+        let dotenv = run_args(&["export", "--format", "dotenv", "--show-secrets"], &env).unwrap();
+        assert!(dotenv.contains("DB_PASSWORD=hunter2\n"));
+        assert!(!run_args(&["export"], &env).unwrap().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_dotenv_quote() {
+        assert_eq!(dotenv_quote("plain-value_1.2"), "plain-value_1.2");
+        assert_eq!(dotenv_quote("has space"), "'has space'");
+        assert_eq!(dotenv_quote("it's $HOME"), "\"it's \\$HOME\"");
+        assert_eq!(dotenv_quote("a\nb"), "\"a\\nb\"");
+    }
+
+    #[test]
+    fn test_diff_environments() {
+        let (_dir, env) = setup(&[
+            ("default.json", DEFAULT_JSON),
+            (
+                "production.json",
+                r#"{"API_URL":"https://prod","DB_PASSWORD":"prod-pw","PROD_ONLY":1}"#,
+            ),
+            ("staging.json", r#"{"API_URL":"https://staging","STAGING_ONLY":true}"#),
+        ]);
+        let diff = run_args(&["diff", "production", "staging", "--color", "never"], &env).unwrap();
+        assert!(diff.contains("~ API_URL\n"), "{}", diff);
+        assert!(
+            diff.contains(r#"    production  "https://prod" (production.json)"#),
+            "{}",
+            diff
+        );
+        assert!(
+            diff.contains(r#"    staging     "https://staging" (staging.json)"#),
+            "{}",
+            diff
+        );
+        assert!(
+            diff.contains("~ DB_PASSWORD\n    production  \"<redacted>\" (production.json)"),
+            "{}",
+            diff
+        );
+        assert!(diff.contains("- PROD_ONLY\n"));
+        assert!(diff.contains("+ STAGING_ONLY\n"));
+        assert!(!diff.contains("PORT"), "{}", diff);
+        assert!(!diff.contains("prod-pw"));
+
+        let shown = run_args(
+            &["diff", "production", "staging", "--show-secrets", "--color", "always"],
+            &env,
+        )
+        .unwrap();
+        assert!(shown.contains("\x1b[33m~ DB_PASSWORD\x1b[0m"), "{}", shown);
+        assert!(shown.contains("\"prod-pw\""));
+
+        let same = run_args(&["diff", "staging", "staging"], &env).unwrap();
+        assert_eq!(same, "No differences between staging and staging\n");
+    }
+
+    fn doctor(env: &HashMap<String, String>) -> (String, bool) {
+        let cli = Cli::try_parse_from(["smooai-config", "doctor"]).unwrap();
+        let mut out = Vec::new();
+        let ok = run(&cli, env.clone(), &mut out).is_ok();
+        (String::from_utf8(out).unwrap(), ok)
+    }
+
+    #[test]
+    fn test_doctor_healthy_local() {
+        let (_dir, env) = setup(&[("default.json", DEFAULT_JSON), ("test.json", "{}")]);
+        let (report, ok) = doctor(&env);
+        assert!(ok, "{}", report);
+        assert!(report.contains("[ok  ] Parsed 6 of 6 JSON files"), "{}", report);
+        assert!(
+            report.contains("[ok  ] Environment `test` loads: default.json, test.json"),
+            "{}",
+            report
+        );
+        assert!(report.contains("[ok  ] Schema loaded"));
+        assert!(report.contains("[warn] Config API not configured"));
+        assert!(!report.contains("FAIL"));
+    }
+
+    #[test]
+    fn test_doctor_reports_every_problem() {
+        let (_dir, mut env) = setup(&[("default.json", r#"{"API_URL":"x"}"#), ("test.json", "{not json")]);
+        env.insert("SMOOAI_CONFIG_API_URL".to_string(), "http://localhost:1".to_string());
+        let (report, ok) = doctor(&env);
+        assert!(!ok);
+        assert!(report.contains("[FAIL] test.json: key must be a string"), "{}", report);
+        assert!(report.contains("       Fix the JSON syntax\n"));
+        assert!(
+            report.contains(
+                "[FAIL] Incomplete config API credentials: SMOOAI_CONFIG_ORG_ID, SMOOAI_CONFIG_API_KEY unset"
+            ),
+            "{}",
+            report
+        );
+
+        let (report, ok) = doctor(&HashMap::from([(
+            "SMOOAI_ENV_CONFIG_DIR".to_string(),
+            "/nonexistent/smooai-config".to_string(),
+        )]));
+        assert!(!ok);
+        assert!(
+            report.contains("[FAIL] [Smooai Config] The directory specified in SMOOAI_ENV_CONFIG_DIR"),
+            "{}",
+            report
+        );
+    }
+
+    #[tokio::test]
+    async fn test_doctor_checks_api_credentials() {
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let (_dir, mut env) = setup(&[("default.json", DEFAULT_JSON)]);
+            env.insert("SMOOAI_CONFIG_API_URL".to_string(), url);
+            env.insert("SMOOAI_CONFIG_ORG_ID".to_string(), "org-123".to_string());
+            env.insert("SMOOAI_CONFIG_API_KEY".to_string(), "bad-key".to_string());
+            let (report, ok) = doctor(&env);
+            assert!(!ok);
+            assert!(
+                report.contains("[FAIL] Config API request failed: HTTP 401"),
+                "{}",
+                report
+            );
+            assert!(report.contains("Check SMOOAI_CONFIG_API_KEY"));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_exec_exports_flattened_config() {
+        let (_dir, env) = setup(&[(
+            "default.json",
+            r#"{"API_URL":"https://api","DB_PASSWORD":"hunter2","DATABASE":{"host":"db","pool":{"maxSize":5}},"HOSTS":["a"],"UNSET":null}"#,
+        )]);
+        let cli = Cli::try_parse_from(["smooai-config", "exec", "--", "my-binary", "--flag", "x"]).unwrap();
+        let Command::Exec { ref command } = cli.command else {
+            panic!("expected exec");
+        };
+        assert_eq!(command, &["my-binary", "--flag", "x"]);
+
+        let manager = manager_for(&env, None, &None);
+        let child = build_exec(&manager, &env, None, command).unwrap();
+        assert_eq!(child.get_program(), "my-binary");
+        assert_eq!(child.get_args().collect::<Vec<_>>(), ["--flag", "x"]);
+        let vars: HashMap<String, Option<String>> = child
+            .get_envs()
+            .map(|(k, v)| {
+                (
+                    k.to_string_lossy().to_string(),
+                    v.map(|v| v.to_string_lossy().to_string()),
+                )
+            })
+            .collect();
+        let var = |k: &str| vars.get(k).cloned().flatten();
+        assert_eq!(var("API_URL").as_deref(), Some("https://api"));
+        assert_eq!(var("DB_PASSWORD").as_deref(), Some("hunter2"));
+        assert_eq!(var("DATABASE_HOST").as_deref(), Some("db"));
+        assert_eq!(var("DATABASE_POOL_MAX_SIZE").as_deref(), Some("5"));
+        assert_eq!(var("HOSTS").as_deref(), Some(r#"["a"]"#));
+        assert_eq!(var("SMOOAI_CONFIG_ENV").as_deref(), Some("test"));
+        assert!(!vars.contains_key("DATABASE"));
+        assert!(!vars.contains_key("UNSET"));
+    }
+
+    #[test]
+    fn test_exec_passes_selected_env_to_child() {
+        let (_dir, env) = setup(&[
+            ("default.json", r#"{"API_URL":"https://api"}"#),
+            ("production.json", r#"{"API_URL":"https://prod"}"#),
+        ]);
+        let cli = Cli::try_parse_from(["smooai-config", "exec", "--env", "production", "--", "my-binary"]).unwrap();
+        let Command::Exec { ref command } = cli.command else {
+            panic!("expected exec");
+        };
+
+        let manager = manager_for(&env, cli.env.as_deref(), &None);
+        let child = build_exec(&manager, &env, cli.env.as_deref(), command).unwrap();
+        let var = |key: &str| {
+            child
+                .get_envs()
+                .find(|(k, _)| *k == key)
+                .and_then(|(_, v)| v)
+                .map(|v| v.to_string_lossy().to_string())
+        };
+        assert_eq!(var("API_URL").as_deref(), Some("https://prod"));
+        // An embedded smooai-config in the child loads the same environment.
+        assert_eq!(var("SMOOAI_CONFIG_ENV").as_deref(), Some("production"));
+    }
+
+    #[test]
+    fn test_init_scaffolds_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema_path = dir.path().join("combined.json");
+        let definition = define_config(
+            Some(json!({"type": "object", "properties": {"apiUrl": {"type": "string", "default": "https://api"}}})),
+            Some(json!({"type": "object", "properties": {"dbPassword": {"type": "string"}}})),
+            None,
+        );
+        std::fs::write(&schema_path, serde_json::to_string(&definition.json_schema).unwrap()).unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let schema = schema_path.to_string_lossy().to_string();
+
+        let report = run_args(&["init", "--dir", &root, "--schema", &schema], &HashMap::new()).unwrap();
+        assert!(
+            report.contains("Set secrets in local.json or the config API: DB_PASSWORD"),
+            "{}",
+            report
+        );
+        let config_dir = dir.path().join(".smooai-config");
+        let read = |name: &str| std::fs::read_to_string(config_dir.join(name)).unwrap();
+        let defaults: Value = serde_json::from_str(&read("default.json")).unwrap();
+        assert_eq!(defaults, json!({"API_URL": "https://api"}));
+        assert_eq!(read("production.json"), "{}\n");
+        assert_eq!(read(".gitignore"), "local.json\n");
+        assert!(definition.check_schemas(&config_dir).unwrap().is_empty());
+
+        // Existing files are kept unless --force.
+        std::fs::write(config_dir.join("production.json"), r#"{"A":1}"#).unwrap();
+        let report = run_args(&["init", "--dir", &root], &HashMap::new()).unwrap();
+        assert!(report.contains("exists   "), "{}", report);
+        assert!(!report.contains("created"), "{}", report);
+        assert_eq!(read("production.json"), r#"{"A":1}"#);
+        assert_eq!(read(".gitignore"), "local.json\n");
+
+        run_args(&["init", "--dir", &root, "--force"], &HashMap::new()).unwrap();
+        assert_eq!(read("production.json"), "{}\n");
+        assert_eq!(read("default.json"), "{}\n");
+    }
+
+    #[test]
+    fn test_watch_prints_changes() {
+        let (dir, env) = setup(&[("default.json", r#"{"API_URL":"https://a","PORT":1,"NEW_UI":false}"#)]);
+        let default_json = dir.path().join(".smooai-config/default.json");
+        let manager = manager_for(&env, None, &None);
+        let mut polls = 0;
+        let mut out = Vec::new();
+        watch(
+            &manager,
+            &["apiUrl".to_string(), "NEW_UI".to_string(), "EXTRA".to_string()],
+            Duration::ZERO,
+            &mut out,
+            || {
+                polls += 1;
+                match polls {
+                    1 => std::fs::write(
+                        &default_json,
+                        r#"{"API_URL":"https://b","PORT":2,"NEW_UI":false,"EXTRA":"x"}"#,
+                    )
+                    .unwrap(),
+                    2 => std::fs::write(&default_json, r#"{"API_URL":"https://b","PORT":2,"NEW_UI":true}"#).unwrap(),
+                    _ => return false,
+                }
+                true
+            },
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "Watching API_URL, EXTRA, NEW_UI every 0s (Ctrl-C to stop)");
+        assert_eq!(lines.len(), 5, "{}", out);
+        assert!(
+            lines[1].ends_with(r#"  API_URL  "https://a" → "https://b"  (default.json)"#),
+            "{}",
+            out
+        );
+        assert!(
+            lines[2].ends_with(r#"  EXTRA  (unset) → "x"  (default.json)"#),
+            "{}",
+            out
+        );
+        assert!(lines[3].ends_with(r#"  EXTRA  "x" → (unset)"#), "{}", out);
+        assert!(lines[4].ends_with("  NEW_UI  false → true  (default.json)"), "{}", out);
+        assert!(lines[1].starts_with("20") && lines[1].contains('T'));
+    }
+
+    #[test]
+    fn test_validate() {
+        let (_dir, env) = setup(&[("default.json", DEFAULT_JSON)]);
+        assert!(run_args(&["validate"], &env).unwrap().starts_with("Config is valid"));
+
+        let (_dir, env) = setup(&[("default.json", r#"{"API_URL":"https://api"}"#)]);
+        let err = run_args(&["validate"], &env).unwrap_err();
+        assert!(err.message.contains("dbPassword"), "{}", err);
+    }
+}
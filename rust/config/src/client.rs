@@ -23,17 +23,23 @@
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use crate::flags;
+use crate::schema::{self, ConfigDefinition, SchemaDiff};
+
+#[cfg(feature = "tls-pinning")]
+use crate::tls_pinning::{pinned_tls_config, CertificatePin};
 use crate::token_provider::{SharedTokenProvider, TokenProvider, TokenProviderError};
+use crate::utils::{jitter_fraction, jittered_ttl};
 
 /// Characters to percent-encode in URL path segments.
 /// Encodes everything except unreserved characters (RFC 3986): A-Z a-z 0-9 - . _ ~
-const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &CONTROLS
+pub(crate) const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &CONTROLS
     .add(b' ')
     .add(b'"')
     .add(b'#')
@@ -53,6 +59,49 @@ const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &CONTROLS
     .add(b'|')
     .add(b'}');
 
+/// Response header the config API can set to override the cache TTL for the
+/// values in that response, in seconds. Takes precedence over `Cache-Control`.
+pub const TTL_HEADER: &str = "x-smooai-config-ttl";
+
+/// Longest TTL a response can ask for; larger values are capped to this.
+pub const MAX_SERVER_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long the server says a response may be cached: [`TTL_HEADER`], else
+/// `Cache-Control: max-age` (`no-store` and `no-cache` mean zero). `None`
+/// when the response says nothing, leaving the client's own TTL in charge.
+/// Capped at [`MAX_SERVER_TTL`].
+pub(crate) fn server_ttl(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    parse_server_ttl(headers).map(|ttl| ttl.min(MAX_SERVER_TTL))
+}
+
+fn parse_server_ttl(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    if let Some(secs) = header(TTL_HEADER).and_then(|value| value.trim().parse().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+    let mut max_age = None;
+    for directive in header("cache-control")?.split(',') {
+        let directive = directive.trim().to_ascii_lowercase();
+        if directive == "no-store" || directive == "no-cache" {
+            return Some(Duration::ZERO);
+        }
+        if let Some(secs) = directive.strip_prefix("max-age=") {
+            max_age = secs.trim_matches('"').parse().ok().map(Duration::from_secs);
+        }
+    }
+    max_age
+}
+
+/// An HTTP client that checks `pins` (CA validation alone when empty).
+#[cfg(feature = "tls-pinning")]
+fn pinned_client(pins: &[CertificatePin]) -> Client {
+    let mut builder = Client::builder();
+    if !pins.is_empty() {
+        builder = builder.use_preconfigured_tls(pinned_tls_config(pins));
+    }
+    builder.build().expect("reqwest client builder")
+}
+
 /// Client for reading configuration values from the Smoo AI config server.
 ///
 /// SMOODEV-975: now uses an [`Arc<TokenProvider>`](crate::token_provider::TokenProvider)
@@ -64,9 +113,13 @@ pub struct ConfigClient {
     org_id: String,
     default_environment: String,
     cache_ttl: Option<Duration>,
+    cache_ttl_jitter: f64,
     client: Client,
     token_provider: SharedTokenProvider,
+    // Values for `org_id`, keyed by `environment:key`.
     cache: HashMap<String, CacheEntry>,
+    // Caches of the organizations `set_org_id` switched away from.
+    org_caches: HashMap<String, HashMap<String, CacheEntry>>,
 }
 
 /// Unified error type for [`ConfigClient`] requests (SMOODEV-975).
@@ -113,6 +166,33 @@ struct ValuesResponse {
     values: HashMap<String, serde_json::Value>,
 }
 
+/// A schema stored on the config server, as returned by the
+/// `/organizations/{org_id}/config/schemas` endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSchema {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub current_version: u64,
+    /// The combined schema, tiers under `properties.public` / `.secret` /
+    /// `.feature_flags` like [`ConfigDefinition::json_schema`].
+    pub json_schema: serde_json::Value,
+}
+
+impl RemoteSchema {
+    /// The server's schema as a [`ConfigDefinition`].
+    pub fn definition(&self) -> ConfigDefinition {
+        ConfigDefinition::from_json_schema(self.json_schema.clone())
+    }
+}
+
+#[derive(Deserialize)]
+struct PushSchemaResponse {
+    schema: RemoteSchema,
+}
+
 /// Response from the server-side feature-flag evaluator.
 ///
 /// Matches the wire contract defined by the TS / Python / Go clients and
@@ -346,17 +426,76 @@ impl ConfigClient {
             org_id: org_id.to_string(),
             default_environment: environment.to_string(),
             cache_ttl: None,
+            cache_ttl_jitter: 0.0,
             client,
             token_provider,
             cache: HashMap::new(),
+            org_caches: HashMap::new(),
         }
     }
 
+    /// The organization requests go to.
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+
+    /// Send later requests to `org_id`, e.g. for an admin tool that inspects
+    /// several customer organizations with one set of credentials.
+    ///
+    /// Each organization keeps its own cache: values cached for the previous
+    /// organization are never served for the new one, and are still there
+    /// when switching back.
+    pub fn set_org_id(&mut self, org_id: &str) {
+        if org_id == self.org_id {
+            return;
+        }
+        let cache = self.org_caches.remove(org_id).unwrap_or_default();
+        let previous = std::mem::replace(&mut self.cache, cache);
+        if !previous.is_empty() {
+            self.org_caches.insert(self.org_id.clone(), previous);
+        }
+        self.org_id = org_id.to_string();
+    }
+
     /// Set the cache TTL duration. `None` means cache never expires (manual invalidation only).
+    ///
+    /// A response carrying [`TTL_HEADER`] or `Cache-Control: max-age`
+    /// overrides this for the values it returns.
     pub fn set_cache_ttl(&mut self, ttl: Option<Duration>) {
         self.cache_ttl = ttl;
     }
 
+    /// Randomly spread each entry's expiry by up to `fraction` of the TTL in
+    /// either direction (`0.1` = ±10%). Off (`0.0`) by default; a NaN or
+    /// infinite `fraction` also turns it off.
+    pub fn set_cache_ttl_jitter(&mut self, fraction: f64) {
+        self.cache_ttl_jitter = jitter_fraction(fraction);
+    }
+
+    /// Only trust the config server when its certificate chain matches one
+    /// of `pins`, on top of normal CA validation. An empty list goes back to
+    /// CA validation alone. See [`crate::tls_pinning`].
+    ///
+    /// When the OAuth issuer is on the same host as the config server, the
+    /// token requests (which carry the client secret) get the same pins.
+    /// Pin a separate issuer with [`set_issuer_tls_pins`](Self::set_issuer_tls_pins).
+    #[cfg(feature = "tls-pinning")]
+    pub fn set_tls_pins(&mut self, pins: &[CertificatePin]) {
+        self.client = pinned_client(pins);
+        let host = |url: &str| reqwest::Url::parse(url).ok()?.host_str().map(str::to_ascii_lowercase);
+        if host(&self.base_url).is_some() && host(&self.base_url) == host(self.token_provider.auth_url()) {
+            self.set_issuer_tls_pins(pins);
+        }
+    }
+
+    /// Only trust the OAuth issuer when its certificate chain matches one of
+    /// `pins`. The client switches to its own copy of the token provider, so
+    /// a provider shared with other callers is left unpinned.
+    #[cfg(feature = "tls-pinning")]
+    pub fn set_issuer_tls_pins(&mut self, pins: &[CertificatePin]) {
+        self.token_provider = Arc::new(self.token_provider.with_http_client(pinned_client(pins)));
+    }
+
     /// Create a config client from environment variables.
     ///
     /// SMOODEV-975: Reads `SMOOAI_CONFIG_API_URL`, `SMOOAI_CONFIG_CLIENT_ID`,
@@ -429,8 +568,12 @@ impl ConfigClient {
         }
     }
 
-    fn compute_expires_at(&self) -> Option<Instant> {
-        self.cache_ttl.map(|ttl| Instant::now() + ttl)
+    /// When an entry cached now expires: after `server_ttl` when the
+    /// response set one, else after the configured TTL.
+    fn compute_expires_at(&self, server_ttl: Option<Duration>) -> Option<Instant> {
+        server_ttl
+            .or(self.cache_ttl)
+            .and_then(|ttl| Instant::now().checked_add(jittered_ttl(ttl, self.cache_ttl_jitter)))
     }
 
     fn get_cached(&self, cache_key: &str) -> Option<serde_json::Value> {
@@ -479,9 +622,10 @@ impl ConfigClient {
                 body,
             });
         }
+        let ttl = server_ttl(resp.headers());
         let response: ValueResponse = resp.json().await?;
 
-        let expires_at = self.compute_expires_at();
+        let expires_at = self.compute_expires_at(ttl);
         self.cache.insert(
             cache_key,
             CacheEntry {
@@ -512,9 +656,10 @@ impl ConfigClient {
                 body,
             });
         }
+        let ttl = server_ttl(resp.headers());
         let response: ValuesResponse = resp.json().await?;
 
-        let expires_at = self.compute_expires_at();
+        let expires_at = self.compute_expires_at(ttl);
         for (key, value) in &response.values {
             self.cache.insert(
                 format!("{}:{}", env, key),
@@ -615,6 +760,58 @@ impl ConfigClient {
         })
     }
 
+    /// Evaluate every flag in a namespace (e.g. `"checkout"`) for one context.
+    ///
+    /// Lists the environment's values once to discover `checkout/...` keys,
+    /// then evaluates each flag server-side with `context`. Flags the server
+    /// doesn't know (404) or that resolve to `null` fall back to the nearest
+    /// namespace default (`checkout/payments/*`, then `checkout/*`); see
+    /// [`crate::flags`]. Results are keyed by full flag key.
+    ///
+    /// # Errors
+    /// The first non-404 evaluation failure, or listing failure (reported
+    /// against the namespace as the key).
+    pub async fn evaluate_namespace(
+        &mut self,
+        namespace: &str,
+        context: Option<HashMap<String, serde_json::Value>>,
+        environment: Option<&str>,
+    ) -> Result<BTreeMap<String, serde_json::Value>, FeatureFlagEvaluationError> {
+        let values = self.get_all_values(environment).await.map_err(|err| match err {
+            ConfigClientError::Request(source) => FeatureFlagEvaluationError::Request {
+                key: namespace.to_string(),
+                source,
+            },
+            other => FeatureFlagEvaluationError::Evaluation {
+                key: namespace.to_string(),
+                status: other.status().unwrap_or(0),
+                message: other.to_string(),
+            },
+        })?;
+
+        let mut keys: Vec<&String> = values
+            .keys()
+            .filter(|k| flags::in_namespace(k, namespace) && !flags::is_namespace_default(k))
+            .collect();
+        keys.sort();
+
+        let mut results = BTreeMap::new();
+        for key in keys {
+            let value = match self.evaluate_feature_flag(key, context.clone(), environment).await {
+                Ok(response) => response.value,
+                Err(FeatureFlagEvaluationError::NotFound { .. }) => serde_json::Value::Null,
+                Err(err) => return Err(err),
+            };
+            let value = if value.is_null() {
+                flags::namespace_default(&values, key).unwrap_or(value)
+            } else {
+                value
+            };
+            results.insert(key.clone(), value);
+        }
+        Ok(results)
+    }
+
     /// Evaluate a segment-aware limit on the server (SMOODEV-2306).
     ///
     /// Mirrors [`evaluate_feature_flag`](Self::evaluate_feature_flag) exactly —
@@ -694,6 +891,85 @@ impl ConfigClient {
         })
     }
 
+    /// Fetch the schema named `name` from the server, or `None` if there is none.
+    pub async fn fetch_schema(&self, name: &str) -> Result<Option<RemoteSchema>, ConfigClientError> {
+        let url = format!("{}/organizations/{}/config/schemas", self.base_url, self.org_id);
+        let schemas: Vec<RemoteSchema> = self.schema_request(reqwest::Method::GET, &url, None).await?;
+        Ok(schemas.into_iter().find(|s| s.name == name))
+    }
+
+    /// Publish `definition` as the schema named `name`: a new version when the
+    /// server already has it, a new schema otherwise. Returns the server's copy.
+    ///
+    /// Same endpoints as `smooai-config push`; `change_description` is shown
+    /// in the server's version history.
+    pub async fn publish_schema(
+        &self,
+        name: &str,
+        definition: &ConfigDefinition,
+        change_description: Option<&str>,
+    ) -> Result<RemoteSchema, ConfigClientError> {
+        let schemas_url = format!("{}/organizations/{}/config/schemas", self.base_url, self.org_id);
+        match self.fetch_schema(name).await? {
+            Some(existing) => {
+                let url = format!(
+                    "{}/{}/push",
+                    schemas_url,
+                    utf8_percent_encode(&existing.id, PATH_SEGMENT_ENCODE_SET)
+                );
+                let mut body = serde_json::json!({"jsonSchema": definition.json_schema});
+                if let Some(description) = change_description {
+                    body["changeDescription"] = serde_json::json!(description);
+                }
+                let response: PushSchemaResponse =
+                    self.schema_request(reqwest::Method::POST, &url, Some(&body)).await?;
+                Ok(response.schema)
+            }
+            None => {
+                let body = serde_json::json!({"name": name, "jsonSchema": definition.json_schema});
+                self.schema_request(reqwest::Method::POST, &schemas_url, Some(&body))
+                    .await
+            }
+        }
+    }
+
+    /// Compare `definition` with the server's schema named `name`.
+    ///
+    /// The diff runs from the server to the local definition, so `added`
+    /// lists keys not yet published and `removed` keys the server still
+    /// declares. An unpublished schema reports every local key as added. An
+    /// empty diff ([`SchemaDiff::is_empty`]) means no drift.
+    pub async fn verify_schema(
+        &self,
+        name: &str,
+        definition: &ConfigDefinition,
+    ) -> Result<SchemaDiff, ConfigClientError> {
+        let remote = self
+            .fetch_schema(name)
+            .await?
+            .map(|s| s.definition())
+            .unwrap_or_default();
+        Ok(schema::diff(&remote, definition))
+    }
+
+    async fn schema_request<T: serde::de::DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<T, ConfigClientError> {
+        let resp = self.send_with_retry(method, url, body, &[]).await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ConfigClientError::HttpStatus {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        Ok(resp.json().await?)
+    }
+
     /// Read a value from the local cache only, without hitting the server.
     ///
     /// Returns `None` when the key is absent or its TTL has expired. Used by
@@ -715,19 +991,23 @@ impl ConfigClient {
     pub fn seed_cache(&mut self, key: &str, value: serde_json::Value, environment: Option<&str>) {
         let env = self.resolve_env(environment).to_string();
         let cache_key = format!("{}:{}", env, key);
-        let expires_at = self.compute_expires_at();
+        let expires_at = self.compute_expires_at(None);
         self.cache.insert(cache_key, CacheEntry { value, expires_at });
     }
 
-    /// Clear the entire local cache.
+    /// Clear the entire local cache, for every organization.
     pub fn invalidate_cache(&mut self) {
         self.cache.clear();
+        self.org_caches.clear();
     }
 
-    /// Clear cached values for a specific environment.
+    /// Clear cached values for a specific environment, in every organization.
     pub fn invalidate_cache_for_environment(&mut self, environment: &str) {
         let prefix = format!("{}:", environment);
         self.cache.retain(|key, _| !key.starts_with(&prefix));
+        for cache in self.org_caches.values_mut() {
+            cache.retain(|key, _| !key.starts_with(&prefix));
+        }
     }
 }
 
@@ -735,6 +1015,72 @@ impl ConfigClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_server_ttl_parsing() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut map = reqwest::header::HeaderMap::new();
+            for (name, value) in pairs {
+                map.insert(*name, value.parse().unwrap());
+            }
+            server_ttl(&map)
+        };
+        assert_eq!(headers(&[]), None);
+        assert_eq!(
+            headers(&[("cache-control", "public, max-age=60")]),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            headers(&[("cache-control", "Max-Age=\"5\"")]),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(headers(&[("cache-control", "no-store")]), Some(Duration::ZERO));
+        assert_eq!(
+            headers(&[("cache-control", "max-age=60, no-cache")]),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(headers(&[("cache-control", "private")]), None);
+        assert_eq!(
+            headers(&[(TTL_HEADER, "10"), ("cache-control", "max-age=60")]),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(headers(&[(TTL_HEADER, "soon")]), None);
+        assert_eq!(
+            headers(&[("cache-control", "max-age=18446744073709551615")]),
+            Some(MAX_SERVER_TTL)
+        );
+        assert_eq!(headers(&[(TTL_HEADER, "18446744073709551615")]), Some(MAX_SERVER_TTL));
+    }
+
+    #[cfg(feature = "tls-pinning")]
+    #[test]
+    fn test_tls_pins_cover_issuer_on_same_host() {
+        let pins = [CertificatePin::Spki([7; 32])];
+        let provider = |auth_url: &str| Arc::new(TokenProvider::new(auth_url, "id", "secret").unwrap());
+
+        let shared = provider("https://config.example.com/auth");
+        let mut client = ConfigClient::with_token_provider(
+            "https://CONFIG.example.com",
+            Arc::clone(&shared),
+            "org-id",
+            "production",
+        );
+        client.set_tls_pins(&pins);
+        assert!(!Arc::ptr_eq(&client.token_provider, &shared));
+        assert_eq!(client.token_provider.auth_url(), "https://config.example.com/auth");
+
+        let separate = provider("https://auth.example.com");
+        let mut client = ConfigClient::with_token_provider(
+            "https://config.example.com",
+            Arc::clone(&separate),
+            "org-id",
+            "production",
+        );
+        client.set_tls_pins(&pins);
+        assert!(Arc::ptr_eq(&client.token_provider, &separate));
+        client.set_issuer_tls_pins(&pins);
+        assert!(!Arc::ptr_eq(&client.token_provider, &separate));
+    }
+
     #[test]
     fn test_new_trims_trailing_slash() {
         let client = ConfigClient::new("https://api.example.com/", "key", "key", "org-id");
@@ -832,6 +1178,19 @@ mod tests {
         assert_eq!(client.cache_ttl, Some(Duration::from_secs(60)));
     }
 
+    #[test]
+    fn test_set_cache_ttl_jitter() {
+        let mut client = ConfigClient::new("https://api.example.com", "key", "key", "org");
+        client.set_cache_ttl(Some(Duration::from_secs(100)));
+        client.set_cache_ttl_jitter(0.1);
+        for _ in 0..50 {
+            let ttl = client.compute_expires_at(None).unwrap() - Instant::now();
+            assert!(ttl <= Duration::from_secs(110) && ttl >= Duration::from_secs(89));
+        }
+        client.set_cache_ttl_jitter(f64::NAN);
+        assert_eq!(client.cache_ttl_jitter, 0.0);
+    }
+
     #[test]
     fn test_value_response_deserialization() {
         let json = r#"{"value": "hello"}"#;
@@ -1030,6 +1389,40 @@ mod integration_tests {
         assert_eq!(value2, serde_json::json!("fresh-value"));
     }
 
+    #[tokio::test]
+    async fn test_server_ttl_overrides_client_ttl() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/config/values/STABLE_KEY$"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Cache-Control", "private, max-age=3600")
+                    .set_body_json(serde_json::json!({"value": "stable"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/config/values/VOLATILE_FLAG$"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(TTL_HEADER, "0")
+                    .insert_header("Cache-Control", "max-age=3600")
+                    .set_body_json(serde_json::json!({"value": true})),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(&mock_server, "test-api-key", "production").await;
+        client.set_cache_ttl(Some(Duration::from_millis(1)));
+        for _ in 0..2 {
+            assert_eq!(client.get_value("STABLE_KEY", None).await.unwrap(), "stable");
+            assert_eq!(client.get_value("VOLATILE_FLAG", None).await.unwrap(), true);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
     // --- Test 6: invalidate_cache forces re-fetch ---
     #[tokio::test]
     async fn test_invalidate_cache_forces_refetch() {
@@ -1144,6 +1537,38 @@ mod integration_tests {
         assert_eq!(staging_cached, serde_json::json!("staging-value"));
     }
 
+    #[tokio::test]
+    async fn test_set_org_id_partitions_cache() {
+        let mock_server = MockServer::start().await;
+        for (org, value) in [("org-a", "a-value"), ("org-b", "b-value")] {
+            Mock::given(method("GET"))
+                .and(path_matcher(format!("/organizations/{}/config/values/SHARED_KEY", org)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": value})))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let mut client = test_client(&mock_server, "test-api-key", "production").await;
+        client.set_org_id("org-a");
+        assert_eq!(client.org_id(), "org-a");
+        assert_eq!(client.get_value("SHARED_KEY", None).await.unwrap(), "a-value");
+
+        client.set_org_id("org-b");
+        assert_eq!(client.get_cached_value("SHARED_KEY", None), None);
+        assert_eq!(client.get_value("SHARED_KEY", None).await.unwrap(), "b-value");
+
+        // Switching back serves org-a's cache (each mock expects one call).
+        client.set_org_id("org-a");
+        assert_eq!(client.get_value("SHARED_KEY", None).await.unwrap(), "a-value");
+        client.set_org_id("org-b");
+        assert_eq!(client.get_value("SHARED_KEY", None).await.unwrap(), "b-value");
+
+        client.invalidate_cache();
+        client.set_org_id("org-a");
+        assert_eq!(client.get_cached_value("SHARED_KEY", None), None);
+    }
+
     // -----------------------------------------------------------------------
     // evaluate_feature_flag
     // -----------------------------------------------------------------------
@@ -1190,6 +1615,56 @@ mod integration_tests {
         assert_eq!(result.rollout_bucket, None);
     }
 
+    // --- Evaluate namespace: bulk evaluation with namespace defaults ---
+    #[tokio::test]
+    async fn test_evaluate_namespace_applies_defaults() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_matcher("/organizations/test-org/config/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "checkout/*": false,
+                    "checkout/new-payment-flow": false,
+                    "checkout/legacy-cart": true,
+                    "search/fuzzy": true
+                }
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_matcher(
+                "/organizations/test-org/config/feature-flags/checkout%2Fnew-payment-flow/evaluate",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "value": true,
+                "source": "rollout",
+                "rolloutBucket": 12
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_matcher(
+                "/organizations/test-org/config/feature-flags/checkout%2Flegacy-cart/evaluate",
+            ))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(&mock_server, "test-api-key", "production").await;
+        let result = client
+            .evaluate_namespace("checkout", None, None)
+            .await
+            .expect("namespace evaluates");
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result["checkout/new-payment-flow"], serde_json::json!(true));
+        assert_eq!(result["checkout/legacy-cart"], serde_json::json!(false));
+    }
+
     // --- Evaluate: None context defaults to empty object ---
     #[tokio::test]
     async fn test_evaluate_feature_flag_defaults_context_to_empty() {
@@ -1422,6 +1897,118 @@ mod integration_tests {
             other => panic!("expected NotFound, got {other:?}"),
         }
     }
+
+    // -----------------------------------------------------------------------
+    // schemas
+    // -----------------------------------------------------------------------
+
+    fn remote(id: &str, name: &str, json_schema: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "organizationId": "test-org",
+            "name": name,
+            "currentVersion": 3,
+            "jsonSchema": json_schema,
+            "createdBy": "someone",
+            "createdAt": "2025-01-01T00:00:00Z",
+            "updatedAt": "2025-01-01T00:00:00Z"
+        })
+    }
+
+    fn local() -> ConfigDefinition {
+        crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string"},
+                "maxRetries": {"type": "integer"}
+            }})),
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_publish_schema_creates_or_pushes_version() {
+        let server = MockServer::start().await;
+        let client = test_client(&server, "token", "production").await;
+        let definition = local();
+
+        Mock::given(method("GET"))
+            .and(path_matcher("/organizations/test-org/config/schemas"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_matcher("/organizations/test-org/config/schemas"))
+            .and(body_json(
+                serde_json::json!({"name": "app", "jsonSchema": definition.json_schema}),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(remote("s1", "app", definition.json_schema.clone())))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let created = client.publish_schema("app", &definition, None).await.unwrap();
+        assert_eq!(created.id, "s1");
+
+        Mock::given(method("GET"))
+            .and(path_matcher("/organizations/test-org/config/schemas"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([remote(
+                "s1",
+                "app",
+                serde_json::json!({})
+            )])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path_matcher("/organizations/test-org/config/schemas/s1/push"))
+            .and(body_json(
+                serde_json::json!({"jsonSchema": definition.json_schema, "changeDescription": "v2"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "schema": remote("s1", "app", definition.json_schema.clone()),
+                "version": {"id": "v", "schemaId": "s1", "version": 4}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let pushed = client.publish_schema("app", &definition, Some("v2")).await.unwrap();
+        assert_eq!(pushed.definition().public_schema, definition.public_schema);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_verify_schema() {
+        let server = MockServer::start().await;
+        let client = test_client(&server, "token", "production").await;
+        let published = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string"},
+                "maxRetries": {"type": "string"},
+                "legacy": {"type": "boolean"}
+            }})),
+            None,
+            None,
+        );
+        Mock::given(method("GET"))
+            .and(path_matcher("/organizations/test-org/config/schemas"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                remote("s0", "other", serde_json::json!({})),
+                remote("s1", "app", published.json_schema.clone())
+            ])))
+            .mount(&server)
+            .await;
+
+        assert_eq!(client.fetch_schema("app").await.unwrap().unwrap().id, "s1");
+        assert!(client.fetch_schema("missing").await.unwrap().is_none());
+
+        let drift = client.verify_schema("app", &local()).await.unwrap();
+        assert_eq!(drift.public.removed, vec!["LEGACY"]);
+        assert_eq!(drift.public.retyped[0].key, "MAX_RETRIES");
+        assert!(drift.secret.is_empty());
+        assert!(client.verify_schema("app", &published).await.unwrap().is_empty());
+
+        let unpublished = client.verify_schema("missing", &local()).await.unwrap();
+        assert_eq!(unpublished.public.added, vec!["API_URL", "MAX_RETRIES"]);
+    }
 }
 
 #[cfg(test)]
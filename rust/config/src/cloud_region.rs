@@ -1,7 +1,39 @@
 //! Cloud provider and region detection from environment variables.
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::env;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+static DETECTORS: RwLock<Vec<Arc<dyn CloudDetector>>> = RwLock::new(Vec::new());
+
+/// Registered-detector results, keyed by a fingerprint of the env they saw.
+type DetectorCache = HashMap<u64, (Option<CloudRegionResult>, Instant)>;
+static DETECTOR_CACHE: Mutex<Option<DetectorCache>> = Mutex::new(None);
+const DETECTOR_CACHE_TTL_SECS: u64 = 3600; // 1 hour
+/// Most distinct envs cached at once; the oldest result is evicted first.
+const MAX_DETECTOR_CACHE_ENTRIES: usize = 16;
+
+/// Clear the cached results of registered detectors (for testing).
+pub fn clear_cloud_region_cache() {
+    if let Ok(mut cache) = DETECTOR_CACHE.lock() {
+        *cache = None;
+    }
+}
+
+/// Re-run detection against the process environment, bypassing and then
+/// replacing the cached detector result.
+pub fn refresh_cloud_region() -> CloudRegionResult {
+    let env = env_map();
+    if let Ok(mut cache) = DETECTOR_CACHE.lock() {
+        if let Some(ref mut entries) = *cache {
+            entries.remove(&env_fingerprint(&env));
+        }
+    }
+    get_cloud_region_from_env(&env)
+}
 
 /// Result of cloud provider/region detection.
 #[derive(Debug, Clone, PartialEq)]
@@ -10,6 +42,75 @@ pub struct CloudRegionResult {
     pub region: String,
 }
 
+/// Custom cloud detection, for private clouds the built-in checks don't know.
+///
+/// Implemented for any `Fn(&HashMap<String, String>) -> Option<CloudRegionResult>`.
+pub trait CloudDetector: Send + Sync {
+    /// Return the provider and region if this environment belongs to the
+    /// detector's cloud, or `None` to fall through to the next detector.
+    fn detect(&self, env: &HashMap<String, String>) -> Option<CloudRegionResult>;
+}
+
+impl<F> CloudDetector for F
+where
+    F: Fn(&HashMap<String, String>) -> Option<CloudRegionResult> + Send + Sync,
+{
+    fn detect(&self, env: &HashMap<String, String>) -> Option<CloudRegionResult> {
+        self(env)
+    }
+}
+
+/// Register a detector with [`get_cloud_region`] and
+/// [`get_cloud_region_from_env`], and so with the cloud-specific file
+/// overlays (`production.mycloud.json`). Detectors run in registration order,
+/// after the `SMOOAI_CONFIG_CLOUD_*` override and before the built-in checks.
+///
+/// Detectors may be slow (e.g. querying a metadata endpoint), so their
+/// combined result is cached per environment (up to 16 of them) for an hour; see
+/// [`refresh_cloud_region`].
+pub fn register_detector(detector: impl CloudDetector + 'static) {
+    DETECTORS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(detector));
+    clear_cloud_region_cache();
+}
+
+/// A platform-as-a-service detected from its well-known env vars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaasProvider {
+    /// Value reported as `CLOUD_PROVIDER`.
+    pub provider: &'static str,
+    /// Vars holding the region, checked in order.
+    pub region_vars: &'static [&'static str],
+    /// Vars whose presence alone identifies the platform; the region is then `unknown`.
+    pub marker_vars: &'static [&'static str],
+}
+
+/// PaaS platforms recognised by [`get_cloud_region_from_env`], in priority order.
+pub const PAAS_PROVIDERS: &[PaasProvider] = &[
+    PaasProvider {
+        provider: "fly",
+        region_vars: &["FLY_REGION"],
+        marker_vars: &["FLY_APP_NAME"],
+    },
+    PaasProvider {
+        provider: "vercel",
+        region_vars: &["VERCEL_REGION"],
+        marker_vars: &["VERCEL"],
+    },
+    PaasProvider {
+        provider: "railway",
+        region_vars: &["RAILWAY_REPLICA_REGION"],
+        marker_vars: &["RAILWAY_ENVIRONMENT", "RAILWAY_PROJECT_ID"],
+    },
+    PaasProvider {
+        provider: "render",
+        region_vars: &["RENDER_REGION"],
+        marker_vars: &["RENDER", "RENDER_SERVICE_ID"],
+    },
+];
+
 /// Detect cloud provider and region from process environment variables.
 pub fn get_cloud_region() -> CloudRegionResult {
     get_cloud_region_from_env(&env_map())
@@ -19,10 +120,15 @@ pub fn get_cloud_region() -> CloudRegionResult {
 ///
 /// Detection order:
 /// 1. SMOOAI_CONFIG_CLOUD_REGION / SMOOAI_CONFIG_CLOUD_PROVIDER (custom override)
-/// 2. AWS_REGION / AWS_DEFAULT_REGION
-/// 3. AZURE_REGION / AZURE_LOCATION
-/// 4. GOOGLE_CLOUD_REGION / CLOUDSDK_COMPUTE_REGION
-/// 5. Default: unknown/unknown
+/// 2. Detectors added with [`register_detector`]
+/// 3. PaaS platforms (fly, vercel, railway, render), see [`PAAS_PROVIDERS`]
+/// 4. AWS_REGION / AWS_DEFAULT_REGION
+/// 5. AZURE_REGION / AZURE_LOCATION
+/// 6. GOOGLE_CLOUD_REGION / CLOUDSDK_COMPUTE_REGION
+/// 7. Default: unknown/unknown
+///
+/// PaaS platforms are checked before the big three because some of them run
+/// on AWS and leak `AWS_REGION` into the environment (Vercel functions do).
 pub fn get_cloud_region_from_env(env: &HashMap<String, String>) -> CloudRegionResult {
     // 1. Custom override
     if env.contains_key("SMOOAI_CONFIG_CLOUD_REGION") || env.contains_key("SMOOAI_CONFIG_CLOUD_PROVIDER") {
@@ -38,7 +144,23 @@ pub fn get_cloud_region_from_env(env: &HashMap<String, String>) -> CloudRegionRe
         };
     }
 
-    // 2. AWS
+    // 2. Registered detectors
+    if let Some(result) = run_detectors(env) {
+        return result;
+    }
+
+    // 3. PaaS
+    for paas in PAAS_PROVIDERS {
+        let region = paas.region_vars.iter().find_map(|var| env.get(*var));
+        if region.is_some() || paas.marker_vars.iter().any(|var| env.contains_key(*var)) {
+            return CloudRegionResult {
+                provider: paas.provider.to_string(),
+                region: region.cloned().unwrap_or_else(|| "unknown".to_string()),
+            };
+        }
+    }
+
+    // 4. AWS
     if let Some(region) = env.get("AWS_REGION").or_else(|| env.get("AWS_DEFAULT_REGION")) {
         return CloudRegionResult {
             provider: "aws".to_string(),
@@ -46,7 +168,7 @@ pub fn get_cloud_region_from_env(env: &HashMap<String, String>) -> CloudRegionRe
         };
     }
 
-    // 3. Azure
+    // 5. Azure
     if let Some(region) = env.get("AZURE_REGION").or_else(|| env.get("AZURE_LOCATION")) {
         return CloudRegionResult {
             provider: "azure".to_string(),
@@ -54,7 +176,7 @@ pub fn get_cloud_region_from_env(env: &HashMap<String, String>) -> CloudRegionRe
         };
     }
 
-    // 4. GCP
+    // 6. GCP
     if let Some(region) = env
         .get("GOOGLE_CLOUD_REGION")
         .or_else(|| env.get("CLOUDSDK_COMPUTE_REGION"))
@@ -65,13 +187,51 @@ pub fn get_cloud_region_from_env(env: &HashMap<String, String>) -> CloudRegionRe
         };
     }
 
-    // 5. Default
+    // 7. Default
     CloudRegionResult {
         provider: "unknown".to_string(),
         region: "unknown".to_string(),
     }
 }
 
+fn run_detectors(env: &HashMap<String, String>) -> Option<CloudRegionResult> {
+    let detectors = DETECTORS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if detectors.is_empty() {
+        return None;
+    }
+
+    let fingerprint = env_fingerprint(env);
+    if let Ok(cache) = DETECTOR_CACHE.lock() {
+        if let Some((ref result, instant)) = cache.as_ref().and_then(|entries| entries.get(&fingerprint)) {
+            if instant.elapsed().as_secs() < DETECTOR_CACHE_TTL_SECS {
+                return result.clone();
+            }
+        }
+    }
+
+    let result = detectors.iter().find_map(|detector| detector.detect(env));
+    if let Ok(mut cache) = DETECTOR_CACHE.lock() {
+        let entries = cache.get_or_insert_with(HashMap::new);
+        entries.retain(|_, (_, at)| at.elapsed().as_secs() < DETECTOR_CACHE_TTL_SECS);
+        if entries.len() >= MAX_DETECTOR_CACHE_ENTRIES && !entries.contains_key(&fingerprint) {
+            let oldest = entries.iter().min_by_key(|(_, (_, at))| *at).map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(fingerprint, (result.clone(), Instant::now()));
+    }
+    result
+}
+
+fn env_fingerprint(env: &HashMap<String, String>) -> u64 {
+    let mut pairs: Vec<_> = env.iter().collect();
+    pairs.sort();
+    let mut hasher = DefaultHasher::new();
+    pairs.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn env_map() -> HashMap<String, String> {
     env::vars().collect()
 }
@@ -80,6 +240,9 @@ fn env_map() -> HashMap<String, String> {
 mod tests {
     use super::*;
 
+    /// Serializes tests that register detectors, since registering clears the cache.
+    static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
     fn make_env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
         pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
     }
@@ -185,4 +348,101 @@ mod tests {
         let result = get_cloud_region_from_env(&env);
         assert_eq!(result.provider, "aws");
     }
+
+    #[test]
+    fn test_fly() {
+        let env = make_env(&[("FLY_REGION", "ord"), ("FLY_APP_NAME", "api")]);
+        let result = get_cloud_region_from_env(&env);
+        assert_eq!(result.provider, "fly");
+        assert_eq!(result.region, "ord");
+    }
+
+    #[test]
+    fn test_vercel_wins_over_leaked_aws_region() {
+        let env = make_env(&[("VERCEL", "1"), ("VERCEL_REGION", "iad1"), ("AWS_REGION", "us-east-1")]);
+        let result = get_cloud_region_from_env(&env);
+        assert_eq!(result.provider, "vercel");
+        assert_eq!(result.region, "iad1");
+    }
+
+    #[test]
+    fn test_railway() {
+        let env = make_env(&[
+            ("RAILWAY_ENVIRONMENT", "production"),
+            ("RAILWAY_REPLICA_REGION", "us-west2"),
+        ]);
+        let result = get_cloud_region_from_env(&env);
+        assert_eq!(result.provider, "railway");
+        assert_eq!(result.region, "us-west2");
+    }
+
+    #[test]
+    fn test_paas_marker_without_region() {
+        let env = make_env(&[("RENDER", "true")]);
+        let result = get_cloud_region_from_env(&env);
+        assert_eq!(result.provider, "render");
+        assert_eq!(result.region, "unknown");
+    }
+
+    #[test]
+    fn test_custom_overrides_paas() {
+        let env = make_env(&[("SMOOAI_CONFIG_CLOUD_PROVIDER", "custom"), ("FLY_REGION", "ord")]);
+        let result = get_cloud_region_from_env(&env);
+        assert_eq!(result.provider, "custom");
+    }
+
+    #[test]
+    fn test_registered_detector() {
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        register_detector(|env: &HashMap<String, String>| {
+            env.get("ACME_CLOUD_ZONE").map(|zone| CloudRegionResult {
+                provider: "acme".to_string(),
+                region: zone.clone(),
+            })
+        });
+
+        let env = make_env(&[("ACME_CLOUD_ZONE", "dc-2"), ("AWS_REGION", "us-east-1")]);
+        let result = get_cloud_region_from_env(&env);
+        assert_eq!(result.provider, "acme");
+        assert_eq!(result.region, "dc-2");
+
+        let env = make_env(&[("ACME_CLOUD_ZONE", "dc-2"), ("SMOOAI_CONFIG_CLOUD_PROVIDER", "custom")]);
+        assert_eq!(get_cloud_region_from_env(&env).provider, "custom");
+
+        let env = make_env(&[("AWS_REGION", "us-east-1")]);
+        assert_eq!(get_cloud_region_from_env(&env).provider, "aws");
+    }
+
+    #[test]
+    fn test_detector_results_are_cached() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        register_detector(|env: &HashMap<String, String>| {
+            let zone = env.get("CACHED_CLOUD_ZONE")?;
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            Some(CloudRegionResult {
+                provider: "cached".to_string(),
+                region: zone.clone(),
+            })
+        });
+
+        let env = make_env(&[("CACHED_CLOUD_ZONE", "z1"), ("CACHED_CLOUD_RUN", "1")]);
+        let before = CALLS.load(Ordering::SeqCst);
+        assert_eq!(get_cloud_region_from_env(&env).region, "z1");
+        assert_eq!(get_cloud_region_from_env(&env).region, "z1");
+        assert_eq!(CALLS.load(Ordering::SeqCst) - before, 1);
+
+        let other = make_env(&[("CACHED_CLOUD_ZONE", "z2"), ("CACHED_CLOUD_RUN", "1")]);
+        assert_eq!(get_cloud_region_from_env(&other).region, "z2");
+
+        // Distinct envs don't grow the cache without bound.
+        for i in 0..MAX_DETECTOR_CACHE_ENTRIES * 2 {
+            let zone = format!("bulk-{}", i);
+            get_cloud_region_from_env(&make_env(&[("CACHED_CLOUD_ZONE", &zone)]));
+        }
+        let cached = DETECTOR_CACHE.lock().unwrap().as_ref().map_or(0, HashMap::len);
+        assert!(cached <= MAX_DETECTOR_CACHE_ENTRIES);
+    }
 }
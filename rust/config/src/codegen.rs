@@ -0,0 +1,353 @@
+//! Type definitions for the other SDKs, generated from a [`ConfigDefinition`].
+//!
+//! Teams that define their schema in Rust (usually via
+//! [`define_config_typed`](crate::schema::define_config_typed)) can emit the
+//! matching TypeScript interfaces or Python `TypedDict`s from the same source:
+//!
+//! ```rust,ignore
+//! let definition = define_config_typed::<PublicConfig, SecretConfig, FeatureFlags>();
+//! std::fs::write("src/config.generated.ts", definition.to_typescript())?;
+//! std::fs::write("config_types.py", definition.to_python_typeddict())?;
+//! ```
+//!
+//! Each tier becomes one type (`PublicConfig`, `SecretConfig`,
+//! `FeatureFlags`) with the property names as declared. Properties missing
+//! from `required` are optional. Referenced definitions (`$ref`, as schemars
+//! emits for nested structs) become named types of their own.
+
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+
+use crate::schema::{ConfigDefinition, ConfigTier};
+
+const HEADER: &str = "Generated by smooai-config from the Rust ConfigDefinition. Do not edit.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    TypeScript,
+    Python,
+}
+
+impl ConfigDefinition {
+    /// TypeScript interfaces for every tier.
+    pub fn to_typescript(&self) -> String {
+        self.emit(Lang::TypeScript)
+    }
+
+    /// Python (3.11+) `TypedDict` classes for every tier.
+    pub fn to_python_typeddict(&self) -> String {
+        self.emit(Lang::Python)
+    }
+
+    fn emit(&self, lang: Lang) -> String {
+        let mut emitter = Emitter {
+            lang,
+            blocks: Vec::new(),
+            seen: HashSet::new(),
+            definitions: Map::new(),
+        };
+        for (tier, name) in [
+            (ConfigTier::Public, "PublicConfig"),
+            (ConfigTier::Secret, "SecretConfig"),
+            (ConfigTier::FeatureFlag, "FeatureFlags"),
+        ] {
+            let schema = self.tier_schema(tier);
+            emitter.definitions = ["definitions", "$defs"]
+                .iter()
+                .filter_map(|k| schema.get(*k).and_then(|d| d.as_object()))
+                .flatten()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            emitter.named_object(name, schema);
+        }
+
+        let mut out = match lang {
+            Lang::TypeScript => format!("// {}\n", HEADER),
+            Lang::Python => format!(
+                "# {}\n\nfrom typing import Any, Literal, NotRequired, TypedDict\n",
+                HEADER
+            ),
+        };
+        for block in emitter.blocks {
+            out.push('\n');
+            if lang == Lang::Python {
+                out.push('\n');
+            }
+            out.push_str(&block);
+        }
+        out
+    }
+}
+
+struct Emitter {
+    lang: Lang,
+    /// Finished type declarations, dependencies first.
+    blocks: Vec<String>,
+    seen: HashSet<String>,
+    /// The current tier's `definitions` / `$defs`.
+    definitions: Map<String, Value>,
+}
+
+impl Emitter {
+    /// Declare `name` as an interface / TypedDict for an object schema.
+    fn named_object(&mut self, name: &str, schema: &Value) {
+        if !self.seen.insert(name.to_string()) {
+            return;
+        }
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        let required: HashSet<&str> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|k| k.as_str())
+            .collect();
+
+        let mut fields = Vec::new();
+        for (prop, prop_schema) in properties.into_iter().flatten() {
+            let ty = self.type_of(prop_schema, &format!("{}{}", name, pascal_case(prop)));
+            let optional = !required.contains(prop.as_str());
+            fields.push(match (self.lang, optional) {
+                (Lang::TypeScript, false) => format!("    {}: {};", ts_property(prop), ty),
+                (Lang::TypeScript, true) => format!("    {}?: {};", ts_property(prop), ty),
+                (Lang::Python, false) => format!("    {}: {}", prop, ty),
+                (Lang::Python, true) => format!("    {}: NotRequired[{}]", prop, ty),
+            });
+        }
+
+        let block = match self.lang {
+            Lang::TypeScript if fields.is_empty() => format!("export interface {} {{}}\n", name),
+            Lang::TypeScript => format!("export interface {} {{\n{}\n}}\n", name, fields.join("\n")),
+            Lang::Python if fields.is_empty() => format!("class {}(TypedDict):\n    pass\n", name),
+            Lang::Python => format!("class {}(TypedDict):\n{}\n", name, fields.join("\n")),
+        };
+        self.blocks.push(block);
+    }
+
+    /// The type expression for `schema`. Nested objects with properties are
+    /// declared separately under `hint`.
+    fn type_of(&mut self, schema: &Value, hint: &str) -> String {
+        if let Some(target) = schema.get("$ref").and_then(|r| r.as_str()) {
+            let name = target.rsplit('/').next().unwrap_or(target).to_string();
+            if let Some(definition) = self.definitions.get(&name).cloned() {
+                return self.type_of_named(&definition, &name);
+            }
+            return self.any();
+        }
+        if let Some(values) = schema.get("enum").and_then(|e| e.as_array()) {
+            return self.literals(values);
+        }
+        if let Some(value) = schema.get("const") {
+            return self.literals(std::slice::from_ref(value));
+        }
+        for key in ["anyOf", "oneOf", "allOf"] {
+            if let Some(branches) = schema.get(key).and_then(|b| b.as_array()) {
+                if key == "allOf" || branches.len() == 1 {
+                    if let Some(first) = branches.first() {
+                        return self.type_of(first, hint);
+                    }
+                }
+                let types: Vec<String> = branches
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| self.type_of(b, &format!("{}{}", hint, i + 1)))
+                    .collect();
+                return self.union(types);
+            }
+        }
+
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if types.is_empty() {
+            return self.any();
+        }
+        let types = types.into_iter().map(|ty| self.primitive(ty, schema, hint)).collect();
+        self.union(types)
+    }
+
+    fn type_of_named(&mut self, schema: &Value, name: &str) -> String {
+        if schema.get("properties").is_some() {
+            self.named_object(name, schema);
+            name.to_string()
+        } else {
+            self.type_of(schema, name)
+        }
+    }
+
+    fn primitive(&mut self, ty: &str, schema: &Value, hint: &str) -> String {
+        let ts = self.lang == Lang::TypeScript;
+        match ty {
+            "string" => if ts { "string" } else { "str" }.to_string(),
+            "integer" => if ts { "number" } else { "int" }.to_string(),
+            "number" => if ts { "number" } else { "float" }.to_string(),
+            "boolean" => if ts { "boolean" } else { "bool" }.to_string(),
+            "null" => if ts { "null" } else { "None" }.to_string(),
+            "array" => {
+                let item = match schema.get("items") {
+                    Some(items) => self.type_of(items, &format!("{}Item", hint)),
+                    None => self.any(),
+                };
+                if ts {
+                    if item.contains(' ') {
+                        format!("({})[]", item)
+                    } else {
+                        format!("{}[]", item)
+                    }
+                } else {
+                    format!("list[{}]", item)
+                }
+            }
+            "object" => {
+                if schema.get("properties").is_some() {
+                    self.named_object(hint, schema);
+                    return hint.to_string();
+                }
+                let value = match schema.get("additionalProperties") {
+                    Some(additional) if additional.is_object() => self.type_of(additional, &format!("{}Value", hint)),
+                    _ => self.any(),
+                };
+                if ts {
+                    format!("Record<string, {}>", value)
+                } else {
+                    format!("dict[str, {}]", value)
+                }
+            }
+            _ => self.any(),
+        }
+    }
+
+    fn literals(&self, values: &[Value]) -> String {
+        let literals: Vec<String> = values
+            .iter()
+            .map(|v| match (self.lang, v) {
+                (Lang::TypeScript, Value::String(s)) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+                (Lang::Python, Value::String(s)) => format!("{:?}", s),
+                (Lang::Python, Value::Bool(b)) => if *b { "True" } else { "False" }.to_string(),
+                (Lang::Python, Value::Null) => "None".to_string(),
+                (_, other) => other.to_string(),
+            })
+            .collect();
+        match self.lang {
+            Lang::TypeScript => literals.join(" | "),
+            Lang::Python => format!("Literal[{}]", literals.join(", ")),
+        }
+    }
+
+    fn union(&self, mut types: Vec<String>) -> String {
+        types.dedup();
+        types.join(" | ")
+    }
+
+    fn any(&self) -> String {
+        match self.lang {
+            Lang::TypeScript => "unknown".to_string(),
+            Lang::Python => "Any".to_string(),
+        }
+    }
+}
+
+/// Quote TypeScript property names that aren't plain identifiers.
+fn ts_property(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "\\'"))
+    }
+}
+
+/// `max_retries` / `maxRetries` -> `MaxRetries`.
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::define_config;
+    use serde_json::json;
+
+    fn definition() -> ConfigDefinition {
+        define_config(
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "apiUrl": {"type": "string"},
+                    "maxRetries": {"type": ["integer", "null"]},
+                    "logLevel": {"enum": ["debug", "info"]},
+                    "database": {"$ref": "#/definitions/Database"},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                },
+                "required": ["apiUrl", "database"],
+                "definitions": {
+                    "Database": {
+                        "type": "object",
+                        "properties": {"host": {"type": "string"}, "port": {"type": "integer"}},
+                        "required": ["host"]
+                    }
+                }
+            })),
+            Some(json!({"type": "object", "properties": {"api-key": {"type": "string"}}})),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_to_typescript() {
+        let ts = definition().to_typescript();
+        assert!(ts.contains("export interface Database {\n    host: string;\n    port?: number;\n}"));
+        assert!(ts.contains("    apiUrl: string;\n"));
+        assert!(ts.contains("    maxRetries?: number | null;\n"));
+        assert!(ts.contains("    logLevel?: 'debug' | 'info';\n"));
+        assert!(ts.contains("    database: Database;\n"));
+        assert!(ts.contains("    tags?: string[];\n"));
+        assert!(ts.contains("export interface SecretConfig {\n    'api-key'?: string;\n}"));
+        assert!(ts.contains("export interface FeatureFlags {}"));
+        // Referenced types are declared before use.
+        assert!(ts.find("interface Database").unwrap() < ts.find("interface PublicConfig").unwrap());
+    }
+
+    #[test]
+    fn test_to_python_typeddict() {
+        let py = definition().to_python_typeddict();
+        assert!(py.contains("from typing import Any, Literal, NotRequired, TypedDict"));
+        assert!(py.contains("class Database(TypedDict):\n    host: str\n    port: NotRequired[int]\n"));
+        assert!(py.contains("    maxRetries: NotRequired[int | None]\n"));
+        assert!(py.contains("    logLevel: NotRequired[Literal[\"debug\", \"info\"]]\n"));
+        assert!(py.contains("    tags: NotRequired[list[str]]\n"));
+        assert!(py.contains("class FeatureFlags(TypedDict):\n    pass\n"));
+    }
+
+    #[test]
+    fn test_nested_objects_get_named_types() {
+        let definition = define_config(
+            Some(json!({"type": "object", "properties": {
+                "cache": {"type": "object", "properties": {"ttl": {"type": "number"}}},
+                "labels": {"type": "object", "additionalProperties": {"type": "string"}}
+            }})),
+            None,
+            None,
+        );
+        let ts = definition.to_typescript();
+        assert!(ts.contains("export interface PublicConfigCache {\n    ttl?: number;\n}"));
+        assert!(ts.contains("    cache?: PublicConfigCache;\n"));
+        assert!(ts.contains("    labels?: Record<string, string>;\n"));
+    }
+}
@@ -0,0 +1,307 @@
+//! Composing config definitions.
+//!
+//! A shared platform crate can publish a base definition (logging, tracing,
+//! database keys) and each service layers its own keys on top:
+//!
+//! ```rust,ignore
+//! let definition = platform::base_definition().merge(&define_config_typed::<Public, Secret, Flags>())?;
+//! ```
+//!
+//! Keys are compared by their UPPER_SNAKE_CASE form, so `logLevel` in one
+//! definition and `log_level` in the other are the same key.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::schema::{build_definition, property_key, type_descriptor, ConfigDefinition, ConfigTier};
+
+const TIERS: [ConfigTier; 3] = [ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag];
+
+/// Why two definitions can't be merged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DefinitionConflict {
+    /// Both declare `key` in `tier` with different types.
+    Type {
+        key: String,
+        tier: ConfigTier,
+        existing: String,
+        incoming: String,
+    },
+    /// The definitions declare `key` in different tiers.
+    Tier {
+        key: String,
+        existing: ConfigTier,
+        incoming: ConfigTier,
+    },
+    /// Both have a referenced definition (`$ref` target) called `name` in
+    /// `tier`, with different contents.
+    Definition { name: String, tier: ConfigTier },
+}
+
+impl fmt::Display for DefinitionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionConflict::Type {
+                key,
+                tier,
+                existing,
+                incoming,
+            } => write!(
+                f,
+                "{} ({}) is {} in one definition and {} in the other",
+                key,
+                tier.as_str(),
+                existing,
+                incoming
+            ),
+            DefinitionConflict::Tier {
+                key,
+                existing,
+                incoming,
+            } => write!(
+                f,
+                "{} is declared as {} in one definition and {} in the other",
+                key,
+                existing.as_str(),
+                incoming.as_str()
+            ),
+            DefinitionConflict::Definition { name, tier } => {
+                write!(
+                    f,
+                    "definition {} ({}) differs between the two definitions",
+                    name,
+                    tier.as_str()
+                )
+            }
+        }
+    }
+}
+
+impl ConfigDefinition {
+    /// Combine `self` with `other`, which is layered on top.
+    ///
+    /// Properties of both are kept, tier by tier; where both declare a key
+    /// with the same type, `other`'s property (description, default, ...)
+    /// wins. `required` lists and referenced definitions are unioned. Keys
+    /// declared with different types or in different tiers are conflicts, and
+    /// every conflict is returned.
+    pub fn merge(&self, other: &ConfigDefinition) -> Result<ConfigDefinition, Vec<DefinitionConflict>> {
+        let mut conflicts = Vec::new();
+
+        let declared_tiers: HashMap<String, ConfigTier> = TIERS
+            .iter()
+            .rev()
+            .flat_map(|tier| properties(self.tier_schema(*tier)).map(|(name, _)| (property_key(name), *tier)))
+            .collect();
+        for tier in TIERS {
+            for (name, _) in properties(other.tier_schema(tier)) {
+                let key = property_key(name);
+                match declared_tiers.get(&key) {
+                    Some(existing) if *existing != tier => conflicts.push(DefinitionConflict::Tier {
+                        key,
+                        existing: *existing,
+                        incoming: tier,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut merged = Vec::new();
+        for tier in TIERS {
+            merged.push(merge_tier(
+                self.tier_schema(tier),
+                other.tier_schema(tier),
+                tier,
+                &mut conflicts,
+            ));
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        let mut merged = merged.into_iter().map(|schema| (!schema.is_null()).then_some(schema));
+        Ok(build_definition(
+            merged.next().flatten(),
+            merged.next().flatten(),
+            merged.next().flatten(),
+        ))
+    }
+}
+
+fn properties(schema: &Value) -> impl Iterator<Item = (&String, &Value)> {
+    schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .into_iter()
+        .flatten()
+}
+
+fn merge_tier(base: &Value, top: &Value, tier: ConfigTier, conflicts: &mut Vec<DefinitionConflict>) -> Value {
+    if base.is_null() {
+        return top.clone();
+    }
+    if top.is_null() {
+        return base.clone();
+    }
+
+    // Top-level keywords other than the ones merged below: `top` wins.
+    let mut merged = base.as_object().cloned().unwrap_or_default();
+    for (keyword, value) in top.as_object().into_iter().flatten() {
+        if !matches!(keyword.as_str(), "properties" | "required" | "definitions" | "$defs") {
+            merged.insert(keyword.clone(), value.clone());
+        }
+    }
+
+    let mut props: Map<String, Value> = properties(base).map(|(k, v)| (k.clone(), v.clone())).collect();
+    for (name, schema) in properties(top) {
+        let key = property_key(name);
+        let existing = props
+            .iter()
+            .find(|(n, _)| property_key(n) == key)
+            .map(|(n, s)| (n.clone(), s.clone()));
+        if let Some((existing_name, existing_schema)) = existing {
+            let (old, new) = (type_descriptor(&existing_schema), type_descriptor(schema));
+            if old != new {
+                conflicts.push(DefinitionConflict::Type {
+                    key,
+                    tier,
+                    existing: old,
+                    incoming: new,
+                });
+                continue;
+            }
+            props.remove(&existing_name);
+        }
+        props.insert(name.clone(), schema.clone());
+    }
+
+    // Required names follow the property spelling that survived above.
+    let mut required: Vec<Value> = Vec::new();
+    for schema in [base, top] {
+        for name in schema.get("required").and_then(|r| r.as_array()).into_iter().flatten() {
+            let name = match name.as_str() {
+                Some(name) => {
+                    let key = property_key(name);
+                    let surviving = props.keys().find(|n| property_key(n) == key);
+                    Value::String(surviving.map_or(name, String::as_str).to_string())
+                }
+                None => name.clone(),
+            };
+            if !required.contains(&name) {
+                required.push(name);
+            }
+        }
+    }
+    merged.insert("properties".to_string(), Value::Object(props));
+    if !required.is_empty() {
+        merged.insert("required".to_string(), Value::Array(required));
+    }
+
+    for keyword in ["definitions", "$defs"] {
+        let mut definitions = base
+            .get(keyword)
+            .and_then(|d| d.as_object())
+            .cloned()
+            .unwrap_or_default();
+        for (name, schema) in top.get(keyword).and_then(|d| d.as_object()).into_iter().flatten() {
+            match definitions.get(name) {
+                Some(existing) if existing != schema => conflicts.push(DefinitionConflict::Definition {
+                    name: name.clone(),
+                    tier,
+                }),
+                _ => {
+                    definitions.insert(name.clone(), schema.clone());
+                }
+            }
+        }
+        if !definitions.is_empty() {
+            merged.insert(keyword.to_string(), Value::Object(definitions));
+        }
+    }
+
+    Value::Object(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::define_config;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    fn base() -> ConfigDefinition {
+        define_config(
+            Some(json!({"type": "object", "properties": {
+                "logLevel": {"type": "string"},
+                "tracingEndpoint": {"type": "string", "format": "uri"}
+            }, "required": ["logLevel"]})),
+            Some(json!({"type": "object", "properties": {"databaseUrl": {"type": "string"}}})),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_merge_composes_tiers() {
+        let service = define_config(
+            Some(json!({"type": "object", "properties": {
+                "log_level": {"type": "string", "default": "info"},
+                "apiUrl": {"type": "string"}
+            }, "required": ["apiUrl"]})),
+            None,
+            Some(json!({"type": "object", "properties": {"newUi": {"type": "boolean"}}})),
+        );
+        let merged = base().merge(&service).unwrap();
+
+        assert_eq!(
+            merged.schema_keys(),
+            HashSet::from(["LOG_LEVEL", "TRACING_ENDPOINT", "API_URL", "DATABASE_URL", "NEW_UI"].map(String::from))
+        );
+        // The service's property replaces the base's for the same key.
+        assert_eq!(merged.public_schema["properties"]["log_level"]["default"], "info");
+        assert!(merged.public_schema["properties"].get("logLevel").is_none());
+        // `required` follows the surviving spelling, so it names a declared property.
+        assert_eq!(merged.required_keys(), vec!["log_level", "apiUrl"]);
+        assert_eq!(
+            merged.json_schema["properties"]["feature_flags"],
+            merged.feature_flag_schema
+        );
+    }
+
+    #[test]
+    fn test_merge_reports_every_conflict() {
+        let service = define_config(
+            Some(json!({"type": "object", "properties": {
+                "logLevel": {"type": "integer"},
+                "databaseUrl": {"type": "string"}
+            }})),
+            None,
+            None,
+        );
+        let conflicts = base().merge(&service).unwrap_err();
+        assert_eq!(
+            conflicts,
+            vec![
+                DefinitionConflict::Tier {
+                    key: "DATABASE_URL".to_string(),
+                    existing: ConfigTier::Secret,
+                    incoming: ConfigTier::Public,
+                },
+                DefinitionConflict::Type {
+                    key: "LOG_LEVEL".to_string(),
+                    tier: ConfigTier::Public,
+                    existing: "string".to_string(),
+                    incoming: "integer".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            conflicts[1].to_string(),
+            "LOG_LEVEL (public) is string in one definition and integer in the other"
+        );
+    }
+}
@@ -7,32 +7,237 @@
 //!
 //! Uses `reqwest::blocking::Client` for synchronous remote fetch, matching the
 //! sync pattern of the other SDKs.
-
-use std::collections::{HashMap, HashSet};
-use std::sync::RwLock;
+//!
+//! [`ConfigManager::local_only`] switches to the legacy `LocalConfigManager`
+//! behaviour (files + env only, file values win).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+#[cfg(feature = "snapshot")]
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
+use percent_encoding::utf8_percent_encode;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use tokio::sync::broadcast;
+use zeroize::Zeroizing;
 
+use crate::access::KeyAccess;
+use crate::at_rest::{AtRestEncryption, KeyEncryptionKey};
+use crate::changes::{changed_keys, ConfigChange, CHANGE_CHANNEL_CAPACITY};
+use crate::client::{server_ttl, PATH_SEGMENT_ENCODE_SET};
 use crate::deferred::{resolve_deferred, DeferredValue};
-use crate::env_config::find_and_process_env_config_with_env;
-use crate::file_config::find_and_process_file_config_with_env;
-use crate::merge::merge_replace_arrays;
-use crate::utils::SmooaiConfigError;
+use crate::deprecation::KeyDeprecation;
+use crate::env_config::process_env_vars_with_env;
+use crate::explain::{Explanation, LayerValue, ValueSource};
+use crate::file_config::{
+    builtin_keys_with_env, detect_local_with_env, find_config_directory_with_env, load_file_layers_with_env,
+};
+use crate::flags;
+use crate::last_known_good::LastKnownGood;
+use crate::locked_memory::LockedBytes;
+use crate::merge::{MergeRules, MergeStrategy};
+use crate::metrics_recorder::{FetchContext, MetricsRecorder};
+use crate::policy::AccessPolicy;
+use crate::redact::{self, redact_strings, redact_text};
+use crate::schema::{property_key, ConfigDefinition, ConfigTier};
+use crate::scoped::ScopedConfig;
+use crate::secret::{SecretValue, Wipe, REDACTED};
+#[cfg(feature = "snapshot")]
+use crate::snapshot::ConfigSnapshot;
+use crate::source::ConfigSource;
+use crate::standard_urls::STANDARD_URLS;
+use crate::status::{self, CacheStats, ConfigStatus, SourceStatus};
+#[cfg(feature = "tls-pinning")]
+use crate::tls_pinning::{pinned_tls_config, CertificatePin};
+use crate::utils::{
+    camel_to_upper_snake, decode_base64_value, format_allowed_values, index_paths, jitter_fraction, jittered_ttl,
+    parse_enum_type_hint, value_at_path, DecodedBytes, SmooaiConfigError,
+};
 
 const DEFAULT_TTL_SECS: u64 = 86400; // 24 hours
+/// How long a failed initialization is replayed to other readers before the
+/// next attempt, so a broken config doesn't trigger a fetch per waiting thread.
+const INIT_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// Tenant override layers kept before the least recently used is evicted.
+const DEFAULT_MAX_TENANTS: usize = 256;
+/// Other-environment views kept before the least recently used is evicted.
+const MAX_ENV_VIEWS: usize = 32;
+/// How long an environment view whose remote fetch failed is served before
+/// the fetch is retried.
+const ENV_VIEW_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Process-level registry backing [`ConfigManager::into_shared`], keyed by
+/// `(profile_name, fingerprint)`. Entries live for the rest of the process
+/// (or until [`clear_shared_managers`]) so libraries that come and go still
+/// land on the same snapshot.
+static SHARED_MANAGERS: Mutex<Option<SharedRegistry>> = Mutex::new(None);
+
+type SharedRegistry = HashMap<(String, u64), Arc<ConfigManager>>;
+
+/// Drop every manager held by the shared registry (for testing).
+///
+/// Callers that already hold an `Arc` keep their manager; subsequent
+/// [`ConfigManager::shared`] / [`ConfigManager::into_shared`] calls build fresh ones.
+pub fn clear_shared_managers() {
+    if let Ok(mut registry) = SHARED_MANAGERS.lock() {
+        *registry = None;
+    }
+}
 
 struct CacheEntry {
-    value: Value,
+    value: CachedValue,
     expires_at: Instant,
 }
 
+enum CachedValue {
+    // Shared so cache hits hand out a pointer instead of a deep copy.
+    Shared(Arc<Value>),
+    // A secret serialized into locked memory (`with_locked_secrets`), wiped
+    // when the entry is evicted, invalidated or dropped; decoded on each hit.
+    Locked(LockedBytes),
+}
+
+impl CachedValue {
+    fn get(&self) -> Arc<Value> {
+        match self {
+            Self::Shared(value) => Arc::clone(value),
+            Self::Locked(bytes) => {
+                Arc::new(serde_json::from_slice(bytes.as_slice()).expect("locked cache entries are valid JSON"))
+            }
+        }
+    }
+}
+
+/// Which source a recorded layer came from. Env layers resolve the actual
+/// variable name per key at explain time.
+enum LayerKind {
+    File(String),
+    Source(String),
+    Remote,
+    LastKnownGood,
+    Tenant(String),
+    Env,
+    BuiltIn,
+    Deferred,
+    Baked,
+    Values,
+    #[cfg(feature = "snapshot")]
+    Snapshot,
+    Override,
+}
+
+#[derive(Default)]
 struct ManagerInner {
     initialized: bool,
     config: HashMap<String, Value>,
-    public_cache: HashMap<String, CacheEntry>,
-    secret_cache: HashMap<String, CacheEntry>,
-    feature_flag_cache: HashMap<String, CacheEntry>,
+    // When the shortest source TTL lapses; the next read reloads everything.
+    refresh_at: Option<Instant>,
+    // Per-source values in merge order, kept for `explain`.
+    layers: Vec<(LayerKind, HashMap<String, Value>)>,
+    // Per-key caches, keyed by strings from `cache_keys` so a key cached in
+    // several tiers is allocated once.
+    public_cache: HashMap<Arc<str>, CacheEntry>,
+    secret_cache: HashMap<Arc<str>, CacheEntry>,
+    feature_flag_cache: HashMap<Arc<str>, CacheEntry>,
+    cache_keys: HashSet<Arc<str>>,
+    // Load outcomes and cache counters for `status`; survive invalidation.
+    health: Vec<SourceStatus>,
+    cache_hits: u64,
+    cache_misses: u64,
+    // Keys marked by `invalidate_key` / `invalidate_tier`, refetched on next read.
+    stale_keys: HashSet<String>,
+    // The last initialization error and when it happened, see INIT_RETRY_BACKOFF.
+    failed_init: Option<(SmooaiConfigError, Instant)>,
+    // Merged config as of the last successful load, diffed to emit change events.
+    published: Option<HashMap<String, Value>>,
+    // Nested values by dot path, built by the first `get_path` after each load.
+    path_index: Option<HashMap<String, Arc<Value>>>,
+    // Merged config for other environments, from `get_public_config_for_env`.
+    env_views: HashMap<String, EnvView>,
+    // Per-tenant remote overrides, from the `*_for_tenant` getters.
+    tenant_layers: HashMap<String, TenantLayer>,
+    // Set by `with_locked_secrets`: loaded values are wiped in place before
+    // they're replaced or dropped.
+    wipe_values: bool,
+}
+
+struct TenantLayer {
+    layer: (LayerKind, HashMap<String, Value>),
+    expires_at: Instant,
+    last_used: Instant,
+}
+
+struct EnvView {
+    config: HashMap<String, Value>,
+    expires_at: Instant,
+    last_used: Instant,
+}
+
+impl ManagerInner {
+    /// The shared cache key for `key`.
+    fn intern(&mut self, key: &str) -> Arc<str> {
+        if let Some(interned) = self.cache_keys.get(key) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(key);
+        self.cache_keys.insert(Arc::clone(&interned));
+        interned
+    }
+
+    fn clear_caches(&mut self) {
+        self.public_cache.clear();
+        self.secret_cache.clear();
+        self.feature_flag_cache.clear();
+        self.cache_keys.clear();
+        if self.wipe_values {
+            self.env_views.values_mut().for_each(|view| wipe_map(&mut view.config));
+            self.tenant_layers
+                .values_mut()
+                .for_each(|tenant| wipe_map(&mut tenant.layer.1));
+        }
+        self.env_views.clear();
+        self.tenant_layers.clear();
+    }
+
+    /// Wipe the merged config and its layers in place, ahead of replacing
+    /// them. A no-op unless `wipe_values` is set.
+    fn wipe_loaded(&mut self) {
+        if self.wipe_values {
+            wipe_map(&mut self.config);
+            self.layers.iter_mut().for_each(|(_, values)| wipe_map(values));
+            if let Some(index) = self.path_index.as_mut() {
+                index.values_mut().filter_map(Arc::get_mut).for_each(Wipe::wipe);
+            }
+        }
+        self.path_index = None;
+    }
+
+    /// Drop `map`, wiping it first when `wipe_values` is set.
+    fn discard(&self, mut map: HashMap<String, Value>) {
+        if self.wipe_values {
+            wipe_map(&mut map);
+        }
+    }
+}
+
+impl Drop for ManagerInner {
+    fn drop(&mut self) {
+        self.wipe_loaded();
+        if let Some(published) = self.published.take() {
+            self.discard(published);
+        }
+        self.clear_caches();
+    }
+}
+
+fn wipe_map(map: &mut HashMap<String, Value>) {
+    map.values_mut().for_each(Wipe::wipe);
 }
 
 /// Unified config manager with lazy init and multi-tier TTL caching.
@@ -40,6 +245,11 @@ struct ManagerInner {
 /// Thread-safe via RwLock. Lazy initialization loads file config, fetches remote
 /// config (if API credentials are available), and loads env config on first access.
 /// Per-key caches with configurable TTL for each tier (public, secret, feature_flag).
+///
+/// Loading is single-flight: initialization and TTL-driven reloads run under
+/// the write lock, so when many threads hit a cold manager at once exactly one
+/// of them fetches and the rest wait for its result. A failed load is handed
+/// to every waiter for a short backoff instead of being retried by each.
 pub struct ConfigManager {
     inner: RwLock<ManagerInner>,
     // Local config params (immutable after construction)
@@ -47,12 +257,36 @@ pub struct ConfigManager {
     env_prefix: String,
     schema_types: Option<HashMap<String, String>>,
     cache_ttl: Duration,
+    // Fraction by which cache and refresh expirations are randomly spread.
+    cache_ttl_jitter: f64,
+    // Tenant override layers cached at once; the least recently used goes first.
+    max_tenants: usize,
+    // Inject ENV, IS_LOCAL, REGION and CLOUD_PROVIDER above files and env vars.
+    builtin_keys: bool,
+    // Treat a debug build in a git checkout, off-cloud, as IS_LOCAL.
+    auto_detect_local: bool,
     env_override: Option<HashMap<String, String>>,
     // Remote API params (immutable after construction)
     api_key: Option<String>,
+    // Where to read the API key when `api_key` isn't set.
+    api_key_file: Option<PathBuf>,
+    #[cfg(feature = "keychain")]
+    keychain_entry: Option<(String, String)>,
+    // The key read from `api_key_file` or the keychain; `None` until read,
+    // and reset by `invalidate` or a 401 so a rotated key is picked up.
+    stored_api_key: Mutex<Option<Option<String>>>,
     base_url: Option<String>,
     org_id: Option<String>,
     environment: Option<String>,
+    // Per-request timeout for remote fetches; `None` is reqwest's default (no timeout).
+    request_timeout: Option<Duration>,
+    // The API's certificate chain must match one of these; empty means
+    // ordinary CA validation only.
+    #[cfg(feature = "tls-pinning")]
+    tls_pins: Vec<CertificatePin>,
+    // Built on first remote fetch and reused, so refreshes keep pooled
+    // connections and TLS sessions.
+    http_client: OnceLock<reqwest::blocking::Client>,
     // Deferred config values
     deferred: HashMap<String, DeferredValue>,
     // SMOODEV-958 — used in the `UndefinedKey` error message to point callers
@@ -63,34 +297,262 @@ pub struct ConfigManager {
     // `schema_keys` has historically also served as an env-var filter, not a
     // strict allow-list.
     strict_schema_keys: bool,
+    // Keys that must resolve to a non-null value after merge; initialization
+    // fails listing all of them otherwise.
+    required_keys: Vec<String>,
+    // Schema used to enforce that each getter only returns keys declared in
+    // its own tier. Keys the schema doesn't mention are not restricted.
+    definition: Option<ConfigDefinition>,
+    // Extra backends merged between files and the remote API, in order.
+    sources: Vec<Box<dyn ConfigSource>>,
+    // LocalConfigManager compatibility: files + env only, file wins, file
+    // errors propagate.
+    local_only: bool,
+    // From `from_values`: the whole config, in place of files, sources, env and remote.
+    fixed_values: Option<HashMap<String, Value>>,
+    // How layers combine; `JsonMergePatch` lets a higher layer delete keys with `null`.
+    merge_rules: MergeRules,
+    // Values layered above every source, including deferred resolvers.
+    overrides: HashMap<String, Value>,
+    // Temporary overrides from `push_overrides`, newest last, keyed by guard id.
+    pushed_overrides: Mutex<Vec<(u64, HashMap<String, Value>)>>,
+    next_override_id: AtomicU64,
+    // Snapshot of the last successful remote fetch, used when the API is down.
+    last_known_good: Option<LastKnownGood>,
+    // Wraps the data keys that seal secrets in the last-known-good and
+    // snapshot files.
+    disk_key: Option<Arc<dyn KeyEncryptionKey>>,
+    // Which keys callers may read; `None` allows everything.
+    access_policy: Option<AccessPolicy>,
+    // Keep secret-tier cache entries in locked, zeroized memory.
+    lock_secrets: bool,
+    // Binary copy of the merged config, written after each load.
+    #[cfg(feature = "snapshot")]
+    snapshot: Option<ConfigSnapshot>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    changes: broadcast::Sender<ConfigChange>,
+    // Secret-tier string values from the last merge (plus the API key),
+    // scrubbed from warnings, errors and displayed values.
+    known_secrets: RwLock<Vec<String>>,
+    // Validate merged values against `definition` at init.
+    #[cfg(feature = "jsonschema")]
+    validate_values: bool,
+    // Deprecated keys from `definition`, and the ones already reported.
+    deprecations: HashMap<String, KeyDeprecation>,
+    reported_deprecations: Mutex<HashSet<String>>,
+    deprecation_handler: Option<DeprecationHandler>,
+    // Keys redacted by `get_all`, `explain` and change events.
+    secret_keys: HashSet<String>,
+    // Every getter call, when `with_access_recording` is on.
+    access_log: Option<Mutex<Vec<KeyAccess>>>,
 }
 
+type DeprecationHandler = Arc<dyn Fn(&KeyDeprecation) + Send + Sync>;
+
 impl ConfigManager {
     /// Create a new manager with default settings.
     pub fn new() -> Self {
         Self {
-            inner: RwLock::new(ManagerInner {
-                initialized: false,
-                config: HashMap::new(),
-                public_cache: HashMap::new(),
-                secret_cache: HashMap::new(),
-                feature_flag_cache: HashMap::new(),
-            }),
+            inner: RwLock::new(ManagerInner::default()),
             schema_keys: None,
             env_prefix: String::new(),
             schema_types: None,
             cache_ttl: Duration::from_secs(DEFAULT_TTL_SECS),
+            cache_ttl_jitter: 0.0,
+            max_tenants: DEFAULT_MAX_TENANTS,
+            builtin_keys: true,
+            auto_detect_local: false,
             env_override: None,
             api_key: None,
+            api_key_file: None,
+            #[cfg(feature = "keychain")]
+            keychain_entry: None,
+            stored_api_key: Mutex::new(None),
             base_url: None,
             org_id: None,
             environment: None,
+            request_timeout: None,
+            #[cfg(feature = "tls-pinning")]
+            tls_pins: Vec::new(),
+            http_client: OnceLock::new(),
             deferred: HashMap::new(),
             schema_path: None,
             strict_schema_keys: false,
+            required_keys: Vec::new(),
+            definition: None,
+            sources: Vec::new(),
+            local_only: false,
+            fixed_values: None,
+            merge_rules: MergeRules::default(),
+            overrides: HashMap::new(),
+            pushed_overrides: Mutex::new(Vec::new()),
+            next_override_id: AtomicU64::new(0),
+            last_known_good: None,
+            disk_key: None,
+            access_policy: None,
+            lock_secrets: false,
+            #[cfg(feature = "snapshot")]
+            snapshot: None,
+            metrics: None,
+            changes: broadcast::channel(CHANGE_CHANNEL_CAPACITY).0,
+            known_secrets: RwLock::new(Vec::new()),
+            #[cfg(feature = "jsonschema")]
+            validate_values: false,
+            deprecations: HashMap::new(),
+            reported_deprecations: Mutex::new(HashSet::new()),
+            deprecation_handler: None,
+            secret_keys: HashSet::new(),
+            access_log: None,
+        }
+    }
+
+    /// Create a manager with [`LocalConfigManager`](crate::LocalConfigManager) semantics.
+    ///
+    /// Only config files and env vars are read — registered sources and the
+    /// remote API are ignored. A file value beats an env var for the same
+    /// top-level key (the opposite of the unified order), and errors loading
+    /// config files are returned instead of falling back to an empty config.
+    pub fn local_only() -> Self {
+        Self {
+            local_only: true,
+            ..Self::new()
+        }
+    }
+
+    /// Create a manager whose config is exactly `values`.
+    ///
+    /// Nothing is read from files, env vars, registered sources or the remote
+    /// API, and there are no built-in keys; deferred values, overrides,
+    /// required keys and the schema still apply. For unit tests and for
+    /// embedding where config is assembled in code.
+    pub fn from_values(values: HashMap<String, Value>) -> Self {
+        Self {
+            fixed_values: Some(values),
+            ..Self::new()
+        }
+    }
+
+    /// Choose how config layers are merged (default [`MergeStrategy::ReplaceArrays`]).
+    ///
+    /// With [`MergeStrategy::JsonMergePatch`] a `null` in a higher layer (e.g.
+    /// `{"FEATURE": null}` in `production.json` or from the remote API)
+    /// removes the key instead of storing `null`.
+    pub fn with_merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_rules.default = strategy;
+        self
+    }
+
+    /// Merge the value at a dot-separated key path (and everything beneath it)
+    /// with `strategy`, overriding [`with_merge_strategy`](Self::with_merge_strategy)
+    /// there. E.g. `.with_merge_strategy_at("CORS_ORIGINS", MergeStrategy::UniqueAppend)`
+    /// lets an environment overlay add one origin without repeating the rest.
+    pub fn with_merge_strategy_at(mut self, path: &str, strategy: MergeStrategy) -> Self {
+        self.merge_rules = self.merge_rules.with_path(path, strategy);
+        self
+    }
+
+    /// Limit how deeply values from sources and the remote API may nest
+    /// (default [`DEFAULT_MAX_MERGE_DEPTH`](crate::merge::DEFAULT_MAX_MERGE_DEPTH)).
+    /// Deeper keys are skipped with a warning rather than merged.
+    pub fn with_max_merge_depth(mut self, max_depth: usize) -> Self {
+        self.merge_rules.max_depth = max_depth;
+        self
+    }
+
+    /// Layer `overrides` above every other source, deferred resolvers included.
+    ///
+    /// Each override replaces the whole top-level value for its key. Handy in
+    /// integration tests, where it avoids mutating the process env.
+    pub fn with_overrides(mut self, overrides: HashMap<String, Value>) -> Self {
+        self.overrides.extend(overrides);
+        self
+    }
+
+    /// Persist every successful remote fetch to `path`, and fall back to that
+    /// snapshot when the remote API is unreachable and the snapshot is no
+    /// older than `max_age`.
+    ///
+    /// The snapshot takes the remote layer's place, so files, sources and env
+    /// still apply around it. It holds secrets in plaintext unless
+    /// [`with_disk_encryption`](Self::with_disk_encryption) is set; see
+    /// [`crate::last_known_good`].
+    pub fn with_last_known_good(mut self, path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        self.last_known_good = Some(LastKnownGood::new(path, max_age));
+        self
+    }
+
+    /// Encrypt secret values in the [last-known-good](Self::with_last_known_good)
+    /// and snapshot files with envelope encryption under `key`; public values
+    /// stay readable. See [`crate::at_rest`].
+    pub fn with_disk_encryption(mut self, key: impl KeyEncryptionKey + 'static) -> Self {
+        self.disk_key = Some(Arc::new(key));
+        self
+    }
+
+    /// Refuse reads the policy doesn't permit with
+    /// [`SmooaiConfigErrorKind::AccessDenied`](crate::SmooaiConfigErrorKind::AccessDenied),
+    /// and leave those keys out of [`get_all`](Self::get_all), namespace
+    /// listings and change events. Use it on a manager handed to code that
+    /// shouldn't see every secret. See [`crate::policy`].
+    pub fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = Some(policy);
+        self
+    }
+
+    /// Keep cached secret-tier values in their own memory pages, locked out
+    /// of swap and core dumps where the OS allows, and zeroize them when they
+    /// are evicted, invalidated or dropped. Each cache hit decodes a fresh
+    /// copy rather than sharing one, so it costs a little more than a plain hit.
+    ///
+    /// The merged config, its per-layer values, the change-event baseline and
+    /// the per-environment and per-tenant views also hold every value. They
+    /// aren't locked, but are zeroized when replaced by a reload, invalidated
+    /// or dropped. Values returned to callers and transient copies made while
+    /// loading (HTTP bodies, file contents) are not covered.
+    pub fn with_locked_secrets(mut self) -> Self {
+        self.lock_secrets = true;
+        if let Ok(inner) = self.inner.get_mut() {
+            inner.wipe_values = true;
+        }
+        self
+    }
+
+    /// Whether the access policy lets callers see `key` at all: from its
+    /// declared tier, or from any tier when the schema doesn't declare it.
+    fn readable(&self, key: &str) -> bool {
+        let Some(ref policy) = self.access_policy else {
+            return true;
+        };
+        match self.definition.as_ref().and_then(|d| d.declared_tier(key)) {
+            Some(tier) => policy.permits(tier, key),
+            None => policy.permits_any(key),
         }
     }
 
+    fn at_rest(&self) -> Option<AtRestEncryption> {
+        self.disk_key
+            .as_ref()
+            .map(|key| AtRestEncryption::new(Arc::clone(key), self.secret_keys.clone()))
+    }
+
+    /// Write the merged config to a binary snapshot at `path` after every
+    /// successful load, for [`init_from_snapshot`](Self::init_from_snapshot)
+    /// to start from on the next cold start. Snapshots older than `max_age`
+    /// are ignored. See [`crate::snapshot`].
+    #[cfg(feature = "snapshot")]
+    pub fn with_snapshot(mut self, path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        self.snapshot = Some(ConfigSnapshot::new(path, max_age));
+        self
+    }
+
+    /// Report cache hits/misses and fetch latency/failures to `recorder`.
+    ///
+    /// See [`crate::metrics_recorder`] for the events and the `metrics` crate bridge.
+    pub fn with_metrics(mut self, recorder: impl MetricsRecorder + 'static) -> Self {
+        self.metrics = Some(Arc::new(recorder));
+        self
+    }
+
     /// Set the schema file path used in the `UndefinedKey` error message.
     pub fn with_schema_path(mut self, path: &str) -> Self {
         self.schema_path = Some(path.to_string());
@@ -105,6 +567,73 @@ impl ConfigManager {
         self
     }
 
+    /// Declare keys that must be present (and non-null) once all sources are merged.
+    ///
+    /// Initialization — [`Self::init`] or the first getter call — fails with
+    /// [`SmooaiConfigErrorKind::MissingRequiredKeys`](crate::utils::SmooaiConfigErrorKind::MissingRequiredKeys)
+    /// listing every missing key at once. A key is satisfied by either its
+    /// declared spelling or its UPPER_SNAKE_CASE form, so
+    /// `with_required_keys(definition.required_keys())` works for camelCase schemas.
+    pub fn with_required_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.required_keys.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Attach the schema definition and enforce tiers on every getter.
+    ///
+    /// Once set, `get_secret_config("API_URL")` for a key the schema declares
+    /// only as public fails with [`SmooaiConfigErrorKind::WrongTier`](crate::SmooaiConfigErrorKind::WrongTier),
+    /// matching the TypeScript SDK. Keys absent from every tier behave as before.
+    ///
+    /// Also adds the schema's [`schema_keys`](ConfigDefinition::schema_keys) and
+    /// [`schema_types`](ConfigDefinition::schema_types), so declared keys are
+    /// read from env and coerced without hand-maintained lists. Keys and types
+    /// already set via `with_schema_keys` / `with_schema_types` are kept.
+    /// Reading a key the schema marks deprecated is reported once; see
+    /// [`with_deprecation_handler`](Self::with_deprecation_handler). Values of
+    /// its [`secret_keys`](ConfigDefinition::secret_keys) are redacted by
+    /// [`get_all`](Self::get_all), [`explain`](Self::explain) and change events.
+    pub fn with_definition(mut self, definition: ConfigDefinition) -> Self {
+        self.schema_keys
+            .get_or_insert_with(HashSet::new)
+            .extend(definition.schema_keys());
+        let types = self.schema_types.get_or_insert_with(HashMap::new);
+        for (key, ty) in definition.schema_types() {
+            types.entry(key).or_insert(ty);
+        }
+        self.deprecations = definition.deprecations();
+        self.secret_keys = definition.secret_keys();
+        self.definition = Some(definition);
+        self
+    }
+
+    /// Report the first read of each deprecated key (see [`crate::deprecation`])
+    /// to `handler` instead of stderr.
+    pub fn with_deprecation_handler(mut self, handler: impl Fn(&KeyDeprecation) + Send + Sync + 'static) -> Self {
+        self.deprecation_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Validate the merged config against the [`with_definition`](Self::with_definition)
+    /// schema when it loads, failing with
+    /// [`SmooaiConfigErrorKind::InvalidValues`](crate::SmooaiConfigErrorKind::InvalidValues)
+    /// listing every violation (feature `jsonschema`). No-op without a definition.
+    #[cfg(feature = "jsonschema")]
+    pub fn with_value_validation(mut self) -> Self {
+        self.validate_values = true;
+        self
+    }
+
+    /// Log every getter call for test assertions. See [`crate::access`].
+    pub fn with_access_recording(mut self) -> Self {
+        self.access_log = Some(Mutex::new(Vec::new()));
+        self
+    }
+
     // Remote API builder methods
 
     /// Set the API key for remote config fetching.
@@ -113,6 +642,25 @@ impl ConfigManager {
         self
     }
 
+    /// Read the API key from `path` (surrounding whitespace trimmed) on first
+    /// use, so it needn't sit in an env var that anything able to read
+    /// `/proc/<pid>/environ` can see. `SMOOAI_CONFIG_API_KEY_FILE` does the
+    /// same from the environment. An explicit [`with_api_key`](Self::with_api_key) wins.
+    pub fn with_api_key_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.api_key_file = Some(path.into());
+        self
+    }
+
+    /// Look the API key up on first use in the OS keychain (macOS Keychain,
+    /// Secret Service on Linux, Windows Credential Manager) under
+    /// `service` / `account`. Checked after the key file; falls back to
+    /// `SMOOAI_CONFIG_API_KEY` when the entry is missing.
+    #[cfg(feature = "keychain")]
+    pub fn with_api_key_from_keychain(mut self, service: &str, account: &str) -> Self {
+        self.keychain_entry = Some((service.to_string(), account.to_string()));
+        self
+    }
+
     /// Set the base URL for the remote config API.
     pub fn with_base_url(mut self, url: &str) -> Self {
         self.base_url = Some(url.to_string());
@@ -131,6 +679,21 @@ impl ConfigManager {
         self
     }
 
+    /// Give up on a remote fetch after `timeout`. The fetch then counts as
+    /// failed, same as a network error.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Only trust the config API when its certificate chain matches one of
+    /// `pins`, on top of normal CA validation. See [`crate::tls_pinning`].
+    #[cfg(feature = "tls-pinning")]
+    pub fn with_tls_pins(mut self, pins: impl IntoIterator<Item = CertificatePin>) -> Self {
+        self.tls_pins = pins.into_iter().collect();
+        self
+    }
+
     // Local config builder methods
 
     /// Set schema keys for env config filtering.
@@ -157,6 +720,41 @@ impl ConfigManager {
         self
     }
 
+    /// Randomly spread cache-entry and source-refresh expirations by up to
+    /// `fraction` of their TTL in either direction (`0.1` = ±10%), so a fleet
+    /// deployed together doesn't refetch in lockstep. Off (`0.0`) by default;
+    /// a NaN or infinite `fraction` also turns it off.
+    pub fn with_cache_ttl_jitter(mut self, fraction: f64) -> Self {
+        self.cache_ttl_jitter = jitter_fraction(fraction);
+        self
+    }
+
+    /// Cache override layers for at most `max` tenants (256 by default),
+    /// evicting the least recently read first. See
+    /// [`get_public_config_for_tenant`](Self::get_public_config_for_tenant).
+    pub fn with_max_tenants(mut self, max: usize) -> Self {
+        self.max_tenants = max.max(1);
+        self
+    }
+
+    /// Whether to set the built-in keys `ENV`, `IS_LOCAL`, `REGION` and
+    /// `CLOUD_PROVIDER` (on by default). They override files, the remote API
+    /// and env vars, so an application that defines its own `ENV` should turn
+    /// them off rather than have its value replaced.
+    pub fn with_builtin_keys(mut self, enabled: bool) -> Self {
+        self.builtin_keys = enabled;
+        self
+    }
+
+    /// When `IS_LOCAL` isn't set, guess it: a debug build, with no cloud
+    /// provider detected, whose config directory sits in a git checkout is
+    /// treated as `IS_LOCAL=true`, turning on the `local.json` layer. An
+    /// explicit `IS_LOCAL` (including `false`) always wins. Off by default.
+    pub fn with_auto_detect_local(mut self, enabled: bool) -> Self {
+        self.auto_detect_local = enabled;
+        self
+    }
+
     /// Override environment variables (for testing).
     pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
         self.env_override = Some(env);
@@ -173,10 +771,191 @@ impl ConfigManager {
         self
     }
 
+    /// Register an additional config source (SSM, Vault, ...).
+    ///
+    /// Sources merge in registration order above config files and below the
+    /// remote API and env vars. A source that fails to load is skipped with a
+    /// warning. See [`crate::source`].
+    pub fn with_source<S: ConfigSource + 'static>(mut self, source: S) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Register built-in deferred resolvers for `DATABASE_URL`, `REDIS_URL` and
+    /// `AMQP_URL`, assembled from the nested `DATABASE`, `REDIS` and `AMQP`
+    /// objects (HOST, PORT, USER, PASSWORD, DB).
+    ///
+    /// Resolvers already registered for those keys via [`Self::with_deferred`]
+    /// are left alone. See [`crate::standard_urls`] for the exact rules.
+    pub fn with_standard_urls(mut self) -> Self {
+        for url in STANDARD_URLS {
+            self.deferred
+                .entry(url.target.to_string())
+                .or_insert_with(|| url.resolver());
+        }
+        self
+    }
+
+    /// Get (or create) the process-wide manager for `profile_name` using the
+    /// default configuration. Equivalent to `ConfigManager::new().into_shared(profile_name)`.
+    pub fn shared(profile_name: &str) -> Arc<Self> {
+        Self::new().into_shared(profile_name)
+    }
+
+    /// Register this manager in the process-level registry and return the shared handle.
+    ///
+    /// Managers are deduplicated by `profile_name` plus a fingerprint of their
+    /// configuration (credentials, environment, schema, env overrides, TTL, ...).
+    /// If an equivalent manager is already registered, `self` is dropped and the
+    /// existing one is returned, so every caller shares one snapshot and one
+    /// remote fetch. Registering a profile with a *different* configuration
+    /// creates a separate manager and prints a warning, since two views of the
+    /// same profile usually means two libraries disagree about setup.
+    ///
+    /// A manager with a metrics recorder, a deprecation handler or a source
+    /// whose [`ConfigSource::fingerprint`] is `None` can't be compared, so it
+    /// is never deduplicated: it's returned as a new, unregistered handle.
+    pub fn into_shared(self, profile_name: &str) -> Arc<Self> {
+        let Some(fingerprint) = self.share_key() else {
+            return Arc::new(self);
+        };
+        let mut guard = match SHARED_MANAGERS.lock() {
+            Ok(guard) => guard,
+            // A poisoned registry shouldn't take config down with it — fall
+            // back to an unshared manager.
+            Err(_) => return Arc::new(self),
+        };
+        let registry = guard.get_or_insert_with(HashMap::new);
+
+        let key = (profile_name.to_string(), fingerprint);
+        if let Some(existing) = registry.get(&key) {
+            return Arc::clone(existing);
+        }
+
+        if registry.keys().any(|(profile, _)| profile == profile_name) {
+            self.warn(format_args!(
+                "shared profile '{}' registered with a different configuration; \
+                 creating a separate manager",
+                profile_name
+            ));
+        }
+
+        let manager = Arc::new(self);
+        registry.insert(key, Arc::clone(&manager));
+        manager
+    }
+
+    /// Stable hash of everything that influences what this manager resolves.
+    ///
+    /// Deferred resolvers are closures and can't be compared, so only their
+    /// keys participate. The API key is hashed, never stored.
+    fn fingerprint(&self) -> u64 {
+        fn sorted<V: Clone>(map: &HashMap<String, V>) -> BTreeMap<String, V> {
+            map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        }
+
+        let mut hasher = DefaultHasher::new();
+        let mut schema_keys: Option<Vec<&String>> = self.schema_keys.as_ref().map(|keys| keys.iter().collect());
+        if let Some(ref mut keys) = schema_keys {
+            keys.sort();
+        }
+        schema_keys.hash(&mut hasher);
+        self.env_prefix.hash(&mut hasher);
+        self.schema_types.as_ref().map(sorted).hash(&mut hasher);
+        self.cache_ttl.hash(&mut hasher);
+        self.cache_ttl_jitter.to_bits().hash(&mut hasher);
+        self.max_tenants.hash(&mut hasher);
+        self.builtin_keys.hash(&mut hasher);
+        self.auto_detect_local.hash(&mut hasher);
+        self.env_override.as_ref().map(sorted).hash(&mut hasher);
+        self.api_key.hash(&mut hasher);
+        self.api_key_file.hash(&mut hasher);
+        #[cfg(feature = "keychain")]
+        self.keychain_entry.hash(&mut hasher);
+        self.base_url.hash(&mut hasher);
+        self.org_id.hash(&mut hasher);
+        self.environment.hash(&mut hasher);
+        let mut deferred_keys: Vec<&String> = self.deferred.keys().collect();
+        deferred_keys.sort();
+        deferred_keys.hash(&mut hasher);
+        self.schema_path.hash(&mut hasher);
+        self.strict_schema_keys.hash(&mut hasher);
+        self.required_keys.hash(&mut hasher);
+        self.local_only.hash(&mut hasher);
+        self.fixed_values
+            .as_ref()
+            .map(|values| {
+                sorted(values)
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .hash(&mut hasher);
+        #[cfg(feature = "jsonschema")]
+        self.validate_values.hash(&mut hasher);
+        self.merge_rules.hash(&mut hasher);
+        self.last_known_good.hash(&mut hasher);
+        self.disk_key.as_ref().map(|key| key.key_id()).hash(&mut hasher);
+        self.access_policy.hash(&mut hasher);
+        self.lock_secrets.hash(&mut hasher);
+        #[cfg(feature = "tls-pinning")]
+        self.tls_pins.hash(&mut hasher);
+        #[cfg(feature = "snapshot")]
+        self.snapshot.hash(&mut hasher);
+        sorted(&self.overrides)
+            .iter()
+            .map(|(k, v)| (k, v.to_string()))
+            .collect::<Vec<_>>()
+            .hash(&mut hasher);
+        self.sources
+            .iter()
+            .map(|s| (s.name(), s.fingerprint()))
+            .collect::<Vec<_>>()
+            .hash(&mut hasher);
+        self.definition
+            .as_ref()
+            .map(|d| serde_json::to_string(d).unwrap_or_default())
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// [`fingerprint`](Self::fingerprint) plus the settings that change
+    /// behaviour but not values, for [`into_shared`](Self::into_shared).
+    /// `None` when something can't be compared: a source without a
+    /// fingerprint, a metrics recorder or a deprecation handler.
+    fn share_key(&self) -> Option<u64> {
+        if self.metrics.is_some()
+            || self.deprecation_handler.is_some()
+            || self.sources.iter().any(|s| s.fingerprint().is_none())
+        {
+            return None;
+        }
+        let mut hasher = DefaultHasher::new();
+        self.fingerprint().hash(&mut hasher);
+        self.request_timeout.hash(&mut hasher);
+        self.access_log.is_some().hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     fn get_env(&self) -> HashMap<String, String> {
         self.env_override.clone().unwrap_or_else(|| std::env::vars().collect())
     }
 
+    /// The env that config loads from: [`get_env`](Self::get_env) plus a
+    /// detected `IS_LOCAL` under [`with_auto_detect_local`](Self::with_auto_detect_local).
+    fn load_env(&self) -> HashMap<String, String> {
+        let mut env = self.get_env();
+        if self.auto_detect_local && !env.contains_key("IS_LOCAL") {
+            let start = find_config_directory_with_env(false, &env)
+                .map(PathBuf::from)
+                .or_else(|_| std::env::current_dir());
+            if start.is_ok_and(|start| detect_local_with_env(&env, &start)) {
+                env.insert("IS_LOCAL".to_string(), "true".to_string());
+            }
+        }
+        env
+    }
+
     fn get_env_var(&self, key: &str) -> Option<String> {
         if let Some(ref env) = self.env_override {
             env.get(key).cloned()
@@ -185,6 +964,16 @@ impl ConfigManager {
         }
     }
 
+    /// `ENV`, `IS_LOCAL`, `REGION` and `CLOUD_PROVIDER` for `env`, unless
+    /// turned off with [`with_builtin_keys`](Self::with_builtin_keys).
+    fn builtin_keys(&self, env: &HashMap<String, String>) -> HashMap<String, Value> {
+        if self.builtin_keys {
+            builtin_keys_with_env(env)
+        } else {
+            HashMap::new()
+        }
+    }
+
     fn resolve_environment(&self) -> String {
         if let Some(ref env) = self.environment {
             return env.clone();
@@ -195,6 +984,18 @@ impl ConfigManager {
         "development".to_string()
     }
 
+    fn record_fetch(&self, source: &str, started: Instant, success: bool) {
+        if let Some(ref metrics) = self.metrics {
+            let environment = self.resolve_environment();
+            let org_id = self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id);
+            let context = FetchContext {
+                environment: &environment,
+                org_id: org_id.as_deref(),
+            };
+            metrics.fetch_in(source, started.elapsed(), success, &context);
+        }
+    }
+
     fn resolve_param(&self, env_var: &str, constructor_value: &Option<String>) -> Option<String> {
         // Constructor value takes precedence
         if let Some(ref val) = constructor_value {
@@ -204,91 +1005,686 @@ impl ConfigManager {
         self.get_env_var(env_var)
     }
 
+    /// The API key: set explicitly, else from the key file or keychain, else
+    /// `SMOOAI_CONFIG_API_KEY`.
+    fn resolve_api_key(&self) -> Option<String> {
+        if let Some(ref key) = self.api_key {
+            return Some(key.clone());
+        }
+        let stored = match self.stored_api_key.lock() {
+            Ok(mut stored) => stored.get_or_insert_with(|| self.read_stored_api_key()).clone(),
+            Err(_) => self.read_stored_api_key(),
+        };
+        stored.or_else(|| self.get_env_var("SMOOAI_CONFIG_API_KEY"))
+    }
+
+    /// Drop the key read from the key file or keychain so the next fetch
+    /// reads it again.
+    fn forget_stored_api_key(&self) {
+        if let Ok(mut stored) = self.stored_api_key.lock() {
+            *stored = None;
+        }
+    }
+
+    fn read_stored_api_key(&self) -> Option<String> {
+        // Warnings here go through `redact::warn` directly: `self.warn`
+        // resolves the API key, which would re-enter `stored_api_key`.
+        let path = self
+            .api_key_file
+            .clone()
+            .or_else(|| self.get_env_var("SMOOAI_CONFIG_API_KEY_FILE").map(PathBuf::from));
+        if let Some(path) = path {
+            match std::fs::read_to_string(&path) {
+                Ok(contents) if !contents.trim().is_empty() => return Some(contents.trim().to_string()),
+                Ok(_) => redact::warn(format_args!("API key file {} is empty", path.display())),
+                Err(e) => redact::warn(format_args!("Failed to read API key file {}: {}", path.display(), e)),
+            }
+        }
+        #[cfg(feature = "keychain")]
+        if let Some((ref service, ref account)) = self.keychain_entry {
+            match keyring::Entry::new(service, account).and_then(|entry| entry.get_password()) {
+                Ok(key) => return Some(key),
+                Err(e) => redact::warn(format_args!(
+                    "Failed to read API key from keychain entry {}/{}: {}",
+                    service, account, e
+                )),
+            }
+        }
+        None
+    }
+
+    /// Load and merge every layer unless already loaded. Callers hold the
+    /// write lock, which is what makes concurrent first reads single-flight.
     fn initialize_inner(&self, inner: &mut ManagerInner) -> Result<(), SmooaiConfigError> {
         if inner.initialized {
             return Ok(());
         }
+        if let Some((ref error, failed_at)) = inner.failed_init {
+            if failed_at.elapsed() < INIT_RETRY_BACKOFF {
+                return Err(error.clone());
+            }
+        }
+        inner.wipe_loaded();
+        let result = self
+            .load_inner(inner)
+            .map_err(|e| e.redact_values(self.known_secrets().iter().map(String::as_str)));
+        inner.failed_init = result.as_ref().err().map(|e| (e.clone(), Instant::now()));
+        if result.is_ok() {
+            #[cfg(feature = "snapshot")]
+            self.save_snapshot(&inner.config);
+            self.publish_changes(inner);
+        }
+        result
+    }
 
-        let env = self.get_env();
-
-        // 1. Load file config (graceful fallback on error)
-        let file_config = find_and_process_file_config_with_env(&env).unwrap_or_default();
+    /// Fingerprint a snapshot must carry to be used: the manager's own plus
+    /// the environment and credentials it would resolve from env vars.
+    #[cfg(feature = "snapshot")]
+    fn snapshot_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.fingerprint().hash(&mut hasher);
+        self.resolve_environment().hash(&mut hasher);
+        self.resolve_api_key().hash(&mut hasher);
+        self.resolve_param("SMOOAI_CONFIG_API_URL", &self.base_url)
+            .hash(&mut hasher);
+        self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id)
+            .hash(&mut hasher);
+        self.get_env_var("SMOOAI_ENV_CONFIG_DIR").hash(&mut hasher);
+        hasher.finish()
+    }
 
-        // 2. Load env config
-        let schema_keys = self.schema_keys.clone().unwrap_or_default();
-        let env_config =
-            find_and_process_env_config_with_env(&schema_keys, &self.env_prefix, self.schema_types.as_ref(), &env);
+    #[cfg(feature = "snapshot")]
+    fn save_snapshot(&self, config: &HashMap<String, Value>) {
+        if let Some(ref snapshot) = self.snapshot {
+            if let Err(e) = snapshot.save(self.snapshot_fingerprint(), config, self.at_rest().as_ref()) {
+                self.warn(e);
+            }
+        }
+    }
 
-        // 3. Remote fetch if credentials available
-        let mut remote_config: HashMap<String, Value> = HashMap::new();
-        let api_key = self.resolve_param("SMOOAI_CONFIG_API_KEY", &self.api_key);
-        let base_url = self.resolve_param("SMOOAI_CONFIG_API_URL", &self.base_url);
-        let org_id = self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id);
+    /// Serve the [snapshot](Self::with_snapshot) if a fresh one exists and
+    /// reload from the real sources on a background thread; otherwise
+    /// [`init`](Self::init) as usual.
+    ///
+    /// Returns the refresh thread when the snapshot was used. Until it
+    /// finishes, reads see the snapshot; afterwards they see the reloaded
+    /// config, and subscribers get a [`ConfigChange`] for every key that
+    /// differed. If the reload fails, the snapshot stays in place until the
+    /// next [`invalidate`](Self::invalidate).
+    #[cfg(feature = "snapshot")]
+    pub fn init_from_snapshot(self: &Arc<Self>) -> Result<Option<JoinHandle<()>>, SmooaiConfigError> {
+        let Some(values) = self
+            .snapshot
+            .as_ref()
+            .and_then(|snapshot| snapshot.load(self.snapshot_fingerprint(), self.at_rest().as_ref()))
+        else {
+            return self.init().map(|()| None);
+        };
+        {
+            let mut inner = self
+                .inner
+                .write()
+                .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+            if inner.initialized {
+                return Ok(None);
+            }
+            status::record(&mut inner.health, "snapshot", Ok(values.len()));
+            inner.wipe_loaded();
+            inner.layers = vec![(LayerKind::Snapshot, values.clone())];
+            inner.config = values;
+            inner.initialized = true;
+            inner.failed_init = None;
+            self.publish_changes(&mut inner);
+        }
+        let manager = Arc::clone(self);
+        Ok(Some(std::thread::spawn(move || manager.reload_behind_snapshot())))
+    }
 
-        if let (Some(ref api_key), Some(ref base_url), Some(ref org_id)) = (&api_key, &base_url, &org_id) {
-            let env_name = self.resolve_environment();
-            let url = format!(
-                "{}/organizations/{}/config/values?environment={}",
-                base_url.trim_end_matches('/'),
-                org_id,
-                env_name
-            );
+    /// Load every layer without holding the lock, so reads keep being served
+    /// from the snapshot, then swap the result in.
+    #[cfg(feature = "snapshot")]
+    fn reload_behind_snapshot(&self) {
+        let mut fresh = ManagerInner::default();
+        if let Err(e) = self.load_inner(&mut fresh) {
+            self.warn(format_args!("Reload after snapshot failed: {}", e));
+            return;
+        }
+        self.save_snapshot(&fresh.config);
+        let Ok(mut inner) = self.inner.write() else {
+            return;
+        };
+        inner.wipe_loaded();
+        inner.config = std::mem::take(&mut fresh.config);
+        inner.layers = std::mem::take(&mut fresh.layers);
+        inner.refresh_at = fresh.refresh_at;
+        inner.health = std::mem::take(&mut fresh.health);
+        inner.initialized = true;
+        inner.failed_init = None;
+        inner.clear_caches();
+        inner.stale_keys.clear();
+        self.publish_changes(&mut inner);
+    }
 
-            let client = reqwest::blocking::Client::new();
-            match client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", api_key))
-                .send()
-            {
-                Ok(resp) if resp.status().is_success() => {
-                    if let Ok(body) = resp.json::<Value>() {
-                        if let Some(values) = body.get("values").and_then(|v| v.as_object()) {
-                            for (k, v) in values {
-                                remote_config.insert(k.clone(), v.clone());
-                            }
-                        }
+    /// Send a [`ConfigChange`] for every key that differs from the last
+    /// published config. The first load only records the baseline.
+    fn publish_changes(&self, inner: &mut ManagerInner) {
+        inner.path_index = None;
+        if let Some(previous) = inner.published.take() {
+            if self.changes.receiver_count() > 0 {
+                let env = self.get_env();
+                let known = self.known_secrets();
+                for key in changed_keys(&previous, &inner.config) {
+                    if !self.readable(&key) {
+                        continue;
                     }
+                    let new = inner.config.get(&key).cloned();
+                    let source = new.as_ref().and_then(|_| {
+                        inner
+                            .layers
+                            .iter()
+                            .rev()
+                            .find(|(_, values)| values.contains_key(&key))
+                            .map(|(kind, _)| self.layer_source(kind, &key, &env))
+                    });
+                    let _ = self.changes.send(ConfigChange {
+                        old: previous.get(&key).map(|v| self.redact(&key, v.clone(), &known)),
+                        new: new.map(|v| self.redact(&key, v, &known)),
+                        source,
+                        key,
+                    });
+                }
+            }
+            inner.discard(previous);
+        }
+        inner.published = Some(inner.config.clone());
+    }
+
+    /// Receive a [`ConfigChange`] for every key that changes when config is
+    /// reloaded. See [`crate::changes`].
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChange> {
+        self.changes.subscribe()
+    }
+
+    fn load_inner(&self, inner: &mut ManagerInner) -> Result<(), SmooaiConfigError> {
+        if let Some(ref values) = self.fixed_values {
+            inner.config = values.clone();
+            return self.finish_load(inner, vec![(LayerKind::Values, values.clone())]);
+        }
+        let env = self.load_env();
+
+        // 1. Load file config (graceful fallback on error, except in local-only mode)
+        let mut layers: Vec<(LayerKind, HashMap<String, Value>)> = Vec::new();
+        let builtins = self.builtin_keys(&env);
+        let mut file_config: HashMap<String, Value> = HashMap::new();
+        let file_layers = match load_file_layers_with_env(&env) {
+            Ok(file_layers) => Some(file_layers),
+            Err(e) => {
+                status::record(&mut inner.health, "files", Err(e.to_string()));
+                if self.local_only {
+                    return Err(e);
+                }
+                None
+            }
+        };
+        if let Some(file_layers) = file_layers {
+            let mut merged = Value::Object(Default::default());
+            for layer in file_layers {
+                self.merge_rules.merge_into(&mut merged, &layer.values);
+                if let Value::Object(map) = layer.values {
+                    layers.push((LayerKind::File(layer.file_name), map.into_iter().collect()));
                 }
-                Ok(resp) => {
-                    eprintln!(
-                        "[Smooai Config] Warning: Remote config fetch returned HTTP {}",
-                        resp.status()
-                    );
+            }
+            if let Value::Object(map) = merged {
+                file_config = map.into_iter().collect();
+            }
+            status::record(&mut inner.health, "files", Ok(file_config.len()));
+            file_config.extend(builtins.clone());
+        }
+
+        // 2. Load registered sources (graceful fallback on error)
+        let mut source_configs: Vec<HashMap<String, Value>> = Vec::new();
+        let sources: &[Box<dyn ConfigSource>] = if self.local_only { &[] } else { &self.sources };
+        for source in sources {
+            let started = Instant::now();
+            let loaded = source.load(&env);
+            self.record_fetch(&source.name(), started, loaded.is_ok());
+            match loaded {
+                Ok(values) => {
+                    status::record(&mut inner.health, &source.name(), Ok(values.len()));
+                    layers.push((LayerKind::Source(source.name()), values.clone()));
+                    source_configs.push(values);
                 }
                 Err(e) => {
-                    eprintln!("[Smooai Config] Warning: Failed to fetch remote config: {}", e);
+                    status::record(&mut inner.health, &source.name(), Err(e.to_string()));
+                    self.warn(format_args!("Failed to load config source {}: {}", source.name(), e));
                 }
             }
         }
+        // Asked after loading so lease-based sources can report what they got.
+        inner.refresh_at = sources
+            .iter()
+            .filter_map(|s| s.ttl())
+            .min()
+            .and_then(|ttl| Instant::now().checked_add(jittered_ttl(ttl, self.cache_ttl_jitter)));
+
+        // 3. Load env config
+        let schema_keys = self.schema_keys.clone().unwrap_or_default();
+        let mut env_config =
+            process_env_vars_with_env(&schema_keys, &self.env_prefix, self.schema_types.as_ref(), &env);
+        env_config.extend(builtins.clone());
 
-        // 4. Merge: file < remote < env (lowest to highest precedence)
-        let file_value = serde_json::to_value(&file_config).unwrap_or(Value::Object(Default::default()));
-        let remote_value = serde_json::to_value(&remote_config).unwrap_or(Value::Object(Default::default()));
-        let env_value = serde_json::to_value(&env_config).unwrap_or(Value::Object(Default::default()));
+        // 4. Remote fetch if credentials available (never in local-only mode)
+        let mut remote_config: HashMap<String, Value> = HashMap::new();
+        let mut remote_kind = LayerKind::Remote;
+        let api_key = self.resolve_api_key();
+        let base_url = self.resolve_param("SMOOAI_CONFIG_API_URL", &self.base_url);
+        let org_id = self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id);
 
-        let merged = merge_replace_arrays(&Value::Object(Default::default()), &file_value);
-        let merged = merge_replace_arrays(&merged, &remote_value);
-        let merged = merge_replace_arrays(&merged, &env_value);
+        if let (false, Some(ref api_key), Some(ref base_url), Some(ref org_id)) =
+            (self.local_only, &api_key, &base_url, &org_id)
+        {
+            let outcome = self
+                .fetch_remote(api_key, base_url, org_id, &self.resolve_environment(), None)
+                .map(|(values, server_ttl)| {
+                    // The server can ask for an earlier reload than the sources do.
+                    if let Some(at) =
+                        server_ttl.and_then(|ttl| Instant::now().checked_add(jittered_ttl(ttl, self.cache_ttl_jitter)))
+                    {
+                        inner.refresh_at = Some(inner.refresh_at.map_or(at, |current| current.min(at)));
+                    }
+                    remote_config = values;
+                    remote_config.len()
+                });
+            let fetched = outcome.is_ok();
+            status::record(&mut inner.health, "remote", outcome);
+
+            if let Some(ref lkg) = self.last_known_good {
+                if fetched {
+                    if let Err(e) = lkg.save_with(&remote_config, self.at_rest().as_ref()) {
+                        self.warn(e);
+                    }
+                } else if let Some(values) = lkg.load_with(self.at_rest().as_ref()) {
+                    self.warn(format_args!("Using last-known-good snapshot {}", lkg.path().display()));
+                    status::record(&mut inner.health, "last-known-good", Ok(values.len()));
+                    remote_config = values;
+                    remote_kind = LayerKind::LastKnownGood;
+                }
+            }
+        }
 
-        // Convert back to HashMap
-        if let Value::Object(map) = merged {
-            inner.config = map.into_iter().collect();
+        let env_only: HashMap<String, Value> = env_config
+            .iter()
+            .filter(|(k, _)| !builtins.contains_key(*k))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if self.local_only {
+            // Env sits below the files here; keep the layer list in precedence order.
+            layers.insert(0, (LayerKind::Env, env_only));
+            layers.push((LayerKind::BuiltIn, builtins));
+
+            // 5. Merge: env < file, replacing whole top-level keys (LocalConfigManager semantics)
+            let mut config = env_config;
+            config.extend(file_config);
+            inner.config = config;
+        } else {
+            layers.push((remote_kind, remote_config.clone()));
+            layers.push((LayerKind::Env, env_only));
+            layers.push((LayerKind::BuiltIn, builtins));
+            inner.config = self.merge_layers(file_config, &source_configs, &remote_config, &env_config);
         }
+        self.finish_load(inner, layers)
+    }
 
-        // 5. Resolve deferred/computed values
-        if !self.deferred.is_empty() {
-            resolve_deferred(&mut inner.config, &self.deferred);
+    /// Fetch every remote value for `environment`, or just `tenant`'s
+    /// overrides within it, with the cache TTL the response asked for.
+    fn fetch_remote(
+        &self,
+        api_key: &str,
+        base_url: &str,
+        org_id: &str,
+        environment: &str,
+        tenant: Option<&str>,
+    ) -> Result<(HashMap<String, Value>, Option<Duration>), String> {
+        let url = format!(
+            "{}/organizations/{}/config/values",
+            base_url.trim_end_matches('/'),
+            org_id
+        );
+
+        let started = Instant::now();
+        let mut request = self
+            .http_client()
+            .get(&url)
+            .query(&[("environment", environment)])
+            .header("Authorization", format!("Bearer {}", api_key));
+        if let Some(tenant) = tenant {
+            request = request.query(&[("tenant", tenant)]);
         }
+        let outcome = match request.send() {
+            Ok(resp) if resp.status().is_success() => {
+                let ttl = server_ttl(resp.headers());
+                let mut values = HashMap::new();
+                if let Ok(body) = resp.json::<Value>() {
+                    if let Some(fetched) = body.get("values").and_then(|v| v.as_object()) {
+                        for (k, v) in fetched {
+                            values.insert(k.clone(), v.clone());
+                        }
+                    }
+                }
+                Ok((values, ttl))
+            }
+            Ok(resp) => {
+                if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+                    self.forget_stored_api_key();
+                }
+                self.warn(format_args!("Remote config fetch returned HTTP {}", resp.status()));
+                Err(format!("HTTP {}", resp.status()))
+            }
+            Err(e) => {
+                self.warn(format_args!("Failed to fetch remote config: {}", e));
+                Err(e.to_string())
+            }
+        };
+        self.record_fetch("remote", started, outcome.is_ok());
+        outcome
+    }
 
-        inner.initialized = true;
-        Ok(())
+    /// How long to keep a remote layer: the server's TTL if it sent one,
+    /// else the cache TTL.
+    fn remote_ttl(&self, server_ttl: Option<Duration>) -> Duration {
+        jittered_ttl(server_ttl.unwrap_or(self.cache_ttl), self.cache_ttl_jitter)
+    }
+
+    /// The config `environment` would load: its files (and built-ins) with
+    /// its remote values on top, and how long to cache it. After a failed
+    /// remote fetch the file values are cached for [`ENV_VIEW_RETRY_BACKOFF`].
+    fn load_env_view(&self, environment: &str) -> (HashMap<String, Value>, Duration) {
+        if let Some(ref values) = self.fixed_values {
+            return (values.clone(), self.remote_ttl(None));
+        }
+        let mut env = self.load_env();
+        env.insert("SMOOAI_CONFIG_ENV".to_string(), environment.to_string());
+
+        let mut config = HashMap::new();
+        match load_file_layers_with_env(&env) {
+            Ok(file_layers) => {
+                let mut merged = Value::Object(Default::default());
+                for layer in file_layers {
+                    self.merge_rules.merge_into(&mut merged, &layer.values);
+                }
+                if let Value::Object(map) = merged {
+                    config = map.into_iter().collect();
+                }
+                config.extend(self.builtin_keys(&env));
+            }
+            Err(e) => self.warn(format_args!("Failed to load config files for {}: {}", environment, e)),
+        }
+
+        let api_key = self.resolve_api_key();
+        let base_url = self.resolve_param("SMOOAI_CONFIG_API_URL", &self.base_url);
+        let org_id = self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id);
+        let (false, Some(api_key), Some(base_url), Some(org_id)) = (self.local_only, api_key, base_url, org_id) else {
+            return (config, self.remote_ttl(None));
+        };
+        match self.fetch_remote(&api_key, &base_url, &org_id, environment, None) {
+            Ok((remote, server_ttl)) => {
+                self.merge_fetched(&mut config, &remote);
+                (config, self.remote_ttl(server_ttl))
+            }
+            Err(e) => {
+                self.warn(format_args!("Failed to fetch remote config for {}: {}", environment, e));
+                (config, ENV_VIEW_RETRY_BACKOFF)
+            }
+        }
+    }
+
+    /// Apply deferred values and overrides on top of the merged layers, then
+    /// check the result.
+    fn finish_load(
+        &self,
+        inner: &mut ManagerInner,
+        mut layers: Vec<(LayerKind, HashMap<String, Value>)>,
+    ) -> Result<(), SmooaiConfigError> {
+        // 6. Resolve deferred/computed values
+        if !self.deferred.is_empty() {
+            resolve_deferred(&mut inner.config, &self.deferred);
+            let resolved = self
+                .deferred
+                .keys()
+                .filter_map(|k| inner.config.get(k).map(|v| (k.clone(), v.clone())))
+                .collect();
+            layers.push((LayerKind::Deferred, resolved));
+        }
+
+        // 7. Overrides sit above everything
+        if !self.overrides.is_empty() {
+            inner.config.extend(self.overrides.clone());
+            layers.push((LayerKind::Override, self.overrides.clone()));
+        }
+        inner.layers = layers;
+        self.remember_secrets(&inner.config);
+
+        // 8. Fail fast on missing required keys (all of them, not just the first)
+        let missing = self.missing_required_keys(&inner.config);
+        if !missing.is_empty() {
+            inner.wipe_loaded();
+            inner.config.clear();
+            inner.layers.clear();
+            return Err(SmooaiConfigError::missing_required_keys(missing));
+        }
+
+        // 9. Optionally check values against the schema
+        #[cfg(feature = "jsonschema")]
+        if let (true, Some(definition)) = (self.validate_values, &self.definition) {
+            let violations = definition.validate_values(&inner.config)?;
+            if !violations.is_empty() {
+                inner.wipe_loaded();
+                inner.config.clear();
+                inner.layers.clear();
+                return Err(SmooaiConfigError::invalid_values(violations));
+            }
+        }
+
+        inner.initialized = true;
+        Ok(())
+    }
+
+    /// Merge: file < sources < remote < env (lowest to highest precedence).
+    fn merge_layers(
+        &self,
+        file_config: HashMap<String, Value>,
+        source_configs: &[HashMap<String, Value>],
+        remote_config: &HashMap<String, Value>,
+        env_config: &HashMap<String, Value>,
+    ) -> HashMap<String, Value> {
+        // The file layers were already merged (and their directives resolved) on load.
+        let mut merged = file_config;
+        for source_config in source_configs {
+            self.merge_fetched(&mut merged, source_config);
+        }
+        self.merge_fetched(&mut merged, remote_config);
+        self.merge_rules.merge_map_into(&mut merged, env_config);
+        merged
+    }
+
+    /// Merge a layer fetched from outside the process, skipping any key whose
+    /// value nests deeper than the merge depth limit.
+    fn merge_fetched(&self, merged: &mut HashMap<String, Value>, layer: &HashMap<String, Value>) {
+        let too_deep: HashSet<&String> = layer
+            .iter()
+            .filter(|(_, value)| self.merge_rules.check_depth(value).is_err())
+            .map(|(key, _)| key)
+            .collect();
+        if too_deep.is_empty() {
+            self.merge_rules.merge_map_into(merged, layer);
+            return;
+        }
+        for key in &too_deep {
+            self.warn(format_args!(
+                "Ignoring {}: nested deeper than the maximum merge depth of {}",
+                key, self.merge_rules.max_depth
+            ));
+        }
+        let allowed: HashMap<String, Value> = layer
+            .iter()
+            .filter(|(key, _)| !too_deep.contains(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        self.merge_rules.merge_map_into(merged, &allowed);
+    }
+
+    /// Report `key` if the schema deprecates it and this is its first read.
+    fn report_deprecation(&self, key: &str) {
+        if self.deprecations.is_empty() {
+            return;
+        }
+        let Some(deprecation) = self
+            .deprecations
+            .get(key)
+            .or_else(|| self.deprecations.get(&property_key(key)))
+        else {
+            return;
+        };
+        let first = self
+            .reported_deprecations
+            .lock()
+            .map(|mut reported| reported.insert(deprecation.key.clone()))
+            .unwrap_or(false);
+        if !first {
+            return;
+        }
+        match self.deprecation_handler {
+            Some(ref handler) => handler(deprecation),
+            None => self.warn(deprecation),
+        }
+    }
+
+    /// The newest pushed override for `key`, if any guard is active.
+    fn pushed_override(&self, key: &str) -> Option<Value> {
+        let pushed = self.pushed_overrides.lock().ok()?;
+        pushed.iter().rev().find_map(|(_, values)| values.get(key).cloned())
+    }
+
+    /// Temporarily layer `overrides` above everything, including
+    /// [`with_overrides`](Self::with_overrides), until the guard is dropped.
+    ///
+    /// Guards nest: the newest active guard wins, and dropping one (in any
+    /// order) restores whatever is beneath it. Overrides apply to every
+    /// thread reading this manager, so in concurrent tests give each test
+    /// its own manager.
+    ///
+    /// ```rust,ignore
+    /// let _guard = manager.push_overrides(HashMap::from([("NEW_CHECKOUT".into(), json!(true))]));
+    /// assert_eq!(manager.get_feature_flag("NEW_CHECKOUT")?, Some(json!(true)));
+    /// ```
+    pub fn push_overrides(&self, overrides: HashMap<String, Value>) -> OverrideGuard<'_> {
+        let id = self.next_override_id.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut pushed) = self.pushed_overrides.lock() {
+            pushed.push((id, overrides));
+        }
+        OverrideGuard { manager: self, id }
+    }
+
+    /// Drop merged state once a source TTL has lapsed so the next lookup reloads.
+    fn expire_if_due(inner: &mut ManagerInner) {
+        if inner.initialized && inner.refresh_at.is_some_and(|at| Instant::now() >= at) {
+            inner.initialized = false;
+            inner.refresh_at = None;
+            inner.clear_caches();
+        }
+    }
+
+    fn missing_required_keys(&self, config: &HashMap<String, Value>) -> Vec<String> {
+        let present = |key: &str| config.get(key).is_some_and(|v| !v.is_null());
+        self.required_keys
+            .iter()
+            .filter(|key| !present(key) && !present(&camel_to_upper_snake(key)))
+            .cloned()
+            .collect()
+    }
+
+    /// Eagerly load and merge every source.
+    ///
+    /// Optional — getters initialize lazily — but calling it at startup surfaces
+    /// configuration problems (e.g. missing required keys) before the first request.
+    pub fn init(&self) -> Result<(), SmooaiConfigError> {
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        self.initialize_inner(&mut inner)
+    }
+
+    fn check_tier(&self, key: &str, requested: ConfigTier) -> Result<(), SmooaiConfigError> {
+        let Some(ref definition) = self.definition else {
+            return Ok(());
+        };
+        if definition.declares(requested, key) {
+            return Ok(());
+        }
+        match definition.declared_tier(key) {
+            Some(declared) => Err(SmooaiConfigError::wrong_tier(key, requested, declared)),
+            None => Ok(()),
+        }
     }
 
     fn get_value(
         &self,
         key: &str,
-        cache_selector: fn(&mut ManagerInner) -> &mut HashMap<String, CacheEntry>,
-    ) -> Result<Option<Value>, SmooaiConfigError> {
+        tier: ConfigTier,
+        cache_selector: fn(&mut ManagerInner) -> &mut HashMap<Arc<str>, CacheEntry>,
+    ) -> Result<Option<Arc<Value>>, SmooaiConfigError> {
+        self.validate_key(key, tier)?;
+        if let Some(value) = self.pushed_override(key) {
+            self.record_access(tier, key, true, true);
+            return Ok(Some(Arc::new(value)));
+        }
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        Self::expire_if_due(&mut inner);
+
+        // Check cache
+        let cache = cache_selector(&mut inner);
+        if let Some(entry) = cache.get(key) {
+            if Instant::now() < entry.expires_at {
+                let value = entry.value.get();
+                inner.cache_hits += 1;
+                self.record_access(tier, key, true, true);
+                if let Some(ref metrics) = self.metrics {
+                    metrics.cache_hit(tier);
+                }
+                return Ok(Some(value));
+            }
+            cache.remove(key);
+        }
+        inner.cache_misses += 1;
+        if let Some(ref metrics) = self.metrics {
+            metrics.cache_miss(tier);
+        }
+
+        // Initialize if needed
+        self.initialize_inner(&mut inner)?;
+        if inner.stale_keys.remove(key) {
+            self.refresh_key(&mut inner, key);
+        }
+
+        // Look up in merged config
+        let value = inner.config.get(key).cloned().map(Arc::new);
+        self.record_access(tier, key, false, value.is_some());
+        if let Some(ref val) = value {
+            let key = inner.intern(key);
+            let entry = self.cache_entry(tier, val);
+            let cache = cache_selector(&mut inner);
+            cache.insert(key, entry);
+        }
+
+        Ok(value)
+    }
+
+    /// Checks every getter runs on a top-level key before reading it.
+    fn validate_key(&self, key: &str, tier: ConfigTier) -> Result<(), SmooaiConfigError> {
         // SMOODEV-847 — guard against empty keys (matches LocalConfigManager
         // and the TS assertKeyDefined). See SMOODEV-841 incident.
         if key.is_empty() {
@@ -308,916 +1704,3967 @@ impl ConfigManager {
                 }
             }
         }
+        self.check_tier(key, tier)?;
+        if let Some(ref policy) = self.access_policy {
+            if !policy.permits(tier, key) {
+                return Err(SmooaiConfigError::access_denied(key, Some(tier)));
+            }
+        }
+        self.report_deprecation(key);
+        Ok(())
+    }
+
+    /// Load config now and cache `keys`, so the first real read is a cache hit.
+    ///
+    /// Call during startup to move file parsing and the remote fetch off the
+    /// first user-facing request. Keys go into the cache of the tier the
+    /// [`with_definition`](Self::with_definition) schema declares, or every
+    /// tier when it doesn't say. Keys with no value are skipped.
+    pub fn prefetch(&self, keys: &[&str]) -> Result<(), SmooaiConfigError> {
         let mut inner = self
             .inner
             .write()
-            .map_err(|_| SmooaiConfigError::new("Failed to acquire write lock"))?;
-
-        // Check cache
-        let cache = cache_selector(&mut inner);
-        if let Some(entry) = cache.get(key) {
-            if Instant::now() < entry.expires_at {
-                return Ok(Some(entry.value.clone()));
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        Self::expire_if_due(&mut inner);
+        self.initialize_inner(&mut inner)?;
+        for key in keys {
+            if inner.stale_keys.remove(*key) {
+                self.refresh_key(&mut inner, key);
             }
-            cache.remove(key);
+            self.warm_key(&mut inner, key);
         }
+        Ok(())
+    }
 
-        // Initialize if needed
+    /// [`prefetch`](Self::prefetch) every key in the merged config.
+    pub fn warm_all(&self) -> Result<(), SmooaiConfigError> {
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        Self::expire_if_due(&mut inner);
         self.initialize_inner(&mut inner)?;
+        for key in std::mem::take(&mut inner.stale_keys) {
+            self.refresh_key(&mut inner, &key);
+        }
+        let keys: Vec<String> = inner.config.keys().cloned().collect();
+        for key in &keys {
+            self.warm_key(&mut inner, key);
+        }
+        Ok(())
+    }
 
-        // Look up in merged config
-        let value = inner.config.get(key).cloned();
-        if let Some(ref val) = value {
-            let cache = cache_selector(&mut inner);
-            cache.insert(
-                key.to_string(),
-                CacheEntry {
-                    value: val.clone(),
-                    expires_at: Instant::now() + self.cache_ttl,
-                },
-            );
+    fn cache_entry(&self, tier: ConfigTier, value: &Arc<Value>) -> CacheEntry {
+        let value = if tier == ConfigTier::Secret && self.lock_secrets {
+            let json = Zeroizing::new(serde_json::to_vec(value.as_ref()).expect("JSON values serialize"));
+            CachedValue::Locked(LockedBytes::new(&json))
+        } else {
+            CachedValue::Shared(Arc::clone(value))
+        };
+        CacheEntry {
+            value,
+            expires_at: Instant::now() + jittered_ttl(self.cache_ttl, self.cache_ttl_jitter),
         }
+    }
 
-        Ok(value)
+    fn warm_key(&self, inner: &mut ManagerInner, key: &str) {
+        let Some(value) = inner.config.get(key).cloned().map(Arc::new) else {
+            return;
+        };
+        let tiers = match self.definition.as_ref().and_then(|d| d.declared_tier(key)) {
+            Some(tier) => vec![tier],
+            None => vec![ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag],
+        };
+        let key = inner.intern(key);
+        for tier in tiers {
+            let cache = match tier {
+                ConfigTier::Public => &mut inner.public_cache,
+                ConfigTier::Secret => &mut inner.secret_cache,
+                ConfigTier::FeatureFlag => &mut inner.feature_flag_cache,
+            };
+            cache.insert(Arc::clone(&key), self.cache_entry(tier, &value));
+        }
     }
 
     /// Retrieve a public config value.
     pub fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
-        self.get_value(key, |inner| &mut inner.public_cache)
-    }
-
-    /// Retrieve a secret config value.
-    pub fn get_secret_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
-        self.get_value(key, |inner| &mut inner.secret_cache)
+        Ok(self.get_public_config_arc(key)?.map(Arc::unwrap_or_clone))
     }
 
-    /// Retrieve a feature flag value.
-    pub fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
-        self.get_value(key, |inner| &mut inner.feature_flag_cache)
+    /// Like [`get_public_config`](Self::get_public_config), but shares the
+    /// cached value instead of copying it, so a cache hit costs a reference
+    /// count bump however large the value is.
+    pub fn get_public_config_arc(&self, key: &str) -> Result<Option<Arc<Value>>, SmooaiConfigError> {
+        self.get_value(key, ConfigTier::Public, |inner| &mut inner.public_cache)
     }
 
-    /// Clear all caches and force re-initialization on next access.
-    pub fn invalidate(&self) {
-        if let Ok(mut inner) = self.inner.write() {
-            inner.initialized = false;
-            inner.config.clear();
-            inner.public_cache.clear();
-            inner.secret_cache.clear();
-            inner.feature_flag_cache.clear();
-        }
+    /// A view whose getters prepend `prefix` to every key, and whose
+    /// [`keys`](ScopedConfig::keys) strip it, for a library that owns a
+    /// namespace such as `PAYMENTS_`. See [`crate::scoped`].
+    pub fn scoped<'a>(&'a self, prefix: &'a str) -> ScopedConfig<'a> {
+        ScopedConfig::new(self, prefix)
     }
 
-    /// Seed the manager's merged config map directly and mark it initialized.
+    /// Retrieve a public config value as `environment` sees it, without
+    /// rebinding the manager — for control-plane services that answer
+    /// questions about several environments.
     ///
-    /// Used by the bake-aware runtime ([`crate::runtime::build_config_runtime`])
-    /// to hydrate public + secret values from a pre-decrypted blob, bypassing
-    /// the normal file/env/remote merge pipeline. After seeding, public and
-    /// secret lookups hit the in-memory map synchronously. Feature flags are
-    /// intentionally omitted from the blob and still fall through to whatever
-    /// live-fetch path the consumer has configured.
-    pub fn seed_from_baked(&self, values: HashMap<String, Value>) -> Result<(), SmooaiConfigError> {
+    /// For the manager's own environment this is
+    /// [`get_public_config`](Self::get_public_config). For any other, the
+    /// value comes from that environment's config files and remote values,
+    /// fetched once and cached per environment for the cache TTL. Env vars,
+    /// registered sources and overrides describe this process and aren't
+    /// applied. Views for up to 32 environments are kept.
+    ///
+    /// `environment` names config files, so one that is empty or contains a
+    /// path separator or `..` is rejected.
+    pub fn get_public_config_for_env(&self, key: &str, environment: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        if environment == self.resolve_environment() {
+            return self.get_public_config(key);
+        }
+        if environment.is_empty() || environment.contains(['/', '\\', '\0']) || environment.contains("..") {
+            return Err(SmooaiConfigError::new(&format!(
+                "Invalid environment name '{}'",
+                environment
+            )));
+        }
+        self.validate_key(key, ConfigTier::Public)?;
         let mut inner = self
             .inner
             .write()
-            .map_err(|_| SmooaiConfigError::new("Failed to acquire write lock"))?;
-        inner.config = values;
-        inner.public_cache.clear();
-        inner.secret_cache.clear();
-        inner.feature_flag_cache.clear();
-        inner.initialized = true;
-        Ok(())
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        let now = Instant::now();
+        let cached = inner
+            .env_views
+            .get(environment)
+            .is_some_and(|view| now < view.expires_at);
+        if !cached {
+            let (config, ttl) = self.load_env_view(environment);
+            if !inner.env_views.contains_key(environment) && inner.env_views.len() >= MAX_ENV_VIEWS {
+                let oldest = inner
+                    .env_views
+                    .iter()
+                    .min_by_key(|(_, view)| view.last_used)
+                    .map(|(environment, _)| environment.clone());
+                if let Some(evicted) = oldest.and_then(|oldest| inner.env_views.remove(&oldest)) {
+                    inner.discard(evicted.config);
+                }
+            }
+            let previous = inner.env_views.insert(
+                environment.to_string(),
+                EnvView {
+                    config,
+                    expires_at: now + ttl,
+                    last_used: now,
+                },
+            );
+            if let Some(previous) = previous {
+                inner.discard(previous.config);
+            }
+        }
+        let view = inner.env_views.get_mut(environment).expect("just loaded");
+        view.last_used = now;
+        let value = view.config.get(key).cloned();
+        self.record_access(ConfigTier::Public, key, cached, value.is_some());
+        Ok(value)
     }
-}
 
-impl Default for ConfigManager {
-    fn default() -> Self {
-        Self::new()
+    /// Retrieve a public config value with `tenant`'s overrides applied.
+    ///
+    /// Tenant overrides come from the remote API
+    /// (`/config/values?environment=...&tenant=...`) and merge above the
+    /// environment's remote values but below env vars and overrides, so
+    /// per-customer settings don't need an environment per customer. Each
+    /// tenant's layer is fetched once and cached for the cache TTL; at most
+    /// [`with_max_tenants`](Self::with_max_tenants) layers are kept, evicting
+    /// the least recently read.
+    pub fn get_public_config_for_tenant(&self, key: &str, tenant: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        self.get_tenant_value(key, ConfigTier::Public, tenant, |inner| &mut inner.public_cache)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::io::Write;
-    use std::sync::Arc;
-    use wiremock::matchers::{header, method, path_regex, query_param};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
+    /// Retrieve a secret config value with `tenant`'s overrides applied. See
+    /// [`get_public_config_for_tenant`](Self::get_public_config_for_tenant).
+    pub fn get_secret_config_for_tenant(
+        &self,
+        key: &str,
+        tenant: &str,
+    ) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        Ok(self
+            .get_tenant_value(key, ConfigTier::Secret, tenant, |inner| &mut inner.secret_cache)?
+            .map(SecretValue::new))
+    }
 
-    fn make_config_dir(dir: &std::path::Path, files: &[(&str, &str)]) -> String {
-        let config_dir = dir.join(".smooai-config");
-        fs::create_dir_all(&config_dir).unwrap();
-        for (name, content) in files {
-            let mut f = fs::File::create(config_dir.join(name)).unwrap();
-            f.write_all(content.as_bytes()).unwrap();
+    /// Retrieve a feature flag value with `tenant`'s overrides applied. See
+    /// [`get_public_config_for_tenant`](Self::get_public_config_for_tenant).
+    pub fn get_feature_flag_for_tenant(&self, key: &str, tenant: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        self.get_tenant_value(key, ConfigTier::FeatureFlag, tenant, |inner| {
+            &mut inner.feature_flag_cache
+        })
+    }
+
+    /// Drop `tenant`'s cached override layer; the next read refetches it.
+    pub fn invalidate_tenant(&self, tenant: &str) {
+        if let Ok(mut inner) = self.inner.write() {
+            if let Some(previous) = inner.tenant_layers.remove(tenant) {
+                inner.discard(previous.layer.1);
+            }
         }
-        config_dir.to_string_lossy().to_string()
     }
 
-    fn make_env(config_dir: &str, extra: &[(&str, &str)]) -> HashMap<String, String> {
-        let mut env: HashMap<String, String> = extra.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
-        env.insert("SMOOAI_ENV_CONFIG_DIR".to_string(), config_dir.to_string());
-        env
+    fn get_tenant_value(
+        &self,
+        key: &str,
+        tier: ConfigTier,
+        tenant: &str,
+        cache_selector: fn(&mut ManagerInner) -> &mut HashMap<Arc<str>, CacheEntry>,
+    ) -> Result<Option<Value>, SmooaiConfigError> {
+        self.validate_key(key, tier)?;
+        if let Some(value) = self.pushed_override(key) {
+            self.record_access(tier, key, true, true);
+            return Ok(Some(value));
+        }
+        let mut guard = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        Self::expire_if_due(&mut guard);
+        self.initialize_inner(&mut guard)?;
+        self.load_tenant_layer(&mut guard, tenant);
+
+        let inner = &*guard;
+        let tenant_layer = &inner.tenant_layers[tenant].layer;
+        if !tenant_layer.1.contains_key(key) {
+            // Nothing tenant-specific: the environment's value, via its cache.
+            drop(guard);
+            return Ok(self.get_value(key, tier, cache_selector)?.map(Arc::unwrap_or_clone));
+        }
+        let value = self.merge_key(inner.layers.iter().chain([tenant_layer]), key);
+        self.record_access(tier, key, false, value.is_some());
+        Ok(value)
     }
 
-    // --- Test 1: Local-Only Mode ---
-    #[test]
-    fn test_local_only_mode() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(
-            dir.path(),
-            &[("default.json", r#"{"API_URL":"http://localhost","MAX_RETRIES":3}"#)],
-        );
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = ConfigManager::new().with_env(env);
+    /// Make sure `tenant`'s override layer is loaded and fresh, evicting the
+    /// least recently used layer when the cache is full.
+    fn load_tenant_layer(&self, inner: &mut ManagerInner, tenant: &str) {
+        let now = Instant::now();
+        if let Some(layer) = inner.tenant_layers.get_mut(tenant) {
+            if now < layer.expires_at {
+                layer.last_used = now;
+                return;
+            }
+        }
 
-        assert_eq!(
-            mgr.get_public_config("API_URL").unwrap(),
-            Some(Value::String("http://localhost".to_string()))
-        );
-        assert_eq!(
-            mgr.get_public_config("MAX_RETRIES").unwrap(),
-            Some(serde_json::json!(3))
+        let api_key = self.resolve_api_key();
+        let base_url = self.resolve_param("SMOOAI_CONFIG_API_URL", &self.base_url);
+        let org_id = self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id);
+        let (values, ttl) = match (
+            self.local_only || self.fixed_values.is_some(),
+            api_key,
+            base_url,
+            org_id,
+        ) {
+            (false, Some(api_key), Some(base_url), Some(org_id)) => {
+                let environment = self.resolve_environment();
+                match self.fetch_remote(&api_key, &base_url, &org_id, &environment, Some(tenant)) {
+                    Ok((values, server_ttl)) => (values, self.remote_ttl(server_ttl)),
+                    // Served (without overrides) this once and retried on the next read.
+                    Err(_) => (HashMap::new(), Duration::ZERO),
+                }
+            }
+            _ => (HashMap::new(), self.remote_ttl(None)),
+        };
+
+        if !inner.tenant_layers.contains_key(tenant) && inner.tenant_layers.len() >= self.max_tenants {
+            let oldest = inner
+                .tenant_layers
+                .iter()
+                .min_by_key(|(_, layer)| layer.last_used)
+                .map(|(tenant, _)| tenant.clone());
+            if let Some(evicted) = oldest.and_then(|oldest| inner.tenant_layers.remove(&oldest)) {
+                inner.discard(evicted.layer.1);
+            }
+        }
+        let previous = inner.tenant_layers.insert(
+            tenant.to_string(),
+            TenantLayer {
+                layer: (LayerKind::Tenant(tenant.to_string()), values),
+                expires_at: now + ttl,
+                last_used: now,
+            },
         );
-        assert_eq!(mgr.get_public_config("NONEXISTENT").unwrap(), None);
+        if let Some(previous) = previous {
+            inner.discard(previous.layer.1);
+        }
     }
 
-    // --- Test 2: Remote Enrichment ---
-    #[tokio::test]
-    async fn test_remote_enrichment() {
-        let mock_server = MockServer::start().await;
+    /// Retrieve a nested public value by dot path, e.g.
+    /// `get_path("DATABASE.options.pool_size")`; numeric segments index
+    /// arrays (`HOSTS.0`). A path without dots is the same as
+    /// [`get_public_config_arc`](Self::get_public_config_arc).
+    ///
+    /// The first `get_path` after each load indexes every nested path, so
+    /// later lookups are one hash probe instead of a walk down the tree.
+    /// Object keys that contain `.` can't be addressed this way.
+    pub fn get_path(&self, path: &str) -> Result<Option<Arc<Value>>, SmooaiConfigError> {
+        let Some((key, rest)) = path.split_once('.') else {
+            return self.get_public_config_arc(path);
+        };
+        self.validate_key(key, ConfigTier::Public)?;
+        if let Some(value) = self.pushed_override(key) {
+            self.record_access(ConfigTier::Public, key, true, true);
+            return Ok(value_at_path(&value, rest).map(|v| Arc::new(v.clone())));
+        }
+        let mut guard = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        Self::expire_if_due(&mut guard);
+        self.initialize_inner(&mut guard)?;
+        if guard.stale_keys.remove(key) {
+            self.refresh_key(&mut guard, key);
+        }
+        let inner = &mut *guard;
+        let indexed = inner.path_index.is_some();
+        let value = inner
+            .path_index
+            .get_or_insert_with(|| index_paths(&inner.config))
+            .get(path)
+            .cloned();
+        self.record_access(ConfigTier::Public, key, indexed, value.is_some());
+        Ok(value)
+    }
 
-        Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/.+/config/values"))
-            .and(query_param("environment", "test"))
-            .and(header("Authorization", "Bearer test-api-key"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "REMOTE_KEY": "remote-value",
-                    "REMOTE_NUM": 42
-                }
-            })))
-            .mount(&mock_server)
-            .await;
+    /// Retrieve a public config value deserialized into `T`, e.g. a
+    /// `#[serde(rename_all = "lowercase")]` enum.
+    ///
+    /// A value that doesn't fit `T` is an error naming the key and, when the
+    /// schema restricts the key to an `enum`, the allowed values.
+    pub fn get_public_config_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SmooaiConfigError> {
+        self.get_public_config(key)?
+            .map(|value| self.deserialize_as(key, value))
+            .transpose()
+    }
 
-        let url = mock_server.uri();
+    /// Retrieve a public config value that must be set: a missing or `null`
+    /// value is a [`MissingRequiredKeys`](crate::SmooaiConfigErrorKind::MissingRequiredKeys)
+    /// error naming `key`.
+    pub fn get_required_public_config(&self, key: &str) -> Result<Value, SmooaiConfigError> {
+        match self.get_public_config(key)? {
+            Some(value) if !value.is_null() => Ok(value),
+            _ => Err(SmooaiConfigError::missing_required_keys(vec![key.to_string()])),
+        }
+    }
+
+    /// Retrieve a public config value, or `default` when it's missing or
+    /// `null`. Other errors (a wrong-tier read, a denied key, a failed load)
+    /// are still returned rather than hidden behind the default.
+    pub fn get_public_config_or(&self, key: &str, default: impl Into<Value>) -> Result<Value, SmooaiConfigError> {
+        match self.get_public_config(key)? {
+            Some(value) if !value.is_null() => Ok(value),
+            _ => Ok(default.into()),
+        }
+    }
+
+    /// Retrieve a secret config value.
+    ///
+    /// The value comes back wrapped in a [`SecretValue`], which redacts itself
+    /// in `Debug` / `Display`; call `expose()` to read it.
+    pub fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        Ok(self
+            .get_value(key, ConfigTier::Secret, |inner| &mut inner.secret_cache)?
+            .map(|value| SecretValue::new(Arc::unwrap_or_clone(value))))
+    }
+
+    /// Retrieve a feature flag value.
+    ///
+    /// Namespaced keys (`checkout/new-payment-flow`) without a value fall back
+    /// to the nearest namespace default (`checkout/*`); see [`crate::flags`].
+    pub fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        Ok(self.get_feature_flag_arc(key)?.map(Arc::unwrap_or_clone))
+    }
+
+    /// [`get_feature_flag`](Self::get_feature_flag) without copying the
+    /// cached value. See [`get_public_config_arc`](Self::get_public_config_arc).
+    pub fn get_feature_flag_arc(&self, key: &str) -> Result<Option<Arc<Value>>, SmooaiConfigError> {
+        match self.get_value(key, ConfigTier::FeatureFlag, |inner| &mut inner.feature_flag_cache)? {
+            Some(value) if !value.is_null() => Ok(Some(value)),
+            value if key.contains(flags::NAMESPACE_SEPARATOR) => {
+                let inner = self
+                    .inner
+                    .read()
+                    .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire read lock"))?;
+                Ok(flags::namespace_default(&inner.config, key).map(Arc::new).or(value))
+            }
+            value => Ok(value),
+        }
+    }
+
+    /// Retrieve a feature flag deserialized into `T`. See
+    /// [`get_public_config_as`](Self::get_public_config_as).
+    pub fn get_feature_flag_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SmooaiConfigError> {
+        self.get_feature_flag(key)?
+            .map(|value| self.deserialize_as(key, value))
+            .transpose()
+    }
+
+    fn deserialize_as<T: DeserializeOwned>(&self, key: &str, value: Value) -> Result<T, SmooaiConfigError> {
+        serde_json::from_value(value).map_err(|e| {
+            let allowed = self.schema_types.as_ref().and_then(|types| {
+                types
+                    .get(key)
+                    .or_else(|| types.get(&property_key(key)))
+                    .and_then(|hint| parse_enum_type_hint(hint))
+            });
+            let message = match allowed {
+                Some(allowed) => format!(
+                    "Config key {} is not one of the allowed values ({}): {}",
+                    key,
+                    format_allowed_values(&allowed),
+                    e
+                ),
+                None => format!("Config key {} could not be deserialized: {}", key, e),
+            };
+            SmooaiConfigError::coercion(key, &message).with_source(e)
+        })
+    }
+
+    /// Whether the feature flag `key` is on; `false` when it's unset, not
+    /// boolean-like, or can't be read. Never errors, for use in hot paths.
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.is_enabled_or(key, false)
+    }
+
+    /// Like [`is_enabled`](Self::is_enabled), returning `default` instead of
+    /// `false` when the flag is unset, not boolean-like, or can't be read.
+    ///
+    /// `true`, non-zero numbers and the strings `"true"` / `"1"` count as on;
+    /// see [`flags::flag_enabled`].
+    pub fn is_enabled_or(&self, key: &str, default: bool) -> bool {
+        match self.get_feature_flag(key) {
+            Ok(Some(value)) => flags::flag_enabled(&value).unwrap_or(default),
+            _ => default,
+        }
+    }
+
+    /// Every flag in `namespace` (e.g. `"checkout"`) with namespace defaults applied.
+    ///
+    /// The local counterpart of [`ConfigClient::evaluate_namespace`](crate::ConfigClient::evaluate_namespace):
+    /// static values only, no segment evaluation.
+    pub fn get_feature_flag_namespace(&self, namespace: &str) -> Result<BTreeMap<String, Value>, SmooaiConfigError> {
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        self.initialize_inner(&mut inner)?;
+        let pushed = self
+            .pushed_overrides
+            .lock()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire override lock"))?;
+        let mut resolved = if pushed.is_empty() {
+            flags::resolve_namespace(&inner.config, namespace)
+        } else {
+            let mut config = inner.config.clone();
+            for (_, values) in pushed.iter() {
+                config.extend(values.clone());
+            }
+            flags::resolve_namespace(&config, namespace)
+        };
+        if let Some(ref policy) = self.access_policy {
+            resolved.retain(|key, _| policy.permits(ConfigTier::FeatureFlag, key));
+        }
+        Ok(resolved)
+    }
+
+    /// Retrieve a base64-encoded binary value (the `"bytes"` type hint) and decode it.
+    ///
+    /// Reads through the secret tier since these are usually certs and keys.
+    /// Invalid base64 is an error naming the key; the decoded bytes come back as
+    /// [`DecodedBytes`], whose `Debug` output never includes the contents.
+    pub fn get_bytes_decoded(&self, key: &str) -> Result<Option<DecodedBytes>, SmooaiConfigError> {
+        match self
+            .get_value(key, ConfigTier::Secret, |inner| &mut inner.secret_cache)?
+            .as_deref()
+        {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => decode_base64_value(key, value).map(Some),
+        }
+    }
+
+    /// Report which source supplied `key` and what every layer held for it.
+    ///
+    /// Layers are listed lowest precedence first: config files in load order,
+    /// registered sources, the remote API, env vars, built-ins, deferred
+    /// resolvers, then overrides. Secret values, in the merged value and in
+    /// every layer, come back as [`REDACTED`], as in [`get_all`](Self::get_all);
+    /// the sources are still reported.
+    pub fn explain(&self, key: &str) -> Result<Explanation, SmooaiConfigError> {
+        if !self.readable(key) {
+            return Err(SmooaiConfigError::access_denied(key, None));
+        }
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        self.initialize_inner(&mut inner)?;
+
+        let env = self.get_env();
+        let known = self.known_secrets();
+        let layers: Vec<LayerValue> = inner
+            .layers
+            .iter()
+            .filter_map(|(kind, values)| {
+                let value = self.redact(key, values.get(key)?.clone(), &known);
+                let source = self.layer_source(kind, key, &env);
+                Some(LayerValue { source, value })
+            })
+            .chain(self.pushed_overrides.lock().ok().into_iter().flat_map(|pushed| {
+                pushed
+                    .iter()
+                    .filter_map(|(_, values)| values.get(key).cloned())
+                    .map(|value| LayerValue {
+                        source: ValueSource::Override,
+                        value: self.redact(key, value, &known),
+                    })
+                    .collect::<Vec<_>>()
+            }))
+            .collect();
+
+        Ok(Explanation {
+            key: key.to_string(),
+            value: self
+                .pushed_override(key)
+                .or_else(|| inner.config.get(key).cloned())
+                .map(|value| self.redact(key, value, &known)),
+            source: layers.last().map(|l| l.source.clone()),
+            layers,
+        })
+    }
+
+    /// Every merged key and value, sorted, with secret values replaced by
+    /// [`REDACTED`]. Active [`push_overrides`](Self::push_overrides) guards apply.
+    ///
+    /// Secret keys are those the [`with_definition`](Self::with_definition)
+    /// schema puts in the secret tier or marks `"x-secret": true`.
+    pub fn get_all(&self) -> Result<BTreeMap<String, Value>, SmooaiConfigError> {
+        let all = self.get_all_exposed()?;
+        let known = self.known_secrets();
+        Ok(all
+            .into_iter()
+            .map(|(k, v)| {
+                let v = self.redact(&k, v, &known);
+                (k, v)
+            })
+            .collect())
+    }
+
+    /// [`get_all`](Self::get_all) limited to keys starting with `prefix`, for
+    /// enumerating a dynamic key family such as per-integration credentials.
+    /// Secret values are still redacted; read them with
+    /// [`get_secret_config`](Self::get_secret_config).
+    pub fn get_all_with_prefix(&self, prefix: &str) -> Result<BTreeMap<String, Value>, SmooaiConfigError> {
+        let mut all = self.get_all_exposed()?;
+        all.retain(|key, _| key.starts_with(prefix));
+        let known = self.known_secrets();
+        Ok(all
+            .into_iter()
+            .map(|(k, v)| {
+                let v = self.redact(&k, v, &known);
+                (k, v)
+            })
+            .collect())
+    }
+
+    /// Whether `key` is set in any tier, even to an explicit `null`.
+    ///
+    /// The getters return `Ok(None)` for a missing key but `Ok(Some(Value::Null))`
+    /// for one set to `null`; this tells "not configured" apart from
+    /// "explicitly disabled" without reading the value. Keys the access
+    /// policy hides report `false`.
+    pub fn contains_key(&self, key: &str) -> Result<bool, SmooaiConfigError> {
+        if !self.readable(key) {
+            return Ok(false);
+        }
+        if self.pushed_override(key).is_some() {
+            return Ok(true);
+        }
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        Self::expire_if_due(&mut inner);
+        self.initialize_inner(&mut inner)?;
+        Ok(inner.config.contains_key(key))
+    }
+
+    /// Every key starting with `prefix`, sorted. Like
+    /// [`get_all`](Self::get_all), it leaves out keys the access policy hides.
+    pub fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, SmooaiConfigError> {
+        let mut all = self.get_all_exposed()?;
+        all.retain(|key, _| key.starts_with(prefix));
+        Ok(all.into_keys().collect())
+    }
+
+    /// [`get_all`](Self::get_all) without redaction: secret values included.
+    /// Only for tooling that must emit real values (e.g. `smooai-config export
+    /// --show-secrets`); prefer the typed getters in application code.
+    pub fn get_all_exposed(&self) -> Result<BTreeMap<String, Value>, SmooaiConfigError> {
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        self.initialize_inner(&mut inner)?;
+        let mut all: BTreeMap<String, Value> = inner.config.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        drop(inner);
+        let pushed = self
+            .pushed_overrides
+            .lock()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire override lock"))?;
+        for (_, values) in pushed.iter() {
+            all.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        all.retain(|key, _| self.readable(key));
+        Ok(all)
+    }
+
+    /// [`get_all`](Self::get_all) as pretty-printed JSON, secrets redacted.
+    pub fn export(&self) -> Result<String, SmooaiConfigError> {
+        serde_json::to_string_pretty(&self.get_all()?)
+            .map_err(|e| SmooaiConfigError::new(&format!("Failed to serialize config: {}", e)))
+    }
+
+    pub(crate) fn is_secret(&self, key: &str) -> bool {
+        !self.secret_keys.is_empty()
+            && (self.secret_keys.contains(key) || self.secret_keys.contains(&property_key(key)))
+    }
+
+    /// Replace secret-tier values with [`REDACTED`], and scrub known secrets
+    /// and credential-shaped text out of the strings in any other value.
+    ///
+    /// `known` is [`known_secrets`](Self::known_secrets), fetched once by the
+    /// caller rather than per key.
+    fn redact(&self, key: &str, value: Value, known: &[String]) -> Value {
+        if value.is_null() {
+            return value;
+        }
+        if self.is_secret(key) {
+            return Value::String(REDACTED.to_string());
+        }
+        redact_strings(value, known)
+    }
+
+    /// Record the secret-tier strings in `config` for [`redact`](Self::redact)
+    /// and [`warn`](Self::warn).
+    fn remember_secrets(&self, config: &HashMap<String, Value>) {
+        fn collect(value: &Value, out: &mut Vec<String>) {
+            match value {
+                Value::String(s) => out.push(s.clone()),
+                Value::Array(items) => items.iter().for_each(|v| collect(v, out)),
+                Value::Object(map) => map.values().for_each(|v| collect(v, out)),
+                _ => {}
+            }
+        }
+        let mut values = Vec::new();
+        for (key, value) in config {
+            if self.is_secret(key) {
+                collect(value, &mut values);
+            }
+        }
+        if let Ok(mut known) = self.known_secrets.write() {
+            *known = values;
+        }
+    }
+
+    /// Secret values to scrub from text: the last merge's secret-tier strings
+    /// and the API key.
+    fn known_secrets(&self) -> Vec<String> {
+        let mut known = self.known_secrets.read().map(|k| k.clone()).unwrap_or_default();
+        known.extend(self.resolve_api_key());
+        known
+    }
+
+    /// Print a warning with known secrets and credentials redacted.
+    fn warn(&self, message: impl std::fmt::Display) {
+        let known = self.known_secrets();
+        redact::warn(redact_text(&message.to_string(), known.iter().map(String::as_str)));
+    }
+
+    fn layer_source(&self, kind: &LayerKind, key: &str, env: &HashMap<String, String>) -> ValueSource {
+        match kind {
+            LayerKind::File(name) => ValueSource::File { name: name.clone() },
+            LayerKind::Source(name) => ValueSource::Source { name: name.clone() },
+            LayerKind::Remote => ValueSource::Remote,
+            LayerKind::LastKnownGood => ValueSource::LastKnownGood,
+            LayerKind::Tenant(tenant) => ValueSource::Tenant { tenant: tenant.clone() },
+            LayerKind::Env => ValueSource::Env {
+                var: self.env_var_name(key, env),
+            },
+            LayerKind::BuiltIn => ValueSource::BuiltIn,
+            LayerKind::Deferred => ValueSource::Deferred,
+            LayerKind::Baked => ValueSource::Baked,
+            LayerKind::Values => ValueSource::Values,
+            #[cfg(feature = "snapshot")]
+            LayerKind::Snapshot => ValueSource::Snapshot,
+            LayerKind::Override => ValueSource::Override,
+        }
+    }
+
+    fn env_var_name(&self, key: &str, env: &HashMap<String, String>) -> String {
+        let prefixed = format!("{}{}", self.env_prefix, key);
+        if !self.env_prefix.is_empty() && env.contains_key(&prefixed) {
+            prefixed
+        } else {
+            key.to_string()
+        }
+    }
+
+    /// Clear all caches and force re-initialization on next access.
+    ///
+    /// An API key read from a key file or the keychain is read again too.
+    pub fn invalidate(&self) {
+        self.forget_stored_api_key();
+        if let Ok(mut inner) = self.inner.write() {
+            inner.initialized = false;
+            inner.refresh_at = None;
+            inner.wipe_loaded();
+            inner.config.clear();
+            inner.layers.clear();
+            inner.clear_caches();
+            inner.stale_keys.clear();
+            inner.failed_init = None;
+        }
+    }
+
+    /// Drop `key` from every per-key cache and refetch just that key from the
+    /// remote API on its next read.
+    ///
+    /// Files and other sources aren't re-read; use [`invalidate`](Self::invalidate)
+    /// for a full reload. Without remote credentials this only clears the caches.
+    pub fn invalidate_key(&self, key: &str) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.public_cache.remove(key);
+            inner.secret_cache.remove(key);
+            inner.feature_flag_cache.remove(key);
+            inner.cache_keys.remove(key);
+            if inner.initialized {
+                inner.stale_keys.insert(key.to_string());
+            }
+        }
+    }
+
+    /// Clear one tier's per-key cache and refetch its keys from the remote
+    /// API individually as they are next read.
+    ///
+    /// Covers keys that were cached for `tier` plus, with a
+    /// [`with_definition`](Self::with_definition) schema, every loaded key the
+    /// schema declares in `tier`. Like [`invalidate_key`](Self::invalidate_key),
+    /// nothing else is reloaded.
+    pub fn invalidate_tier(&self, tier: ConfigTier) {
+        let Ok(mut inner) = self.inner.write() else {
+            return;
+        };
+        let cache = match tier {
+            ConfigTier::Public => &mut inner.public_cache,
+            ConfigTier::Secret => &mut inner.secret_cache,
+            ConfigTier::FeatureFlag => &mut inner.feature_flag_cache,
+        };
+        let mut stale: HashSet<String> = cache.drain().map(|(key, _)| key.to_string()).collect();
+        if let Some(ref definition) = self.definition {
+            stale.extend(inner.config.keys().filter(|k| definition.declares(tier, k)).cloned());
+        }
+        if inner.initialized {
+            inner.stale_keys.extend(stale);
+        }
+    }
+
+    /// The client for remote fetches, built on first use.
+    fn http_client(&self) -> &reqwest::blocking::Client {
+        self.http_client.get_or_init(|| {
+            let mut builder = reqwest::blocking::Client::builder();
+            if let Some(timeout) = self.request_timeout {
+                builder = builder.timeout(timeout);
+            }
+            #[cfg(feature = "tls-pinning")]
+            if !self.tls_pins.is_empty() {
+                builder = builder.use_preconfigured_tls(pinned_tls_config(&self.tls_pins));
+            }
+            builder.build().expect("reqwest client builder")
+        })
+    }
+
+    /// Refetch `key` from the remote API and re-merge it across the recorded layers.
+    fn refresh_key(&self, inner: &mut ManagerInner, key: &str) {
+        let api_key = self.resolve_api_key();
+        let base_url = self.resolve_param("SMOOAI_CONFIG_API_URL", &self.base_url);
+        let org_id = self.resolve_param("SMOOAI_CONFIG_ORG_ID", &self.org_id);
+        let (false, Some(api_key), Some(base_url), Some(org_id)) = (self.local_only, api_key, base_url, org_id) else {
+            return;
+        };
+        let url = format!(
+            "{}/organizations/{}/config/values/{}",
+            base_url.trim_end_matches('/'),
+            org_id,
+            utf8_percent_encode(key, PATH_SEGMENT_ENCODE_SET)
+        );
+
+        let started = Instant::now();
+        let fetched = self
+            .http_client()
+            .get(&url)
+            .query(&[("environment", self.resolve_environment())])
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send();
+        let value = match fetched {
+            Ok(resp) if resp.status() == reqwest::StatusCode::NOT_FOUND => Ok(None),
+            Ok(resp) if resp.status().is_success() => resp
+                .json::<Value>()
+                .map(|body| body.get("value").cloned())
+                .map_err(|e| e.to_string()),
+            Ok(resp) => {
+                if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+                    self.forget_stored_api_key();
+                }
+                Err(format!("HTTP {}", resp.status()))
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        self.record_fetch("remote", started, value.is_ok());
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                self.warn(format_args!("Failed to refetch {}: {}", key, e));
+                return;
+            }
+        };
+
+        let Some((_, remote)) = inner
+            .layers
+            .iter_mut()
+            .find(|(kind, _)| matches!(kind, LayerKind::Remote | LayerKind::LastKnownGood))
+        else {
+            return;
+        };
+        let replaced = match value {
+            Some(value) => remote.insert(key.to_string(), value),
+            None => remote.remove(key),
+        };
+        let merged = match self.merge_key(&inner.layers, key) {
+            Some(value) => inner.config.insert(key.to_string(), value),
+            None => inner.config.remove(key),
+        };
+        if inner.wipe_values {
+            replaced.into_iter().chain(merged).for_each(|mut old| old.wipe());
+        }
+        self.publish_changes(inner);
+    }
+
+    /// Recompute one key's merged value from the recorded layers, in the same
+    /// precedence `initialize_inner` uses. Built-ins are merged with the env
+    /// vars and win over them; they follow the env layer, so a stable sort
+    /// keeps that order.
+    fn merge_key<'a>(
+        &self,
+        layers: impl IntoIterator<Item = &'a (LayerKind, HashMap<String, Value>)>,
+        key: &str,
+    ) -> Option<Value> {
+        let rank = |kind: &LayerKind| match kind {
+            LayerKind::File(_) | LayerKind::Baked | LayerKind::Values => 0,
+            #[cfg(feature = "snapshot")]
+            LayerKind::Snapshot => 0,
+            LayerKind::Source(_) => 1,
+            LayerKind::Remote | LayerKind::LastKnownGood => 2,
+            LayerKind::Tenant(_) => 3,
+            LayerKind::Env | LayerKind::BuiltIn => 4,
+            LayerKind::Deferred => 5,
+            LayerKind::Override => 6,
+        };
+        let mut ordered: Vec<&(LayerKind, HashMap<String, Value>)> = layers.into_iter().collect();
+        ordered.sort_by_key(|(kind, _)| rank(kind));
+
+        let mut merged = Value::Object(Default::default());
+        for (_, values) in ordered {
+            if let Some(value) = values.get(key) {
+                let patch = Value::Object([(key.to_string(), value.clone())].into_iter().collect());
+                self.merge_rules.merge_into(&mut merged, &patch);
+            }
+        }
+        merged.get(key).cloned()
+    }
+
+    /// Per-source health and cache statistics, for `/healthz`-style endpoints.
+    ///
+    /// Reports the current state without loading anything, so a manager that
+    /// hasn't been read yet shows no sources. Never includes config values.
+    pub fn status(&self) -> ConfigStatus {
+        let Ok(inner) = self.inner.read() else {
+            return ConfigStatus {
+                initialized: false,
+                merged_keys: 0,
+                sources: Vec::new(),
+                cache: CacheStats::default(),
+            };
+        };
+        ConfigStatus {
+            initialized: inner.initialized,
+            merged_keys: inner.config.len(),
+            sources: inner.health.clone(),
+            cache: CacheStats {
+                public_entries: inner.public_cache.len(),
+                secret_entries: inner.secret_cache.len(),
+                feature_flag_entries: inner.feature_flag_cache.len(),
+                hits: inner.cache_hits,
+                misses: inner.cache_misses,
+            },
+        }
+    }
+
+    fn record_access(&self, tier: ConfigTier, key: &str, cache_hit: bool, found: bool) {
+        if let Some(Ok(mut log)) = self.access_log.as_ref().map(|log| log.lock()) {
+            log.push(KeyAccess {
+                tier,
+                key: key.to_string(),
+                cache_hit,
+                found,
+            });
+        }
+    }
+
+    /// Every getter call since the manager was built or
+    /// [`clear_accesses`](Self::clear_accesses), oldest first. Empty unless
+    /// [`with_access_recording`](Self::with_access_recording) is on.
+    pub fn accesses(&self) -> Vec<KeyAccess> {
+        self.access_log
+            .as_ref()
+            .and_then(|log| log.lock().ok().map(|log| log.clone()))
+            .unwrap_or_default()
+    }
+
+    /// The distinct keys read so far, whether or not they had a value.
+    pub fn accessed_keys(&self) -> BTreeSet<String> {
+        self.accesses().into_iter().map(|access| access.key).collect()
+    }
+
+    /// Keys in the merged config that no getter has read, ignoring the
+    /// built-in keys (`ENV`, `REGION`, ...). Loads config if needed.
+    pub fn unread_keys(&self) -> Result<BTreeSet<String>, SmooaiConfigError> {
+        let accessed = self.accessed_keys();
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        self.initialize_inner(&mut inner)?;
+        Ok(inner
+            .layers
+            .iter()
+            .filter(|(kind, _)| !matches!(kind, LayerKind::BuiltIn))
+            .flat_map(|(_, values)| values.keys())
+            .filter(|key| inner.config.contains_key(*key) && !accessed.contains(*key))
+            .cloned()
+            .collect())
+    }
+
+    /// Forget recorded accesses, e.g. between phases of a test.
+    pub fn clear_accesses(&self) {
+        if let Some(Ok(mut log)) = self.access_log.as_ref().map(|log| log.lock()) {
+            log.clear();
+        }
+    }
+
+    /// Seed the manager's merged config map directly and mark it initialized.
+    ///
+    /// Used by the bake-aware runtime ([`crate::runtime::build_config_runtime`])
+    /// to hydrate public + secret values from a pre-decrypted blob, bypassing
+    /// the normal file/env/remote merge pipeline. After seeding, public and
+    /// secret lookups hit the in-memory map synchronously. Feature flags are
+    /// intentionally omitted from the blob and still fall through to whatever
+    /// live-fetch path the consumer has configured.
+    pub fn seed_from_baked(&self, values: HashMap<String, Value>) -> Result<(), SmooaiConfigError> {
+        let mut inner = self
+            .inner
+            .write()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Failed to acquire write lock"))?;
+        inner.refresh_at = None;
+        inner.wipe_loaded();
+        inner.layers = vec![(LayerKind::Baked, values.clone())];
+        inner.config = values;
+        if !self.overrides.is_empty() {
+            inner.config.extend(self.overrides.clone());
+            inner.layers.push((LayerKind::Override, self.overrides.clone()));
+        }
+        inner.clear_caches();
+        inner.initialized = true;
+        Ok(())
+    }
+}
+
+/// Keeps overrides from [`ConfigManager::push_overrides`] active; dropping it
+/// removes them.
+#[must_use = "overrides are removed as soon as the guard is dropped"]
+pub struct OverrideGuard<'a> {
+    manager: &'a ConfigManager,
+    id: u64,
+}
+
+impl Drop for OverrideGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut pushed) = self.manager.pushed_overrides.lock() {
+            pushed.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+impl ConfigManager {
+    pub(crate) fn is_initialized(&self) -> bool {
+        self.inner.read().map(|inner| inner.initialized).unwrap_or(false)
+    }
+
+    pub(crate) fn public_cache_is_empty(&self) -> bool {
+        self.inner
+            .read()
+            .map(|inner| inner.public_cache.is_empty())
+            .unwrap_or(true)
+    }
+}
+
+impl Default for ConfigManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::SmooaiConfigErrorKind;
+    use std::fs;
+    use std::io::Write;
+    use std::sync::Arc;
+    use wiremock::matchers::{header, method, path, path_regex, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_config_dir(dir: &std::path::Path, files: &[(&str, &str)]) -> String {
+        let config_dir = dir.join(".smooai-config");
+        fs::create_dir_all(&config_dir).unwrap();
+        for (name, content) in files {
+            let mut f = fs::File::create(config_dir.join(name)).unwrap();
+            f.write_all(content.as_bytes()).unwrap();
+        }
+        config_dir.to_string_lossy().to_string()
+    }
+
+    fn make_env(config_dir: &str, extra: &[(&str, &str)]) -> HashMap<String, String> {
+        let mut env: HashMap<String, String> = extra.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        env.insert("SMOOAI_ENV_CONFIG_DIR".to_string(), config_dir.to_string());
+        env
+    }
+
+    // --- Test 1: Local-Only Mode ---
+    #[test]
+    fn test_local_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[("default.json", r#"{"API_URL":"http://localhost","MAX_RETRIES":3}"#)],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(Value::String("http://localhost".to_string()))
+        );
+        assert_eq!(
+            mgr.get_public_config("MAX_RETRIES").unwrap(),
+            Some(serde_json::json!(3))
+        );
+        assert_eq!(mgr.get_public_config("NONEXISTENT").unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_public_config_for_env() {
+        let mock_server = MockServer::start().await;
+        for (environment, api_url) in [("test", "http://test-api"), ("production", "http://prod-api")] {
+            Mock::given(method("GET"))
+                .and(path("/organizations/org-123/config/values"))
+                .and(query_param("environment", environment))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "values": {"API_URL": api_url}
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(
+                dir.path(),
+                &[
+                    ("default.json", r#"{"REGION_NAME":"default","RETRIES":3}"#),
+                    ("production.json", r#"{"REGION_NAME":"prod"}"#),
+                ],
+            );
+            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+            let mgr = ConfigManager::new()
+                .with_api_key("test-api-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(env);
+
+            assert_eq!(
+                mgr.get_public_config_for_env("API_URL", "test").unwrap(),
+                Some(serde_json::json!("http://test-api"))
+            );
+            for _ in 0..2 {
+                assert_eq!(
+                    mgr.get_public_config_for_env("API_URL", "production").unwrap(),
+                    Some(serde_json::json!("http://prod-api"))
+                );
+            }
+            assert_eq!(
+                mgr.get_public_config_for_env("REGION_NAME", "production").unwrap(),
+                Some(serde_json::json!("prod"))
+            );
+            assert_eq!(
+                mgr.get_public_config_for_env("RETRIES", "production").unwrap(),
+                Some(serde_json::json!(3))
+            );
+            assert_eq!(
+                mgr.get_public_config_for_env("ENV", "production").unwrap(),
+                Some(serde_json::json!("production"))
+            );
+            // The manager's own environment is untouched.
+            assert_eq!(
+                mgr.get_public_config("REGION_NAME").unwrap(),
+                Some(serde_json::json!("default"))
+            );
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_env_view_rejects_paths_and_backs_off_failures() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values"))
+            .and(query_param("environment", "staging"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(
+                dir.path(),
+                &[("default.json", "{}"), ("staging.json", r#"{"API_URL":"http://file"}"#)],
+            );
+            let mgr = ConfigManager::new()
+                .with_api_key("test-api-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(make_env(&config_dir, &[]));
+
+            for environment in ["../../etc/x", "a/b", "a\\b", ".."] {
+                let err = mgr.get_public_config_for_env("API_URL", environment).unwrap_err();
+                assert!(err.message.contains("Invalid environment name"), "{}", err.message);
+            }
+            // The failed fetch isn't retried on every read.
+            for _ in 0..3 {
+                assert_eq!(
+                    mgr.get_public_config_for_env("API_URL", "staging").unwrap(),
+                    Some(serde_json::json!("http://file"))
+                );
+            }
+
+            let local = ConfigManager::new()
+                .with_environment("test")
+                .with_env(make_env(&config_dir, &[]));
+            for n in 0..MAX_ENV_VIEWS + 8 {
+                local
+                    .get_public_config_for_env("API_URL", &format!("env-{}", n))
+                    .unwrap();
+            }
+            let views = &local.inner.read().unwrap().env_views;
+            assert_eq!(views.len(), MAX_ENV_VIEWS);
+            assert!(views.contains_key(&format!("env-{}", MAX_ENV_VIEWS + 7)));
+            assert!(!views.contains_key("env-0"));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tenant_override_layer() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values"))
+            .and(query_param_is_missing("tenant"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {"API_URL": "http://api", "THEME": "blue", "DB_PASSWORD": "shared"}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        for (tenant, values, calls) in [
+            (
+                "acme",
+                serde_json::json!({"THEME": "red", "DB_PASSWORD": "acme-only"}),
+                2,
+            ),
+            ("globex", serde_json::json!({}), 1),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/organizations/org-123/config/values"))
+                .and(query_param("environment", "test"))
+                .and(query_param("tenant", tenant))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"values": values})))
+                .expect(calls)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
+            let mgr = ConfigManager::new()
+                .with_api_key("test-api-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(make_env(&config_dir, &[]))
+                .with_max_tenants(1);
+
+            for _ in 0..2 {
+                assert_eq!(
+                    mgr.get_public_config_for_tenant("THEME", "acme").unwrap(),
+                    Some(serde_json::json!("red"))
+                );
+            }
+            assert_eq!(
+                mgr.get_public_config_for_tenant("API_URL", "acme").unwrap(),
+                Some(serde_json::json!("http://api"))
+            );
+            assert_eq!(
+                mgr.get_secret_config_for_tenant("DB_PASSWORD", "acme").unwrap(),
+                Some(serde_json::json!("acme-only").into())
+            );
+            // The environment's own values are untouched.
+            assert_eq!(mgr.get_public_config("THEME").unwrap(), Some(serde_json::json!("blue")));
+
+            // Reading globex evicts acme, so acme is fetched again.
+            assert_eq!(
+                mgr.get_public_config_for_tenant("THEME", "globex").unwrap(),
+                Some(serde_json::json!("blue"))
+            );
+            assert_eq!(
+                mgr.get_public_config_for_tenant("THEME", "acme").unwrap(),
+                Some(serde_json::json!("red"))
+            );
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_ttl_schedules_reload() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Cache-Control", "max-age=0")
+                    .set_body_json(serde_json::json!({"values": {"NEW_FLOW": true}})),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
+            let mgr = ConfigManager::new()
+                .with_api_key("test-api-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(make_env(&config_dir, &[]));
+            for _ in 0..2 {
+                assert_eq!(
+                    mgr.get_public_config("NEW_FLOW").unwrap(),
+                    Some(serde_json::json!(true))
+                );
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_oversized_server_ttl_is_capped() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Cache-Control", "max-age=18446744073709551615")
+                    .set_body_json(serde_json::json!({"values": {"NEW_FLOW": true}})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
+            let mgr = ConfigManager::new()
+                .with_api_key("test-api-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(make_env(&config_dir, &[]));
+            assert_eq!(
+                mgr.get_public_config("NEW_FLOW").unwrap(),
+                Some(serde_json::json!(true))
+            );
+            assert_eq!(
+                mgr.get_public_config_for_env("NEW_FLOW", "staging").unwrap(),
+                Some(serde_json::json!(true))
+            );
+        })
+        .await
+        .unwrap();
+    }
+
+    // --- Test 2: Remote Enrichment ---
+    #[tokio::test]
+    async fn test_remote_enrichment() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .and(query_param("environment", "test"))
+            .and(header("Authorization", "Bearer test-api-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "REMOTE_KEY": "remote-value",
+                    "REMOTE_NUM": 42
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL_KEY":"local-value"}"#)]);
+            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+            let mgr = ConfigManager::new()
+                .with_api_key("test-api-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(env);
+
+            let local = mgr.get_public_config("LOCAL_KEY").unwrap();
+            let remote = mgr.get_public_config("REMOTE_KEY").unwrap();
+            let remote_num = mgr.get_public_config("REMOTE_NUM").unwrap();
+            (local, remote, remote_num)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.0, Some(Value::String("local-value".to_string())));
+        assert_eq!(result.1, Some(Value::String("remote-value".to_string())));
+        assert_eq!(result.2, Some(serde_json::json!(42)));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_read_from_file() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .and(header("Authorization", "Bearer file-key"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"values": {"REMOTE_KEY": "remote-value"}})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
+            let key_file = dir.path().join("api-key");
+            fs::write(&key_file, "file-key\n").unwrap();
+            let key_file = key_file.to_string_lossy().to_string();
+
+            let from_builder = ConfigManager::new()
+                .with_api_key_file(&key_file)
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_env(make_env(
+                    &config_dir,
+                    &[("SMOOAI_CONFIG_ENV", "test"), ("SMOOAI_CONFIG_API_KEY", "env-key")],
+                ));
+            assert_eq!(
+                from_builder.get_public_config("REMOTE_KEY").unwrap(),
+                Some(serde_json::json!("remote-value"))
+            );
+
+            let from_env = ConfigManager::new()
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_env(make_env(
+                    &config_dir,
+                    &[("SMOOAI_CONFIG_ENV", "test"), ("SMOOAI_CONFIG_API_KEY_FILE", &key_file)],
+                ));
+            assert_eq!(
+                from_env.get_public_config("REMOTE_KEY").unwrap(),
+                Some(serde_json::json!("remote-value"))
+            );
+
+            // A missing file falls back to the env var (which the mock rejects).
+            let missing = ConfigManager::new()
+                .with_api_key_file(dir.path().join("nope"))
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_env(make_env(
+                    &config_dir,
+                    &[("SMOOAI_CONFIG_ENV", "test"), ("SMOOAI_CONFIG_API_KEY", "env-key")],
+                ));
+            assert_eq!(missing.resolve_api_key().as_deref(), Some("env-key"));
+            assert_eq!(missing.get_public_config("REMOTE_KEY").unwrap(), None);
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_api_key_file_reread_after_401_and_invalidate() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .and(header("Authorization", "Bearer old-key"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
+            let key_file = dir.path().join("api-key");
+            fs::write(&key_file, "old-key").unwrap();
+
+            let mgr = ConfigManager::new()
+                .with_api_key_file(&key_file)
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]));
+            assert_eq!(mgr.resolve_api_key().as_deref(), Some("old-key"));
+
+            // The key was rotated on disk; the 401 makes the manager re-read it.
+            fs::write(&key_file, "new-key").unwrap();
+            assert_eq!(mgr.resolve_api_key().as_deref(), Some("old-key"));
+            assert_eq!(mgr.get_public_config("REMOTE_KEY").unwrap(), None);
+            assert_eq!(mgr.resolve_api_key().as_deref(), Some("new-key"));
+
+            fs::write(&key_file, "newer-key").unwrap();
+            mgr.invalidate();
+            assert_eq!(mgr.resolve_api_key().as_deref(), Some("newer-key"));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[cfg(feature = "keychain")]
+    #[test]
+    fn test_missing_keychain_entry_falls_back_to_env() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let mgr = ConfigManager::new()
+            .with_api_key_from_keychain("smooai-config-test", "missing")
+            .with_env(HashMap::from([(
+                "SMOOAI_CONFIG_API_KEY".to_string(),
+                "env-key".to_string(),
+            )]));
+        assert_eq!(mgr.resolve_api_key().as_deref(), Some("env-key"));
+    }
+
+    #[tokio::test]
+    async fn test_remote_client_reused_and_timed_out() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"values": {"REMOTE_KEY": "remote-value"}}))
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL_KEY":"local"}"#)]);
+            let mgr = ConfigManager::new()
+                .with_api_key("test-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]))
+                .with_request_timeout(Duration::from_millis(50));
+
+            assert_eq!(mgr.get_public_config("REMOTE_KEY").unwrap(), None);
+            assert_eq!(
+                mgr.get_public_config("LOCAL_KEY").unwrap(),
+                Some(serde_json::json!("local"))
+            );
+
+            let client: *const reqwest::blocking::Client = mgr.http_client();
+            mgr.invalidate();
+            mgr.init().unwrap();
+            assert!(std::ptr::eq(client, mgr.http_client()));
+        })
+        .await
+        .unwrap();
+    }
+
+    // --- Test 3: Merge Precedence (env > remote > file) ---
+    #[tokio::test]
+    async fn test_merge_precedence() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "API_URL": "http://remote-api",
+                    "REMOTE_ONLY": "from-remote"
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(
+                dir.path(),
+                &[(
+                    "default.json",
+                    r#"{"API_URL":"http://file-api","FILE_ONLY":"from-file"}"#,
+                )],
+            );
+
+            let mut schema_keys = HashSet::new();
+            schema_keys.insert("API_URL".to_string());
+
+            let env = make_env(
+                &config_dir,
+                &[("SMOOAI_CONFIG_ENV", "test"), ("API_URL", "http://env-api")],
+            );
+
+            let mgr = ConfigManager::new()
+                .with_api_key("test-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_schema_keys(schema_keys)
+                .with_env(env);
+
+            let api_url = mgr.get_public_config("API_URL").unwrap();
+            let remote_only = mgr.get_public_config("REMOTE_ONLY").unwrap();
+            let file_only = mgr.get_public_config("FILE_ONLY").unwrap();
+            (api_url, remote_only, file_only)
+        })
+        .await
+        .unwrap();
+
+        // Env wins over remote and file
+        assert_eq!(result.0, Some(Value::String("http://env-api".to_string())));
+        // Remote still accessible
+        assert_eq!(result.1, Some(Value::String("from-remote".to_string())));
+        // File still accessible
+        assert_eq!(result.2, Some(Value::String("from-file".to_string())));
+    }
+
+    // --- Test 4: Nested Object Merge ---
+    #[tokio::test]
+    async fn test_nested_object_merge() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "DATABASE": {"host": "remote-db.example.com", "ssl": true}
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(
+                dir.path(),
+                &[(
+                    "default.json",
+                    r#"{"DATABASE":{"host":"localhost","port":5432,"ssl":false}}"#,
+                )],
+            );
+            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+            let mgr = ConfigManager::new()
+                .with_api_key("test-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(env);
+
+            mgr.get_public_config("DATABASE").unwrap()
+        })
+        .await
+        .unwrap();
+
+        let db = result.unwrap();
+        let obj = db.as_object().unwrap();
+        // Remote overrides host and ssl
+        assert_eq!(obj["host"], serde_json::json!("remote-db.example.com"));
+        assert_eq!(obj["ssl"], serde_json::json!(true));
+        // File's port is preserved
+        assert_eq!(obj["port"], serde_json::json!(5432));
+    }
+
+    // --- Test 5: Graceful Degradation (500 response) ---
+    #[tokio::test]
+    async fn test_graceful_degradation_on_server_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://fallback"}"#)]);
+            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+            let mgr = ConfigManager::new()
+                .with_api_key("test-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_env(env);
+
+            mgr.get_public_config("API_URL").unwrap()
+        })
+        .await
+        .unwrap();
+
+        // Falls back to file config
+        assert_eq!(result, Some(Value::String("http://fallback".to_string())));
+    }
+
+    // --- Test 6: Three Tiers Independent ---
+    #[test]
+    fn test_three_tiers_independent() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"API_URL":"http://localhost","DB_PASS":"secret123","ENABLE_BETA":true}"#,
+            )],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        // Each tier sees the same merged config
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(Value::String("http://localhost".to_string()))
+        );
+        assert_eq!(
+            mgr.get_secret_config("DB_PASS").unwrap(),
+            Some(Value::String("secret123".to_string()).into())
+        );
+        assert_eq!(mgr.get_feature_flag("ENABLE_BETA").unwrap(), Some(Value::Bool(true)));
+
+        // Each tier has its own cache — accessing same key in different tiers
+        // doesn't interfere
+        assert_eq!(
+            mgr.get_secret_config("API_URL").unwrap(),
+            Some(Value::String("http://localhost".to_string()).into())
+        );
+    }
+
+    // --- Test 7: Cache Behavior ---
+    #[test]
+    fn test_cache_behavior() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new()
+            .with_cache_ttl(Duration::from_millis(50))
+            .with_env(env);
+
+        // First access initializes and caches
+        let val1 = mgr.get_public_config("API_URL").unwrap();
+        assert_eq!(val1, Some(Value::String("http://localhost".to_string())));
+
+        // Second access should come from cache
+        let val2 = mgr.get_public_config("API_URL").unwrap();
+        assert_eq!(val2, Some(Value::String("http://localhost".to_string())));
+
+        // Wait for cache to expire
+        std::thread::sleep(Duration::from_millis(60));
+
+        // After expiry, still returns the same value (re-reads from merged config)
+        let val3 = mgr.get_public_config("API_URL").unwrap();
+        assert_eq!(val3, Some(Value::String("http://localhost".to_string())));
+    }
+
+    // --- Test 8: API Creds from Env ---
+    #[tokio::test]
+    async fn test_api_creds_from_env() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/env-org-id/config/values"))
+            .and(header("Authorization", "Bearer env-api-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "FROM_REMOTE": "yes"
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL":"val"}"#)]);
+            let env = make_env(
+                &config_dir,
+                &[
+                    ("SMOOAI_CONFIG_ENV", "test"),
+                    ("SMOOAI_CONFIG_API_KEY", "env-api-key"),
+                    ("SMOOAI_CONFIG_API_URL", &url),
+                    ("SMOOAI_CONFIG_ORG_ID", "env-org-id"),
+                ],
+            );
+
+            // No constructor API params — all from env
+            let mgr = ConfigManager::new().with_env(env);
+            mgr.get_public_config("FROM_REMOTE").unwrap()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, Some(Value::String("yes".to_string())));
+    }
+
+    // --- Test 9: API Creds from Constructor ---
+    #[tokio::test]
+    async fn test_api_creds_from_constructor() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/ctor-org/config/values"))
+            .and(header("Authorization", "Bearer ctor-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "CTOR_REMOTE": "works"
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL":"val"}"#)]);
+            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+            let mgr = ConfigManager::new()
+                .with_api_key("ctor-key")
+                .with_base_url(&url)
+                .with_org_id("ctor-org")
+                .with_environment("test")
+                .with_env(env);
+
+            mgr.get_public_config("CTOR_REMOTE").unwrap()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, Some(Value::String("works".to_string())));
+    }
+
+    // --- Test 10: Thread Safety ---
+    #[test]
+    fn test_thread_safety() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[("default.json", r#"{"API_URL":"http://localhost","COUNT":42}"#)],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = Arc::new(ConfigManager::new().with_env(env));
+
+        let mut handles = vec![];
+        for _ in 0..10 {
+            let mgr = Arc::clone(&mgr);
+            handles.push(std::thread::spawn(move || {
+                let val = mgr.get_public_config("API_URL").unwrap();
+                assert_eq!(val, Some(Value::String("http://localhost".to_string())));
+                let count = mgr.get_public_config("COUNT").unwrap();
+                assert_eq!(count, Some(serde_json::json!(42)));
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    // --- Test 11: Full Integration (temp dir + mock HTTP + env) ---
+    #[tokio::test]
+    async fn test_full_integration() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "REMOTE_SETTING": "from-api",
+                    "SHARED_KEY": "remote-wins-over-file"
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
+        let result = tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(
+                dir.path(),
+                &[(
+                    "default.json",
+                    r#"{"FILE_SETTING":"from-file","SHARED_KEY":"file-value"}"#,
+                )],
+            );
+
+            let mut schema_keys = HashSet::new();
+            schema_keys.insert("SHARED_KEY".to_string());
+
+            let env = make_env(
+                &config_dir,
+                &[("SMOOAI_CONFIG_ENV", "test"), ("SHARED_KEY", "env-wins-over-all")],
+            );
+
+            let mgr = ConfigManager::new()
+                .with_api_key("test-key")
+                .with_base_url(&url)
+                .with_org_id("org-123")
+                .with_environment("test")
+                .with_schema_keys(schema_keys)
+                .with_env(env);
+
+            let file = mgr.get_public_config("FILE_SETTING").unwrap();
+            let remote = mgr.get_public_config("REMOTE_SETTING").unwrap();
+            let shared = mgr.get_public_config("SHARED_KEY").unwrap();
+            (file, remote, shared)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.0, Some(Value::String("from-file".to_string())));
+        assert_eq!(result.1, Some(Value::String("from-api".to_string())));
+        // Env wins over remote and file
+        assert_eq!(result.2, Some(Value::String("env-wins-over-all".to_string())));
+    }
+
+    // --- Test 12: Environment Resolution ---
+    #[test]
+    fn test_environment_resolution_from_constructor() {
+        let mgr = ConfigManager::new().with_environment("staging");
+        assert_eq!(mgr.resolve_environment(), "staging");
+    }
+
+    #[test]
+    fn test_environment_resolution_from_env_var() {
+        let env: HashMap<String, String> = [("SMOOAI_CONFIG_ENV".to_string(), "production".to_string())]
+            .into_iter()
+            .collect();
+        let mgr = ConfigManager::new().with_env(env);
+        assert_eq!(mgr.resolve_environment(), "production");
+    }
+
+    #[test]
+    fn test_environment_resolution_default() {
+        let env: HashMap<String, String> = HashMap::new();
+        let mgr = ConfigManager::new().with_env(env);
+        assert_eq!(mgr.resolve_environment(), "development");
+    }
+
+    #[test]
+    fn test_environment_constructor_overrides_env_var() {
+        let env: HashMap<String, String> = [("SMOOAI_CONFIG_ENV".to_string(), "from-env".to_string())]
+            .into_iter()
+            .collect();
+        let mgr = ConfigManager::new().with_environment("from-constructor").with_env(env);
+        assert_eq!(mgr.resolve_environment(), "from-constructor");
+    }
+
+    // --- Test 13: Invalidation Re-fetches ---
+    #[tokio::test]
+    async fn test_invalidation_refetches() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {
+                    "DYNAMIC": "value-1"
+                }
+            })))
+            .expect(2) // Should be called twice (initial + after invalidation)
+            .mount(&mock_server)
+            .await;
+
+        let url = mock_server.uri();
         let result = tokio::task::spawn_blocking(move || {
             let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL_KEY":"local-value"}"#)]);
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL":"val"}"#)]);
             let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
 
             let mgr = ConfigManager::new()
-                .with_api_key("test-api-key")
+                .with_api_key("test-key")
                 .with_base_url(&url)
                 .with_org_id("org-123")
                 .with_environment("test")
                 .with_env(env);
 
-            let local = mgr.get_public_config("LOCAL_KEY").unwrap();
-            let remote = mgr.get_public_config("REMOTE_KEY").unwrap();
-            let remote_num = mgr.get_public_config("REMOTE_NUM").unwrap();
-            (local, remote, remote_num)
+            // First access
+            let val1 = mgr.get_public_config("DYNAMIC").unwrap();
+
+            // Invalidate
+            mgr.invalidate();
+
+            // Second access should re-initialize (re-fetch remote)
+            let val2 = mgr.get_public_config("DYNAMIC").unwrap();
+
+            (val1, val2)
         })
         .await
         .unwrap();
 
-        assert_eq!(result.0, Some(Value::String("local-value".to_string())));
-        assert_eq!(result.1, Some(Value::String("remote-value".to_string())));
-        assert_eq!(result.2, Some(serde_json::json!(42)));
+        assert_eq!(result.0, Some(Value::String("value-1".to_string())));
+        assert_eq!(result.1, Some(Value::String("value-1".to_string())));
     }
 
-    // --- Test 3: Merge Precedence (env > remote > file) ---
+    // --- Test: Lazy Initialization ---
+    #[test]
+    fn test_lazy_initialization() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        assert!(!mgr.inner.read().unwrap().initialized);
+        mgr.get_public_config("API_URL").unwrap();
+        assert!(mgr.inner.read().unwrap().initialized);
+    }
+
+    // --- Test: Returns None for Missing Key ---
+    #[test]
+    fn test_returns_none_for_missing_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"test"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        assert_eq!(mgr.get_public_config("NONEXISTENT").unwrap(), None);
+    }
+
+    // --- Test: Invalidate Clears State ---
+    #[test]
+    fn test_invalidate_clears_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        mgr.get_public_config("API_URL").unwrap();
+        assert!(mgr.inner.read().unwrap().initialized);
+
+        mgr.invalidate();
+        assert!(!mgr.inner.read().unwrap().initialized);
+        assert!(mgr.inner.read().unwrap().public_cache.is_empty());
+        assert!(mgr.inner.read().unwrap().config.is_empty());
+    }
+
+    // --- Test: Invalidate Allows Reinitialization ---
+    #[test]
+    fn test_invalidate_allows_reinitialization() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        mgr.get_public_config("API_URL").unwrap();
+        mgr.invalidate();
+
+        let result = mgr.get_public_config("API_URL").unwrap();
+        assert_eq!(result, Some(Value::String("http://localhost".to_string())));
+    }
+
+    // --- Test: Basic Deferred Value ---
+    #[test]
+    fn test_basic_deferred_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"HOST":"localhost","PORT":5432}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+        let mgr = ConfigManager::new().with_env(env).with_deferred(
+            "FULL_URL",
+            Box::new(|config| {
+                let host = config["HOST"].as_str().unwrap_or("unknown");
+                let port = config["PORT"].as_u64().unwrap_or(0);
+                serde_json::json!(format!("{}:{}", host, port))
+            }),
+        );
+
+        assert_eq!(
+            mgr.get_public_config("FULL_URL").unwrap(),
+            Some(serde_json::json!("localhost:5432"))
+        );
+        // Original values preserved
+        assert_eq!(
+            mgr.get_public_config("HOST").unwrap(),
+            Some(serde_json::json!("localhost"))
+        );
+        assert_eq!(mgr.get_public_config("PORT").unwrap(), Some(serde_json::json!(5432)));
+    }
+
+    // --- Test: Multiple Deferred See Pre-Resolution Snapshot ---
+    #[test]
+    fn test_multiple_deferred_see_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"BASE":"hello"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_deferred(
+                "A",
+                Box::new(|config| {
+                    let base = config["BASE"].as_str().unwrap_or("");
+                    serde_json::json!(format!("{}-a", base))
+                }),
+            )
+            .with_deferred(
+                "B",
+                Box::new(|config| {
+                    // B should NOT see A's resolved value
+                    serde_json::json!(config.contains_key("A"))
+                }),
+            );
+
+        assert_eq!(mgr.get_public_config("A").unwrap(), Some(serde_json::json!("hello-a")));
+        // B should see that A was NOT in the snapshot
+        assert_eq!(mgr.get_public_config("B").unwrap(), Some(serde_json::json!(false)));
+    }
+
+    // --- Test: Deferred Runs After Full Merge ---
+    #[test]
+    fn test_deferred_runs_after_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"HOST":"file-host"}"#)]);
+
+        let mut schema_keys = HashSet::new();
+        schema_keys.insert("HOST".to_string());
+
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test"), ("HOST", "env-host")]);
+
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_schema_keys(schema_keys)
+            .with_deferred(
+                "API_URL",
+                Box::new(|config| {
+                    let host = config["HOST"].as_str().unwrap_or("unknown");
+                    serde_json::json!(format!("https://{}/api", host))
+                }),
+            );
+
+        // Env overrides file, deferred sees env value
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("https://env-host/api"))
+        );
+    }
+
+    // --- Test: No Remote Without Credentials ---
+    #[test]
+    fn test_no_remote_without_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+        // No API key, base URL, or org ID — should work fine with just local config
+        let mgr = ConfigManager::new().with_env(env);
+
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(Value::String("http://localhost".to_string()))
+        );
+    }
+
+    // --- Test: Graceful Fallback When No Config Files ---
+    #[test]
+    fn test_graceful_fallback_no_config_files() {
+        // Point to a directory with no config files
+        let dir = tempfile::tempdir().unwrap();
+        let empty_dir = dir.path().join("empty");
+        fs::create_dir_all(&empty_dir).unwrap();
+
+        let env: HashMap<String, String> = [(
+            "SMOOAI_ENV_CONFIG_DIR".to_string(),
+            empty_dir.to_string_lossy().to_string(),
+        )]
+        .into_iter()
+        .collect();
+
+        let mgr = ConfigManager::new().with_env(env);
+
+        // Should not error — file config failure is graceful
+        let result = mgr.get_public_config("ANYTHING").unwrap();
+        assert_eq!(result, None);
+    }
+
+    // --- Test: Constructor Params Override Env Vars ---
     #[tokio::test]
-    async fn test_merge_precedence() {
+    async fn test_constructor_params_override_env_vars() {
         let mock_server = MockServer::start().await;
 
+        // The mock expects the constructor org ID, not the env var one
         Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/.+/config/values"))
+            .and(path_regex(r"/organizations/ctor-org/config/values"))
+            .and(header("Authorization", "Bearer ctor-key"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "API_URL": "http://remote-api",
-                    "REMOTE_ONLY": "from-remote"
-                }
+                "values": {"RESULT": "from-ctor-params"}
             })))
             .mount(&mock_server)
             .await;
 
         let url = mock_server.uri();
         let result = tokio::task::spawn_blocking(move || {
-            let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(
-                dir.path(),
-                &[(
-                    "default.json",
-                    r#"{"API_URL":"http://file-api","FILE_ONLY":"from-file"}"#,
-                )],
-            );
-
-            let mut schema_keys = HashSet::new();
-            schema_keys.insert("API_URL".to_string());
-
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"L":"v"}"#)]);
             let env = make_env(
                 &config_dir,
-                &[("SMOOAI_CONFIG_ENV", "test"), ("API_URL", "http://env-api")],
+                &[
+                    ("SMOOAI_CONFIG_ENV", "test"),
+                    ("SMOOAI_CONFIG_API_KEY", "env-key"),
+                    ("SMOOAI_CONFIG_API_URL", "http://should-not-use"),
+                    ("SMOOAI_CONFIG_ORG_ID", "env-org"),
+                ],
             );
 
             let mgr = ConfigManager::new()
-                .with_api_key("test-key")
+                .with_api_key("ctor-key")
                 .with_base_url(&url)
-                .with_org_id("org-123")
+                .with_org_id("ctor-org")
                 .with_environment("test")
-                .with_schema_keys(schema_keys)
                 .with_env(env);
 
-            let api_url = mgr.get_public_config("API_URL").unwrap();
-            let remote_only = mgr.get_public_config("REMOTE_ONLY").unwrap();
-            let file_only = mgr.get_public_config("FILE_ONLY").unwrap();
-            (api_url, remote_only, file_only)
+            mgr.get_public_config("RESULT").unwrap()
         })
         .await
         .unwrap();
 
-        // Env wins over remote and file
-        assert_eq!(result.0, Some(Value::String("http://env-api".to_string())));
-        // Remote still accessible
-        assert_eq!(result.1, Some(Value::String("from-remote".to_string())));
-        // File still accessible
-        assert_eq!(result.2, Some(Value::String("from-file".to_string())));
+        assert_eq!(result, Some(Value::String("from-ctor-params".to_string())));
     }
 
-    // --- Test 4: Nested Object Merge ---
-    #[tokio::test]
-    async fn test_nested_object_merge() {
-        let mock_server = MockServer::start().await;
+    // --- SMOODEV-958: undefined-key error ---
+    #[test]
+    fn test_undefined_key_returns_friendly_error() {
+        use crate::utils::SmooaiConfigErrorKind;
 
-        Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/.+/config/values"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "DATABASE": {"host": "remote-db.example.com", "ssl": true}
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"KNOWN":"v"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mut schema: HashSet<String> = HashSet::new();
+        schema.insert("KNOWN".to_string());
+        let mgr = ConfigManager::new()
+            .with_schema_keys(schema)
+            .with_strict_schema_keys(true)
+            .with_env(env);
+
+        let err = mgr.get_public_config("BOGUS").unwrap_err();
+        assert!(err.message.contains("'BOGUS'"));
+        assert!(err.message.contains("not defined"));
+        assert!(err.message.contains("SecretConfigKeys"));
+        assert!(err.message.contains(".smooai-config/config.ts"));
+        match err.kind {
+            SmooaiConfigErrorKind::UndefinedKey { key, schema_path } => {
+                assert_eq!(key, "BOGUS");
+                assert_eq!(schema_path, ".smooai-config/config.ts");
+            }
+            _ => panic!("expected UndefinedKey kind"),
+        }
+    }
+
+    #[test]
+    fn test_undefined_key_honors_custom_schema_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mut schema: HashSet<String> = HashSet::new();
+        schema.insert("KNOWN".to_string());
+        let mgr = ConfigManager::new()
+            .with_schema_keys(schema)
+            .with_strict_schema_keys(true)
+            .with_schema_path("custom/path.ts")
+            .with_env(env);
+
+        let err = mgr.get_secret_config("BOGUS").unwrap_err();
+        assert!(err.message.contains("custom/path.ts"));
+    }
+
+    #[test]
+    fn test_known_key_passes_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"KNOWN":"v"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mut schema: HashSet<String> = HashSet::new();
+        schema.insert("KNOWN".to_string());
+        let mgr = ConfigManager::new().with_schema_keys(schema).with_env(env);
+
+        assert_eq!(
+            mgr.get_public_config("KNOWN").unwrap(),
+            Some(Value::String("v".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_no_schema_keys_disables_guard() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+        // No schema_keys → guard disabled → returns None for unknown keys.
+        assert_eq!(mgr.get_public_config("WHATEVER").unwrap(), None);
+    }
+
+    // --- Required keys ---
+    #[test]
+    fn test_required_keys_reports_all_missing() {
+        use crate::utils::SmooaiConfigErrorKind;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"x","DB_HOST":null}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new()
+            .with_required_keys(["API_URL", "DB_HOST", "JWT_SECRET"])
+            .with_env(env);
+
+        let err = mgr.init().unwrap_err();
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::MissingRequiredKeys {
+                keys: vec!["DB_HOST".to_string(), "JWT_SECRET".to_string()]
+            }
+        );
+        // First access fails the same way and the manager stays uninitialized.
+        assert!(mgr.get_public_config("API_URL").is_err());
+        assert!(!mgr.inner.read().unwrap().initialized);
+    }
+
+    #[test]
+    fn test_required_keys_accept_upper_snake_spelling() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"x","jwtSecret":"s"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new()
+            .with_required_keys(vec!["apiUrl".to_string(), "jwtSecret".to_string()])
+            .with_env(env);
+
+        mgr.init().unwrap();
+        assert_eq!(mgr.get_public_config("API_URL").unwrap(), Some(serde_json::json!("x")));
+    }
+
+    #[test]
+    fn test_definition_supplies_schema_keys_and_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"MAX_RETRIES":1}"#)]);
+        let env = make_env(
+            &config_dir,
+            &[("SMOOAI_CONFIG_ENV", "test"), ("MAX_RETRIES", "5"), ("NEW_UI", "true")],
+        );
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {"maxRetries": {"type": "integer"}}})),
+            None,
+            Some(serde_json::json!({"type": "object", "properties": {"newUi": {"type": "boolean"}}})),
+        );
+        let mgr = ConfigManager::new().with_env(env).with_definition(definition);
+
+        assert_eq!(
+            mgr.get_public_config("MAX_RETRIES").unwrap(),
+            Some(serde_json::json!(5.0))
+        );
+        assert_eq!(mgr.get_feature_flag("NEW_UI").unwrap(), Some(serde_json::json!(true)));
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn test_value_validation_fails_init() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"MAX_RETRIES":"three"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {"maxRetries": {"type": "integer"}}})),
+            None,
+            None,
+        );
+
+        let lenient = ConfigManager::new()
+            .with_env(env.clone())
+            .with_definition(definition.clone());
+        assert!(lenient.get_public_config("MAX_RETRIES").is_ok());
+
+        let strict = ConfigManager::new()
+            .with_env(env)
+            .with_definition(definition)
+            .with_value_validation();
+        let err = strict.get_public_config("MAX_RETRIES").unwrap_err();
+        let SmooaiConfigErrorKind::InvalidValues { violations } = err.kind else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "/MAX_RETRIES");
+    }
+
+    // --- Tier enforcement ---
+    #[test]
+    fn test_definition_enforces_tiers() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"API_URL":"https://api","API_KEY":"sk","EXTRA":"x"}"#,
+            )],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {"apiUrl": {"type": "string"}}})),
+            Some(serde_json::json!({"type": "object", "properties": {"apiKey": {"type": "string"}}})),
+            None,
+        );
+        let mgr = ConfigManager::new().with_env(env).with_definition(definition);
+
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("https://api"))
+        );
+        assert_eq!(
+            mgr.get_secret_config("API_KEY").unwrap(),
+            Some(serde_json::json!("sk").into())
+        );
+
+        let err = mgr.get_secret_config("API_URL").unwrap_err();
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::WrongTier {
+                key: "API_URL".to_string(),
+                requested: ConfigTier::Secret,
+                declared: ConfigTier::Public,
+            }
+        );
+        assert!(mgr.get_feature_flag("API_KEY").is_err());
+        assert!(mgr.get_bytes_decoded("API_URL").is_err());
+
+        // Undeclared keys are not restricted.
+        assert_eq!(
+            mgr.get_secret_config("EXTRA").unwrap(),
+            Some(serde_json::json!("x").into())
+        );
+    }
+
+    #[test]
+    fn test_builtin_keys_can_be_turned_off() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"ENV":"blue-green"}"#)]);
+        let env = make_env(
+            &config_dir,
+            &[("SMOOAI_CONFIG_ENV", "production"), ("REGION", "eu-ops")],
+        );
+        let schema = HashSet::from(["REGION".to_string()]);
+
+        let mgr = ConfigManager::new()
+            .with_env(env.clone())
+            .with_schema_keys(schema.clone());
+        assert_eq!(
+            mgr.get_public_config("ENV").unwrap(),
+            Some(serde_json::json!("production"))
+        );
+        assert_eq!(
+            mgr.get_public_config("IS_LOCAL").unwrap(),
+            Some(serde_json::json!(false))
+        );
+
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_schema_keys(schema)
+            .with_builtin_keys(false);
+        assert_eq!(
+            mgr.get_public_config("ENV").unwrap(),
+            Some(serde_json::json!("blue-green"))
+        );
+        assert_eq!(
+            mgr.get_public_config("REGION").unwrap(),
+            Some(serde_json::json!("eu-ops"))
+        );
+        assert_eq!(mgr.get_public_config("IS_LOCAL").unwrap(), None);
+    }
+
+    #[test]
+    fn test_auto_detect_local() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[
+                ("default.json", r#"{"API_URL":"https://api"}"#),
+                ("local.json", r#"{"API_URL":"http://localhost"}"#),
+            ],
+        );
+        let read = |extra: &[(&str, &str)], auto: bool| {
+            ConfigManager::new()
+                .with_env(make_env(&config_dir, extra))
+                .with_auto_detect_local(auto)
+                .get_public_config("API_URL")
+                .unwrap()
+                .unwrap()
+        };
+
+        let local = if cfg!(debug_assertions) {
+            "http://localhost"
+        } else {
+            "https://api"
+        };
+        assert_eq!(read(&[], true), local);
+        assert_eq!(read(&[], false), "https://api");
+        assert_eq!(read(&[("IS_LOCAL", "false")], true), "https://api");
+        assert_eq!(read(&[("AWS_REGION", "us-east-1")], true), "https://api");
+    }
+
+    #[test]
+    fn test_contains_key_tells_null_from_missing() {
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("CHECKOUT_URL".to_string(), serde_json::json!("https://pay")),
+            ("LEGACY_URL".to_string(), Value::Null),
+        ]));
+        assert!(mgr.contains_key("CHECKOUT_URL").unwrap());
+        assert!(mgr.contains_key("LEGACY_URL").unwrap());
+        assert_eq!(mgr.get_public_config("LEGACY_URL").unwrap(), Some(Value::Null));
+        assert!(!mgr.contains_key("MISSING").unwrap());
+        assert_eq!(mgr.get_public_config("MISSING").unwrap(), None);
+
+        let _guard = mgr.push_overrides(HashMap::from([("MISSING".to_string(), Value::Null)]));
+        assert!(mgr.contains_key("MISSING").unwrap());
+    }
+
+    #[test]
+    fn test_prefix_listing() {
+        let definition = crate::schema::define_config(
+            None,
+            Some(serde_json::json!({"type": "object", "properties": {
+                "integrationSlackToken": {"type": "string"},
+                "integrationGithubToken": {"type": "string"}
+            }})),
+            None,
+        );
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("INTEGRATION_SLACK_TOKEN".to_string(), serde_json::json!("xoxb")),
+            ("INTEGRATION_GITHUB_TOKEN".to_string(), serde_json::json!("ghp")),
+            ("INTEGRATION_ENABLED".to_string(), serde_json::json!(true)),
+            ("API_URL".to_string(), serde_json::json!("https://api")),
+        ]))
+        .with_definition(definition);
+
+        assert_eq!(
+            mgr.keys_with_prefix("INTEGRATION_").unwrap(),
+            [
+                "INTEGRATION_ENABLED",
+                "INTEGRATION_GITHUB_TOKEN",
+                "INTEGRATION_SLACK_TOKEN"
+            ]
+        );
+        let all = mgr.get_all_with_prefix("INTEGRATION_").unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all["INTEGRATION_ENABLED"], serde_json::json!(true));
+        assert_eq!(all["INTEGRATION_SLACK_TOKEN"], serde_json::json!(REDACTED));
+        assert!(mgr.keys_with_prefix("NOPE_").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_required_and_default_getters() {
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("API_URL".to_string(), serde_json::json!("https://api")),
+            ("UNSET".to_string(), Value::Null),
+        ]));
+        assert_eq!(
+            mgr.get_required_public_config("API_URL").unwrap(),
+            serde_json::json!("https://api")
+        );
+        for key in ["MISSING", "UNSET"] {
+            let err = mgr.get_required_public_config(key).unwrap_err();
+            assert_eq!(
+                err.kind,
+                SmooaiConfigErrorKind::MissingRequiredKeys {
+                    keys: vec![key.to_string()]
                 }
-            })))
-            .mount(&mock_server)
-            .await;
+            );
+            assert_eq!(mgr.get_public_config_or(key, 3).unwrap(), serde_json::json!(3));
+        }
+        assert_eq!(
+            mgr.get_public_config_or("API_URL", "fallback").unwrap(),
+            serde_json::json!("https://api")
+        );
+        assert!(mgr.get_public_config_or("", "fallback").is_err());
+    }
 
-        let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
-            let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(
-                dir.path(),
-                &[(
-                    "default.json",
-                    r#"{"DATABASE":{"host":"localhost","port":5432,"ssl":false}}"#,
-                )],
+    #[test]
+    fn test_locked_secrets_cache() {
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("DB_PASSWORD".to_string(), serde_json::json!("hunter2")),
+            ("API_URL".to_string(), serde_json::json!("https://api")),
+        ]))
+        .with_locked_secrets();
+
+        for _ in 0..2 {
+            assert_eq!(
+                mgr.get_secret_config("DB_PASSWORD").unwrap(),
+                Some(serde_json::json!("hunter2").into())
             );
-            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        }
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("https://api"))
+        );
+        {
+            let inner = mgr.inner.read().unwrap();
+            assert!(matches!(
+                inner.secret_cache["DB_PASSWORD"].value,
+                CachedValue::Locked(_)
+            ));
+            assert!(matches!(inner.public_cache["API_URL"].value, CachedValue::Shared(_)));
+        }
+
+        mgr.invalidate_key("DB_PASSWORD");
+        assert!(!mgr.inner.read().unwrap().secret_cache.contains_key("DB_PASSWORD"));
+        assert_eq!(
+            mgr.get_secret_config("DB_PASSWORD").unwrap(),
+            Some(serde_json::json!("hunter2").into())
+        );
+    }
+
+    #[test]
+    fn test_locked_secrets_wipe_loaded_values() {
+        let mgr = ConfigManager::from_values(HashMap::from([(
+            "DB_PASSWORD".to_string(),
+            serde_json::json!({"primary": "hunter2"}),
+        )]))
+        .with_locked_secrets();
+        mgr.get_secret_config("DB_PASSWORD").unwrap();
+
+        let mut inner = mgr.inner.write().unwrap();
+        inner.wipe_loaded();
+        assert_eq!(inner.config["DB_PASSWORD"], Value::Null);
+        assert!(inner.layers.iter().all(|(_, values)| values["DB_PASSWORD"].is_null()));
+        drop(inner);
+
+        // Reloads replace the wiped values with fresh ones.
+        mgr.invalidate();
+        assert_eq!(
+            mgr.get_secret_config("DB_PASSWORD").unwrap(),
+            Some(serde_json::json!({"primary": "hunter2"}).into())
+        );
+
+        let plain = ConfigManager::from_values(HashMap::from([("K".to_string(), serde_json::json!("v"))]));
+        plain.get_public_config("K").unwrap();
+        let mut inner = plain.inner.write().unwrap();
+        inner.wipe_loaded();
+        assert_eq!(inner.config["K"], serde_json::json!("v"));
+    }
+
+    #[test]
+    fn test_locked_secrets_wiped_when_load_fails_checks() {
+        let values = HashMap::from([("DB_PASSWORD".to_string(), serde_json::json!("hunter2"))]);
+        let mgr = ConfigManager::from_values(values.clone())
+            .with_required_keys(["API_URL"])
+            .with_locked_secrets();
+        let err = mgr.get_secret_config("DB_PASSWORD").unwrap_err();
+        assert!(matches!(err.kind, SmooaiConfigErrorKind::MissingRequiredKeys { .. }));
+        let inner = mgr.inner.read().unwrap();
+        assert!(inner.config.is_empty() && inner.layers.is_empty());
+        drop(inner);
+
+        #[cfg(feature = "jsonschema")]
+        {
+            let definition = crate::schema::define_config(
+                Some(serde_json::json!({"type": "object", "properties": {"maxRetries": {"type": "integer"}}})),
+                Some(serde_json::json!({"type": "object", "properties": {"dbPassword": {"type": "string"}}})),
+                None,
+            );
+            let mut values = values;
+            values.insert("MAX_RETRIES".to_string(), serde_json::json!("three"));
+            let mgr = ConfigManager::from_values(values)
+                .with_definition(definition)
+                .with_value_validation()
+                .with_locked_secrets();
+            let err = mgr.get_secret_config("DB_PASSWORD").unwrap_err();
+            assert!(matches!(err.kind, SmooaiConfigErrorKind::InvalidValues { .. }));
+            let inner = mgr.inner.read().unwrap();
+            assert!(inner.config.is_empty() && inner.layers.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_access_policy_limits_reads() {
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {"apiUrl": {"type": "string"}}})),
+            Some(serde_json::json!({"type": "object", "properties": {
+                "pluginToken": {"type": "string"},
+                "dbPassword": {"type": "string"}
+            }})),
+            None,
+        );
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("API_URL".to_string(), serde_json::json!("https://api")),
+            ("PLUGIN_TOKEN".to_string(), serde_json::json!("pt")),
+            ("DB_PASSWORD".to_string(), serde_json::json!("hunter2")),
+            ("checkout/new-flow".to_string(), serde_json::json!(true)),
+            ("checkout/admin".to_string(), serde_json::json!(true)),
+        ]))
+        .with_definition(definition)
+        .with_access_policy(
+            AccessPolicy::deny_by_default()
+                .allow(ConfigTier::Public, "API_*")
+                .allow(ConfigTier::Secret, "PLUGIN_*")
+                .allow(ConfigTier::FeatureFlag, "checkout/*")
+                .deny(ConfigTier::FeatureFlag, "checkout/admin"),
+        );
 
-            let mgr = ConfigManager::new()
-                .with_api_key("test-key")
-                .with_base_url(&url)
-                .with_org_id("org-123")
-                .with_environment("test")
-                .with_env(env);
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("https://api"))
+        );
+        assert_eq!(
+            mgr.get_secret_config("PLUGIN_TOKEN").unwrap(),
+            Some(serde_json::json!("pt").into())
+        );
+        let err = mgr.get_secret_config("DB_PASSWORD").unwrap_err();
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::AccessDenied {
+                key: "DB_PASSWORD".to_string(),
+                tier: Some(ConfigTier::Secret),
+            }
+        );
+        assert!(mgr.explain("DB_PASSWORD").is_err());
+        assert!(mgr.get_feature_flag("checkout/admin").is_err());
 
-            mgr.get_public_config("DATABASE").unwrap()
-        })
-        .await
-        .unwrap();
+        let all = mgr.get_all_exposed().unwrap();
+        assert!(all.contains_key("API_URL") && all.contains_key("PLUGIN_TOKEN"));
+        assert!(!all.contains_key("DB_PASSWORD") && !all.contains_key("checkout/admin"));
+        assert_eq!(
+            mgr.get_feature_flag_namespace("checkout")
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["checkout/new-flow"]
+        );
+    }
 
-        let db = result.unwrap();
-        let obj = db.as_object().unwrap();
-        // Remote overrides host and ssl
-        assert_eq!(obj["host"], serde_json::json!("remote-db.example.com"));
-        assert_eq!(obj["ssl"], serde_json::json!(true));
-        // File's port is preserved
-        assert_eq!(obj["port"], serde_json::json!(5432));
+    // --- Sources ---
+    struct StaticSource(&'static str, Result<HashMap<String, Value>, SmooaiConfigError>);
+
+    impl ConfigSource for StaticSource {
+        fn name(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn load(&self, _env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+            self.1.clone()
+        }
     }
 
-    // --- Test 5: Graceful Degradation (500 response) ---
-    #[tokio::test]
-    async fn test_graceful_degradation_on_server_error() {
-        let mock_server = MockServer::start().await;
+    #[test]
+    fn test_sources_merge_between_files_and_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"A":"file","B":"file","C":"file"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test"), ("C", "env")]);
+        let values = |pairs: &[(&str, &str)]| -> HashMap<String, Value> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), serde_json::json!(v)))
+                .collect()
+        };
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_schema_keys(HashSet::from(["C".to_string()]))
+            .with_source(StaticSource("first", Ok(values(&[("B", "first"), ("C", "first")]))))
+            .with_source(StaticSource("broken", Err(SmooaiConfigError::new("unreachable"))))
+            .with_source(StaticSource("second", Ok(values(&[("B", "second")]))));
+
+        assert_eq!(mgr.get_public_config("A").unwrap(), Some(serde_json::json!("file")));
+        assert_eq!(mgr.get_public_config("B").unwrap(), Some(serde_json::json!("second")));
+        assert_eq!(mgr.get_public_config("C").unwrap(), Some(serde_json::json!("env")));
+        assert_eq!(
+            mgr.explain("B").unwrap().source,
+            Some(ValueSource::Source {
+                name: "second".to_string()
+            })
+        );
+    }
 
-        Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/.+/config/values"))
-            .respond_with(ResponseTemplate::new(500))
-            .mount(&mock_server)
-            .await;
+    #[test]
+    fn test_max_merge_depth_skips_deep_source_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"DEEP":"file"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let values = HashMap::from([
+            ("DEEP".to_string(), serde_json::json!({"a": {"b": {"c": 1}}})),
+            ("SHALLOW".to_string(), serde_json::json!({"a": 1})),
+        ]);
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_max_merge_depth(2)
+            .with_source(StaticSource("remote-ish", Ok(values)));
 
-        let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
-            let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://fallback"}"#)]);
-            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        assert_eq!(mgr.get_public_config("DEEP").unwrap(), Some(serde_json::json!("file")));
+        assert_eq!(
+            mgr.get_public_config("SHALLOW").unwrap(),
+            Some(serde_json::json!({"a": 1}))
+        );
+    }
 
-            let mgr = ConfigManager::new()
-                .with_api_key("test-key")
-                .with_base_url(&url)
-                .with_org_id("org-123")
-                .with_environment("test")
-                .with_env(env);
+    #[test]
+    fn test_json_merge_patch_strategy_deletes_nulls() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[
+                ("default.json", r#"{"A":"file","NESTED":{"x":1,"y":2}}"#),
+                ("test.json", r#"{"A":null,"NESTED":{"y":null}}"#),
+            ],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
 
-            mgr.get_public_config("API_URL").unwrap()
-        })
-        .await
-        .unwrap();
+        let default = ConfigManager::new().with_env(env.clone());
+        assert_eq!(default.get_public_config("A").unwrap(), Some(Value::Null));
+        assert_eq!(
+            default.get_public_config("NESTED").unwrap(),
+            Some(serde_json::json!({"x": 1, "y": null}))
+        );
 
-        // Falls back to file config
-        assert_eq!(result, Some(Value::String("http://fallback".to_string())));
+        let patch = ConfigManager::new()
+            .with_env(env)
+            .with_merge_strategy(MergeStrategy::JsonMergePatch);
+        assert_eq!(patch.get_public_config("A").unwrap(), None);
+        assert_eq!(
+            patch.get_public_config("NESTED").unwrap(),
+            Some(serde_json::json!({"x": 1}))
+        );
     }
 
-    // --- Test 6: Three Tiers Independent ---
     #[test]
-    fn test_three_tiers_independent() {
+    fn test_merge_strategy_at_path() {
         let dir = tempfile::tempdir().unwrap();
         let config_dir = make_config_dir(
             dir.path(),
-            &[(
-                "default.json",
-                r#"{"API_URL":"http://localhost","DB_PASS":"secret123","ENABLE_BETA":true}"#,
-            )],
+            &[
+                ("default.json", r#"{"CORS_ORIGINS":["https://a.com"],"TAGS":["base"]}"#),
+                (
+                    "test.json",
+                    r#"{"CORS_ORIGINS":["https://a.com","https://test.com"],"TAGS":["test"]}"#,
+                ),
+            ],
         );
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = ConfigManager::new().with_env(env);
 
-        // Each tier sees the same merged config
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_merge_strategy_at("CORS_ORIGINS", MergeStrategy::UniqueAppend);
         assert_eq!(
-            mgr.get_public_config("API_URL").unwrap(),
-            Some(Value::String("http://localhost".to_string()))
+            mgr.get_public_config("CORS_ORIGINS").unwrap(),
+            Some(serde_json::json!(["https://a.com", "https://test.com"]))
         );
         assert_eq!(
-            mgr.get_secret_config("DB_PASS").unwrap(),
-            Some(Value::String("secret123".to_string()))
+            mgr.get_public_config("TAGS").unwrap(),
+            Some(serde_json::json!(["test"]))
         );
-        assert_eq!(mgr.get_feature_flag("ENABLE_BETA").unwrap(), Some(Value::Bool(true)));
+    }
+
+    // --- Overrides ---
+    #[test]
+    fn test_static_overrides_beat_env_and_explain() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"file"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test"), ("API_URL", "from-env")]);
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_overrides(HashMap::from([("API_URL".to_string(), serde_json::json!("override"))]));
 
-        // Each tier has its own cache — accessing same key in different tiers
-        // doesn't interfere
         assert_eq!(
-            mgr.get_secret_config("API_URL").unwrap(),
-            Some(Value::String("http://localhost".to_string()))
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("override"))
         );
+        let explanation = mgr.explain("API_URL").unwrap();
+        assert_eq!(explanation.source, Some(ValueSource::Override));
     }
 
-    // --- Test 7: Cache Behavior ---
     #[test]
-    fn test_cache_behavior() {
+    fn test_pushed_overrides_nest_and_restore() {
         let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"file"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+        let get = || mgr.get_public_config("API_URL").unwrap();
+        assert_eq!(get(), Some(serde_json::json!("file")));
+
+        let outer = mgr.push_overrides(HashMap::from([("API_URL".to_string(), serde_json::json!("outer"))]));
+        assert_eq!(get(), Some(serde_json::json!("outer")));
+        let inner = mgr.push_overrides(HashMap::from([("API_URL".to_string(), serde_json::json!("inner"))]));
+        assert_eq!(get(), Some(serde_json::json!("inner")));
+
+        // Dropping out of order leaves the newer frame in place.
+        drop(outer);
+        assert_eq!(get(), Some(serde_json::json!("inner")));
+        drop(inner);
+        assert_eq!(get(), Some(serde_json::json!("file")));
+    }
+
+    #[test]
+    fn test_pushed_overrides_apply_to_flag_namespaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"checkout/new-flow":false}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        let _guard = mgr.push_overrides(HashMap::from([(
+            "checkout/new-flow".to_string(),
+            serde_json::json!(true),
+        )]));
+        let flags = mgr.get_feature_flag_namespace("checkout").unwrap();
+        assert_eq!(flags.get("checkout/new-flow"), Some(&serde_json::json!(true)));
+    }
+
+    // --- Status ---
+    #[test]
+    fn test_status_reports_sources_and_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"A":"file","B":"file"}"#)]);
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
         let mgr = ConfigManager::new()
-            .with_cache_ttl(Duration::from_millis(50))
-            .with_env(env);
+            .with_env(env)
+            .with_source(StaticSource(
+                "good",
+                Ok(HashMap::from([("C".to_string(), serde_json::json!(1))])),
+            ))
+            .with_source(StaticSource("bad", Err(SmooaiConfigError::new("unreachable"))));
+
+        let before = mgr.status();
+        assert!(!before.initialized);
+        assert!(before.sources.is_empty());
+
+        mgr.get_public_config("A").unwrap();
+        mgr.get_public_config("A").unwrap();
+        let status = mgr.status();
+        assert!(status.initialized);
+        assert!(!status.is_healthy());
+        let names: Vec<&str> = status.sources.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["files", "good", "bad"]);
+        assert_eq!(status.sources[0].key_count, 2);
+        assert_eq!(status.sources[1].state, crate::status::SourceState::Loaded);
+        assert_eq!(status.sources[2].state, crate::status::SourceState::Failed);
+        assert_eq!(
+            status.sources[2].last_error.as_deref(),
+            Some("[Smooai Config] unreachable")
+        );
+        assert_eq!(status.cache.public_entries, 1);
+        assert_eq!((status.cache.hits, status.cache.misses), (1, 1));
 
-        // First access initializes and caches
-        let val1 = mgr.get_public_config("API_URL").unwrap();
-        assert_eq!(val1, Some(Value::String("http://localhost".to_string())));
+        // Health history survives invalidation.
+        mgr.invalidate();
+        assert_eq!(mgr.status().sources.len(), 3);
+    }
 
-        // Second access should come from cache
-        let val2 = mgr.get_public_config("API_URL").unwrap();
-        assert_eq!(val2, Some(Value::String("http://localhost".to_string())));
+    // --- Metrics ---
+    #[derive(Default, Clone)]
+    struct EventLog(Arc<Mutex<Vec<String>>>);
 
-        // Wait for cache to expire
-        std::thread::sleep(Duration::from_millis(60));
+    impl MetricsRecorder for EventLog {
+        fn cache_hit(&self, tier: ConfigTier) {
+            self.0.lock().unwrap().push(format!("hit:{}", tier.as_str()));
+        }
 
-        // After expiry, still returns the same value (re-reads from merged config)
-        let val3 = mgr.get_public_config("API_URL").unwrap();
-        assert_eq!(val3, Some(Value::String("http://localhost".to_string())));
+        fn cache_miss(&self, tier: ConfigTier) {
+            self.0.lock().unwrap().push(format!("miss:{}", tier.as_str()));
+        }
+
+        fn fetch(&self, source: &str, _duration: Duration, success: bool) {
+            self.0.lock().unwrap().push(format!("fetch:{}:{}", source, success));
+        }
+    }
+
+    #[test]
+    fn test_metrics_recorder_sees_cache_and_fetch_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"A":"file"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let log = EventLog::default();
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_source(StaticSource("bad", Err(SmooaiConfigError::new("down"))))
+            .with_metrics(log.clone());
+
+        mgr.get_public_config("A").unwrap();
+        mgr.get_public_config("A").unwrap();
+        mgr.get_secret_config("A").unwrap();
+
+        assert_eq!(
+            *log.0.lock().unwrap(),
+            ["miss:public", "fetch:bad:false", "hit:public", "miss:secret"]
+        );
+    }
+
+    // --- Single-flight loading ---
+    struct SlowSource {
+        loads: Arc<std::sync::atomic::AtomicUsize>,
+        ttl: Option<Duration>,
+    }
+
+    impl ConfigSource for SlowSource {
+        fn name(&self) -> String {
+            "slow".to_string()
+        }
+
+        fn load(&self, _env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            // Widen the window in which other threads pile up.
+            std::thread::sleep(Duration::from_millis(50));
+            Ok(HashMap::from([("A".to_string(), serde_json::json!("source"))]))
+        }
+
+        fn ttl(&self) -> Option<Duration> {
+            self.ttl
+        }
+    }
+
+    fn read_concurrently(mgr: &Arc<ConfigManager>, threads: usize) -> Vec<Result<Option<Value>, SmooaiConfigError>> {
+        let barrier = Arc::new(std::sync::Barrier::new(threads));
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let (mgr, barrier) = (Arc::clone(mgr), Arc::clone(&barrier));
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    mgr.get_public_config("A")
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
     }
 
-    // --- Test 8: API Creds from Env ---
     #[tokio::test]
-    async fn test_api_creds_from_env() {
+    async fn test_concurrent_first_reads_fetch_once() {
         let mock_server = MockServer::start().await;
-
         Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/env-org-id/config/values"))
-            .and(header("Authorization", "Bearer env-api-key"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "FROM_REMOTE": "yes"
-                }
-            })))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"values": {"B": "remote"}}))
+                    .set_delay(Duration::from_millis(50)),
+            )
+            // Once for the first load, once for the TTL reload.
+            .expect(2)
             .mount(&mock_server)
             .await;
 
         let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
+        tokio::task::spawn_blocking(move || {
             let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL":"val"}"#)]);
-            let env = make_env(
-                &config_dir,
-                &[
-                    ("SMOOAI_CONFIG_ENV", "test"),
-                    ("SMOOAI_CONFIG_API_KEY", "env-api-key"),
-                    ("SMOOAI_CONFIG_API_URL", &url),
-                    ("SMOOAI_CONFIG_ORG_ID", "env-org-id"),
-                ],
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"A":"file"}"#)]);
+            let loads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let mgr = Arc::new(
+                ConfigManager::new()
+                    .with_api_key("test-key")
+                    .with_base_url(&url)
+                    .with_org_id("org-123")
+                    .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]))
+                    .with_source(SlowSource {
+                        loads: Arc::clone(&loads),
+                        ttl: Some(Duration::from_secs(1)),
+                    }),
             );
 
-            // No constructor API params — all from env
-            let mgr = ConfigManager::new().with_env(env);
-            mgr.get_public_config("FROM_REMOTE").unwrap()
+            for result in read_concurrently(&mgr, 50) {
+                assert_eq!(result.unwrap(), Some(serde_json::json!("source")));
+            }
+            assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+            // A lapsed TTL also reloads exactly once.
+            std::thread::sleep(Duration::from_millis(1100));
+            read_concurrently(&mgr, 50);
+            assert_eq!(loads.load(Ordering::SeqCst), 2);
         })
         .await
         .unwrap();
+    }
 
-        assert_eq!(result, Some(Value::String("yes".to_string())));
+    #[test]
+    fn test_failed_load_is_shared_by_waiters() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{}"#)]);
+        let loads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mgr = Arc::new(
+            ConfigManager::new()
+                .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]))
+                .with_required_keys(["MISSING"])
+                .with_source(SlowSource {
+                    loads: Arc::clone(&loads),
+                    ttl: None,
+                }),
+        );
+
+        assert!(read_concurrently(&mgr, 20).iter().all(|r| r.is_err()));
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // invalidate() retries immediately.
+        mgr.invalidate();
+        assert!(mgr.get_public_config("A").is_err());
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
     }
 
-    // --- Test 9: API Creds from Constructor ---
-    #[tokio::test]
-    async fn test_api_creds_from_constructor() {
-        let mock_server = MockServer::start().await;
+    // --- Change events ---
+    #[test]
+    fn test_subscribe_receives_changes_on_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"A":1,"B":"same","C":true}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+        let mut changes = mgr.subscribe();
 
-        Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/ctor-org/config/values"))
-            .and(header("Authorization", "Bearer ctor-key"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "CTOR_REMOTE": "works"
-                }
-            })))
-            .mount(&mock_server)
-            .await;
+        mgr.get_public_config("A").unwrap();
+        assert!(changes.try_recv().is_err(), "initial load is not a change");
 
-        let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
-            let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL":"val"}"#)]);
-            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        fs::write(
+            std::path::Path::new(&config_dir).join("default.json"),
+            r#"{"A":2,"B":"same","D":"new"}"#,
+        )
+        .unwrap();
+        mgr.invalidate();
+        mgr.get_public_config("A").unwrap();
+
+        let received: Vec<ConfigChange> = std::iter::from_fn(|| changes.try_recv().ok()).collect();
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[0].key, "A");
+        assert_eq!(received[0].old, Some(serde_json::json!(1)));
+        assert_eq!(received[0].new, Some(serde_json::json!(2)));
+        assert_eq!(
+            received[0].source,
+            Some(ValueSource::File {
+                name: "default.json".to_string()
+            })
+        );
+        assert_eq!((received[1].key.as_str(), &received[1].new), ("C", &None));
+        assert_eq!(received[1].source, None);
+        assert_eq!((received[2].key.as_str(), &received[2].old), ("D", &None));
+    }
+
+    // --- Boolean flags ---
+    #[test]
+    fn test_is_enabled_coerces_and_never_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"ON":true,"ONE":1,"STR":"TRUE","OFF":"0","ODD":"maybe","checkout/*":true}"#,
+            )],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        assert!(mgr.is_enabled("ON"));
+        assert!(mgr.is_enabled("ONE"));
+        assert!(mgr.is_enabled("STR"));
+        assert!(mgr.is_enabled("checkout/new-flow"));
+        assert!(!mgr.is_enabled_or("OFF", true));
+        assert!(mgr.is_enabled_or("ODD", true));
+        assert!(!mgr.is_enabled("MISSING"));
+        assert!(mgr.is_enabled_or("MISSING", true));
+        // Errors (here: an empty key) fall back to the default too.
+        assert!(mgr.is_enabled_or("", true));
+    }
+
+    // --- Typed getters ---
+    #[derive(Debug, PartialEq, serde::Deserialize, schemars::JsonSchema)]
+    #[serde(rename_all = "lowercase")]
+    enum LogLevel {
+        Debug,
+        Info,
+    }
+
+    #[derive(Default, serde::Deserialize, schemars::JsonSchema)]
+    #[allow(dead_code)]
+    struct EnumPublicConfig {
+        log_level: Option<LogLevel>,
+    }
+
+    #[test]
+    fn test_get_public_config_as_enum() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let definition = crate::schema::define_config_typed::<
+            EnumPublicConfig,
+            crate::schema::EmptySchema,
+            crate::schema::EmptySchema,
+        >();
+        let manager = |level: &str| {
+            ConfigManager::new()
+                .with_env(make_env(
+                    &config_dir,
+                    &[("SMOOAI_CONFIG_ENV", "test"), ("LOG_LEVEL", level)],
+                ))
+                .with_definition(definition.clone())
+        };
+
+        // Env coercion matches the schema's enum case-insensitively.
+        let mgr = manager("INFO");
+        assert_eq!(
+            mgr.get_public_config_as::<LogLevel>("LOG_LEVEL").unwrap(),
+            Some(LogLevel::Info)
+        );
+        assert_eq!(mgr.get_public_config_as::<LogLevel>("MISSING").unwrap(), None);
+        let err = mgr.get_public_config_as::<u32>("API_URL").unwrap_err();
+        assert!(err.message.contains("API_URL could not be deserialized"), "{}", err);
 
-            let mgr = ConfigManager::new()
-                .with_api_key("ctor-key")
-                .with_base_url(&url)
-                .with_org_id("ctor-org")
-                .with_environment("test")
-                .with_env(env);
+        // Values outside the set are rejected from env.
+        let mgr = manager("verbose");
+        assert_eq!(mgr.get_public_config("LOG_LEVEL").unwrap(), None);
 
-            mgr.get_public_config("CTOR_REMOTE").unwrap()
-        })
-        .await
-        .unwrap();
+        // Values from other sources are checked when read.
+        let mgr = ConfigManager::new()
+            .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]))
+            .with_definition(definition)
+            .with_overrides(HashMap::from([("LOG_LEVEL".to_string(), serde_json::json!("trace"))]));
+        let err = mgr.get_public_config_as::<LogLevel>("LOG_LEVEL").unwrap_err();
+        assert!(
+            err.message
+                .contains(r#"LOG_LEVEL is not one of the allowed values ("debug", "info")"#),
+            "{}",
+            err
+        );
+    }
 
-        assert_eq!(result, Some(Value::String("works".to_string())));
+    // --- Redaction ---
+    #[test]
+    fn test_secret_keys_redacted_in_get_all_export_and_explain() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"API_URL":"http://localhost","WEBHOOK_TOKEN":"tok","DB_PASSWORD":"hunter2"}"#,
+            )],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string"},
+                "webhookToken": {"type": "string", "x-secret": true}
+            }})),
+            Some(serde_json::json!({"type": "object", "properties": {"dbPassword": {"type": "string"}}})),
+            None,
+        );
+        let mgr = ConfigManager::new().with_env(env).with_definition(definition);
+
+        let all = mgr.get_all().unwrap();
+        assert_eq!(all["API_URL"], serde_json::json!("http://localhost"));
+        assert_eq!(all["WEBHOOK_TOKEN"], serde_json::json!(REDACTED));
+        assert_eq!(all["DB_PASSWORD"], serde_json::json!(REDACTED));
+        let exported = mgr.export().unwrap();
+        assert!(!exported.contains("hunter2") && !exported.contains("\"tok\""));
+
+        let explanation = mgr.explain("WEBHOOK_TOKEN").unwrap();
+        assert_eq!(explanation.value, Some(serde_json::json!(REDACTED)));
+        assert!(explanation
+            .layers
+            .iter()
+            .all(|l| l.value == serde_json::json!(REDACTED)));
+        // Getters still return the real value.
+        assert_eq!(
+            mgr.get_public_config("WEBHOOK_TOKEN").unwrap(),
+            Some(serde_json::json!("tok"))
+        );
     }
 
-    // --- Test 10: Thread Safety ---
     #[test]
-    fn test_thread_safety() {
+    fn test_secrets_scrubbed_from_public_values() {
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "databaseUrl": {"type": "string"},
+                "debugHeaders": {"type": "object"}
+            }})),
+            Some(serde_json::json!({"type": "object", "properties": {"dbPassword": {"type": "string"}}})),
+            None,
+        );
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("DB_PASSWORD".to_string(), serde_json::json!("hunter2-prod")),
+            (
+                "DATABASE_URL".to_string(),
+                serde_json::json!("postgres://app:hunter2-prod@db/app"),
+            ),
+            (
+                "DEBUG_HEADERS".to_string(),
+                serde_json::json!({"auth": "Bearer abcdef0123456789", "note": "pw is hunter2-prod"}),
+            ),
+        ]))
+        .with_definition(definition);
+
+        let all = mgr.get_all().unwrap();
+        assert_eq!(
+            all["DATABASE_URL"],
+            serde_json::json!("postgres://app:<redacted>@db/app")
+        );
+        assert_eq!(
+            all["DEBUG_HEADERS"],
+            serde_json::json!({"auth": "Bearer <redacted>", "note": "pw is <redacted>"})
+        );
+        assert!(!mgr.export().unwrap().contains("hunter2"));
+        let explanation = mgr.explain("DATABASE_URL").unwrap();
+        assert!(!format!("{:?}", explanation).contains("hunter2"));
+        // Getters are untouched.
+        assert_eq!(
+            mgr.get_public_config("DATABASE_URL").unwrap(),
+            Some(serde_json::json!("postgres://app:hunter2-prod@db/app"))
+        );
+    }
+
+    // --- Deprecations ---
+    #[test]
+    fn test_deprecated_key_reported_once() {
         let dir = tempfile::tempdir().unwrap();
         let config_dir = make_config_dir(
             dir.path(),
-            &[("default.json", r#"{"API_URL":"http://localhost","COUNT":42}"#)],
+            &[(
+                "default.json",
+                r#"{"API_URL":"http://old","API_BASE_URL":"http://new"}"#,
+            )],
         );
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = Arc::new(ConfigManager::new().with_env(env));
+        let definition = crate::schema::define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string", "x-deprecated": "apiBaseUrl"},
+                "apiBaseUrl": {"type": "string"}
+            }})),
+            None,
+            None,
+        );
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reported);
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_definition(definition)
+            .with_deprecation_handler(move |d| sink.lock().unwrap().push(d.clone()));
 
-        let mut handles = vec![];
-        for _ in 0..10 {
-            let mgr = Arc::clone(&mgr);
-            handles.push(std::thread::spawn(move || {
-                let val = mgr.get_public_config("API_URL").unwrap();
-                assert_eq!(val, Some(Value::String("http://localhost".to_string())));
-                let count = mgr.get_public_config("COUNT").unwrap();
-                assert_eq!(count, Some(serde_json::json!(42)));
-            }));
-        }
+        assert_eq!(
+            mgr.get_public_config("API_BASE_URL").unwrap(),
+            Some(serde_json::json!("http://new"))
+        );
+        assert!(reported.lock().unwrap().is_empty());
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("http://old"))
+        );
+        mgr.get_public_config("apiUrl").unwrap();
+        mgr.invalidate();
+        mgr.get_public_config("API_URL").unwrap();
 
-        for handle in handles {
-            handle.join().unwrap();
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].key, "API_URL");
+        assert_eq!(reported[0].replacement.as_deref(), Some("API_BASE_URL"));
+    }
+
+    // --- Prefetch ---
+    #[test]
+    fn test_prefetch_and_warm_all_fill_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"A":1,"B":2,"C":3}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env.clone());
+
+        mgr.prefetch(&["A", "MISSING"]).unwrap();
+        let status = mgr.status();
+        assert!(status.initialized);
+        assert_eq!(status.cache.public_entries, 1);
+        assert_eq!(status.cache.secret_entries, 1);
+        assert_eq!(mgr.get_public_config("A").unwrap(), Some(serde_json::json!(1)));
+        assert_eq!(mgr.status().cache.hits, 1);
+        {
+            // One allocation for the key, shared by every tier's cache.
+            let inner = mgr.inner.read().unwrap();
+            let (public, _) = inner.public_cache.get_key_value("A").unwrap();
+            let (secret, _) = inner.secret_cache.get_key_value("A").unwrap();
+            assert!(Arc::ptr_eq(public, secret));
+            assert_eq!(inner.cache_keys.len(), 1);
         }
+
+        let warmed = ConfigManager::new().with_env(env);
+        warmed.warm_all().unwrap();
+        assert!(warmed.status().cache.feature_flag_entries >= 3);
     }
 
-    // --- Test 11: Full Integration (temp dir + mock HTTP + env) ---
+    // --- Targeted invalidation ---
     #[tokio::test]
-    async fn test_full_integration() {
+    async fn test_invalidate_key_and_tier_refetch_single_keys() {
         let mock_server = MockServer::start().await;
-
         Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/.+/config/values"))
+            .and(path("/organizations/org-123/config/values"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "REMOTE_SETTING": "from-api",
-                    "SHARED_KEY": "remote-wins-over-file"
-                }
+                "values": {"NEW_FLOW": false, "API_URL": "http://old", "GONE": 1}
             })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values/NEW_FLOW"))
+            .and(query_param("environment", "test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": true})))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values/GONE"))
+            .respond_with(ResponseTemplate::new(404))
             .mount(&mock_server)
             .await;
 
         let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
+        tokio::task::spawn_blocking(move || {
             let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(
-                dir.path(),
-                &[(
-                    "default.json",
-                    r#"{"FILE_SETTING":"from-file","SHARED_KEY":"file-value"}"#,
-                )],
-            );
-
-            let mut schema_keys = HashSet::new();
-            schema_keys.insert("SHARED_KEY".to_string());
-
-            let env = make_env(
-                &config_dir,
-                &[("SMOOAI_CONFIG_ENV", "test"), ("SHARED_KEY", "env-wins-over-all")],
-            );
-
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"GONE":0}"#)]);
             let mgr = ConfigManager::new()
                 .with_api_key("test-key")
                 .with_base_url(&url)
                 .with_org_id("org-123")
                 .with_environment("test")
-                .with_schema_keys(schema_keys)
-                .with_env(env);
+                .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]));
 
-            let file = mgr.get_public_config("FILE_SETTING").unwrap();
-            let remote = mgr.get_public_config("REMOTE_SETTING").unwrap();
-            let shared = mgr.get_public_config("SHARED_KEY").unwrap();
-            (file, remote, shared)
+            assert_eq!(
+                mgr.get_feature_flag("NEW_FLOW").unwrap(),
+                Some(serde_json::json!(false))
+            );
+            assert_eq!(mgr.get_public_config("GONE").unwrap(), Some(serde_json::json!(1)));
+
+            mgr.invalidate_tier(ConfigTier::FeatureFlag);
+            assert_eq!(mgr.get_feature_flag("NEW_FLOW").unwrap(), Some(serde_json::json!(true)));
+
+            // Deleted remotely: the file value shows through again.
+            mgr.invalidate_key("GONE");
+            assert_eq!(mgr.get_public_config("GONE").unwrap(), Some(serde_json::json!(0)));
+            assert_eq!(
+                mgr.get_public_config("API_URL").unwrap(),
+                Some(serde_json::json!("http://old"))
+            );
         })
         .await
         .unwrap();
-
-        assert_eq!(result.0, Some(Value::String("from-file".to_string())));
-        assert_eq!(result.1, Some(Value::String("from-api".to_string())));
-        // Env wins over remote and file
-        assert_eq!(result.2, Some(Value::String("env-wins-over-all".to_string())));
-    }
-
-    // --- Test 12: Environment Resolution ---
-    #[test]
-    fn test_environment_resolution_from_constructor() {
-        let mgr = ConfigManager::new().with_environment("staging");
-        assert_eq!(mgr.resolve_environment(), "staging");
     }
 
-    #[test]
-    fn test_environment_resolution_from_env_var() {
-        let env: HashMap<String, String> = [("SMOOAI_CONFIG_ENV".to_string(), "production".to_string())]
-            .into_iter()
-            .collect();
-        let mgr = ConfigManager::new().with_env(env);
-        assert_eq!(mgr.resolve_environment(), "production");
-    }
-
-    #[test]
-    fn test_environment_resolution_default() {
-        let env: HashMap<String, String> = HashMap::new();
-        let mgr = ConfigManager::new().with_env(env);
-        assert_eq!(mgr.resolve_environment(), "development");
-    }
-
-    #[test]
-    fn test_environment_constructor_overrides_env_var() {
-        let env: HashMap<String, String> = [("SMOOAI_CONFIG_ENV".to_string(), "from-env".to_string())]
-            .into_iter()
-            .collect();
-        let mgr = ConfigManager::new().with_environment("from-constructor").with_env(env);
-        assert_eq!(mgr.resolve_environment(), "from-constructor");
-    }
-
-    // --- Test 13: Invalidation Re-fetches ---
     #[tokio::test]
-    async fn test_invalidation_refetches() {
+    async fn test_invalidate_key_keeps_builtin_over_remote() {
         let mock_server = MockServer::start().await;
-
         Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/.+/config/values"))
+            .and(path("/organizations/org-123/config/values"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {
-                    "DYNAMIC": "value-1"
-                }
+                "values": {"ENV": "from-remote"}
             })))
-            .expect(2) // Should be called twice (initial + after invalidation)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/organizations/org-123/config/values/ENV"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"value": "from-remote"})))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
+        tokio::task::spawn_blocking(move || {
             let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL":"val"}"#)]);
-            let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-
+            let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
             let mgr = ConfigManager::new()
                 .with_api_key("test-key")
                 .with_base_url(&url)
                 .with_org_id("org-123")
                 .with_environment("test")
-                .with_env(env);
-
-            // First access
-            let val1 = mgr.get_public_config("DYNAMIC").unwrap();
-
-            // Invalidate
-            mgr.invalidate();
-
-            // Second access should re-initialize (re-fetch remote)
-            let val2 = mgr.get_public_config("DYNAMIC").unwrap();
+                .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]));
 
-            (val1, val2)
+            assert_eq!(mgr.get_public_config("ENV").unwrap(), Some(serde_json::json!("test")));
+            mgr.invalidate_key("ENV");
+            assert_eq!(mgr.get_public_config("ENV").unwrap(), Some(serde_json::json!("test")));
         })
         .await
         .unwrap();
-
-        assert_eq!(result.0, Some(Value::String("value-1".to_string())));
-        assert_eq!(result.1, Some(Value::String("value-1".to_string())));
     }
 
-    // --- Test: Lazy Initialization ---
-    #[test]
-    fn test_lazy_initialization() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = ConfigManager::new().with_env(env);
+    // --- Last-known-good ---
+    #[tokio::test]
+    async fn test_last_known_good_used_when_remote_down() {
+        let healthy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/organizations/.+/config/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": {"REMOTE_KEY": "remote-value"}
+            })))
+            .mount(&healthy)
+            .await;
+        let down = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&down)
+            .await;
 
-        assert!(!mgr.inner.read().unwrap().initialized);
-        mgr.get_public_config("API_URL").unwrap();
-        assert!(mgr.inner.read().unwrap().initialized);
-    }
+        let (healthy_url, down_url) = (healthy.uri(), down.uri());
+        tokio::task::spawn_blocking(move || {
+            let dir = tempfile::tempdir().unwrap();
+            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"LOCAL_KEY":"local"}"#)]);
+            let snapshot = dir.path().join("lkg.json");
+            let manager = |url: &str, max_age: Duration| {
+                ConfigManager::new()
+                    .with_api_key("test-key")
+                    .with_base_url(url)
+                    .with_org_id("org-123")
+                    .with_environment("test")
+                    .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]))
+                    .with_last_known_good(&snapshot, max_age)
+            };
+
+            let first = manager(&healthy_url, Duration::from_secs(3600));
+            assert_eq!(
+                first.get_public_config("REMOTE_KEY").unwrap(),
+                Some(serde_json::json!("remote-value"))
+            );
+            assert!(snapshot.exists());
 
-    // --- Test: Returns None for Missing Key ---
-    #[test]
-    fn test_returns_none_for_missing_key() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"test"}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = ConfigManager::new().with_env(env);
+            let restarted = manager(&down_url, Duration::from_secs(3600));
+            assert_eq!(
+                restarted.get_public_config("REMOTE_KEY").unwrap(),
+                Some(serde_json::json!("remote-value"))
+            );
+            assert_eq!(
+                restarted.get_public_config("LOCAL_KEY").unwrap(),
+                Some(serde_json::json!("local"))
+            );
+            assert_eq!(
+                restarted.explain("REMOTE_KEY").unwrap().source,
+                Some(ValueSource::LastKnownGood)
+            );
 
-        assert_eq!(mgr.get_public_config("NONEXISTENT").unwrap(), None);
+            // Snapshots older than max_age are ignored.
+            let mut stale = serde_json::from_slice::<Value>(&fs::read(&snapshot).unwrap()).unwrap();
+            stale["saved_at"] = serde_json::json!(0);
+            fs::write(&snapshot, stale.to_string()).unwrap();
+            let expired = manager(&down_url, Duration::from_secs(3600));
+            assert_eq!(expired.get_public_config("REMOTE_KEY").unwrap(), None);
+        })
+        .await
+        .unwrap();
     }
 
-    // --- Test: Invalidate Clears State ---
+    // --- Flag namespaces ---
     #[test]
-    fn test_invalidate_clears_state() {
+    fn test_feature_flag_namespace_defaults() {
         let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"checkout/*":false,"checkout/payments/*":true,"checkout/new-flow":true,"search/fuzzy":true}"#,
+            )],
+        );
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
         let mgr = ConfigManager::new().with_env(env);
 
-        mgr.get_public_config("API_URL").unwrap();
-        assert!(mgr.inner.read().unwrap().initialized);
+        assert_eq!(
+            mgr.get_feature_flag("checkout/new-flow").unwrap(),
+            Some(serde_json::json!(true))
+        );
+        assert_eq!(
+            mgr.get_feature_flag("checkout/payments/apple-pay").unwrap(),
+            Some(serde_json::json!(true))
+        );
+        assert_eq!(
+            mgr.get_feature_flag("checkout/unlisted").unwrap(),
+            Some(serde_json::json!(false))
+        );
+        assert_eq!(mgr.get_feature_flag("search/unlisted").unwrap(), None);
 
-        mgr.invalidate();
-        assert!(!mgr.inner.read().unwrap().initialized);
-        assert!(mgr.inner.read().unwrap().public_cache.is_empty());
-        assert!(mgr.inner.read().unwrap().config.is_empty());
+        let namespace = mgr.get_feature_flag_namespace("checkout").unwrap();
+        assert_eq!(namespace.keys().collect::<Vec<_>>(), vec!["checkout/new-flow"]);
     }
 
-    // --- Test: Invalidate Allows Reinitialization ---
-    #[test]
-    fn test_invalidate_allows_reinitialization() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = ConfigManager::new().with_env(env);
+    struct CountingSource {
+        loads: Arc<std::sync::atomic::AtomicUsize>,
+        ttl: Duration,
+    }
 
-        mgr.get_public_config("API_URL").unwrap();
-        mgr.invalidate();
+    impl ConfigSource for CountingSource {
+        fn name(&self) -> String {
+            "counting".to_string()
+        }
 
-        let result = mgr.get_public_config("API_URL").unwrap();
-        assert_eq!(result, Some(Value::String("http://localhost".to_string())));
+        fn load(&self, _env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+            let n = self.loads.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            Ok(HashMap::from([("N".to_string(), serde_json::json!(n))]))
+        }
+
+        fn ttl(&self) -> Option<Duration> {
+            Some(self.ttl)
+        }
     }
 
-    // --- Test: Basic Deferred Value ---
     #[test]
-    fn test_basic_deferred_value() {
+    fn test_source_ttl_triggers_reload() {
         let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"HOST":"localhost","PORT":5432}"#)]);
+        let config_dir = make_config_dir(dir.path(), &[("default.json", "{}")]);
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let loads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mgr = ConfigManager::new().with_env(env).with_source(CountingSource {
+            loads: Arc::clone(&loads),
+            ttl: Duration::from_millis(50),
+        });
+
+        assert_eq!(mgr.get_secret_config("N").unwrap(), Some(serde_json::json!(1).into()));
+        assert_eq!(mgr.get_secret_config("N").unwrap(), Some(serde_json::json!(1).into()));
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(mgr.get_secret_config("N").unwrap(), Some(serde_json::json!(2).into()));
+    }
 
-        let mgr = ConfigManager::new().with_env(env).with_deferred(
-            "FULL_URL",
-            Box::new(|config| {
-                let host = config["HOST"].as_str().unwrap_or("unknown");
-                let port = config["PORT"].as_u64().unwrap_or(0);
-                serde_json::json!(format!("{}:{}", host, port))
-            }),
+    // --- Standard URLs ---
+    #[test]
+    fn test_with_standard_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"DATABASE":{"HOST":"db","USER":"app","PASSWORD":"pw","DB":"main"},"REDIS":{"HOST":"cache"}}"#,
+            )],
         );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env).with_standard_urls();
 
         assert_eq!(
-            mgr.get_public_config("FULL_URL").unwrap(),
-            Some(serde_json::json!("localhost:5432"))
+            mgr.get_secret_config("DATABASE_URL").unwrap(),
+            Some(serde_json::json!("postgres://app:pw@db:5432/main").into())
         );
-        // Original values preserved
         assert_eq!(
-            mgr.get_public_config("HOST").unwrap(),
-            Some(serde_json::json!("localhost"))
+            mgr.get_secret_config("REDIS_URL").unwrap(),
+            Some(serde_json::json!("redis://cache:6379").into())
         );
-        assert_eq!(mgr.get_public_config("PORT").unwrap(), Some(serde_json::json!(5432)));
+        assert_eq!(mgr.get_secret_config("AMQP_URL").unwrap(), Some(Value::Null.into()));
     }
 
-    // --- Test: Multiple Deferred See Pre-Resolution Snapshot ---
+    // --- from_values ---
     #[test]
-    fn test_multiple_deferred_see_snapshot() {
+    fn test_from_values_skips_loading() {
         let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"BASE":"hello"}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://file"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test"), ("PORT", "9090")]);
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("API_URL".to_string(), serde_json::json!("http://memory")),
+            ("PORT".to_string(), serde_json::json!(8080)),
+        ]))
+        .with_env(env)
+        .with_schema_keys(HashSet::from(["PORT".to_string()]))
+        .with_overrides(HashMap::from([("DEBUG".to_string(), serde_json::json!(true))]));
 
-        let mgr = ConfigManager::new()
-            .with_env(env)
-            .with_deferred(
-                "A",
-                Box::new(|config| {
-                    let base = config["BASE"].as_str().unwrap_or("");
-                    serde_json::json!(format!("{}-a", base))
-                }),
-            )
-            .with_deferred(
-                "B",
-                Box::new(|config| {
-                    // B should NOT see A's resolved value
-                    serde_json::json!(config.contains_key("A"))
-                }),
-            );
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("http://memory"))
+        );
+        assert_eq!(mgr.get_public_config("PORT").unwrap(), Some(serde_json::json!(8080)));
+        assert_eq!(mgr.get_feature_flag("DEBUG").unwrap(), Some(serde_json::json!(true)));
+        assert_eq!(mgr.get_public_config("ENV").unwrap(), None);
+        assert_eq!(mgr.explain("PORT").unwrap().source, Some(ValueSource::Values));
 
-        assert_eq!(mgr.get_public_config("A").unwrap(), Some(serde_json::json!("hello-a")));
-        // B should see that A was NOT in the snapshot
-        assert_eq!(mgr.get_public_config("B").unwrap(), Some(serde_json::json!(false)));
+        mgr.invalidate();
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("http://memory"))
+        );
+        assert!(mgr.status().sources.is_empty());
     }
 
-    // --- Test: Deferred Runs After Full Merge ---
     #[test]
-    fn test_deferred_runs_after_merge() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"HOST":"file-host"}"#)]);
-
-        let mut schema_keys = HashSet::new();
-        schema_keys.insert("HOST".to_string());
+    fn test_from_values_checks_required_keys() {
+        let mgr = ConfigManager::from_values(HashMap::new()).with_required_keys(["API_URL"]);
+        let err = mgr.init().unwrap_err();
+        assert!(matches!(err.kind, SmooaiConfigErrorKind::MissingRequiredKeys { .. }));
+    }
 
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test"), ("HOST", "env-host")]);
+    // --- Snapshots ---
+    #[cfg(feature = "snapshot")]
+    #[test]
+    fn test_init_from_snapshot_then_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://old"}"#)]);
+        let snapshot = dir.path().join("config.bin");
+        let manager = |environment: &str| {
+            Arc::new(
+                ConfigManager::new()
+                    .with_env(make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", environment)]))
+                    .with_snapshot(&snapshot, Duration::from_secs(3600)),
+            )
+        };
 
-        let mgr = ConfigManager::new()
-            .with_env(env)
-            .with_schema_keys(schema_keys)
-            .with_deferred(
-                "API_URL",
-                Box::new(|config| {
-                    let host = config["HOST"].as_str().unwrap_or("unknown");
-                    serde_json::json!(format!("https://{}/api", host))
-                }),
-            );
+        // Nothing to start from yet: a normal load, which writes the snapshot.
+        assert!(manager("test").init_from_snapshot().unwrap().is_none());
+        assert!(snapshot.exists());
 
-        // Env overrides file, deferred sees env value
+        fs::write(
+            std::path::Path::new(&config_dir).join("default.json"),
+            r#"{"API_URL":"http://new"}"#,
+        )
+        .unwrap();
+        let mgr = manager("test");
+        let mut changes = mgr.subscribe();
+        let refresh = mgr.init_from_snapshot().unwrap().expect("served from snapshot");
+        refresh.join().unwrap();
+
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.key, "API_URL");
+        assert_eq!(change.old, Some(serde_json::json!("http://old")));
+        assert_eq!(change.new, Some(serde_json::json!("http://new")));
         assert_eq!(
             mgr.get_public_config("API_URL").unwrap(),
-            Some(serde_json::json!("https://env-host/api"))
+            Some(serde_json::json!("http://new"))
         );
+
+        // Another environment doesn't pick up this one's snapshot.
+        assert!(manager("staging").init_from_snapshot().unwrap().is_none());
     }
 
-    // --- Test: No Remote Without Credentials ---
+    // --- Shared values ---
     #[test]
-    fn test_no_remote_without_credentials() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://localhost"}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+    fn test_arc_getters_share_cached_value() {
+        let mgr = ConfigManager::from_values(HashMap::from([
+            ("ROUTES".to_string(), serde_json::json!({"a": [1, 2, 3]})),
+            ("checkout/*".to_string(), serde_json::json!(true)),
+        ]));
+
+        let first = mgr.get_public_config_arc("ROUTES").unwrap().unwrap();
+        let second = mgr.get_public_config_arc("ROUTES").unwrap().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(*first, serde_json::json!({"a": [1, 2, 3]}));
+        assert_eq!(
+            mgr.get_public_config("ROUTES").unwrap(),
+            Some(serde_json::json!({"a": [1, 2, 3]}))
+        );
 
-        // No API key, base URL, or org ID — should work fine with just local config
-        let mgr = ConfigManager::new().with_env(env);
+        mgr.invalidate();
+        let reloaded = mgr.get_public_config_arc("ROUTES").unwrap().unwrap();
+        assert!(!Arc::ptr_eq(&first, &reloaded));
+        assert_eq!(first, reloaded);
 
+        assert_eq!(mgr.get_public_config_arc("MISSING").unwrap(), None);
         assert_eq!(
-            mgr.get_public_config("API_URL").unwrap(),
-            Some(Value::String("http://localhost".to_string()))
+            mgr.get_feature_flag_arc("checkout/apple-pay").unwrap().as_deref(),
+            Some(&serde_json::json!(true))
         );
     }
 
-    // --- Test: Graceful Fallback When No Config Files ---
     #[test]
-    fn test_graceful_fallback_no_config_files() {
-        // Point to a directory with no config files
-        let dir = tempfile::tempdir().unwrap();
-        let empty_dir = dir.path().join("empty");
-        fs::create_dir_all(&empty_dir).unwrap();
+    fn test_get_path() {
+        let mgr = ConfigManager::from_values(HashMap::from([
+            (
+                "DATABASE".to_string(),
+                serde_json::json!({"options": {"pool_size": 10}}),
+            ),
+            ("HOSTS".to_string(), serde_json::json!(["a", "b"])),
+        ]));
+
+        let pool_size = mgr.get_path("DATABASE.options.pool_size").unwrap().unwrap();
+        assert_eq!(*pool_size, serde_json::json!(10));
+        assert!(Arc::ptr_eq(
+            &pool_size,
+            &mgr.get_path("DATABASE.options.pool_size").unwrap().unwrap()
+        ));
+        assert_eq!(
+            mgr.get_path("HOSTS.1").unwrap().as_deref(),
+            Some(&serde_json::json!("b"))
+        );
+        assert_eq!(
+            mgr.get_path("HOSTS").unwrap().as_deref(),
+            Some(&serde_json::json!(["a", "b"]))
+        );
+        assert_eq!(mgr.get_path("DATABASE.options.missing").unwrap(), None);
+        assert!(mgr.get_path(".options").is_err());
 
-        let env: HashMap<String, String> = [(
-            "SMOOAI_ENV_CONFIG_DIR".to_string(),
-            empty_dir.to_string_lossy().to_string(),
-        )]
-        .into_iter()
-        .collect();
+        let _guard = mgr.push_overrides(HashMap::from([(
+            "DATABASE".to_string(),
+            serde_json::json!({"options": {"pool_size": 2}}),
+        )]));
+        assert_eq!(
+            mgr.get_path("DATABASE.options.pool_size").unwrap().as_deref(),
+            Some(&serde_json::json!(2))
+        );
+    }
 
-        let mgr = ConfigManager::new().with_env(env);
+    // --- Access recording ---
+    #[test]
+    fn test_access_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"API_URL":"http://localhost","DEAD_KEY":1,"NEW_CHECKOUT":true}"#,
+            )],
+        );
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = ConfigManager::new().with_env(env).with_access_recording();
 
-        // Should not error — file config failure is graceful
-        let result = mgr.get_public_config("ANYTHING").unwrap();
-        assert_eq!(result, None);
-    }
+        mgr.get_public_config("API_URL").unwrap();
+        mgr.get_public_config("API_URL").unwrap();
+        mgr.get_feature_flag("MISSING").unwrap();
+        assert_eq!(
+            mgr.accesses(),
+            vec![
+                KeyAccess {
+                    tier: ConfigTier::Public,
+                    key: "API_URL".to_string(),
+                    cache_hit: false,
+                    found: true,
+                },
+                KeyAccess {
+                    tier: ConfigTier::Public,
+                    key: "API_URL".to_string(),
+                    cache_hit: true,
+                    found: true,
+                },
+                KeyAccess {
+                    tier: ConfigTier::FeatureFlag,
+                    key: "MISSING".to_string(),
+                    cache_hit: false,
+                    found: false,
+                },
+            ]
+        );
+        assert_eq!(
+            mgr.accessed_keys(),
+            BTreeSet::from(["API_URL".to_string(), "MISSING".to_string()])
+        );
+        assert_eq!(
+            mgr.unread_keys().unwrap(),
+            BTreeSet::from(["DEAD_KEY".to_string(), "NEW_CHECKOUT".to_string()])
+        );
 
-    // --- Test: Constructor Params Override Env Vars ---
-    #[tokio::test]
-    async fn test_constructor_params_override_env_vars() {
-        let mock_server = MockServer::start().await;
+        mgr.clear_accesses();
+        assert!(mgr.is_enabled("NEW_CHECKOUT"));
+        assert_eq!(mgr.accessed_keys(), BTreeSet::from(["NEW_CHECKOUT".to_string()]));
+    }
 
-        // The mock expects the constructor org ID, not the env var one
-        Mock::given(method("GET"))
-            .and(path_regex(r"/organizations/ctor-org/config/values"))
-            .and(header("Authorization", "Bearer ctor-key"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "values": {"RESULT": "from-ctor-params"}
-            })))
-            .mount(&mock_server)
-            .await;
+    #[test]
+    fn test_access_recording_is_opt_in() {
+        let mgr = ConfigManager::from_values(HashMap::from([("A".to_string(), serde_json::json!(1))]));
+        mgr.get_public_config("A").unwrap();
+        assert!(mgr.accesses().is_empty());
+    }
 
-        let url = mock_server.uri();
-        let result = tokio::task::spawn_blocking(move || {
-            let dir = tempfile::tempdir().unwrap();
-            let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"L":"v"}"#)]);
-            let env = make_env(
-                &config_dir,
-                &[
-                    ("SMOOAI_CONFIG_ENV", "test"),
-                    ("SMOOAI_CONFIG_API_KEY", "env-key"),
-                    ("SMOOAI_CONFIG_API_URL", "http://should-not-use"),
-                    ("SMOOAI_CONFIG_ORG_ID", "env-org"),
-                ],
-            );
+    // --- Explain ---
+    #[test]
+    fn test_explain_reports_layers_and_winner() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[
+                ("default.json", r#"{"API_URL":"http://localhost","PORT":80}"#),
+                ("production.json", r#"{"API_URL":"https://prod"}"#),
+            ],
+        );
+        let env = make_env(
+            &config_dir,
+            &[("SMOOAI_CONFIG_ENV", "production"), ("APP_API_URL", "https://env")],
+        );
+        let mgr = ConfigManager::new()
+            .with_env(env)
+            .with_env_prefix("APP_")
+            .with_schema_keys(HashSet::from(["API_URL".to_string()]));
 
-            let mgr = ConfigManager::new()
-                .with_api_key("ctor-key")
-                .with_base_url(&url)
-                .with_org_id("ctor-org")
-                .with_environment("test")
-                .with_env(env);
+        let explained = mgr.explain("API_URL").unwrap();
+        assert_eq!(explained.value, Some(serde_json::json!("https://env")));
+        assert_eq!(
+            explained.source,
+            Some(ValueSource::Env {
+                var: "APP_API_URL".to_string()
+            })
+        );
+        let sources: Vec<String> = explained.layers.iter().map(|l| l.source.to_string()).collect();
+        assert_eq!(
+            sources,
+            vec!["default.json", "production.json", "env var `APP_API_URL`"]
+        );
 
-            mgr.get_public_config("RESULT").unwrap()
-        })
-        .await
-        .unwrap();
+        let port = mgr.explain("PORT").unwrap();
+        assert_eq!(
+            port.source,
+            Some(ValueSource::File {
+                name: "default.json".to_string()
+            })
+        );
+        assert_eq!(mgr.explain("ENV").unwrap().source, Some(ValueSource::BuiltIn));
 
-        assert_eq!(result, Some(Value::String("from-ctor-params".to_string())));
+        let missing = mgr.explain("NOPE").unwrap();
+        assert!(missing.value.is_none() && missing.layers.is_empty());
     }
 
-    // --- SMOODEV-958: undefined-key error ---
+    // --- Bytes values ---
     #[test]
-    fn test_undefined_key_returns_friendly_error() {
-        use crate::utils::SmooaiConfigErrorKind;
-
+    fn test_get_bytes_decoded() {
         let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"KNOWN":"v"}"#)]);
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"CERT":"aGVsbG8=","BAD":"%%%"}"#)]);
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mut schema: HashSet<String> = HashSet::new();
-        schema.insert("KNOWN".to_string());
-        let mgr = ConfigManager::new()
-            .with_schema_keys(schema)
-            .with_strict_schema_keys(true)
-            .with_env(env);
+        let mgr = ConfigManager::new().with_env(env);
 
-        let err = mgr.get_public_config("BOGUS").unwrap_err();
-        assert!(err.message.contains("'BOGUS'"));
-        assert!(err.message.contains("not defined"));
-        assert!(err.message.contains("SecretConfigKeys"));
-        assert!(err.message.contains(".smooai-config/config.ts"));
-        match err.kind {
-            SmooaiConfigErrorKind::UndefinedKey { key, schema_path } => {
-                assert_eq!(key, "BOGUS");
-                assert_eq!(schema_path, ".smooai-config/config.ts");
-            }
-            _ => panic!("expected UndefinedKey kind"),
-        }
+        assert_eq!(mgr.get_bytes_decoded("CERT").unwrap().unwrap().as_bytes(), b"hello");
+        assert!(mgr.get_bytes_decoded("MISSING").unwrap().is_none());
+        let err = mgr.get_bytes_decoded("BAD").unwrap_err();
+        assert!(err.message.contains("BAD"));
     }
 
+    // --- Shared registry ---
     #[test]
-    fn test_undefined_key_honors_custom_schema_path() {
+    fn test_shared_dedupes_identical_configuration() {
         let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{}"#)]);
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://shared"}"#)]);
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mut schema: HashSet<String> = HashSet::new();
-        schema.insert("KNOWN".to_string());
-        let mgr = ConfigManager::new()
-            .with_schema_keys(schema)
-            .with_strict_schema_keys(true)
-            .with_schema_path("custom/path.ts")
-            .with_env(env);
 
-        let err = mgr.get_secret_config("BOGUS").unwrap_err();
-        assert!(err.message.contains("custom/path.ts"));
+        let a = ConfigManager::new()
+            .with_env(env.clone())
+            .into_shared("registry-dedupe");
+        let b = ConfigManager::new().with_env(env).into_shared("registry-dedupe");
+        assert!(Arc::ptr_eq(&a, &b));
+
+        // Snapshot is shared: initializing through one handle initializes both.
+        a.get_public_config("API_URL").unwrap();
+        assert!(b.inner.read().unwrap().initialized);
     }
 
     #[test]
-    fn test_known_key_passes_through() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"KNOWN":"v"}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mut schema: HashSet<String> = HashSet::new();
-        schema.insert("KNOWN".to_string());
-        let mgr = ConfigManager::new().with_schema_keys(schema).with_env(env);
+    fn test_shared_separates_different_configuration() {
+        let a = ConfigManager::new()
+            .with_environment("staging")
+            .into_shared("registry-separate");
+        let b = ConfigManager::new()
+            .with_environment("production")
+            .into_shared("registry-separate");
+        assert!(!Arc::ptr_eq(&a, &b));
+    }
 
-        assert_eq!(
-            mgr.get_public_config("KNOWN").unwrap(),
-            Some(Value::String("v".to_string()))
-        );
+    #[test]
+    fn test_shared_separates_profiles() {
+        let a = ConfigManager::new()
+            .with_org_id("org")
+            .into_shared("registry-profile-a");
+        let b = ConfigManager::new()
+            .with_org_id("org")
+            .into_shared("registry-profile-b");
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert!(Arc::ptr_eq(
+            &a,
+            &ConfigManager::new()
+                .with_org_id("org")
+                .into_shared("registry-profile-a")
+        ));
     }
 
     #[test]
-    fn test_no_schema_keys_disables_guard() {
-        let dir = tempfile::tempdir().unwrap();
-        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{}"#)]);
-        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
-        let mgr = ConfigManager::new().with_env(env);
-        // No schema_keys → guard disabled → returns None for unknown keys.
-        assert_eq!(mgr.get_public_config("WHATEVER").unwrap(), None);
+    fn test_shared_compares_sources_and_remaining_settings() {
+        struct Fingerprinted(Option<u64>);
+        impl ConfigSource for Fingerprinted {
+            fn name(&self) -> String {
+                "fingerprinted".to_string()
+            }
+            fn load(&self, _env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+                Ok(HashMap::new())
+            }
+            fn fingerprint(&self) -> Option<u64> {
+                self.0
+            }
+        }
+        let shared = |source: Fingerprinted| ConfigManager::new().with_source(source).into_shared("registry-sources");
+
+        assert!(Arc::ptr_eq(
+            &shared(Fingerprinted(Some(1))),
+            &shared(Fingerprinted(Some(1)))
+        ));
+        assert!(!Arc::ptr_eq(
+            &shared(Fingerprinted(Some(1))),
+            &shared(Fingerprinted(Some(2)))
+        ));
+        assert!(!Arc::ptr_eq(&shared(Fingerprinted(None)), &shared(Fingerprinted(None))));
+
+        let timeout = |secs| {
+            ConfigManager::new()
+                .with_request_timeout(Duration::from_secs(secs))
+                .into_shared("registry-timeout")
+        };
+        assert!(!Arc::ptr_eq(&timeout(1), &timeout(2)));
+        let with_handler = || {
+            ConfigManager::new()
+                .with_deprecation_handler(|_| {})
+                .into_shared("registry-handler")
+        };
+        assert!(!Arc::ptr_eq(&with_handler(), &with_handler()));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_map_ordering() {
+        let env_a: HashMap<String, String> = [("A", "1"), ("B", "2"), ("C", "3")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let env_b: HashMap<String, String> = [("C", "3"), ("A", "1"), ("B", "2")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(
+            ConfigManager::new().with_env(env_a).fingerprint(),
+            ConfigManager::new().with_env(env_b).fingerprint()
+        );
     }
 
     #[test]
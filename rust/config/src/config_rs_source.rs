@@ -0,0 +1,184 @@
+//! [`config::Source`] over a [`ConfigManager`] (feature `config-rs`).
+//!
+//! Projects part-way through migrating from the `config` crate can mount
+//! Smoo AI config as one layer of their existing builder:
+//!
+//! ```rust,ignore
+//! let settings = config::Config::builder()
+//!     .add_source(config::File::with_name("settings"))
+//!     .add_source(ConfigRsSource::new(ConfigManager::new()))
+//!     .build()?;
+//! ```
+//!
+//! As with [`FigmentProvider`](crate::figment_provider), keys are lowercased
+//! unless [`preserve_case`](ConfigRsSource::preserve_case) is set, nested
+//! objects become tables, and secret values are included unredacted.
+
+use std::fmt;
+use std::sync::Arc;
+
+use config::{ConfigError, Map, Source, Value, ValueKind};
+
+use crate::config_manager::ConfigManager;
+#[allow(deprecated)]
+use crate::local::LocalConfigManager;
+
+const ORIGIN: &str = "smooai-config";
+
+/// Serves every merged key of a [`ConfigManager`] to a `config::Config`.
+#[derive(Clone)]
+pub struct ConfigRsSource {
+    manager: Arc<ConfigManager>,
+    lowercase: bool,
+}
+
+impl ConfigRsSource {
+    /// Serve `manager`'s merged config, loaded on each `build()`.
+    pub fn new(manager: impl Into<Arc<ConfigManager>>) -> Self {
+        ConfigRsSource {
+            manager: manager.into(),
+            lowercase: true,
+        }
+    }
+
+    /// Keep keys as the manager spells them (`API_URL`) instead of lowercasing.
+    pub fn preserve_case(mut self) -> Self {
+        self.lowercase = false;
+        self
+    }
+}
+
+impl fmt::Debug for ConfigRsSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigRsSource")
+            .field("lowercase", &self.lowercase)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<ConfigManager> for ConfigRsSource {
+    fn from(manager: ConfigManager) -> Self {
+        ConfigRsSource::new(manager)
+    }
+}
+
+#[allow(deprecated)]
+impl From<LocalConfigManager> for ConfigRsSource {
+    fn from(manager: LocalConfigManager) -> Self {
+        ConfigRsSource::new(manager.into_config_manager())
+    }
+}
+
+impl Source for ConfigRsSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let all = self
+            .manager
+            .get_all_exposed()
+            .map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        Ok(all
+            .into_iter()
+            .map(|(key, value)| {
+                let key = if self.lowercase { key.to_lowercase() } else { key };
+                (key, to_config_value(value))
+            })
+            .collect())
+    }
+}
+
+fn to_config_value(value: serde_json::Value) -> Value {
+    let origin = Some(ORIGIN.to_string());
+    let kind = match value {
+        serde_json::Value::Null => ValueKind::Nil,
+        serde_json::Value::Bool(b) => ValueKind::Boolean(b),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => ValueKind::I64(i),
+            (None, Some(u)) => ValueKind::U64(u),
+            _ => ValueKind::Float(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => ValueKind::String(s),
+        serde_json::Value::Array(items) => ValueKind::Array(items.into_iter().map(to_config_value).collect()),
+        serde_json::Value::Object(map) => {
+            ValueKind::Table(map.into_iter().map(|(k, v)| (k, to_config_value(v))).collect())
+        }
+    };
+    Value::new(origin.as_ref(), kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigFixture;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+        replicas: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Settings {
+        api_url: String,
+        db_password: String,
+        database: Database,
+        ratio: f64,
+        log_level: String,
+    }
+
+    fn manager() -> (ConfigFixture, ConfigManager) {
+        let fixture = ConfigFixture::new().with_file(
+            "default.json",
+            json!({
+                "API_URL": "https://api",
+                "DB_PASSWORD": "hunter2",
+                "RATIO": 0.5,
+                "DATABASE": {"host": "db", "port": 5432, "replicas": ["r1", "r2"]}
+            }),
+        );
+        let manager = fixture.manager();
+        (fixture, manager)
+    }
+
+    #[test]
+    fn test_layers_into_config_builder() {
+        let (_fixture, manager) = manager();
+        let settings: Settings = config::Config::builder()
+            .set_default("log_level", "info")
+            .unwrap()
+            .set_default("api_url", "https://default")
+            .unwrap()
+            .add_source(ConfigRsSource::new(manager))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                api_url: "https://api".to_string(),
+                db_password: "hunter2".to_string(),
+                database: Database {
+                    host: "db".to_string(),
+                    port: 5432,
+                    replicas: vec!["r1".to_string(), "r2".to_string()],
+                },
+                ratio: 0.5,
+                log_level: "info".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_preserve_case() {
+        let (_fixture, manager) = manager();
+        let values = ConfigRsSource::new(manager).preserve_case().collect().unwrap();
+        assert_eq!(values["API_URL"].clone().into_string().unwrap(), "https://api");
+        assert!(!values.contains_key("api_url"));
+    }
+}
@@ -0,0 +1,397 @@
+//! Consul KV source with blocking-query watches (feature `consul`).
+//!
+//! Reads every key under a prefix with a recursive KV read and exposes them
+//! as config keys relative to that prefix, nesting on `/` the same way the
+//! SSM source does:
+//!
+//! ```text
+//! myapp/production/API_URL        -> API_URL
+//! myapp/production/DATABASE/HOST  -> DATABASE.HOST (nested object)
+//! ```
+//!
+//! Values stay strings, matching how env vars are read. Clones of a
+//! `ConsulSource` share their watch state, so a clone registered with the
+//! manager and one used for [`ConsulSource::watch`] see the same index:
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use smooai_config::{ConfigManager, ConsulSource};
+//!
+//! let consul = ConsulSource::new("myapp/production/");
+//! let manager = Arc::new(ConfigManager::new().with_source(consul.clone()));
+//! let watched = Arc::clone(&manager);
+//! let _watch = consul.watch(move || watched.invalidate());
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::{Map, Value};
+
+use crate::redact;
+use crate::source::{insert_nested, ConfigSource, SourceWatch};
+use crate::utils::SmooaiConfigError;
+
+const DEFAULT_ADDRESS: &str = "http://127.0.0.1:8500";
+/// Default blocking-query wait. Consul caps it at 10 minutes.
+pub const DEFAULT_WATCH_WAIT: Duration = Duration::from_secs(300);
+/// Pause after a failed watch request before retrying.
+const WATCH_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct WatchState {
+    /// Last `X-Consul-Index` seen; 0 before the first read.
+    index: AtomicU64,
+    /// Env from the most recent `load`, reused by the watch thread.
+    env: Mutex<Option<HashMap<String, String>>>,
+}
+
+/// Config source backed by Consul's KV store.
+#[derive(Clone)]
+pub struct ConsulSource {
+    prefix: String,
+    address: Option<String>,
+    token: Option<String>,
+    datacenter: Option<String>,
+    watch_wait: Duration,
+    state: Arc<WatchState>,
+}
+
+impl std::fmt::Debug for ConsulSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsulSource")
+            .field("prefix", &self.prefix)
+            .field("address", &self.address)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("datacenter", &self.datacenter)
+            .field("watch_wait", &self.watch_wait)
+            .finish()
+    }
+}
+
+impl ConsulSource {
+    /// Load keys under `prefix` (e.g. `myapp/production/`).
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_start_matches('/').to_string(),
+            address: None,
+            token: None,
+            datacenter: None,
+            watch_wait: DEFAULT_WATCH_WAIT,
+            state: Arc::new(WatchState::default()),
+        }
+    }
+
+    /// Agent address (default: `CONSUL_HTTP_ADDR`, then `http://127.0.0.1:8500`).
+    pub fn with_address(mut self, address: &str) -> Self {
+        self.address = Some(address.to_string());
+        self
+    }
+
+    /// ACL token (default: `CONSUL_HTTP_TOKEN`).
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Datacenter to read from (default: the agent's own).
+    pub fn with_datacenter(mut self, datacenter: &str) -> Self {
+        self.datacenter = Some(datacenter.to_string());
+        self
+    }
+
+    /// How long each blocking query waits for a change (default 5 minutes).
+    pub fn with_watch_wait(mut self, wait: Duration) -> Self {
+        self.watch_wait = wait;
+        self
+    }
+
+    /// The last `X-Consul-Index` observed by `load` or a watch; 0 before the first read.
+    pub fn last_index(&self) -> u64 {
+        self.state.index.load(Ordering::SeqCst)
+    }
+
+    fn address(&self, env: &HashMap<String, String>) -> String {
+        let address = self
+            .address
+            .clone()
+            .or_else(|| env.get("CONSUL_HTTP_ADDR").cloned())
+            .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+        let address = address.trim_end_matches('/');
+        if address.contains("://") {
+            address.to_string()
+        } else {
+            // CONSUL_HTTP_ADDR is commonly set as host:port.
+            format!("http://{}", address)
+        }
+    }
+
+    /// Recursive read of the prefix. With `index`, this is a blocking query
+    /// that returns once the index moves past it or the wait elapses.
+    fn read(
+        &self,
+        env: &HashMap<String, String>,
+        index: Option<u64>,
+    ) -> Result<(u64, Option<Vec<Value>>), SmooaiConfigError> {
+        let url = format!("{}/v1/kv/{}", self.address(env), self.prefix);
+        let mut query: Vec<(&str, String)> = vec![("recurse", "true".to_string())];
+        if let Some(ref dc) = self.datacenter {
+            query.push(("dc", dc.clone()));
+        }
+        let mut timeout = Duration::from_secs(30);
+        if let Some(index) = index {
+            query.push(("index", index.to_string()));
+            query.push(("wait", format!("{}s", self.watch_wait.as_secs().max(1))));
+            // Consul adds up to wait/16 of jitter on top of the wait.
+            timeout += self.watch_wait + self.watch_wait / 16;
+        }
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| SmooaiConfigError::new(&format!("Failed to build Consul client: {}", e)))?;
+        let mut request = client.get(&url).query(&query);
+        if let Some(token) = self.token.clone().or_else(|| env.get("CONSUL_HTTP_TOKEN").cloned()) {
+            request = request.header("X-Consul-Token", token);
+        }
+        let response = request.send().map_err(|e| {
+            SmooaiConfigError::remote_fetch(None, &format!("Consul request failed: {}", e)).with_source(e)
+        })?;
+
+        let new_index = response
+            .headers()
+            .get("X-Consul-Index")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        match response.status() {
+            // No keys under the prefix yet.
+            reqwest::StatusCode::NOT_FOUND => Ok((new_index, None)),
+            status if status.is_success() => {
+                let entries = response
+                    .json::<Vec<Value>>()
+                    .map_err(|e| SmooaiConfigError::new(&format!("Invalid Consul KV response: {}", e)))?;
+                Ok((new_index, Some(entries)))
+            }
+            status => Err(SmooaiConfigError::remote_fetch(
+                Some(status.as_u16()),
+                &format!("Consul returned HTTP {} for {}", status, self.prefix),
+            )),
+        }
+    }
+
+    /// Block until the prefix changes or the watch wait elapses.
+    ///
+    /// Returns `true` when Consul reports a new index. The first call after
+    /// construction only records the current index.
+    pub fn wait_for_change(&self) -> Result<bool, SmooaiConfigError> {
+        let env = self
+            .state
+            .env
+            .lock()
+            .ok()
+            .and_then(|env| env.clone())
+            .unwrap_or_else(|| std::env::vars().collect());
+        let previous = self.last_index();
+        let (index, _) = self.read(&env, (previous > 0).then_some(previous))?;
+        // An index that goes backwards means the KV store was reset; start over.
+        let index = if index < previous { 0 } else { index };
+        self.state.index.store(index, Ordering::SeqCst);
+        Ok(previous > 0 && index != previous)
+    }
+
+    /// Run blocking queries on a background thread, calling `on_change`
+    /// whenever the prefix changes — typically to invalidate a manager.
+    ///
+    /// Failed requests are logged and retried after a short pause. The watch
+    /// stops when the returned handle is dropped or stopped.
+    pub fn watch<F>(&self, on_change: F) -> SourceWatch
+    where
+        F: Fn() + Send + 'static,
+    {
+        let source = self.clone();
+        SourceWatch::spawn(move |stopped| {
+            while !stopped.load(Ordering::SeqCst) {
+                match source.wait_for_change() {
+                    Ok(true) if !stopped.load(Ordering::SeqCst) => on_change(),
+                    Ok(_) => {}
+                    Err(e) => {
+                        redact::warn(format_args!("Consul watch on {} failed: {}", source.prefix, e));
+                        std::thread::sleep(WATCH_RETRY_DELAY);
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl ConfigSource for ConsulSource {
+    fn name(&self) -> String {
+        format!("consul:{}", self.prefix)
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        (
+            &self.prefix,
+            &self.address,
+            &self.token,
+            &self.datacenter,
+            self.watch_wait,
+        )
+            .hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        if let Ok(mut stored) = self.state.env.lock() {
+            *stored = Some(env.clone());
+        }
+        let (index, entries) = self.read(env, None)?;
+        self.state.index.store(index, Ordering::SeqCst);
+
+        let mut values = Map::new();
+        for entry in entries.into_iter().flatten() {
+            let Some(key) = entry.get("Key").and_then(|k| k.as_str()) else {
+                continue;
+            };
+            // Folder placeholders carry no value.
+            let Some(encoded) = entry.get("Value").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let bytes = STANDARD
+                .decode(encoded)
+                .map_err(|_| SmooaiConfigError::new(&format!("Consul key {} has an invalid base64 value", key)))?;
+            let relative = key.strip_prefix(&self.prefix).unwrap_or(key);
+            insert_nested(
+                &mut values,
+                relative,
+                '/',
+                Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+            );
+        }
+        Ok(values.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn entry(key: &str, value: Option<&str>) -> Value {
+        json!({"Key": key, "Value": value.map(|v| STANDARD.encode(v)), "ModifyIndex": 1})
+    }
+
+    #[tokio::test]
+    async fn test_load_nests_below_prefix() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/kv/myapp/production/"))
+            .and(query_param("recurse", "true"))
+            .and(header("X-Consul-Token", "acl"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Consul-Index", "7")
+                    .set_body_json(json!([
+                        entry("myapp/production/", None),
+                        entry("myapp/production/API_URL", Some("https://api")),
+                        entry("myapp/production/DATABASE/HOST", Some("db")),
+                    ])),
+            )
+            .mount(&server)
+            .await;
+
+        let env = HashMap::from([
+            ("CONSUL_HTTP_ADDR".to_string(), server.uri()),
+            ("CONSUL_HTTP_TOKEN".to_string(), "acl".to_string()),
+        ]);
+        let (values, index) = tokio::task::spawn_blocking(move || {
+            let source = ConsulSource::new("myapp/production/");
+            (source.load(&env), source.last_index())
+        })
+        .await
+        .unwrap();
+        let values = values.unwrap();
+        assert_eq!(values["API_URL"], json!("https://api"));
+        assert_eq!(values["DATABASE"], json!({"HOST": "db"}));
+        assert_eq!(index, 7);
+    }
+
+    #[tokio::test]
+    async fn test_missing_prefix_is_empty() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).insert_header("X-Consul-Index", "3"))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let values =
+            tokio::task::spawn_blocking(move || ConsulSource::new("empty/").with_address(&uri).load(&HashMap::new()))
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(values.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_index_changes() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("index", "7"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Consul-Index", "8")
+                    .set_body_json(json!([])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("index", "8"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Consul-Index", "8")
+                    .set_body_json(json!([]))
+                    .set_delay(Duration::from_millis(50)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Consul-Index", "7")
+                    .set_body_json(json!([])),
+            )
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let changes = tokio::task::spawn_blocking(move || {
+            let source = ConsulSource::new("app/")
+                .with_address(&uri)
+                .with_watch_wait(Duration::from_secs(1));
+            source.load(&HashMap::new()).unwrap();
+
+            let (tx, rx) = std::sync::mpsc::channel();
+            let watch = source.clone().watch(move || {
+                let _ = tx.send(());
+            });
+            let first = rx.recv_timeout(Duration::from_secs(5)).is_ok();
+            let second = rx.recv_timeout(Duration::from_millis(300)).is_ok();
+            watch.stop();
+            (first, second, source.last_index())
+        })
+        .await
+        .unwrap();
+        assert_eq!(changes, (true, false, 8));
+    }
+}
@@ -8,6 +8,9 @@ use std::collections::HashMap;
 
 use serde_json::Value;
 
+use crate::redact;
+use crate::utils::render_template;
+
 /// A deferred config value — a closure that computes a value from the merged config.
 pub type DeferredValue = Box<dyn Fn(&HashMap<String, Value>) -> Value + Send + Sync>;
 
@@ -27,6 +30,20 @@ pub fn resolve_deferred(config: &mut HashMap<String, Value>, deferred: &HashMap<
     }
 }
 
+/// A deferred value composed from other keys with
+/// [`render_template`], e.g. `template("postgres://{{DB_HOST}}:{{DB_PORT}}")`.
+/// Resolves to `null` (with a warning) when a referenced key is missing.
+pub fn template(template: &str) -> DeferredValue {
+    let template = template.to_string();
+    Box::new(move |config| match render_template(&template, config) {
+        Ok(rendered) => Value::String(rendered),
+        Err(e) => {
+            redact::warn(format_args!("{}", e.message.trim_start_matches("[Smooai Config] ")));
+            Value::Null
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +133,19 @@ mod tests {
 
         assert_eq!(config["KEY"], json!("value"));
     }
+
+    #[test]
+    fn test_template_deferred() {
+        let mut config: HashMap<String, Value> = HashMap::new();
+        config.insert("HOST".to_string(), json!("localhost"));
+        config.insert("PORT".to_string(), json!(5432));
+
+        let mut deferred: HashMap<String, DeferredValue> = HashMap::new();
+        deferred.insert("URL".to_string(), template("{{HOST}}:{{PORT}}"));
+        deferred.insert("BROKEN".to_string(), template("{{NOPE}}"));
+        resolve_deferred(&mut config, &deferred);
+
+        assert_eq!(config["URL"], json!("localhost:5432"));
+        assert_eq!(config["BROKEN"], Value::Null);
+    }
 }
@@ -0,0 +1,152 @@
+//! Deprecated keys, declared in the schema.
+//!
+//! A property is deprecated by annotating it with `x-deprecated`:
+//!
+//! ```json
+//! "apiUrl": { "type": "string", "x-deprecated": "API_BASE_URL", "deprecatedSince": "2025-06" }
+//! "legacyMode": { "type": "boolean", "x-deprecated": true }
+//! "region": {
+//!   "type": "string",
+//!   "x-deprecated": { "replacement": "AWS_REGION", "since": "7.0", "message": "Set by the platform now." }
+//! }
+//! ```
+//!
+//! A string names the replacement key. The standard `"deprecated": true`
+//! keyword (what schemars emits for `#[deprecated]` fields) is honoured too.
+//!
+//! [`ConfigManager::with_definition`](crate::ConfigManager::with_definition)
+//! picks these up; the first read of each deprecated key is reported once,
+//! to stderr by default or to a handler set with
+//! [`ConfigManager::with_deprecation_handler`](crate::ConfigManager::with_deprecation_handler).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::{property_key, ConfigDefinition, ConfigTier};
+
+/// A deprecated key and what to use instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyDeprecation {
+    /// UPPER_SNAKE_CASE key.
+    pub key: String,
+    pub tier: ConfigTier,
+    pub since: Option<String>,
+    /// Key to read instead, if there is one.
+    pub replacement: Option<String>,
+    pub message: Option<String>,
+}
+
+impl fmt::Display for KeyDeprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is deprecated", self.key)?;
+        if let Some(ref since) = self.since {
+            write!(f, " since {}", since)?;
+        }
+        if let Some(ref replacement) = self.replacement {
+            write!(f, "; use {} instead", replacement)?;
+        }
+        if let Some(ref message) = self.message {
+            write!(f, ". {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl ConfigDefinition {
+    /// Every deprecated property, keyed like
+    /// [`schema_keys`](ConfigDefinition::schema_keys). When a key is declared
+    /// in several tiers the first tier wins.
+    pub fn deprecations(&self) -> HashMap<String, KeyDeprecation> {
+        let mut deprecations = HashMap::new();
+        for tier in [ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag] {
+            let Some(properties) = self.tier_schema(tier).get("properties").and_then(|p| p.as_object()) else {
+                continue;
+            };
+            for (name, schema) in properties {
+                let key = property_key(name);
+                if deprecations.contains_key(&key) {
+                    continue;
+                }
+                if let Some(deprecation) = parse(&key, tier, schema) {
+                    deprecations.insert(key, deprecation);
+                }
+            }
+        }
+        deprecations
+    }
+}
+
+fn parse(key: &str, tier: ConfigTier, schema: &Value) -> Option<KeyDeprecation> {
+    let mut deprecation = KeyDeprecation {
+        key: key.to_string(),
+        tier,
+        since: string_field(schema, "deprecatedSince"),
+        replacement: None,
+        message: None,
+    };
+    match schema.get("x-deprecated") {
+        Some(Value::Bool(true)) => {}
+        Some(Value::String(replacement)) => deprecation.replacement = Some(property_key(replacement)),
+        Some(Value::Object(details)) => {
+            let details = Value::Object(details.clone());
+            deprecation.replacement = string_field(&details, "replacement").map(|r| property_key(&r));
+            deprecation.message = string_field(&details, "message");
+            deprecation.since = string_field(&details, "since").or(deprecation.since);
+        }
+        _ if schema.get("deprecated") == Some(&Value::Bool(true)) => {}
+        _ => return None,
+    }
+    Some(deprecation)
+}
+
+fn string_field(schema: &Value, field: &str) -> Option<String> {
+    schema.get(field).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::define_config;
+    use serde_json::json;
+
+    #[test]
+    fn test_deprecations_from_annotations() {
+        let definition = define_config(
+            Some(json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string", "x-deprecated": "apiBaseUrl", "deprecatedSince": "2025-06"},
+                "apiBaseUrl": {"type": "string"},
+                "legacy_mode": {"type": "boolean", "deprecated": true},
+                "region": {"type": "string", "x-deprecated": {
+                    "replacement": "AWS_REGION", "since": "7.0", "message": "Set by the platform now."
+                }}
+            }})),
+            None,
+            Some(json!({"type": "object", "properties": {"oldFlag": {"type": "boolean", "x-deprecated": true}}})),
+        );
+        let deprecations = definition.deprecations();
+        assert_eq!(deprecations.len(), 4);
+        assert_eq!(
+            deprecations["API_URL"].to_string(),
+            "API_URL is deprecated since 2025-06; use API_BASE_URL instead"
+        );
+        assert_eq!(deprecations["LEGACY_MODE"].to_string(), "LEGACY_MODE is deprecated");
+        assert_eq!(
+            deprecations["REGION"].to_string(),
+            "REGION is deprecated since 7.0; use AWS_REGION instead. Set by the platform now."
+        );
+        assert_eq!(deprecations["OLD_FLAG"].tier, ConfigTier::FeatureFlag);
+    }
+
+    #[test]
+    fn test_x_deprecated_false_is_not_deprecated() {
+        let definition = define_config(
+            Some(json!({"type": "object", "properties": {"apiUrl": {"type": "string", "x-deprecated": false}}})),
+            None,
+            None,
+        );
+        assert!(definition.deprecations().is_empty());
+    }
+}
@@ -0,0 +1,453 @@
+//! A serde [`Deserializer`] over the merged config.
+//!
+//! [`ConfigManager::deserializer`] lets a whole app config struct be read in
+//! one go, straight from the merged map rather than via an intermediate
+//! `serde_json::Value`:
+//!
+//! ```rust,ignore
+//! #[derive(Deserialize)]
+//! struct AppConfig { api_url: String, database: Database, new_checkout: Option<bool> }
+//!
+//! let config = AppConfig::deserialize(manager.deserializer())?;
+//! ```
+//!
+//! Struct fields match keys in any case: `api_url`, `apiUrl` and `API_URL`
+//! all read `API_URL`, at every nesting level. Numbers and booleans may also
+//! be given as strings (`"8080"`, `"true"`), as env vars usually are. Errors
+//! are [`SmooaiConfigError`]s; a bad value is a
+//! [`Coercion`](crate::SmooaiConfigErrorKind::Coercion) error naming its
+//! dotted path (`DATABASE.port`). Secret values are deserialized as-is.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::Value;
+
+use crate::config_manager::ConfigManager;
+use crate::schema::property_key;
+use crate::utils::{SmooaiConfigError, SmooaiConfigErrorKind};
+
+/// Deserializer over a snapshot of a manager's merged config. See [`crate::deserializer`].
+pub struct ConfigDeserializer {
+    values: Result<BTreeMap<String, Value>, SmooaiConfigError>,
+}
+
+impl ConfigManager {
+    /// A serde [`Deserializer`] over every merged key, so
+    /// `T::deserialize(manager.deserializer())` reads a whole config struct.
+    /// Loads config first if needed; a load failure is returned by `deserialize`.
+    pub fn deserializer(&self) -> ConfigDeserializer {
+        ConfigDeserializer {
+            values: self.get_all_exposed(),
+        }
+    }
+}
+
+impl de::Error for SmooaiConfigError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SmooaiConfigError::new(&msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        SmooaiConfigError::new(&format!(
+            "Missing config key {} (field `{}`)",
+            property_key(field),
+            field
+        ))
+    }
+}
+
+impl<'de> Deserializer<'de> for ConfigDeserializer {
+    type Error = SmooaiConfigError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let values = self.values?;
+        visitor.visit_map(Entries::new(values.iter(), &[], ""))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let values = self.values?;
+        visitor.visit_map(Entries::new(values.iter(), fields, ""))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// One value of the merged config; `path` is its dotted key for errors.
+struct ValueDeserializer<'a> {
+    value: &'a Value,
+    path: String,
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                if let Value::String(s) = self.value {
+                    if let Ok(parsed) = s.trim().parse::<$ty>() {
+                        return visitor.$visit(parsed);
+                    }
+                }
+                self.deserialize_any(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = SmooaiConfigError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(u), _) => visitor.visit_u64(u),
+                (None, Some(i)) => visitor.visit_i64(i),
+                _ => visitor.visit_f64(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => visitor.visit_str(s),
+            Value::Array(items) => visitor.visit_seq(Items {
+                iter: items.iter().enumerate(),
+                path: self.path,
+            }),
+            Value::Object(map) => visitor.visit_map(Entries::new(map.iter(), &[], &self.path)),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if let Value::String(s) = self.value {
+            match s.trim().to_lowercase().as_str() {
+                "true" => return visitor.visit_bool(true),
+                "false" => return visitor.visit_bool(false),
+                _ => {}
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Object(map) => visitor.visit_map(Entries::new(map.iter(), fields, &self.path)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        // Enums are rare in config and serde_json already handles every
+        // representation; only this value is cloned.
+        self.value
+            .clone()
+            .deserialize_enum(name, variants, visitor)
+            .map_err(de::Error::custom)
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+}
+
+/// Map entries, with keys renamed to the struct field they match.
+struct Entries<'a, I> {
+    iter: I,
+    /// `(UPPER_SNAKE key, field name)` for each struct field.
+    fields: Vec<(String, &'static str)>,
+    parent: String,
+    pending: Option<(String, &'a Value)>,
+}
+
+impl<'a, I: Iterator<Item = (&'a String, &'a Value)>> Entries<'a, I> {
+    fn new(iter: I, fields: &'static [&'static str], parent: &str) -> Self {
+        Entries {
+            iter,
+            fields: fields.iter().map(|f| (property_key(f), *f)).collect(),
+            parent: parent.to_string(),
+            pending: None,
+        }
+    }
+}
+
+impl<'de, 'a, I: Iterator<Item = (&'a String, &'a Value)>> MapAccess<'de> for Entries<'a, I> {
+    type Error = SmooaiConfigError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        let name = match self.fields.is_empty() {
+            true => key.as_str(),
+            false => {
+                let normalized = property_key(key);
+                self.fields
+                    .iter()
+                    .find(|(k, _)| *k == normalized)
+                    .map_or(key.as_str(), |(_, field)| field)
+            }
+        };
+        let path = match self.parent.is_empty() {
+            true => key.clone(),
+            false => format!("{}.{}", self.parent, key),
+        };
+        self.pending = Some((path, value));
+        seed.deserialize(name.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let (path, value) = self
+            .pending
+            .take()
+            .ok_or_else(|| SmooaiConfigError::new("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(ValueDeserializer {
+            value,
+            path: path.clone(),
+        })
+        .map_err(|e| at_path(e, &path))
+    }
+}
+
+/// Array elements; their paths are `KEY[index]`.
+struct Items<I> {
+    iter: I,
+    path: String,
+}
+
+impl<'de, 'a, I: Iterator<Item = (usize, &'a Value)>> SeqAccess<'de> for Items<I> {
+    type Error = SmooaiConfigError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        let Some((index, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        let path = format!("{}[{}]", self.path, index);
+        seed.deserialize(ValueDeserializer {
+            value,
+            path: path.clone(),
+        })
+        .map(Some)
+        .map_err(|e| at_path(e, &path))
+    }
+}
+
+/// Attach `path` to an error from deserializing the value there, unless a
+/// deeper value already did.
+fn at_path(error: SmooaiConfigError, path: &str) -> SmooaiConfigError {
+    if matches!(error.kind, SmooaiConfigErrorKind::Coercion { .. }) {
+        return error;
+    }
+    let message = error.message.trim_start_matches("[Smooai Config] ");
+    SmooaiConfigError::coercion(path, &format!("Invalid value for {}: {}", path, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum LogLevel {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+        #[serde(rename = "maxConnections")]
+        max_connections: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        api_url: String,
+        database: Database,
+        log_level: LogLevel,
+        replicas: Vec<String>,
+        new_checkout: Option<bool>,
+        missing: Option<String>,
+        ratio: f64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct CamelConfig {
+        api_url: String,
+        db_password: String,
+    }
+
+    fn make_manager(default_json: &str) -> (tempfile::TempDir, ConfigManager) {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".smooai-config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(config_dir.join("default.json"), default_json).unwrap();
+        let vars = HashMap::from([
+            (
+                "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                config_dir.to_string_lossy().to_string(),
+            ),
+            ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+        ]);
+        (dir, ConfigManager::new().with_env(vars))
+    }
+
+    const DEFAULT_JSON: &str = r#"{
+        "API_URL": "https://api",
+        "DATABASE": {"HOST": "db", "port": "5432", "max_connections": 10},
+        "LOG_LEVEL": "info",
+        "REPLICAS": ["r1", "r2"],
+        "NEW_CHECKOUT": "true",
+        "RATIO": 0.5,
+        "DB_PASSWORD": "hunter2"
+    }"#;
+
+    #[test]
+    fn test_deserializes_struct_with_field_name_mapping() {
+        let (_dir, manager) = make_manager(DEFAULT_JSON);
+        let config = AppConfig::deserialize(manager.deserializer()).unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                api_url: "https://api".to_string(),
+                database: Database {
+                    host: "db".to_string(),
+                    port: 5432,
+                    max_connections: 10,
+                },
+                log_level: LogLevel::Info,
+                replicas: vec!["r1".to_string(), "r2".to_string()],
+                new_checkout: Some(true),
+                missing: None,
+                ratio: 0.5,
+            }
+        );
+
+        let camel = CamelConfig::deserialize(manager.deserializer()).unwrap();
+        assert_eq!(camel.api_url, "https://api");
+        assert_eq!(camel.db_password, "hunter2");
+
+        let all = HashMap::<String, serde_json::Value>::deserialize(manager.deserializer()).unwrap();
+        assert_eq!(all["API_URL"], "https://api");
+        assert_eq!(all["ENV"], "test");
+    }
+
+    #[test]
+    fn test_errors_name_the_key() {
+        let (_dir, manager) = make_manager(
+            r#"{"API_URL":"x","DATABASE":{"HOST":"db","PORT":"not a port","MAX_CONNECTIONS":1},
+                "LOG_LEVEL":"info","REPLICAS":[],"RATIO":1}"#,
+        );
+        let err = AppConfig::deserialize(manager.deserializer()).unwrap_err();
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::Coercion {
+                key: "DATABASE.PORT".to_string()
+            }
+        );
+        assert!(
+            err.message
+                .starts_with("[Smooai Config] Invalid value for DATABASE.PORT: invalid type: string"),
+            "{}",
+            err
+        );
+
+        let (_dir, manager) = make_manager(r#"{"REPLICAS":["a",1]}"#);
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Replicas {
+            replicas: Vec<String>,
+        }
+        let err = Replicas::deserialize(manager.deserializer()).unwrap_err();
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::Coercion {
+                key: "REPLICAS[1]".to_string()
+            }
+        );
+
+        let err = CamelConfig::deserialize(manager.deserializer()).unwrap_err();
+        assert!(
+            err.message.contains("Missing config key API_URL (field `apiUrl`)"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_string_values_coerce() {
+        let (_dir, manager) = make_manager(r#"{"PORT":"8080","DEBUG":"FALSE","RATIO":" 0.25"}"#);
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct EnvConfig {
+            port: u16,
+            debug: bool,
+            ratio: f32,
+        }
+        let config = EnvConfig::deserialize(manager.deserializer()).unwrap();
+        assert_eq!(
+            config,
+            EnvConfig {
+                port: 8080,
+                debug: false,
+                ratio: 0.25
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_error_is_returned() {
+        let (_dir, manager) = make_manager("{not json");
+        let manager = manager.with_required_keys(["API_URL"]);
+        assert!(HashMap::<String, serde_json::Value>::deserialize(manager.deserializer()).is_err());
+    }
+}
@@ -5,14 +5,15 @@ use std::collections::{HashMap, HashSet};
 use serde_json::Value;
 
 use crate::cloud_region::get_cloud_region_from_env;
-use crate::utils::coerce_boolean;
+use crate::redact;
+use crate::utils::{coerce_boolean, coerce_enum, format_allowed_values, parse_enum_type_hint, ENUM_TYPE_HINT_PREFIX};
 
 /// Extract config values from environment variables.
 ///
 /// For each env var:
 /// - Strip prefix if present
 /// - Check if key is in schema_keys
-/// - Coerce types based on schema_types
+/// - Coerce types based on schema_types; values outside an enum hint's set are dropped
 /// - Sets built-in keys: ENV, IS_LOCAL, REGION, CLOUD_PROVIDER
 pub fn find_and_process_env_config(
     schema_keys: &HashSet<String>,
@@ -37,6 +38,24 @@ pub fn find_and_process_env_config_with_env(
         .unwrap_or_else(|| "development".to_string());
     let is_local = coerce_boolean(env.get("IS_LOCAL").map(|s| s.as_str()).unwrap_or(""));
 
+    let mut result = process_env_vars_with_env(schema_keys, prefix, schema_types, env);
+
+    // Set built-in keys
+    result.insert("ENV".to_string(), Value::String(env_name));
+    result.insert("IS_LOCAL".to_string(), Value::Bool(is_local));
+    result.insert("REGION".to_string(), Value::String(cloud_region.region));
+    result.insert("CLOUD_PROVIDER".to_string(), Value::String(cloud_region.provider));
+
+    result
+}
+
+/// The schema keys set in `env`, coerced, without the built-in keys.
+pub(crate) fn process_env_vars_with_env(
+    schema_keys: &HashSet<String>,
+    prefix: &str,
+    schema_types: Option<&HashMap<String, String>>,
+    env: &HashMap<String, String>,
+) -> HashMap<String, Value> {
     let mut result: HashMap<String, Value> = HashMap::new();
 
     for (key, value) in env {
@@ -69,12 +88,31 @@ pub fn find_and_process_env_config_with_env(
                             continue;
                         }
                     }
+                    // Base64 blobs stay strings; ConfigManager::get_bytes_decoded
+                    // validates and decodes them at read time.
+                    "bytes" => {}
                     "json" | "object" => {
                         if let Ok(parsed) = serde_json::from_str::<Value>(value) {
                             result.insert(key_to_use.to_string(), parsed);
                             continue;
                         }
                     }
+                    hint if hint.starts_with(ENUM_TYPE_HINT_PREFIX) => {
+                        if let Some(allowed) = parse_enum_type_hint(hint) {
+                            match coerce_enum(value, &allowed) {
+                                Some(coerced) => {
+                                    result.insert(key_to_use.to_string(), coerced);
+                                }
+                                None => redact::warn(format_args!(
+                                    "Ignoring {}={:?}: expected one of {}",
+                                    key,
+                                    value,
+                                    format_allowed_values(&allowed)
+                                )),
+                            }
+                            continue;
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -83,12 +121,6 @@ pub fn find_and_process_env_config_with_env(
         result.insert(key_to_use.to_string(), Value::String(value.clone()));
     }
 
-    // Set built-in keys
-    result.insert("ENV".to_string(), Value::String(env_name));
-    result.insert("IS_LOCAL".to_string(), Value::Bool(is_local));
-    result.insert("REGION".to_string(), Value::String(cloud_region.region));
-    result.insert("CLOUD_PROVIDER".to_string(), Value::String(cloud_region.provider));
-
     result
 }
 
@@ -126,6 +158,25 @@ mod tests {
         assert_eq!(result["API_URL"], Value::String("http://example.com".to_string()));
     }
 
+    #[test]
+    fn test_coerces_enum_and_rejects_values_outside_set() {
+        let schema_keys = keys(&["LOG_LEVEL", "TIMEOUT"]);
+        let mut types = HashMap::new();
+        types.insert(
+            "LOG_LEVEL".to_string(),
+            crate::utils::enum_type_hint(&[Value::from("debug"), Value::from("info")]),
+        );
+        types.insert("TIMEOUT".to_string(), crate::utils::enum_type_hint(&[Value::from(30)]));
+        let env = make_env(&[("LOG_LEVEL", "INFO"), ("TIMEOUT", "30")]);
+        let result = find_and_process_env_config_with_env(&schema_keys, "", Some(&types), &env);
+        assert_eq!(result["LOG_LEVEL"], Value::from("info"));
+        assert_eq!(result["TIMEOUT"], Value::from(30));
+
+        let env = make_env(&[("LOG_LEVEL", "verbose")]);
+        let result = find_and_process_env_config_with_env(&schema_keys, "", Some(&types), &env);
+        assert!(!result.contains_key("LOG_LEVEL"));
+    }
+
     #[test]
     fn test_coerces_boolean() {
         let schema_keys = keys(&["ENABLE_DEBUG"]);
@@ -158,6 +209,16 @@ mod tests {
         assert_eq!(db["port"], serde_json::json!(5432));
     }
 
+    #[test]
+    fn test_bytes_hint_keeps_string() {
+        let schema_keys = keys(&["TLS_CERT"]);
+        let mut types = HashMap::new();
+        types.insert("TLS_CERT".to_string(), "bytes".to_string());
+        let env = make_env(&[("TLS_CERT", "MTIz")]);
+        let result = find_and_process_env_config_with_env(&schema_keys, "", Some(&types), &env);
+        assert_eq!(result["TLS_CERT"], Value::String("MTIz".to_string()));
+    }
+
     #[test]
     fn test_sets_builtin_keys() {
         let env = make_env(&[("SMOOAI_CONFIG_ENV", "production"), ("AWS_REGION", "us-east-1")]);
@@ -0,0 +1,466 @@
+//! etcd v3 source (feature `etcd`).
+//!
+//! Reads every key under a prefix through etcd's JSON gateway
+//! (`/v3/kv/range`) and exposes them as config keys relative to that prefix.
+//! Keys are split on a delimiter into nested objects (default `/`):
+//!
+//! ```text
+//! /myapp/production/API_URL        -> API_URL
+//! /myapp/production/DATABASE/HOST  -> DATABASE.HOST (nested object)
+//! ```
+//!
+//! [`EtcdSource::watch`] follows the range with a `/v3/watch` stream and calls
+//! back on every change. Clones share their revision, so one clone can be
+//! registered with the manager and another watched:
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use smooai_config::{ConfigManager, EtcdSource};
+//!
+//! let etcd = EtcdSource::new("/myapp/production/");
+//! let manager = Arc::new(ConfigManager::new().with_source(etcd.clone()));
+//! let watched = Arc::clone(&manager);
+//! let _watch = etcd.watch(move || watched.invalidate());
+//! ```
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::{json, Map, Value};
+
+use crate::redact;
+use crate::source::{insert_nested, ConfigSource, SourceWatch};
+use crate::utils::SmooaiConfigError;
+
+const DEFAULT_ENDPOINT: &str = "http://127.0.0.1:2379";
+/// Pause before reconnecting a dropped or failed watch stream.
+const WATCH_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct WatchState {
+    /// Store revision of the last read or watch event; 0 before the first read.
+    revision: AtomicI64,
+    /// Env from the most recent `load`, reused by the watch thread.
+    env: Mutex<Option<HashMap<String, String>>>,
+}
+
+/// Config source backed by an etcd v3 key range.
+#[derive(Clone)]
+pub struct EtcdSource {
+    prefix: String,
+    endpoint: Option<String>,
+    credentials: Option<(String, String)>,
+    delimiter: Option<char>,
+    state: Arc<WatchState>,
+}
+
+impl std::fmt::Debug for EtcdSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EtcdSource")
+            .field("prefix", &self.prefix)
+            .field("endpoint", &self.endpoint)
+            .field("user", &self.credentials.as_ref().map(|(user, _)| user))
+            .field("delimiter", &self.delimiter)
+            .finish()
+    }
+}
+
+impl EtcdSource {
+    /// Load keys under `prefix` (e.g. `/myapp/production/`).
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            endpoint: None,
+            credentials: None,
+            delimiter: Some('/'),
+            state: Arc::new(WatchState::default()),
+        }
+    }
+
+    /// Gateway endpoint (default: first of `ETCD_ENDPOINTS`, then `http://127.0.0.1:2379`).
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Authenticate as `user` (etcd RBAC).
+    pub fn with_credentials(mut self, user: &str, password: &str) -> Self {
+        self.credentials = Some((user.to_string(), password.to_string()));
+        self
+    }
+
+    /// Delimiter that splits keys into nested objects; `None` keeps each
+    /// relative key as a flat top-level entry.
+    pub fn with_delimiter(mut self, delimiter: Option<char>) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Store revision of the last read or watch event; 0 before the first read.
+    pub fn last_revision(&self) -> i64 {
+        self.state.revision.load(Ordering::SeqCst)
+    }
+
+    fn endpoint(&self, env: &HashMap<String, String>) -> String {
+        let endpoint = self
+            .endpoint
+            .clone()
+            .or_else(|| {
+                env.get("ETCD_ENDPOINTS")
+                    .and_then(|e| e.split(',').next())
+                    .map(|e| e.trim().to_string())
+            })
+            .filter(|e| !e.is_empty())
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        let endpoint = endpoint.trim_end_matches('/');
+        if endpoint.contains("://") {
+            endpoint.to_string()
+        } else {
+            format!("http://{}", endpoint)
+        }
+    }
+
+    /// The `range_end` covering every key that starts with the prefix.
+    fn range_end(&self) -> Vec<u8> {
+        let mut end = self.prefix.as_bytes().to_vec();
+        while let Some(last) = end.pop() {
+            if last < 0xff {
+                end.push(last + 1);
+                return end;
+            }
+        }
+        // Empty (or all-0xff) prefix: the whole keyspace.
+        vec![0]
+    }
+
+    fn auth_token(
+        &self,
+        client: &reqwest::blocking::Client,
+        endpoint: &str,
+    ) -> Result<Option<String>, SmooaiConfigError> {
+        let Some((ref user, ref password)) = self.credentials else {
+            return Ok(None);
+        };
+        let body = post_json(
+            client,
+            &format!("{}/v3/auth/authenticate", endpoint),
+            None,
+            &json!({ "name": user, "password": password }),
+        )?;
+        body.get("token")
+            .and_then(|t| t.as_str())
+            .map(|t| Some(t.to_string()))
+            .ok_or_else(|| SmooaiConfigError::new("etcd authentication returned no token"))
+    }
+
+    /// Run one watch stream until it ends, errors, or `stopped` is set.
+    fn watch_stream<F: Fn()>(&self, stopped: &AtomicBool, on_change: &F) -> Result<(), SmooaiConfigError> {
+        let env = self
+            .state
+            .env
+            .lock()
+            .ok()
+            .and_then(|env| env.clone())
+            .unwrap_or_else(|| std::env::vars().collect());
+        let endpoint = self.endpoint(&env);
+        // No overall timeout: the stream stays open for as long as the watch runs.
+        let client = reqwest::blocking::Client::builder()
+            .timeout(None)
+            .build()
+            .map_err(|e| SmooaiConfigError::new(&format!("Failed to build etcd client: {}", e)))?;
+        let token = self.auth_token(&client, &endpoint)?;
+
+        let mut create = json!({
+            "key": STANDARD.encode(&self.prefix),
+            "range_end": STANDARD.encode(self.range_end()),
+        });
+        let revision = self.last_revision();
+        if revision > 0 {
+            create["start_revision"] = json!((revision + 1).to_string());
+        }
+        let mut request = client
+            .post(format!("{}/v3/watch", endpoint))
+            .json(&json!({ "create_request": create }));
+        if let Some(token) = token {
+            request = request.header("Authorization", token);
+        }
+        let response = request.send().map_err(|e| {
+            SmooaiConfigError::remote_fetch(None, &format!("etcd watch request failed: {}", e)).with_source(e)
+        })?;
+        if !response.status().is_success() {
+            return Err(SmooaiConfigError::remote_fetch(
+                Some(response.status().as_u16()),
+                &format!("etcd watch returned HTTP {}", response.status()),
+            ));
+        }
+
+        // The gateway streams one JSON message per line.
+        for line in BufReader::new(response).lines() {
+            if stopped.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            let line = line.map_err(|e| SmooaiConfigError::new(&format!("etcd watch stream failed: {}", e)))?;
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            let Some(result) = message.get("result") else {
+                continue;
+            };
+            if let Some(revision) = result
+                .pointer("/header/revision")
+                .and_then(|r| r.as_str())
+                .and_then(|r| r.parse::<i64>().ok())
+            {
+                self.state.revision.fetch_max(revision, Ordering::SeqCst);
+            }
+            if result
+                .get("events")
+                .and_then(|e| e.as_array())
+                .is_some_and(|events| !events.is_empty())
+            {
+                on_change();
+            }
+        }
+        Ok(())
+    }
+
+    /// Watch the range on a background thread, calling `on_change` after
+    /// every batch of updates — typically to invalidate a manager.
+    ///
+    /// The watch resumes from the last seen revision, so no update between
+    /// `load` and the watch starting (or across reconnects) is missed. It
+    /// stops when the returned handle is dropped or stopped.
+    pub fn watch<F>(&self, on_change: F) -> SourceWatch
+    where
+        F: Fn() + Send + 'static,
+    {
+        let source = self.clone();
+        SourceWatch::spawn(move |stopped| {
+            while !stopped.load(Ordering::SeqCst) {
+                if let Err(e) = source.watch_stream(&stopped, &on_change) {
+                    redact::warn(format_args!("etcd watch on {} failed: {}", source.prefix, e));
+                }
+                if !stopped.load(Ordering::SeqCst) {
+                    std::thread::sleep(WATCH_RETRY_DELAY);
+                }
+            }
+        })
+    }
+}
+
+fn post_json(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    token: Option<&str>,
+    body: &Value,
+) -> Result<Value, SmooaiConfigError> {
+    let mut request = client.post(url).json(body);
+    if let Some(token) = token {
+        request = request.header("Authorization", token);
+    }
+    let response = request
+        .send()
+        .map_err(|e| SmooaiConfigError::remote_fetch(None, &format!("etcd request failed: {}", e)).with_source(e))?;
+    let status = response.status();
+    let body: Value = response.json().unwrap_or(Value::Null);
+    if !status.is_success() {
+        let message = body.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+        return Err(SmooaiConfigError::remote_fetch(
+            Some(status.as_u16()),
+            &format!(
+                "etcd returned HTTP {}{}",
+                status,
+                if message.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", message)
+                }
+            ),
+        ));
+    }
+    Ok(body)
+}
+
+impl ConfigSource for EtcdSource {
+    fn name(&self) -> String {
+        format!("etcd:{}", self.prefix)
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        if let Ok(mut stored) = self.state.env.lock() {
+            *stored = Some(env.clone());
+        }
+        let endpoint = self.endpoint(env);
+        let client = reqwest::blocking::Client::new();
+        let token = self.auth_token(&client, &endpoint)?;
+        let body = post_json(
+            &client,
+            &format!("{}/v3/kv/range", endpoint),
+            token.as_deref(),
+            &json!({
+                "key": STANDARD.encode(&self.prefix),
+                "range_end": STANDARD.encode(self.range_end()),
+            }),
+        )?;
+
+        if let Some(revision) = body
+            .pointer("/header/revision")
+            .and_then(|r| r.as_str())
+            .and_then(|r| r.parse::<i64>().ok())
+        {
+            self.state.revision.store(revision, Ordering::SeqCst);
+        }
+
+        let mut values = Map::new();
+        for kv in body.get("kvs").and_then(|k| k.as_array()).into_iter().flatten() {
+            let decode = |field: &str| {
+                kv.get(field)
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| STANDARD.decode(v).ok())
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            };
+            let Some(key) = decode("key") else {
+                continue;
+            };
+            // The gateway omits empty values.
+            let value = Value::String(decode("value").unwrap_or_default());
+            let relative = key.strip_prefix(&self.prefix).unwrap_or(&key);
+            match self.delimiter {
+                Some(delimiter) => insert_nested(&mut values, relative, delimiter, value),
+                None if !relative.is_empty() => {
+                    values.insert(relative.to_string(), value);
+                }
+                None => {}
+            }
+        }
+        Ok(values.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn kv(key: &str, value: &str) -> Value {
+        json!({"key": STANDARD.encode(key), "value": STANDARD.encode(value), "mod_revision": "3"})
+    }
+
+    async fn mock_range(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path("/v3/kv/range"))
+            .and(body_json(json!({
+                "key": STANDARD.encode("/app/"),
+                "range_end": STANDARD.encode("/app0"),
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "header": {"revision": "12"},
+                "kvs": [kv("/app/API_URL", "https://api"), kv("/app/DATABASE/HOST", "db")],
+                "count": "2"
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_range_end_increments_last_byte() {
+        assert_eq!(EtcdSource::new("/app/").range_end(), b"/app0".to_vec());
+        assert_eq!(EtcdSource::new("").range_end(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_load_nests_on_delimiter() {
+        let server = MockServer::start().await;
+        mock_range(&server).await;
+
+        let env = HashMap::from([("ETCD_ENDPOINTS".to_string(), format!("{},http://other", server.uri()))]);
+        let (nested, flat, revision) = tokio::task::spawn_blocking(move || {
+            let source = EtcdSource::new("/app/");
+            let nested = source.load(&env).unwrap();
+            let flat = EtcdSource::new("/app/").with_delimiter(None).load(&env).unwrap();
+            (nested, flat, source.last_revision())
+        })
+        .await
+        .unwrap();
+        assert_eq!(nested["API_URL"], json!("https://api"));
+        assert_eq!(nested["DATABASE"], json!({"HOST": "db"}));
+        assert_eq!(flat["DATABASE/HOST"], json!("db"));
+        assert_eq!(revision, 12);
+    }
+
+    #[tokio::test]
+    async fn test_authenticates_with_credentials() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v3/auth/authenticate"))
+            .and(body_json(json!({"name": "app", "password": "pw"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"token": "tok"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v3/kv/range"))
+            .and(header("Authorization", "tok"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"header": {"revision": "1"}})))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let values = tokio::task::spawn_blocking(move || {
+            EtcdSource::new("/app/")
+                .with_endpoint(&uri)
+                .with_credentials("app", "pw")
+                .load(&HashMap::new())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(values.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_resumes_after_load_revision() {
+        let server = MockServer::start().await;
+        mock_range(&server).await;
+        let stream = [
+            json!({"result": {"header": {"revision": "12"}, "created": true}}),
+            json!({"result": {"header": {"revision": "13"}, "events": [{"kv": kv("/app/API_URL", "x")}]}}),
+        ]
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+        Mock::given(method("POST"))
+            .and(path("/v3/watch"))
+            .and(body_json(json!({"create_request": {
+                "key": STANDARD.encode("/app/"),
+                "range_end": STANDARD.encode("/app0"),
+                "start_revision": "13",
+            }})))
+            .respond_with(ResponseTemplate::new(200).set_body_string(stream))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let (changed, revision) = tokio::task::spawn_blocking(move || {
+            let source = EtcdSource::new("/app/").with_endpoint(&uri);
+            source.load(&HashMap::new()).unwrap();
+            let (tx, rx) = std::sync::mpsc::channel();
+            let watch = source.watch(move || {
+                let _ = tx.send(());
+            });
+            let changed = rx.recv_timeout(Duration::from_secs(5)).is_ok();
+            watch.stop();
+            (changed, source.last_revision())
+        })
+        .await
+        .unwrap();
+        assert!(changed);
+        assert_eq!(revision, 13);
+    }
+}
@@ -0,0 +1,170 @@
+//! Example config files generated from a schema.
+//!
+//! [`ConfigDefinition::generate_example`] fills in every declared key so a
+//! new service can scaffold its config directory from the schema:
+//!
+//! ```rust,ignore
+//! let example = definition.generate_example();
+//! std::fs::write(".smooai-config/default.json", serde_json::to_string_pretty(&example)?)?;
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::schema::{property_key, resolve_ref, ConfigDefinition};
+
+impl ConfigDefinition {
+    /// A `default.json`-shaped object with a value for every declared key,
+    /// all tiers together, keyed by UPPER_SNAKE_CASE name.
+    ///
+    /// Each value is the property's `default`, else its `const`, first `enum`
+    /// value or first `examples` entry, else a placeholder for its type:
+    /// format-appropriate strings (`user@example.com`, `https://example.com`,
+    /// ...), `0`, `false`, `[]`, or an object filled in the same way. When a
+    /// key is declared in several tiers the first tier wins.
+    pub fn generate_example(&self) -> Value {
+        let mut example = Map::new();
+        for root in [&self.public_schema, &self.secret_schema, &self.feature_flag_schema] {
+            let Some(properties) = root.get("properties").and_then(|p| p.as_object()) else {
+                continue;
+            };
+            for (name, schema) in properties {
+                example
+                    .entry(property_key(name))
+                    .or_insert_with(|| example_value(root, schema, 0));
+            }
+        }
+        Value::Object(example)
+    }
+}
+
+/// Recursion limit for self-referencing definitions.
+const MAX_DEPTH: usize = 16;
+
+fn example_value(root: &Value, schema: &Value, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+    let schema = resolve_ref(root, schema);
+    if let Some(value) = schema.get("default").or_else(|| schema.get("const")) {
+        return value.clone();
+    }
+    for key in ["enum", "examples"] {
+        if let Some(first) = schema.get(key).and_then(|v| v.as_array()).and_then(|v| v.first()) {
+            return first.clone();
+        }
+    }
+    for key in ["anyOf", "oneOf", "allOf"] {
+        if let Some(branch) = schema.get(key).and_then(|b| b.as_array()).and_then(|b| {
+            b.iter()
+                .find(|b| b.get("type").and_then(|t| t.as_str()) != Some("null"))
+        }) {
+            return example_value(root, branch, depth + 1);
+        }
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => Some(ty.as_str()),
+        Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).find(|t| *t != "null"),
+        _ => None,
+    };
+    match ty {
+        Some("string") => Value::from(string_placeholder(schema)),
+        // schemars writes integer bounds as floats (`"minimum": 0.0`).
+        Some("integer") => Value::from(schema.get("minimum").and_then(|m| m.as_f64()).unwrap_or(0.0).ceil() as i64),
+        Some("number") => schema.get("minimum").cloned().unwrap_or_else(|| Value::from(0)),
+        Some("boolean") => Value::Bool(false),
+        Some("array") => Value::Array(Vec::new()),
+        Some("object") | None if schema.get("properties").is_some() => {
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            Value::Object(
+                properties
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, prop)| (name.clone(), example_value(root, prop, depth + 1)))
+                    .collect(),
+            )
+        }
+        Some("object") => Value::Object(Map::new()),
+        _ => Value::Null,
+    }
+}
+
+fn string_placeholder(schema: &Value) -> String {
+    let placeholder = match schema.get("format").and_then(|f| f.as_str()) {
+        Some("email") => "user@example.com",
+        Some("uri") => "https://example.com",
+        Some("uuid") => "00000000-0000-0000-0000-000000000000",
+        Some("date-time") => "1970-01-01T00:00:00Z",
+        Some("ipv4") => "127.0.0.1",
+        Some("ipv6") => "::1",
+        _ => "",
+    };
+    let min_length = schema.get("minLength").and_then(|m| m.as_u64()).unwrap_or(0) as usize;
+    if placeholder.len() < min_length {
+        format!("{}{}", placeholder, "x".repeat(min_length - placeholder.len()))
+    } else {
+        placeholder.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{define_config, define_config_typed, EmptySchema};
+    use serde_json::json;
+
+    #[test]
+    fn test_generate_example_fills_every_key() {
+        let definition = define_config(
+            Some(json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string", "format": "uri"},
+                "adminEmail": {"type": "string", "format": "email"},
+                "maxRetries": {"type": "integer", "default": 3},
+                "timeout": {"type": ["number", "null"], "minimum": 5},
+                "logLevel": {"type": "string", "enum": ["info", "debug"]},
+                "name": {"type": "string", "minLength": 2},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "database": {"type": "object", "properties": {
+                    "host": {"type": "string", "examples": ["localhost"]},
+                    "ssl": {"type": "boolean"}
+                }}
+            }})),
+            Some(json!({"type": "object", "properties": {"apiKey": {"type": "string"}}})),
+            Some(json!({"type": "object", "properties": {"newUi": {"type": "boolean", "default": true}}})),
+        );
+        assert_eq!(
+            definition.generate_example(),
+            json!({
+                "API_URL": "https://example.com",
+                "ADMIN_EMAIL": "user@example.com",
+                "MAX_RETRIES": 3,
+                "TIMEOUT": 5,
+                "LOG_LEVEL": "info",
+                "NAME": "xx",
+                "TAGS": [],
+                "DATABASE": {"host": "localhost", "ssl": false},
+                "API_KEY": "",
+                "NEW_UI": true
+            })
+        );
+    }
+
+    #[derive(Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct PublicConfig {
+        database: Database,
+        replica: Option<Database>,
+    }
+
+    #[test]
+    fn test_generate_example_resolves_typed_references() {
+        let definition = define_config_typed::<PublicConfig, EmptySchema, EmptySchema>();
+        let example = definition.generate_example();
+        assert_eq!(example["DATABASE"], json!({"host": "", "port": 0}));
+        assert_eq!(example["REPLICA"], json!({"host": "", "port": 0}));
+    }
+}
@@ -0,0 +1,93 @@
+//! Source provenance for merged config values.
+//!
+//! [`ConfigManager::explain`](crate::ConfigManager::explain) answers "why is
+//! this value X in prod" by listing every layer that defined the key, in merge
+//! order, and which of them won.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Where a config value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValueSource {
+    /// A JSON file in the config directory, e.g. `production.aws.json`.
+    File { name: String },
+    /// A source registered with `with_source`, by its `name()`.
+    Source { name: String },
+    /// The remote config API.
+    Remote,
+    /// A last-known-good snapshot, used because the remote API was unreachable.
+    LastKnownGood,
+    /// A tenant's override layer from the remote API.
+    Tenant { tenant: String },
+    /// An environment variable (the actual variable name, prefix included).
+    Env { var: String },
+    /// Derived from the environment: `ENV`, `IS_LOCAL`, `REGION`, `CLOUD_PROVIDER`.
+    BuiltIn,
+    /// A deferred resolver registered with `with_deferred`.
+    Deferred,
+    /// Seeded from a baked runtime blob.
+    Baked,
+    /// Passed to `ConfigManager::from_values`.
+    Values,
+    /// A binary snapshot served by `ConfigManager::init_from_snapshot` until
+    /// the real sources have reloaded.
+    Snapshot,
+    /// `with_overrides` or an active `push_overrides` guard.
+    Override,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::File { name } => write!(f, "{}", name),
+            ValueSource::Source { name } => write!(f, "{}", name),
+            ValueSource::Remote => write!(f, "remote API"),
+            ValueSource::LastKnownGood => write!(f, "last-known-good snapshot"),
+            ValueSource::Tenant { tenant } => write!(f, "tenant `{}` overrides", tenant),
+            ValueSource::Env { var } => write!(f, "env var `{}`", var),
+            ValueSource::BuiltIn => write!(f, "built-in"),
+            ValueSource::Deferred => write!(f, "deferred resolver"),
+            ValueSource::Baked => write!(f, "baked blob"),
+            ValueSource::Values => write!(f, "in-memory values"),
+            ValueSource::Snapshot => write!(f, "config snapshot"),
+            ValueSource::Override => write!(f, "override"),
+        }
+    }
+}
+
+/// The value a single layer held for a key.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayerValue {
+    pub source: ValueSource,
+    pub value: Value,
+}
+
+/// Provenance report for one key.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation {
+    pub key: String,
+    /// The merged value getters return, if any.
+    pub value: Option<Value>,
+    /// The highest-precedence layer that defined the key. For objects, lower
+    /// layers may still contribute nested fields — see `layers`.
+    pub source: Option<ValueSource>,
+    /// Every layer that defined the key, lowest precedence first.
+    pub layers: Vec<LayerValue>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.value, &self.source) {
+            (Some(value), Some(source)) => writeln!(f, "{} = {} (from {})", self.key, value, source)?,
+            _ => writeln!(f, "{} is not set", self.key)?,
+        }
+        for layer in &self.layers {
+            writeln!(f, "  {}: {}", layer.source, layer.value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+//! C API for embedding the config engine (feature `ffi`).
+//!
+//! The declarations live in `include/smooai_config.h`, generated with
+//! `cbindgen --config cbindgen.toml --output include/smooai_config.h`.
+//! Build a shared or static library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`).
+//!
+//! ```c
+//! SmooaiConfig *config = smooai_config_new();
+//! char *url = smooai_config_get(config, SMOOAI_CONFIG_TIER_PUBLIC, "API_URL");
+//! if (url == NULL && smooai_config_last_error() != NULL) { /* report it */ }
+//! smooai_config_string_free(url);
+//! smooai_config_free(config);
+//! ```
+//!
+//! Values come back as JSON text (`"https://..."`, `42`, `true`), so callers
+//! parse them with whatever JSON library they already use. Every call
+//! catches panics; nothing unwinds across the boundary.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use serde_json::Value;
+
+use crate::config_manager::ConfigManager;
+use crate::utils::SmooaiConfigError;
+
+/// Opaque handle to a [`ConfigManager`].
+pub struct SmooaiConfig {
+    manager: ConfigManager,
+}
+
+/// Which tier [`smooai_config_get`] reads from. Passed as a plain integer,
+/// since C can hand over any value for an enum parameter.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmooaiConfigTier {
+    Public = 0,
+    Secret = 1,
+    FeatureFlag = 2,
+}
+
+impl TryFrom<u32> for SmooaiConfigTier {
+    type Error = SmooaiConfigError;
+
+    fn try_from(tier: u32) -> Result<Self, Self::Error> {
+        match tier {
+            0 => Ok(Self::Public),
+            1 => Ok(Self::Secret),
+            2 => Ok(Self::FeatureFlag),
+            other => Err(SmooaiConfigError::new(&format!("Unknown config tier {}", other))),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: Option<String>) {
+    // Interior NULs can't cross into C; drop them rather than the message.
+    let message = message.map(|m| CString::new(m.replace('\0', "")).expect("no interior NUL"));
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = message);
+}
+
+/// Run `f`, recording its error (or panic) for [`smooai_config_last_error`].
+fn guarded<T>(fallback: T, f: impl FnOnce() -> Result<T, SmooaiConfigError>) -> T {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => {
+            set_last_error(None);
+            value
+        }
+        Ok(Err(err)) => {
+            set_last_error(Some(err.to_string()));
+            fallback
+        }
+        Err(_) => {
+            set_last_error(Some("[Smooai Config] Panic inside smooai-config".to_string()));
+            fallback
+        }
+    }
+}
+
+/// Create a config handle configured from the process environment, like
+/// `ConfigManager::new()`. Free it with [`smooai_config_free`].
+#[no_mangle]
+pub extern "C" fn smooai_config_new() -> *mut SmooaiConfig {
+    guarded(ptr::null_mut(), || {
+        Ok(Box::into_raw(Box::new(SmooaiConfig {
+            manager: ConfigManager::new(),
+        })))
+    })
+}
+
+/// Look up `key` in `tier` (a [`SmooaiConfigTier`] value) and return its
+/// value as JSON text.
+///
+/// Returns NULL when the key has no value or on error (including an unknown
+/// `tier`); in the error case
+/// [`smooai_config_last_error`] describes it. Free the result with
+/// [`smooai_config_string_free`].
+///
+/// # Safety
+///
+/// `config` must come from [`smooai_config_new`] and not yet be freed, and
+/// `key` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn smooai_config_get(config: *const SmooaiConfig, tier: u32, key: *const c_char) -> *mut c_char {
+    guarded(ptr::null_mut(), || {
+        if config.is_null() || key.is_null() {
+            return Err(SmooaiConfigError::new("smooai_config_get called with a NULL argument"));
+        }
+        let tier = SmooaiConfigTier::try_from(tier)?;
+        let manager = &(*config).manager;
+        let key = CStr::from_ptr(key)
+            .to_str()
+            .map_err(|_| SmooaiConfigError::new("Config key is not valid UTF-8"))?;
+        let value = match tier {
+            SmooaiConfigTier::Public => manager.get_public_config(key)?,
+            SmooaiConfigTier::Secret => manager.get_secret_config(key)?.map(|secret| secret.into_exposed()),
+            SmooaiConfigTier::FeatureFlag => manager.get_feature_flag(key)?,
+        };
+        Ok(match value {
+            None | Some(Value::Null) => ptr::null_mut(),
+            Some(value) => CString::new(value.to_string())
+                .expect("JSON text has no interior NUL")
+                .into_raw(),
+        })
+    })
+}
+
+/// The error from the last failed call on this thread, or NULL if it
+/// succeeded. Valid until the next `smooai_config_*` call on this thread;
+/// do not free it.
+#[no_mangle]
+pub extern "C" fn smooai_config_last_error() -> *const c_char {
+    LAST_ERROR.with(|slot| slot.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Free a string returned by [`smooai_config_get`]. NULL is a no-op.
+///
+/// # Safety
+///
+/// `value` must come from [`smooai_config_get`] and not already be freed.
+#[no_mangle]
+pub unsafe extern "C" fn smooai_config_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Free a handle from [`smooai_config_new`]. NULL is a no-op.
+///
+/// # Safety
+///
+/// `config` must come from [`smooai_config_new`] and not already be freed.
+#[no_mangle]
+pub unsafe extern "C" fn smooai_config_free(config: *mut SmooaiConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn handle(dir: &std::path::Path) -> *mut SmooaiConfig {
+        let config_dir = dir.join(".smooai-config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(
+            config_dir.join("default.json"),
+            r#"{"API_URL":"https://api.example.com","PORT":8080,"DB_PASSWORD":"hunter2","NEW_CHECKOUT":true}"#,
+        )
+        .unwrap();
+        let manager = ConfigManager::new().with_env(HashMap::from([
+            (
+                "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                config_dir.to_string_lossy().to_string(),
+            ),
+            ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+        ]));
+        Box::into_raw(Box::new(SmooaiConfig { manager }))
+    }
+
+    unsafe fn get(config: *const SmooaiConfig, tier: SmooaiConfigTier, key: &str) -> Option<String> {
+        let key = CString::new(key).unwrap();
+        let value = smooai_config_get(config, tier as u32, key.as_ptr());
+        if value.is_null() {
+            return None;
+        }
+        let text = CStr::from_ptr(value).to_str().unwrap().to_string();
+        smooai_config_string_free(value);
+        Some(text)
+    }
+
+    #[test]
+    fn test_get_returns_json_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = handle(dir.path());
+        unsafe {
+            assert_eq!(
+                get(config, SmooaiConfigTier::Public, "API_URL").as_deref(),
+                Some("\"https://api.example.com\"")
+            );
+            assert_eq!(get(config, SmooaiConfigTier::Public, "PORT").as_deref(), Some("8080"));
+            assert_eq!(
+                get(config, SmooaiConfigTier::Secret, "DB_PASSWORD").as_deref(),
+                Some("\"hunter2\"")
+            );
+            assert_eq!(
+                get(config, SmooaiConfigTier::FeatureFlag, "NEW_CHECKOUT").as_deref(),
+                Some("true")
+            );
+            assert_eq!(get(config, SmooaiConfigTier::Public, "MISSING"), None);
+            assert!(smooai_config_last_error().is_null());
+            smooai_config_free(config);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        unsafe {
+            let key = CString::new("API_URL").unwrap();
+            assert!(smooai_config_get(ptr::null(), SmooaiConfigTier::Public as u32, key.as_ptr()).is_null());
+            let error = CStr::from_ptr(smooai_config_last_error()).to_str().unwrap();
+            assert!(error.contains("NULL argument"), "{}", error);
+
+            let dir = tempfile::tempdir().unwrap();
+            let config = handle(dir.path());
+            assert!(smooai_config_get(config, 3, key.as_ptr()).is_null());
+            let error = CStr::from_ptr(smooai_config_last_error()).to_str().unwrap();
+            assert!(error.contains("Unknown config tier 3"), "{}", error);
+            smooai_config_free(config);
+
+            smooai_config_free(ptr::null_mut());
+            smooai_config_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_header_declares_exports() {
+        let header = include_str!("../include/smooai_config.h");
+        for function in [
+            "smooai_config_new(void)",
+            "smooai_config_get(",
+            "smooai_config_last_error(void)",
+            "smooai_config_string_free(",
+            "smooai_config_free(",
+        ] {
+            assert!(header.contains(function), "header is missing {}", function);
+        }
+        assert!(header.contains("SMOOAI_CONFIG_TIER_FEATURE_FLAG = 2"));
+        assert!(header.contains("uint32_t tier"));
+    }
+}
@@ -0,0 +1,153 @@
+//! [`figment::Provider`] over a [`ConfigManager`] (feature `figment`).
+//!
+//! Lets Rocket and other figment-based apps layer Smoo AI config into their
+//! existing figment, under or over their own profiles:
+//!
+//! ```rust,ignore
+//! let figment = rocket::Config::figment()
+//!     .merge(FigmentProvider::new(ConfigManager::new()));
+//! let app: AppConfig = figment.extract()?;
+//! ```
+//!
+//! Keys are lowercased like figment's own `Env` provider (`API_URL` becomes
+//! `api_url`) unless [`preserve_case`](FigmentProvider::preserve_case) is set.
+//! Nested objects become nested dictionaries. Secret values are included
+//! unredacted, since the application is the consumer.
+
+use std::sync::Arc;
+
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+
+use crate::config_manager::ConfigManager;
+#[allow(deprecated)]
+use crate::local::LocalConfigManager;
+
+/// Serves every merged key of a [`ConfigManager`] to a figment.
+pub struct FigmentProvider {
+    manager: Arc<ConfigManager>,
+    profile: Profile,
+    lowercase: bool,
+}
+
+impl FigmentProvider {
+    /// Provide `manager`'s merged config under figment's default profile.
+    pub fn new(manager: impl Into<Arc<ConfigManager>>) -> Self {
+        FigmentProvider {
+            manager: manager.into(),
+            profile: Profile::Default,
+            lowercase: true,
+        }
+    }
+
+    /// Put the values under `profile` instead, so they only apply when the
+    /// figment selects it (e.g. Rocket's `release`).
+    pub fn with_profile(mut self, profile: impl Into<Profile>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Keep keys as the manager spells them (`API_URL`) instead of lowercasing.
+    pub fn preserve_case(mut self) -> Self {
+        self.lowercase = false;
+        self
+    }
+}
+
+impl From<ConfigManager> for FigmentProvider {
+    fn from(manager: ConfigManager) -> Self {
+        FigmentProvider::new(manager)
+    }
+}
+
+#[allow(deprecated)]
+impl From<LocalConfigManager> for FigmentProvider {
+    fn from(manager: LocalConfigManager) -> Self {
+        FigmentProvider::new(manager.into_config_manager())
+    }
+}
+
+impl Provider for FigmentProvider {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("Smoo AI config")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let all = self.manager.get_all_exposed().map_err(|e| Error::from(e.to_string()))?;
+        let mut dict = Dict::new();
+        for (key, value) in all {
+            let key = if self.lowercase { key.to_lowercase() } else { key };
+            dict.insert(key, Value::serialize(value)?);
+        }
+        Ok(Map::from([(self.profile.clone(), dict)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigFixture;
+    use figment::Figment;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        api_url: String,
+        db_password: String,
+        database: Database,
+        env: String,
+    }
+
+    fn manager() -> (ConfigFixture, ConfigManager) {
+        let fixture = ConfigFixture::new().with_file(
+            "default.json",
+            json!({"API_URL": "https://api", "DB_PASSWORD": "hunter2", "DATABASE": {"host": "db", "port": 5432}}),
+        );
+        let manager = fixture.manager();
+        (fixture, manager)
+    }
+
+    #[test]
+    fn test_extracts_lowercased_keys() {
+        let (_fixture, manager) = manager();
+        let config: AppConfig = Figment::from(FigmentProvider::new(manager)).extract().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                api_url: "https://api".to_string(),
+                db_password: "hunter2".to_string(),
+                database: Database {
+                    host: "db".to_string(),
+                    port: 5432
+                },
+                env: "test".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile_and_case() {
+        let (_fixture, manager) = manager();
+        let provider = FigmentProvider::new(manager).with_profile("release").preserve_case();
+        let figment = Figment::new()
+            .merge(figment::providers::Serialized::defaults(HashMap::from([(
+                "API_URL",
+                "https://default",
+            )])))
+            .merge(provider);
+
+        assert_eq!(figment.extract_inner::<String>("API_URL").unwrap(), "https://default");
+        let release = figment.select("release");
+        assert_eq!(release.extract_inner::<String>("API_URL").unwrap(), "https://api");
+        assert_eq!(release.extract_inner::<u16>("DATABASE.port").unwrap(), 5432);
+        assert!(release.extract_inner::<String>("api_url").is_err());
+    }
+}
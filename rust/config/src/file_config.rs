@@ -9,8 +9,8 @@ use std::time::Instant;
 use serde_json::Value;
 
 use crate::cloud_region::get_cloud_region_from_env;
-use crate::merge::merge_replace_arrays;
-use crate::utils::{coerce_boolean, SmooaiConfigError};
+use crate::merge::MergeRules;
+use crate::utils::{coerce_boolean, SmooaiConfigError, SmooaiConfigErrorKind};
 
 static CONFIG_DIR_CACHE: Mutex<Option<(String, Instant)>> = Mutex::new(None);
 const CONFIG_DIR_TTL_SECS: u64 = 3600; // 1 hour
@@ -46,7 +46,10 @@ pub fn find_config_directory_with_env(
         return Err(SmooaiConfigError::new(&format!(
             "The directory specified in SMOOAI_ENV_CONFIG_DIR does not exist: {}",
             config_dir
-        )));
+        ))
+        .with_kind(SmooaiConfigErrorKind::ConfigDirNotFound {
+            path: config_dir.clone(),
+        }));
     }
 
     // 2. Check cache
@@ -106,7 +109,10 @@ pub fn find_config_directory_with_env(
         "Could not find config directory, searched {} levels up from {}",
         levels_up_limit,
         cwd.display()
-    )))
+    ))
+    .with_kind(SmooaiConfigErrorKind::ConfigDirNotFound {
+        path: cwd.display().to_string(),
+    }))
 }
 
 /// Load and merge JSON config files in priority order.
@@ -128,9 +134,148 @@ pub fn find_and_process_file_config(
 pub fn find_and_process_file_config_with_env(
     env: &HashMap<String, String>,
 ) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+    let layers = load_file_layers_with_env(env)?;
+    let mut final_config = Value::Object(serde_json::Map::new());
+    for layer in &layers {
+        MergeRules::default().merge_into(&mut final_config, &layer.values);
+    }
+
+    // Convert to HashMap
+    let mut result: HashMap<String, Value> = match final_config {
+        Value::Object(map) => map.into_iter().collect(),
+        _ => HashMap::new(),
+    };
+
+    // Set built-in keys
+    result.extend(builtin_keys_with_env(env));
+
+    Ok(result)
+}
+
+/// One config file that was found and parsed, before merging.
+#[derive(Debug, Clone)]
+pub struct FileLayer {
+    /// File name relative to the config directory (e.g. `production.aws.json`).
+    pub file_name: String,
+    /// Parsed file contents.
+    pub values: Value,
+}
+
+/// Load each JSON config file that exists, in merge order, without merging.
+///
+/// Same file list and error handling as [`find_and_process_file_config_with_env`];
+/// used where callers need to know which file supplied a value.
+///
+/// With the `templates` feature, `{name}.tera` (e.g. `production.json.tera`)
+/// is rendered with Tera and parsed in place of `{name}`. Templates see `env`
+/// (the env map), `environment`, `cloud_provider` and `region`. Having both a
+/// template and the plain file for the same name is an error.
+pub fn load_file_layers_with_env(env: &HashMap<String, String>) -> Result<Vec<FileLayer>, SmooaiConfigError> {
     let config_dir = find_config_directory_with_env(false, env)?;
     let config_path = PathBuf::from(&config_dir);
 
+    let files = config_file_names(env);
+
+    let mut layers = Vec::new();
+
+    for file_name in &files {
+        let file_path = config_path.join(file_name);
+
+        #[cfg(feature = "templates")]
+        {
+            let template_name = format!("{}.tera", file_name);
+            let template_path = config_path.join(&template_name);
+            if template_path.is_file() {
+                if file_path.exists() {
+                    return Err(SmooaiConfigError::new(&format!(
+                        "Both {} and {} exist in {}; keep only one",
+                        file_name, template_name, config_dir
+                    )));
+                }
+                layers.push(FileLayer {
+                    values: render_config_template(&template_path, env)?,
+                    file_name: template_name,
+                });
+                continue;
+            }
+        }
+
+        match fs::read_to_string(&file_path) {
+            Ok(content) => {
+                let values: Value = serde_json::from_str(&content)
+                    .map_err(|e| SmooaiConfigError::file_parse(&file_path.display().to_string(), e))?;
+                layers.push(FileLayer {
+                    file_name: file_name.clone(),
+                    values,
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if file_name == "default.json" {
+                    return Err(SmooaiConfigError::new(&format!(
+                        "Required default.json not found in {}",
+                        config_dir
+                    )));
+                }
+                // Optional files skip silently
+            }
+            Err(e) => {
+                return Err(
+                    SmooaiConfigError::new(&format!("Error reading {}: {}", file_path.display(), e))
+                        .with_kind(SmooaiConfigErrorKind::Io {
+                            path: file_path.display().to_string(),
+                        })
+                        .with_source(e),
+                );
+            }
+        }
+    }
+
+    Ok(layers)
+}
+
+/// Render a `.json.tera` config file against `env` and parse the result.
+#[cfg(feature = "templates")]
+fn render_config_template(path: &Path, env: &HashMap<String, String>) -> Result<Value, SmooaiConfigError> {
+    let display = path.display().to_string();
+    let source = fs::read_to_string(path).map_err(|e| {
+        SmooaiConfigError::new(&format!("Error reading {}: {}", display, e))
+            .with_kind(SmooaiConfigErrorKind::Io { path: display.clone() })
+            .with_source(e)
+    })?;
+
+    let cloud_region = get_cloud_region_from_env(env);
+    let mut context = tera::Context::new();
+    context.insert("env", env);
+    context.insert(
+        "environment",
+        env.get("SMOOAI_CONFIG_ENV")
+            .map(String::as_str)
+            .unwrap_or("development"),
+    );
+    context.insert("cloud_provider", &cloud_region.provider);
+    context.insert("region", &cloud_region.region);
+
+    let rendered = tera::Tera::one_off(&source, &context, false).map_err(|e| {
+        // Tera's top-level message is generic; the cause chain says what went wrong.
+        let mut message = e.to_string();
+        let mut cause = std::error::Error::source(&e);
+        while let Some(inner) = cause {
+            message.push_str(": ");
+            message.push_str(&inner.to_string());
+            cause = inner.source();
+        }
+        SmooaiConfigError::new(&format!("Error rendering {}: {}", display, message))
+            .with_kind(SmooaiConfigErrorKind::FileParse { path: display.clone() })
+            .with_source(e)
+    })?;
+    serde_json::from_str(&rendered).map_err(|e| SmooaiConfigError::file_parse(&display, e))
+}
+
+/// Config file names for `env`, in merge order (lowest precedence first).
+///
+/// `default.json`, then `local.json` when `IS_LOCAL` is set, then `{env}.json`,
+/// `{env}.{provider}.json` and `{env}.{provider}.{region}.json`.
+pub fn config_file_names(env: &HashMap<String, String>) -> Vec<String> {
     let is_local = coerce_boolean(env.get("IS_LOCAL").map(|s| s.as_str()).unwrap_or(""));
     let env_name = env
         .get("SMOOAI_CONFIG_ENV")
@@ -138,7 +283,6 @@ pub fn find_and_process_file_config_with_env(
         .unwrap_or_else(|| "development".to_string());
     let cloud_region = get_cloud_region_from_env(env);
 
-    // Build file list
     let mut files = vec!["default.json".to_string()];
     if is_local {
         files.push("local.json".to_string());
@@ -156,48 +300,33 @@ pub fn find_and_process_file_config_with_env(
         }
     }
 
-    let mut final_config = Value::Object(serde_json::Map::new());
+    files
+}
 
-    for file_name in &files {
-        let file_path = config_path.join(file_name);
-        match fs::read_to_string(&file_path) {
-            Ok(content) => {
-                let file_config: Value = serde_json::from_str(&content)
-                    .map_err(|e| SmooaiConfigError::new(&format!("Error parsing {}: {}", file_path.display(), e)))?;
-                final_config = merge_replace_arrays(&final_config, &file_config);
-            }
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                if file_name == "default.json" {
-                    return Err(SmooaiConfigError::new(&format!(
-                        "Required default.json not found in {}",
-                        config_dir
-                    )));
-                }
-                // Optional files skip silently
-            }
-            Err(e) => {
-                return Err(SmooaiConfigError::new(&format!(
-                    "Error reading {}: {}",
-                    file_path.display(),
-                    e
-                )));
-            }
-        }
-    }
+/// Whether this looks like a developer machine rather than a deployment: a
+/// debug build, no cloud provider detected in `env`, and a `.git` entry in
+/// `start` or one of its parents.
+pub fn detect_local_with_env(env: &HashMap<String, String>, start: &Path) -> bool {
+    cfg!(debug_assertions)
+        && get_cloud_region_from_env(env).provider == "unknown"
+        && start.ancestors().any(|dir| dir.join(".git").exists())
+}
 
-    // Convert to HashMap
-    let mut result: HashMap<String, Value> = match final_config {
-        Value::Object(map) => map.into_iter().collect(),
-        _ => HashMap::new(),
-    };
+/// Built-in keys derived from the environment: ENV, IS_LOCAL, REGION, CLOUD_PROVIDER.
+pub fn builtin_keys_with_env(env: &HashMap<String, String>) -> HashMap<String, Value> {
+    let is_local = coerce_boolean(env.get("IS_LOCAL").map(|s| s.as_str()).unwrap_or(""));
+    let env_name = env
+        .get("SMOOAI_CONFIG_ENV")
+        .cloned()
+        .unwrap_or_else(|| "development".to_string());
+    let cloud_region = get_cloud_region_from_env(env);
 
-    // Set built-in keys
+    let mut result = HashMap::new();
     result.insert("ENV".to_string(), Value::String(env_name));
     result.insert("IS_LOCAL".to_string(), Value::Bool(is_local));
     result.insert("REGION".to_string(), Value::String(cloud_region.region));
     result.insert("CLOUD_PROVIDER".to_string(), Value::String(cloud_region.provider));
-
-    Ok(result)
+    result
 }
 
 #[cfg(test)]
@@ -294,6 +423,97 @@ mod tests {
         assert_eq!(db["ssl"], json!(true));
     }
 
+    #[test]
+    fn test_load_file_layers_in_merge_order() {
+        let dir = tempfile::tempdir().unwrap();
+        make_config_dir(
+            dir.path(),
+            &[
+                ("default.json", r#"{"API_URL":"http://localhost"}"#),
+                ("production.json", r#"{"API_URL":"https://prod"}"#),
+            ],
+        );
+        let env = make_env(dir.path(), &[("SMOOAI_CONFIG_ENV", "production")]);
+        let layers = load_file_layers_with_env(&env).unwrap();
+        let names: Vec<&str> = layers.iter().map(|l| l.file_name.as_str()).collect();
+        assert_eq!(names, vec!["default.json", "production.json"]);
+        assert_eq!(layers[1].values["API_URL"], json!("https://prod"));
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_renders_tera_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        make_config_dir(
+            dir.path(),
+            &[
+                ("default.json", r#"{"API_URL":"http://localhost","REPLICA_REGIONS":[]}"#),
+                (
+                    "production.json.tera",
+                    r#"{
+                        "API_URL": "https://{{ region }}.api.example.com",
+                        "REPLICA_REGIONS": [{% for r in env.REPLICAS | split(pat=",") %}"{{ r }}"{% if not loop.last %},{% endif %}{% endfor %}],
+                        {% if environment == "production" %}"DEBUG": false{% else %}"DEBUG": true{% endif %}
+                    }"#,
+                ),
+            ],
+        );
+        let env = make_env(
+            dir.path(),
+            &[
+                ("SMOOAI_CONFIG_ENV", "production"),
+                ("AWS_REGION", "us-east-1"),
+                ("REPLICAS", "us-west-2,eu-west-1"),
+            ],
+        );
+        let layers = load_file_layers_with_env(&env).unwrap();
+        assert_eq!(layers[1].file_name, "production.json.tera");
+
+        let result = find_and_process_file_config_with_env(&env).unwrap();
+        assert_eq!(result["API_URL"], json!("https://us-east-1.api.example.com"));
+        assert_eq!(result["REPLICA_REGIONS"], json!(["us-west-2", "eu-west-1"]));
+        assert_eq!(result["DEBUG"], json!(false));
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_tera_template_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        make_config_dir(
+            dir.path(),
+            &[
+                ("default.json", "{}"),
+                ("default.json.tera", "{}"),
+                ("staging.json.tera", r#"{"URL": "{{ env.MISSING }}"}"#),
+            ],
+        );
+        let env = make_env(dir.path(), &[("SMOOAI_CONFIG_ENV", "staging")]);
+        let err = load_file_layers_with_env(&env).unwrap_err();
+        assert!(err.message.contains("Both default.json and default.json.tera exist"));
+
+        fs::remove_file(dir.path().join(".smooai-config/default.json")).unwrap();
+        let err = load_file_layers_with_env(&env).unwrap_err();
+        assert!(
+            matches!(err.kind, SmooaiConfigErrorKind::FileParse { ref path } if path.ends_with("staging.json.tera"))
+        );
+        assert!(err.message.contains("MISSING"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_detect_local() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::create_dir_all(repo.path().join(".git")).unwrap();
+        make_config_dir(repo.path(), &[("default.json", "{}")]);
+        let config_dir = repo.path().join(".smooai-config");
+        let no_repo = tempfile::tempdir().unwrap();
+
+        let env = HashMap::new();
+        assert_eq!(detect_local_with_env(&env, &config_dir), cfg!(debug_assertions));
+        assert!(!detect_local_with_env(&env, no_repo.path()));
+        let aws = HashMap::from([("AWS_REGION".to_string(), "us-east-1".to_string())]);
+        assert!(!detect_local_with_env(&aws, &config_dir));
+    }
+
     #[test]
     fn test_sets_builtin_keys() {
         let dir = tempfile::tempdir().unwrap();
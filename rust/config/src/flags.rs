@@ -0,0 +1,162 @@
+//! Hierarchical feature flag namespaces.
+//!
+//! Flag keys may be grouped with `/`, e.g. `checkout/new-payment-flow` or
+//! `checkout/payments/apple-pay`. A key ending in `/*` sets the default for
+//! every flag in that namespace (and its sub-namespaces) that has no value of
+//! its own; the most specific default wins:
+//!
+//! ```text
+//! checkout/*                  = false
+//! checkout/payments/*         = true
+//! checkout/new-payment-flow   = true
+//! ```
+//!
+//! Here `checkout/payments/apple-pay` resolves to `true` and
+//! `checkout/legacy-cart` to `false`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+
+/// Separator between namespace segments in a flag key.
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// Final segment marking a namespace-level default (`checkout/*`).
+pub const NAMESPACE_DEFAULT: &str = "*";
+
+/// Whether `key` is a namespace default (`checkout/*`) rather than a flag.
+pub fn is_namespace_default(key: &str) -> bool {
+    key == NAMESPACE_DEFAULT || key.ends_with(&format!("{}{}", NAMESPACE_SEPARATOR, NAMESPACE_DEFAULT))
+}
+
+/// Whether `key` lives in `namespace` or one of its sub-namespaces.
+pub fn in_namespace(key: &str, namespace: &str) -> bool {
+    let namespace = namespace.trim_end_matches(NAMESPACE_SEPARATOR);
+    key.strip_prefix(namespace)
+        .is_some_and(|rest| rest.starts_with(NAMESPACE_SEPARATOR))
+}
+
+/// Default keys that apply to `key`, most specific first.
+///
+/// `checkout/payments/apple-pay` → `["checkout/payments/*", "checkout/*"]`.
+pub fn namespace_default_keys(key: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut prefix = key;
+    while let Some(idx) = prefix.rfind(NAMESPACE_SEPARATOR) {
+        prefix = &prefix[..idx];
+        keys.push(format!("{}{}{}", prefix, NAMESPACE_SEPARATOR, NAMESPACE_DEFAULT));
+    }
+    keys
+}
+
+/// The namespace default for `key` from `values`, if one is set.
+pub fn namespace_default(values: &HashMap<String, Value>, key: &str) -> Option<Value> {
+    namespace_default_keys(key)
+        .iter()
+        .find_map(|k| values.get(k).filter(|v| !v.is_null()).cloned())
+}
+
+/// Interpret a flag value as on/off.
+///
+/// Booleans map directly, numbers are on when non-zero, and the strings
+/// `true`/`1`/`false`/`0` (any case, surrounding whitespace ignored) are
+/// recognized. Anything else, including `null`, has no boolean reading.
+pub fn flag_enabled(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0),
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Every flag in `namespace` with its value, namespace defaults applied.
+///
+/// Default entries themselves are not included. Keys are returned in full
+/// (`checkout/new-payment-flow`), sorted.
+pub fn resolve_namespace(values: &HashMap<String, Value>, namespace: &str) -> BTreeMap<String, Value> {
+    values
+        .iter()
+        .filter(|(key, _)| in_namespace(key, namespace) && !is_namespace_default(key))
+        .map(|(key, value)| {
+            let resolved = if value.is_null() {
+                namespace_default(values, key).unwrap_or(Value::Null)
+            } else {
+                value.clone()
+            };
+            (key.clone(), resolved)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn flags() -> HashMap<String, Value> {
+        [
+            ("checkout/*", json!(false)),
+            ("checkout/payments/*", json!(true)),
+            ("checkout/new-payment-flow", json!(true)),
+            ("checkout/payments/apple-pay", Value::Null),
+            ("checkout/legacy-cart", Value::Null),
+            ("checkoutv2/other", json!(true)),
+            ("search/fuzzy", json!(false)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+
+    #[test]
+    fn test_namespace_default_keys_most_specific_first() {
+        assert_eq!(
+            namespace_default_keys("checkout/payments/apple-pay"),
+            vec!["checkout/payments/*", "checkout/*"]
+        );
+        assert!(namespace_default_keys("flat").is_empty());
+    }
+
+    #[test]
+    fn test_in_namespace_respects_segment_boundaries() {
+        assert!(in_namespace("checkout/a", "checkout"));
+        assert!(in_namespace("checkout/a/b", "checkout/"));
+        assert!(!in_namespace("checkoutv2/a", "checkout"));
+        assert!(!in_namespace("checkout", "checkout"));
+    }
+
+    #[test]
+    fn test_resolve_namespace_applies_defaults() {
+        let resolved = resolve_namespace(&flags(), "checkout");
+        assert_eq!(
+            resolved.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("checkout/legacy-cart".to_string(), json!(false)),
+                ("checkout/new-payment-flow".to_string(), json!(true)),
+                ("checkout/payments/apple-pay".to_string(), json!(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_namespace_default_for_unlisted_flag() {
+        assert_eq!(namespace_default(&flags(), "checkout/payments/new"), Some(json!(true)));
+        assert_eq!(namespace_default(&flags(), "search/new"), None);
+    }
+
+    #[test]
+    fn test_flag_enabled() {
+        assert_eq!(flag_enabled(&json!(true)), Some(true));
+        assert_eq!(flag_enabled(&json!(0)), Some(false));
+        assert_eq!(flag_enabled(&json!(2.5)), Some(true));
+        assert_eq!(flag_enabled(&json!(" True ")), Some(true));
+        assert_eq!(flag_enabled(&json!("0")), Some(false));
+        assert_eq!(flag_enabled(&json!("yes")), None);
+        assert_eq!(flag_enabled(&Value::Null), None);
+    }
+}
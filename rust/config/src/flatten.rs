@@ -0,0 +1,224 @@
+//! Flattening nested config structs into env-style keys.
+//!
+//! A typed config with a nested struct normally declares one `DATABASE` key
+//! holding an object. Env vars can't hold nested values, so services that
+//! configure everything through env often want `DATABASE_HOST` and
+//! `DATABASE_PORT` instead. [`KeyFlattening::Flatten`] rewrites each tier's
+//! schema that way before anything is derived from it, so
+//! [`schema_keys`](ConfigDefinition::schema_keys),
+//! [`schema_types`](ConfigDefinition::schema_types),
+//! [`required_keys`](ConfigDefinition::required_keys) and tier checks all see
+//! the flat keys:
+//!
+//! ```rust,ignore
+//! #[derive(Default, JsonSchema)]
+//! struct Database { host: String, port: u16 }
+//!
+//! #[derive(Default, JsonSchema)]
+//! struct PublicConfig { api_url: String, database: Database }
+//!
+//! let definition = define_config_typed_with::<PublicConfig, EmptySchema, EmptySchema>(KeyFlattening::Flatten);
+//! // keys: API_URL, DATABASE_HOST, DATABASE_PORT
+//! ```
+//!
+//! Only objects with declared `properties` are flattened; arrays and free-form
+//! maps stay single JSON-valued keys. A nested key is required only when it
+//! and every struct above it are.
+
+use serde_json::{Map, Value};
+
+use crate::schema::{property_key, resolve_ref, ConfigDefinition};
+
+/// How nested structs in a typed schema become config keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyFlattening {
+    /// One key per top-level field, holding nested structs as objects.
+    #[default]
+    Nested,
+    /// One key per leaf field, named by its UPPER_SNAKE_CASE path (`DATABASE_HOST`).
+    Flatten,
+}
+
+impl ConfigDefinition {
+    /// This definition with nested structs flattened into leaf keys. See
+    /// [`crate::flatten`].
+    pub fn flattened(&self) -> ConfigDefinition {
+        let mut json_schema = self.json_schema.clone();
+        let public_schema = flatten_tier(&self.public_schema);
+        let secret_schema = flatten_tier(&self.secret_schema);
+        let feature_flag_schema = flatten_tier(&self.feature_flag_schema);
+        for (name, tier) in [
+            ("public", &public_schema),
+            ("secret", &secret_schema),
+            ("feature_flags", &feature_flag_schema),
+        ] {
+            if let Some(slot) = json_schema.pointer_mut(&format!("/properties/{}", name)) {
+                if !tier.is_null() {
+                    *slot = tier.clone();
+                }
+            }
+        }
+        ConfigDefinition {
+            public_schema,
+            secret_schema,
+            feature_flag_schema,
+            json_schema,
+        }
+    }
+}
+
+fn flatten_tier(schema: &Value) -> Value {
+    if schema.get("properties").is_none() {
+        return schema.clone();
+    }
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    collect(schema, schema, "", true, &mut properties, &mut required);
+
+    let mut flat = schema.clone();
+    flat["properties"] = Value::Object(properties);
+    if let Some(obj) = flat.as_object_mut() {
+        if required.is_empty() {
+            obj.remove("required");
+        } else {
+            obj.insert("required".to_string(), Value::from(required));
+        }
+    }
+    flat
+}
+
+/// Add the leaf properties of `schema` to `properties`. `prefix` is the
+/// UPPER_SNAKE_CASE path of `schema` itself, empty at the top level where
+/// names are kept as declared.
+fn collect(
+    root: &Value,
+    schema: &Value,
+    prefix: &str,
+    parent_required: bool,
+    properties: &mut Map<String, Value>,
+    required: &mut Vec<String>,
+) {
+    let Some(props) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return;
+    };
+    let declared_required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|r| r.as_str())
+        .collect();
+
+    for (name, prop) in props {
+        let path = if prefix.is_empty() {
+            property_key(name)
+        } else {
+            format!("{}_{}", prefix, property_key(name))
+        };
+        let is_required = parent_required && declared_required.contains(&name.as_str());
+        let resolved = resolve_ref(root, prop);
+        if resolved
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .is_some_and(|p| !p.is_empty())
+        {
+            collect(root, resolved, &path, is_required, properties, required);
+            continue;
+        }
+        let key = if prefix.is_empty() { name.clone() } else { path };
+        if is_required {
+            required.push(key.clone());
+        }
+        properties.insert(key, prop.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{define_config, define_config_typed_with, EmptySchema};
+    use std::collections::HashSet;
+
+    #[derive(Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct Pool {
+        max_size: u32,
+    }
+
+    #[derive(Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct Database {
+        host: String,
+        port: u16,
+        pool: Pool,
+    }
+
+    #[derive(Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+    struct PublicConfig {
+        api_url: String,
+        database: Database,
+        replica: Option<Database>,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_flatten_typed_nested_structs() {
+        let definition = define_config_typed_with::<PublicConfig, EmptySchema, EmptySchema>(KeyFlattening::Flatten);
+        let keys = definition.schema_keys();
+        for key in [
+            "API_URL",
+            "DATABASE_HOST",
+            "DATABASE_PORT",
+            "DATABASE_POOL_MAX_SIZE",
+            "REPLICA_HOST",
+            "TAGS",
+        ] {
+            assert!(keys.contains(key), "missing {}", key);
+        }
+        assert!(!keys.contains("DATABASE"));
+
+        let types = definition.schema_types();
+        assert_eq!(types["DATABASE_PORT"], "number");
+        assert_eq!(types["TAGS"], "json");
+
+        let required: HashSet<String> = definition.required_keys().into_iter().collect();
+        assert!(required.contains("DATABASE_HOST") && required.contains("DATABASE_POOL_MAX_SIZE"));
+        assert!(!required.contains("REPLICA_HOST"));
+        assert_eq!(
+            definition.json_schema["properties"]["public"]["properties"]["DATABASE_HOST"]["type"],
+            "string"
+        );
+    }
+
+    #[test]
+    fn test_nested_mode_keeps_objects() {
+        let definition = define_config_typed_with::<PublicConfig, EmptySchema, EmptySchema>(KeyFlattening::Nested);
+        let keys = definition.schema_keys();
+        assert!(keys.contains("DATABASE"));
+        assert!(!keys.contains("DATABASE_HOST"));
+    }
+
+    #[test]
+    fn test_flatten_inline_camel_case_objects() {
+        let definition = define_config(
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dbConfig": {
+                        "type": "object",
+                        "properties": {"hostName": {"type": "string"}},
+                        "required": ["hostName"]
+                    },
+                    "labels": {"type": "object", "additionalProperties": {"type": "string"}}
+                },
+                "required": ["dbConfig"]
+            })),
+            None,
+            None,
+        )
+        .flattened();
+        assert_eq!(
+            definition.schema_keys(),
+            HashSet::from(["DB_CONFIG_HOST_NAME", "LABELS"].map(String::from))
+        );
+        assert_eq!(definition.required_keys(), vec!["DB_CONFIG_HOST_NAME"]);
+    }
+}
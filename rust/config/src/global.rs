@@ -0,0 +1,49 @@
+//! Process-wide [`ConfigManager`] singleton.
+//!
+//! The application configures the manager once at startup; libraries deep in
+//! the dependency tree then read through [`global`] instead of having a
+//! handle threaded through every constructor:
+//!
+//! ```rust,ignore
+//! // main.rs
+//! smooai_config::global_init(ConfigManager::new().with_schema_keys(keys))?;
+//!
+//! // somewhere in a library
+//! let url = smooai_config::global().get_public_config("API_URL")?;
+//! ```
+
+use std::sync::OnceLock;
+
+use crate::config_manager::ConfigManager;
+use crate::utils::SmooaiConfigError;
+
+static GLOBAL: OnceLock<ConfigManager> = OnceLock::new();
+
+/// Install `manager` as the process-wide manager.
+///
+/// Fails if a manager is already installed, either by an earlier
+/// `global_init` or because [`global`] was called first and installed the
+/// default. Call this early in `main`, before anything reads config.
+pub fn global_init(manager: ConfigManager) -> Result<&'static ConfigManager, SmooaiConfigError> {
+    let mut manager = Some(manager);
+    let installed = GLOBAL.get_or_init(|| manager.take().expect("initializer runs once"));
+    if manager.is_some() {
+        return Err(SmooaiConfigError::new(
+            "Global config manager is already initialized; call global_init before the first global()",
+        ));
+    }
+    Ok(installed)
+}
+
+/// The process-wide manager.
+///
+/// If [`global_init`] hasn't run, this installs `ConfigManager::new()`, which
+/// reads everything from the process env.
+pub fn global() -> &'static ConfigManager {
+    GLOBAL.get_or_init(ConfigManager::new)
+}
+
+/// The process-wide manager, or `None` if nothing has installed one yet.
+pub fn try_global() -> Option<&'static ConfigManager> {
+    GLOBAL.get()
+}
@@ -0,0 +1,219 @@
+//! Last-known-good snapshots of remote config.
+//!
+//! With [`ConfigManager::with_last_known_good`](crate::ConfigManager::with_last_known_good)
+//! every successful remote fetch is written to disk. If the remote API is
+//! unreachable on a later start, the snapshot stands in for the remote layer
+//! as long as it is younger than the configured max age.
+//!
+//! The snapshot holds secret values in plaintext unless the manager has
+//! [disk encryption](crate::at_rest). On Unix it is created with mode `0600`;
+//! keep it somewhere only the service user can read.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::at_rest::{AtRestEncryption, SealedSecrets};
+use crate::redact;
+use crate::utils::{SmooaiConfigError, SmooaiConfigErrorKind};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    /// Seconds since the Unix epoch when the values were fetched.
+    saved_at: u64,
+    values: HashMap<String, Value>,
+    /// Secret values, when saved with encryption; `values` then holds the rest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secrets: Option<SealedSecrets>,
+}
+
+/// Where the snapshot lives and how old it may be before it's ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LastKnownGood {
+    path: PathBuf,
+    max_age: Duration,
+}
+
+impl LastKnownGood {
+    pub fn new(path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        Self {
+            path: path.into(),
+            max_age,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Persist `values`, replacing any previous snapshot atomically.
+    pub fn save(&self, values: &HashMap<String, Value>) -> Result<(), SmooaiConfigError> {
+        self.save_with(values, None)
+    }
+
+    /// [`save`](Self::save), sealing secret values with `encryption` if given.
+    pub fn save_with(
+        &self,
+        values: &HashMap<String, Value>,
+        encryption: Option<&AtRestEncryption>,
+    ) -> Result<(), SmooaiConfigError> {
+        let (values, secrets) = match encryption {
+            Some(encryption) => encryption.seal(values).map(|(public, sealed)| (public, Some(sealed)))?,
+            None => (values.clone(), None),
+        };
+        let snapshot = Snapshot {
+            saved_at: now_secs(),
+            values,
+            secrets,
+        };
+        let body = serde_json::to_vec(&snapshot)
+            .map_err(|e| SmooaiConfigError::new(&format!("Failed to serialize snapshot: {}", e)))?;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        write_private(&tmp, &body).map_err(|e| io_error(&tmp, e))?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| io_error(&self.path, e))
+    }
+
+    /// The saved values, unless the snapshot is missing, unreadable or older than `max_age`.
+    pub fn load(&self) -> Option<HashMap<String, Value>> {
+        self.load_with(None)
+    }
+
+    /// [`load`](Self::load), opening sealed secrets with `encryption`. A
+    /// snapshot whose secrets can't be opened is ignored.
+    pub fn load_with(&self, encryption: Option<&AtRestEncryption>) -> Option<HashMap<String, Value>> {
+        let body = std::fs::read(&self.path).ok()?;
+        let snapshot: Snapshot = serde_json::from_slice(&body).ok()?;
+        let age = Duration::from_secs(now_secs().saturating_sub(snapshot.saved_at));
+        if age > self.max_age {
+            return None;
+        }
+        open_secrets(snapshot.values, snapshot.secrets.as_ref(), encryption)
+    }
+}
+
+/// Merge sealed secrets back into `values`; `None` when they can't be opened.
+pub(crate) fn open_secrets(
+    mut values: HashMap<String, Value>,
+    secrets: Option<&SealedSecrets>,
+    encryption: Option<&AtRestEncryption>,
+) -> Option<HashMap<String, Value>> {
+    let Some(sealed) = secrets else {
+        return Some(values);
+    };
+    match encryption.map(|encryption| encryption.open(sealed)) {
+        Some(Ok(secrets)) => {
+            values.extend(secrets);
+            Some(values)
+        }
+        Some(Err(e)) => {
+            redact::warn(format_args!("Ignoring cached config: {}", e));
+            None
+        }
+        None => None,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub(crate) fn io_error(path: &Path, e: std::io::Error) -> SmooaiConfigError {
+    SmooaiConfigError::new(&format!("Failed to write snapshot {}: {}", path.display(), e))
+        .with_kind(SmooaiConfigErrorKind::Io {
+            path: path.display().to_string(),
+        })
+        .with_source(e)
+}
+
+#[cfg(unix)]
+pub(crate) fn write_private(path: &Path, body: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(body)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn write_private(path: &Path, body: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let lkg = LastKnownGood::new(dir.path().join("nested/lkg.json"), Duration::from_secs(60));
+        assert!(lkg.load().is_none());
+
+        let values = HashMap::from([("API_URL".to_string(), json!("https://api"))]);
+        lkg.save(&values).unwrap();
+        assert_eq!(lkg.load(), Some(values));
+    }
+
+    #[test]
+    fn test_stale_snapshot_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lkg.json");
+        let stale = json!({"saved_at": now_secs() - 120, "values": {"A": 1}});
+        std::fs::write(&path, stale.to_string()).unwrap();
+
+        assert!(LastKnownGood::new(&path, Duration::from_secs(60)).load().is_none());
+        assert!(LastKnownGood::new(&path, Duration::from_secs(600)).load().is_some());
+    }
+
+    #[test]
+    fn test_secrets_sealed_at_rest() {
+        use crate::at_rest::LocalKeyFile;
+        use std::collections::HashSet;
+        use std::sync::Arc;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = Arc::new(LocalKeyFile::open_or_create(dir.path().join("lkg.key")).unwrap());
+        let encryption = AtRestEncryption::new(key, HashSet::from(["DB_PASSWORD".to_string()]));
+        let lkg = LastKnownGood::new(dir.path().join("lkg.json"), Duration::from_secs(60));
+        let values = HashMap::from([
+            ("API_URL".to_string(), json!("https://api")),
+            ("DB_PASSWORD".to_string(), json!("hunter2")),
+        ]);
+        lkg.save_with(&values, Some(&encryption)).unwrap();
+
+        let on_disk = std::fs::read_to_string(lkg.path()).unwrap();
+        assert!(on_disk.contains("https://api"), "public values stay readable");
+        assert!(!on_disk.contains("hunter2"));
+        assert_eq!(lkg.load_with(Some(&encryption)), Some(values));
+        assert!(lkg.load().is_none(), "secrets can't be opened without the key");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_snapshot_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let lkg = LastKnownGood::new(dir.path().join("lkg.json"), Duration::from_secs(60));
+        lkg.save(&HashMap::new()).unwrap();
+        let mode = std::fs::metadata(lkg.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -3,43 +3,174 @@
 //! Provides schema definition, JSON Schema generation, runtime config client,
 //! and local file/env-based configuration with caching.
 
+pub mod access;
+pub mod at_rest;
+#[cfg(feature = "aws")]
+pub mod aws;
 pub mod bootstrap;
 pub mod build;
+#[cfg(feature = "bundle")]
+pub mod bundle_source;
+pub mod changes;
+#[cfg(feature = "clap")]
+pub mod clap_defaults;
+#[cfg(feature = "cli")]
+pub mod cli;
 pub mod client;
 pub mod cloud_region;
+pub mod codegen;
+pub mod compose;
 pub mod config_manager;
+#[cfg(feature = "config-rs")]
+pub mod config_rs_source;
+#[cfg(feature = "consul")]
+pub mod consul_source;
 pub mod container;
 pub mod deferred;
+pub mod deprecation;
+pub mod deserializer;
 pub mod env_config;
 pub mod eso_manifests;
 pub mod eso_refresher;
+#[cfg(feature = "etcd")]
+pub mod etcd_source;
+pub mod example;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "figment")]
+pub mod figment_provider;
 pub mod file_config;
+pub mod flags;
+pub mod flatten;
+pub mod global;
+pub mod last_known_good;
 pub mod local;
+mod locked_memory;
 pub mod merge;
+pub mod merge_laws;
+pub mod metrics_recorder;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "pem")]
+pub mod pem;
+pub mod policy;
+#[cfg(feature = "prometheus")]
+pub mod prometheus_collector;
+pub mod provider;
+pub mod redact;
+#[cfg(feature = "redis")]
+pub mod redis_source;
 pub mod runtime;
+#[cfg(feature = "schedule")]
+pub mod schedule;
 pub mod schema;
+pub mod schema_files;
 pub mod schema_validator;
+pub mod scoped;
+pub mod secret;
+#[cfg(feature = "secrets-manager")]
+pub mod secrets_manager_source;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod source;
+#[cfg(feature = "ssm")]
+pub mod ssm_source;
+pub mod standard_urls;
+pub mod status;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "tls-pinning")]
+pub mod tls_pinning;
 pub mod token_provider;
 pub mod utils;
+pub mod value_validator;
+#[cfg(feature = "vault")]
+pub mod vault_source;
 
+pub use access::KeyAccess;
+pub use at_rest::{AtRestEncryption, KeyEncryptionKey, LocalKeyFile, SealedSecrets};
 pub use bootstrap::{bootstrap_fetch, BootstrapError};
 pub use build::{build_bundle, BuildBundleOptions, BuildBundleResult, BuildError, Classification, Classifier};
+#[cfg(feature = "bundle")]
+pub use bundle_source::BundleSource;
+pub use changes::ConfigChange;
+#[cfg(feature = "clap")]
+pub use clap_defaults::with_config_defaults;
 pub use client::{
     clamp_limit, ConfigClient, EvaluateFeatureFlagResponse, EvaluateLimitResponse, FeatureFlagEvaluationError,
-    LimitEvaluationError, LimitSpec,
+    LimitEvaluationError, LimitSpec, RemoteSchema,
 };
-pub use cloud_region::{get_cloud_region, get_cloud_region_from_env, CloudRegionResult};
-pub use config_manager::ConfigManager;
+pub use cloud_region::{
+    clear_cloud_region_cache, get_cloud_region, get_cloud_region_from_env, refresh_cloud_region, register_detector,
+    CloudDetector, CloudRegionResult, PaasProvider, PAAS_PROVIDERS,
+};
+pub use compose::DefinitionConflict;
+pub use config_manager::{ConfigManager, OverrideGuard};
+#[cfg(feature = "config-rs")]
+pub use config_rs_source::ConfigRsSource;
+#[cfg(feature = "consul")]
+pub use consul_source::ConsulSource;
 pub use container::{
     config_health, init_container_config, ConfigBootstrapError, ConfigError, ConfigHealth, ConfigKeyUnresolvedError,
     ConfigTier as ContainerConfigTier, ContainerConfigHandle, InitContainerConfigOptions, Mode, SelectModeInputs,
     DEFAULT_CACHE_TTL, DEFAULT_TOKEN_REFRESH_BUFFER_SECONDS,
 };
 pub use container::{select_mode, FeatureFlagAccessor, PublicConfigAccessor, SecretConfigAccessor};
+pub use deprecation::KeyDeprecation;
+pub use deserializer::ConfigDeserializer;
 pub use env_config::find_and_process_env_config;
+#[cfg(feature = "etcd")]
+pub use etcd_source::EtcdSource;
+pub use explain::{Explanation, LayerValue, ValueSource};
+#[cfg(feature = "figment")]
+pub use figment_provider::FigmentProvider;
 pub use file_config::{find_and_process_file_config, find_config_directory};
-pub use local::LocalConfigManager;
-pub use merge::merge_replace_arrays;
+pub use flatten::KeyFlattening;
+pub use global::{global, global_init, try_global};
+pub use last_known_good::LastKnownGood;
+#[allow(deprecated)]
+pub use local::{LocalConfigManager, LocalOnlyConfigManager};
+pub use merge::{
+    json_merge_patch, merge_many, merge_replace_arrays, merge_replace_arrays_into, merge_with_provenance, MergeRules,
+    MergeStrategy, Provenance, DEFAULT_MAX_MERGE_DEPTH,
+};
+#[cfg(feature = "metrics")]
+pub use metrics_recorder::MetricsCrateRecorder;
+pub use metrics_recorder::{FetchContext, MetricsRecorder};
+#[cfg(feature = "otel")]
+pub use otel::OtelRecorder;
+#[cfg(feature = "pem")]
+pub use pem::{certificates_from_value, private_key_from_value, PemError};
+pub use policy::AccessPolicy;
+#[cfg(feature = "prometheus")]
+pub use prometheus_collector::PrometheusCollector;
+pub use provider::{ConfigProvider, StaticConfigProvider};
+#[cfg(feature = "redis")]
+pub use redis_source::RedisSource;
 pub use runtime::{build_config_runtime, read_baked_config, BakedConfig, RuntimeError, RuntimeOptions};
+#[cfg(feature = "schedule")]
+pub use schedule::{Schedule, ScheduleError};
+pub use scoped::ScopedConfig;
+pub use secret::{Secret, SecretString, SecretValue, REDACTED};
+#[cfg(feature = "secrets-manager")]
+pub use secrets_manager_source::SecretsManagerSource;
+#[cfg(feature = "snapshot")]
+pub use snapshot::ConfigSnapshot;
+pub use source::{ConfigSource, SourceWatch};
+#[cfg(feature = "ssm")]
+pub use ssm_source::SsmSource;
+pub use status::{CacheStats, ConfigStatus, SourceState, SourceStatus};
+#[cfg(any(test, feature = "testing"))]
+pub use testing::{ConfigFixture, FakeConfigServer};
+#[cfg(feature = "tls-pinning")]
+pub use tls_pinning::CertificatePin;
 pub use token_provider::{SharedTokenProvider, TokenProvider, TokenProviderError};
-pub use utils::{camel_to_upper_snake, coerce_boolean, SmooaiConfigError, SmooaiConfigErrorKind};
+pub use utils::{
+    camel_to_kebab, camel_to_upper_snake, coerce_boolean, jittered_ttl, kebab_to_camel, kebab_to_upper_snake,
+    render_template, render_template_with, upper_snake_to_camel, upper_snake_to_kebab, DecodedBytes, KeyCase,
+    MissingKey, SmooaiConfigError, SmooaiConfigErrorKind,
+};
+pub use value_validator::ValueViolation;
+#[cfg(feature = "vault")]
+pub use vault_source::{VaultAuth, VaultSource};
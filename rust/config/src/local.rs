@@ -1,207 +1,172 @@
 //! Local configuration manager with lazy init and multi-tier TTL caching.
+//!
+//! Kept for existing callers. New code should use
+//! [`ConfigManager::local_only`], which this type wraps: both share one
+//! implementation, so behaviour can't drift between them.
 
-use std::collections::{HashMap, HashSet};
-use std::sync::RwLock;
-use std::time::{Duration, Instant};
+#![allow(deprecated)]
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::env_config::find_and_process_env_config_with_env;
-use crate::file_config::find_and_process_file_config_with_env;
+use crate::config_manager::ConfigManager;
+use crate::secret::SecretValue;
 use crate::utils::SmooaiConfigError;
 
-const DEFAULT_TTL_SECS: u64 = 86400; // 24 hours
-
-struct CacheEntry {
-    value: Value,
-    expires_at: Instant,
-}
-
-struct Inner {
-    initialized: bool,
-    file_config: Option<HashMap<String, Value>>,
-    env_config: Option<HashMap<String, Value>>,
-    public_cache: HashMap<String, CacheEntry>,
-    secret_cache: HashMap<String, CacheEntry>,
-    feature_flag_cache: HashMap<String, CacheEntry>,
-}
-
 /// Main entry point for local config with lazy init and multi-tier TTL caching.
 ///
 /// Thread-safe via RwLock. Lazy initialization loads file config + env config on first access.
 /// Per-key caches with 24h TTL for each tier (public, secret, feature_flag).
 /// File config takes precedence over env config.
+///
+/// Deprecated in favour of [`ConfigManager::local_only`], which behaves
+/// identically; [`into_config_manager`](Self::into_config_manager) converts
+/// an existing instance.
+#[deprecated(note = "use ConfigManager::local_only (type LocalOnlyConfigManager)")]
 pub struct LocalConfigManager {
-    inner: RwLock<Inner>,
-    schema_keys: Option<HashSet<String>>,
-    env_prefix: String,
-    schema_types: Option<HashMap<String, String>>,
-    cache_ttl: Duration,
-    env_override: Option<HashMap<String, String>>,
+    inner: ConfigManager,
 }
 
+/// The replacement for [`LocalConfigManager`]: a [`ConfigManager`] built with
+/// [`ConfigManager::local_only`].
+pub type LocalOnlyConfigManager = ConfigManager;
+
 impl LocalConfigManager {
     /// Create a new manager with default settings.
     pub fn new() -> Self {
         Self {
-            inner: RwLock::new(Inner {
-                initialized: false,
-                file_config: None,
-                env_config: None,
-                public_cache: HashMap::new(),
-                secret_cache: HashMap::new(),
-                feature_flag_cache: HashMap::new(),
-            }),
-            schema_keys: None,
-            env_prefix: String::new(),
-            schema_types: None,
-            cache_ttl: Duration::from_secs(DEFAULT_TTL_SECS),
-            env_override: None,
+            inner: ConfigManager::local_only(),
+        }
+    }
+
+    /// Create a manager whose config is exactly `values`, without reading
+    /// files or env vars. See [`ConfigManager::from_values`].
+    pub fn from_values(values: HashMap<String, Value>) -> Self {
+        Self {
+            inner: ConfigManager::from_values(values),
         }
     }
 
     /// Set schema keys for env config filtering.
     pub fn with_schema_keys(mut self, keys: HashSet<String>) -> Self {
-        self.schema_keys = Some(keys);
+        self.inner = self.inner.with_schema_keys(keys);
         self
     }
 
     /// Set env var prefix for stripping.
     pub fn with_env_prefix(mut self, prefix: &str) -> Self {
-        self.env_prefix = prefix.to_string();
+        self.inner = self.inner.with_env_prefix(prefix);
         self
     }
 
     /// Set schema type hints for coercion.
     pub fn with_schema_types(mut self, types: HashMap<String, String>) -> Self {
-        self.schema_types = Some(types);
+        self.inner = self.inner.with_schema_types(types);
         self
     }
 
     /// Set cache TTL.
     pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
-        self.cache_ttl = ttl;
+        self.inner = self.inner.with_cache_ttl(ttl);
+        self
+    }
+
+    /// Whether to set the built-in keys. See [`ConfigManager::with_builtin_keys`].
+    pub fn with_builtin_keys(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_builtin_keys(enabled);
+        self
+    }
+
+    /// Guess `IS_LOCAL` when it isn't set. See [`ConfigManager::with_auto_detect_local`].
+    pub fn with_auto_detect_local(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_auto_detect_local(enabled);
         self
     }
 
     /// Override environment variables (for testing).
     pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
-        self.env_override = Some(env);
+        self.inner = self.inner.with_env(env);
         self
     }
 
-    fn get_env(&self) -> HashMap<String, String> {
-        self.env_override.clone().unwrap_or_else(|| std::env::vars().collect())
+    /// Retrieve a public config value.
+    pub fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        self.inner.get_public_config(key)
     }
 
-    fn initialize_inner(&self, inner: &mut Inner) -> Result<(), SmooaiConfigError> {
-        if inner.initialized {
-            return Ok(());
-        }
+    /// Retrieve a public config value deserialized into `T`. See
+    /// [`ConfigManager::get_public_config_as`].
+    pub fn get_public_config_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SmooaiConfigError> {
+        self.inner.get_public_config_as(key)
+    }
 
-        let env = self.get_env();
-
-        let file_config = find_and_process_file_config_with_env(&env)?;
-        inner.file_config = Some(file_config);
-
-        let schema_keys = self.schema_keys.clone().unwrap_or_default();
-        let env_config =
-            find_and_process_env_config_with_env(&schema_keys, &self.env_prefix, self.schema_types.as_ref(), &env);
-        inner.env_config = Some(env_config);
-        inner.initialized = true;
-
-        Ok(())
-    }
-
-    fn get_value(
-        &self,
-        key: &str,
-        cache_selector: fn(&mut Inner) -> &mut HashMap<String, CacheEntry>,
-    ) -> Result<Option<Value>, SmooaiConfigError> {
-        // SMOODEV-847 — guard against empty keys. Matches assertKeyDefined
-        // behavior in the TypeScript SDK; gives a clear error instead of
-        // silently returning None for an unset key (which can mask schema
-        // mistakes). Cost real prod debug time on the TS side.
-        if key.is_empty() {
-            return Err(SmooaiConfigError::new(
-                "@smooai/config: get() called with empty key. \
-                 Most common cause: reading a typed-keys constant for a key that's not declared in your schema. \
-                 Add it to .smooai-config/config.ts and run `smooai-config push`",
-            ));
-        }
-        let mut inner = self
-            .inner
-            .write()
-            .map_err(|_| SmooaiConfigError::new("Failed to acquire write lock"))?;
-
-        // Check cache
-        let cache = cache_selector(&mut inner);
-        if let Some(entry) = cache.get(key) {
-            if Instant::now() < entry.expires_at {
-                return Ok(Some(entry.value.clone()));
-            }
-            cache.remove(key);
-        }
+    /// Retrieve a public config value that must be set. See
+    /// [`ConfigManager::get_required_public_config`].
+    pub fn get_required_public_config(&self, key: &str) -> Result<Value, SmooaiConfigError> {
+        self.inner.get_required_public_config(key)
+    }
 
-        // Initialize if needed
-        self.initialize_inner(&mut inner)?;
-
-        // File config takes precedence
-        let file_value = inner.file_config.as_ref().and_then(|fc| fc.get(key)).cloned();
-        if let Some(value) = file_value {
-            let cache = cache_selector(&mut inner);
-            cache.insert(
-                key.to_string(),
-                CacheEntry {
-                    value: value.clone(),
-                    expires_at: Instant::now() + self.cache_ttl,
-                },
-            );
-            return Ok(Some(value));
-        }
+    /// Retrieve a public config value, or `default` when it's missing. See
+    /// [`ConfigManager::get_public_config_or`].
+    pub fn get_public_config_or(&self, key: &str, default: impl Into<Value>) -> Result<Value, SmooaiConfigError> {
+        self.inner.get_public_config_or(key, default)
+    }
 
-        // Env config fallback
-        let env_value = inner.env_config.as_ref().and_then(|ec| ec.get(key)).cloned();
-        if let Some(value) = env_value {
-            let cache = cache_selector(&mut inner);
-            cache.insert(
-                key.to_string(),
-                CacheEntry {
-                    value: value.clone(),
-                    expires_at: Instant::now() + self.cache_ttl,
-                },
-            );
-            return Ok(Some(value));
-        }
+    /// Whether `key` is set, even to `null`. See [`ConfigManager::contains_key`].
+    pub fn contains_key(&self, key: &str) -> Result<bool, SmooaiConfigError> {
+        self.inner.contains_key(key)
+    }
 
-        Ok(None)
+    /// Every key starting with `prefix`, sorted. See
+    /// [`ConfigManager::keys_with_prefix`].
+    pub fn keys_with_prefix(&self, prefix: &str) -> Result<Vec<String>, SmooaiConfigError> {
+        self.inner.keys_with_prefix(prefix)
     }
 
-    /// Retrieve a public config value.
-    pub fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
-        self.get_value(key, |inner| &mut inner.public_cache)
+    /// Every value whose key starts with `prefix`, secrets redacted. See
+    /// [`ConfigManager::get_all_with_prefix`].
+    pub fn get_all_with_prefix(&self, prefix: &str) -> Result<BTreeMap<String, Value>, SmooaiConfigError> {
+        self.inner.get_all_with_prefix(prefix)
     }
 
     /// Retrieve a secret config value.
-    pub fn get_secret_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
-        self.get_value(key, |inner| &mut inner.secret_cache)
+    pub fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        self.inner.get_secret_config(key)
     }
 
     /// Retrieve a feature flag value.
     pub fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
-        self.get_value(key, |inner| &mut inner.feature_flag_cache)
+        self.inner.get_feature_flag(key)
+    }
+
+    /// Whether the feature flag `key` is on. See [`ConfigManager::is_enabled`].
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.inner.is_enabled(key)
+    }
+
+    /// Whether the feature flag `key` is on, or `default` when that can't be
+    /// determined. See [`ConfigManager::is_enabled_or`].
+    pub fn is_enabled_or(&self, key: &str, default: bool) -> bool {
+        self.inner.is_enabled_or(key, default)
     }
 
     /// Clear all caches and force re-initialization on next access.
     pub fn invalidate(&self) {
-        if let Ok(mut inner) = self.inner.write() {
-            inner.initialized = false;
-            inner.file_config = None;
-            inner.env_config = None;
-            inner.public_cache.clear();
-            inner.secret_cache.clear();
-            inner.feature_flag_cache.clear();
-        }
+        self.inner.invalidate();
+    }
+
+    /// The underlying local-only [`ConfigManager`].
+    pub fn into_config_manager(self) -> ConfigManager {
+        self.inner
+    }
+}
+
+impl From<LocalConfigManager> for ConfigManager {
+    fn from(local: LocalConfigManager) -> Self {
+        local.into_config_manager()
     }
 }
 
@@ -240,9 +205,9 @@ mod tests {
         let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
         let mgr = LocalConfigManager::new().with_env(env);
 
-        assert!(!mgr.inner.read().unwrap().initialized);
+        assert!(!mgr.inner.is_initialized());
         mgr.get_public_config("API_URL").unwrap();
-        assert!(mgr.inner.read().unwrap().initialized);
+        assert!(mgr.inner.is_initialized());
     }
 
     #[test]
@@ -265,6 +230,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_values() {
+        let mgr = LocalConfigManager::from_values(HashMap::from([
+            ("API_URL".to_string(), serde_json::json!("http://memory")),
+            ("DB_PASSWORD".to_string(), serde_json::json!("hunter2")),
+        ]));
+
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("http://memory"))
+        );
+        assert_eq!(
+            mgr.get_secret_config("DB_PASSWORD").unwrap().unwrap().expose_str(),
+            Some("hunter2")
+        );
+        assert_eq!(mgr.get_public_config("ENV").unwrap(), None);
+    }
+
     #[test]
     fn test_returns_none_for_missing_key() {
         let dir = tempfile::tempdir().unwrap();
@@ -283,11 +266,11 @@ mod tests {
         let mgr = LocalConfigManager::new().with_env(env);
 
         mgr.get_public_config("API_URL").unwrap();
-        assert!(mgr.inner.read().unwrap().initialized);
+        assert!(mgr.inner.is_initialized());
 
         mgr.invalidate();
-        assert!(!mgr.inner.read().unwrap().initialized);
-        assert!(mgr.inner.read().unwrap().public_cache.is_empty());
+        assert!(!mgr.inner.is_initialized());
+        assert!(mgr.inner.public_cache_is_empty());
     }
 
     #[test]
@@ -303,4 +286,123 @@ mod tests {
         let result = mgr.get_public_config("API_URL").unwrap();
         assert_eq!(result, Some(Value::String("http://localhost".to_string())));
     }
+
+    #[test]
+    fn test_file_beats_env_at_top_level() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(
+            dir.path(),
+            &[(
+                "default.json",
+                r#"{"API_URL":"http://file","DATABASE":{"host":"file-host"}}"#,
+            )],
+        );
+        let env = make_env(
+            &config_dir,
+            &[
+                ("SMOOAI_CONFIG_ENV", "test"),
+                ("API_URL", "http://env"),
+                ("ONLY_ENV", "env-value"),
+                ("DATABASE", r#"{"port":5432}"#),
+            ],
+        );
+        let keys: HashSet<String> = ["API_URL", "ONLY_ENV", "DATABASE"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let mgr = LocalConfigManager::new().with_schema_keys(keys).with_env(env);
+
+        assert_eq!(
+            mgr.get_public_config("API_URL").unwrap(),
+            Some(serde_json::json!("http://file"))
+        );
+        assert_eq!(
+            mgr.get_public_config("ONLY_ENV").unwrap(),
+            Some(serde_json::json!("env-value"))
+        );
+        // Whole top-level values are replaced, not deep-merged.
+        assert_eq!(
+            mgr.get_public_config("DATABASE").unwrap(),
+            Some(serde_json::json!({"host": "file-host"}))
+        );
+    }
+
+    #[test]
+    fn test_ignores_remote_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"API_URL":"http://file"}"#)]);
+        let env = make_env(
+            &config_dir,
+            &[
+                ("SMOOAI_CONFIG_ENV", "test"),
+                ("SMOOAI_CONFIG_API_KEY", "key"),
+                // Nothing listens here; a fetch attempt would only warn, but the
+                // explanation would list a remote layer.
+                ("SMOOAI_CONFIG_API_URL", "http://127.0.0.1:9"),
+                ("SMOOAI_CONFIG_ORG_ID", "org"),
+            ],
+        );
+        let mgr = LocalConfigManager::new().with_env(env).into_config_manager();
+
+        let explanation = mgr.explain("API_URL").unwrap();
+        assert_eq!(explanation.value, Some(serde_json::json!("http://file")));
+        assert!(!explanation
+            .layers
+            .iter()
+            .any(|l| l.source == crate::explain::ValueSource::Remote));
+    }
+
+    #[test]
+    fn test_file_errors_propagate() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", "{not json")]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+
+        assert!(LocalConfigManager::new()
+            .with_env(env.clone())
+            .get_public_config("API_URL")
+            .is_err());
+        // The unified manager degrades to env-only instead.
+        assert!(ConfigManager::new().with_env(env).get_public_config("API_URL").is_ok());
+    }
+
+    #[test]
+    fn test_is_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = make_config_dir(dir.path(), &[("default.json", r#"{"NEW_UI":"true"}"#)]);
+        let env = make_env(&config_dir, &[("SMOOAI_CONFIG_ENV", "test")]);
+        let mgr = LocalConfigManager::new().with_env(env);
+
+        assert!(mgr.is_enabled("NEW_UI"));
+        assert!(!mgr.is_enabled("MISSING"));
+        assert!(mgr.is_enabled_or("MISSING", true));
+    }
+
+    #[test]
+    fn test_prefix_listing() {
+        let mgr = LocalConfigManager::from_values(HashMap::from([
+            ("FEATURE_A".to_string(), serde_json::json!(1)),
+            ("FEATURE_B".to_string(), serde_json::json!(2)),
+            ("OTHER".to_string(), serde_json::json!(3)),
+        ]));
+        assert_eq!(mgr.keys_with_prefix("FEATURE_").unwrap(), ["FEATURE_A", "FEATURE_B"]);
+        assert_eq!(
+            mgr.get_all_with_prefix("FEATURE_").unwrap()["FEATURE_B"],
+            serde_json::json!(2)
+        );
+    }
+
+    #[test]
+    fn test_required_and_default_getters() {
+        let mgr = LocalConfigManager::from_values(HashMap::from([(
+            "API_URL".to_string(),
+            serde_json::json!("http://api"),
+        )]));
+        assert_eq!(
+            mgr.get_required_public_config("API_URL").unwrap(),
+            serde_json::json!("http://api")
+        );
+        assert!(mgr.get_required_public_config("MISSING").is_err());
+        assert_eq!(mgr.get_public_config_or("MISSING", 5).unwrap(), serde_json::json!(5));
+    }
 }
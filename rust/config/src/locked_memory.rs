@@ -0,0 +1,139 @@
+//! Buffers kept out of swap and wiped when dropped, for
+//! [`ConfigManager::with_locked_secrets`](crate::ConfigManager::with_locked_secrets).
+//!
+//! Each buffer gets its own page-aligned allocation, so locking and
+//! unlocking one never affects another. Locking uses `mlock` on Unix (and
+//! `MADV_DONTDUMP` on Linux to keep the pages out of core dumps); elsewhere,
+//! or when `RLIMIT_MEMLOCK` is exhausted, the buffer is still zeroized on drop.
+
+use std::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use zeroize::Zeroize;
+
+use crate::redact;
+
+/// Set after the first failed `mlock`, so the warning is printed once.
+static LOCK_FAILURE_REPORTED: AtomicBool = AtomicBool::new(false);
+
+fn page_size() -> usize {
+    static PAGE_SIZE: OnceLock<usize> = OnceLock::new();
+    *PAGE_SIZE.get_or_init(|| {
+        #[cfg(unix)]
+        {
+            // SAFETY: sysconf has no preconditions.
+            let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+            if size > 0 {
+                return size as usize;
+            }
+        }
+        4096
+    })
+}
+
+/// Bytes in their own locked, page-aligned allocation, zeroized on drop.
+pub(crate) struct LockedBytes {
+    ptr: NonNull<u8>,
+    len: usize,
+    layout: Layout,
+    locked: bool,
+}
+
+// SAFETY: LockedBytes owns its allocation exclusively and only hands out
+// shared references to it.
+unsafe impl Send for LockedBytes {}
+unsafe impl Sync for LockedBytes {}
+
+impl LockedBytes {
+    /// Copy `bytes` into a fresh locked allocation.
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        let page = page_size();
+        let size = bytes.len().max(1).div_ceil(page) * page;
+        let layout = Layout::from_size_align(size, page).expect("page-aligned layout");
+        // SAFETY: `layout` has a non-zero size.
+        let ptr = NonNull::new(unsafe { alloc_zeroed(layout) }).unwrap_or_else(|| handle_alloc_error(layout));
+        // SAFETY: the allocation is at least `bytes.len()` long and can't
+        // overlap `bytes`, which is borrowed from elsewhere.
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr.as_ptr(), bytes.len()) };
+        let locked = lock(ptr, size);
+        if !locked && !LOCK_FAILURE_REPORTED.swap(true, Ordering::Relaxed) {
+            redact::warn(
+                "Could not lock secret cache memory (check RLIMIT_MEMLOCK); cached secrets are still wiped on release",
+            );
+        }
+        Self {
+            ptr,
+            len: bytes.len(),
+            layout,
+            locked,
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `layout.size() >= len` initialized bytes
+        // owned by `self`.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for LockedBytes {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` is a live allocation of `layout.size()` bytes owned by `self`.
+        let buffer = unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size()) };
+        buffer.zeroize();
+        if self.locked {
+            unlock(self.ptr, self.layout.size());
+        }
+        // SAFETY: allocated in `new` with this same layout.
+        unsafe { dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+#[cfg(unix)]
+fn lock(ptr: NonNull<u8>, size: usize) -> bool {
+    #[cfg(target_os = "linux")]
+    // SAFETY: the range is one whole, page-aligned allocation we own.
+    unsafe {
+        libc::madvise(ptr.as_ptr().cast(), size, libc::MADV_DONTDUMP);
+    }
+    // SAFETY: as above.
+    unsafe { libc::mlock(ptr.as_ptr().cast(), size) == 0 }
+}
+
+#[cfg(not(unix))]
+fn lock(_ptr: NonNull<u8>, _size: usize) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn unlock(ptr: NonNull<u8>, size: usize) {
+    // SAFETY: the range was locked by `lock` and is still allocated.
+    unsafe {
+        libc::munlock(ptr.as_ptr().cast(), size);
+    }
+}
+
+#[cfg(not(unix))]
+fn unlock(_ptr: NonNull<u8>, _size: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_bytes_round_trip() {
+        let secret = LockedBytes::new(b"hunter2");
+        assert_eq!(secret.as_slice(), b"hunter2");
+        assert_eq!(secret.ptr.as_ptr() as usize % page_size(), 0);
+
+        let empty = LockedBytes::new(b"");
+        assert!(empty.as_slice().is_empty());
+
+        let large = vec![7u8; page_size() + 1];
+        let large_locked = LockedBytes::new(&large);
+        assert_eq!(large_locked.as_slice(), &large[..]);
+        assert_eq!(large_locked.layout.size(), 2 * page_size());
+    }
+}
@@ -1,35 +1,460 @@
-//! Deep merge utility with array replacement.
+//! Deep merge utilities: array replacement (the default), RFC 7386 JSON Merge
+//! Patch, and array-combining strategies that can be chosen per key path.
+//!
+//! Layers merged through [`MergeStrategy`] or [`MergeRules`] may also carry
+//! inline directives: an object whose only key is one of
+//!
+//! - `{"$delete": true}`: remove the key from the merged result,
+//! - `{"$replace": value}`: use `value` as-is instead of deep-merging it,
+//! - `{"$append": [..]}`: append to the lower layer's array,
+//! - `{"$merge": value}`: deep-merge `value`, merging arrays item by item
+//!   by position instead of replacing them.
+//!
+//! Directives are resolved during the merge and never appear in its output.
+
+use std::collections::{BTreeMap, HashMap};
 
 use serde_json::Value;
 
+use crate::utils::SmooaiConfigError;
+
+/// Default for [`MergeRules::max_depth`]. Real config rarely nests more than
+/// a handful of levels; anything near this is almost certainly malformed.
+pub const DEFAULT_MAX_MERGE_DEPTH: usize = 64;
+
+/// How config layers are combined, selected per manager with
+/// `ConfigManager::with_merge_strategy` (or per key path with
+/// `ConfigManager::with_merge_strategy_at`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MergeStrategy {
+    /// [`merge_replace_arrays`]: objects recurse, arrays and primitives
+    /// (including `null`) overwrite.
+    #[default]
+    ReplaceArrays,
+    /// [`json_merge_patch`]: like `ReplaceArrays`, except a `null` in a
+    /// higher layer deletes the key instead of storing `null`.
+    JsonMergePatch,
+    /// Like `ReplaceArrays`, except a higher layer's array is appended to the
+    /// lower layer's.
+    Concat,
+    /// Like `Concat`, but items already present are not appended again.
+    UniqueAppend,
+    /// Arrays of objects are matched on the named field: matching items are
+    /// merged, the rest appended. Items without the field are appended.
+    MergeByKey(&'static str),
+}
+
+impl MergeStrategy {
+    /// Merge `source` over `target` with this strategy, resolving any
+    /// directives in `source`.
+    pub fn merge(self, target: &Value, source: &Value) -> Value {
+        MergeRules::new(self).merge(target, source)
+    }
+}
+
+/// A default [`MergeStrategy`] plus overrides for particular key paths.
+///
+/// Paths are dot-separated from the top-level key (`CORS_ORIGINS`,
+/// `DATABASE.replicas`) and apply to everything beneath them unless a longer
+/// path overrides them again.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MergeRules {
+    pub default: MergeStrategy,
+    pub paths: BTreeMap<String, MergeStrategy>,
+    /// Deepest nesting of objects and arrays [`merge_many`](Self::merge_many)
+    /// and [`check_depth`](Self::check_depth) accept.
+    pub max_depth: usize,
+}
+
+impl Default for MergeRules {
+    fn default() -> Self {
+        Self::new(MergeStrategy::default())
+    }
+}
+
+impl MergeRules {
+    pub fn new(default: MergeStrategy) -> Self {
+        Self {
+            default,
+            paths: BTreeMap::new(),
+            max_depth: DEFAULT_MAX_MERGE_DEPTH,
+        }
+    }
+
+    /// Use `strategy` at `path` and below.
+    pub fn with_path(mut self, path: &str, strategy: MergeStrategy) -> Self {
+        self.paths.insert(path.to_string(), strategy);
+        self
+    }
+
+    /// Set [`max_depth`](Self::max_depth).
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Error if `value` nests objects and arrays more than
+    /// [`max_depth`](Self::max_depth) levels deep. The check itself does not
+    /// recurse, so it is safe on any input.
+    pub fn check_depth(&self, value: &Value) -> Result<(), SmooaiConfigError> {
+        let mut stack = vec![(value, 0usize)];
+        while let Some((value, depth)) = stack.pop() {
+            let children: Box<dyn Iterator<Item = &Value>> = match value {
+                Value::Object(map) => Box::new(map.values()),
+                Value::Array(items) => Box::new(items.iter()),
+                _ => continue,
+            };
+            if depth + 1 > self.max_depth {
+                return Err(SmooaiConfigError::new(&format!(
+                    "Config value nests deeper than the maximum merge depth of {}",
+                    self.max_depth
+                )));
+            }
+            stack.extend(children.map(|child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+
+    /// Fold `layers` (lowest precedence first) into one value, after checking
+    /// each against [`max_depth`](Self::max_depth) so a pathological document
+    /// errors instead of overflowing the stack.
+    pub fn merge_many(&self, layers: &[Value]) -> Result<Value, SmooaiConfigError> {
+        let mut merged = Value::Object(Default::default());
+        for layer in layers {
+            self.check_depth(layer)?;
+            self.merge_into(&mut merged, layer);
+        }
+        Ok(merged)
+    }
+
+    /// The strategy in effect at `path`: its own, its nearest ancestor's, or the default.
+    pub fn strategy_at(&self, path: &str) -> MergeStrategy {
+        let mut path = path;
+        loop {
+            if let Some(strategy) = self.paths.get(path) {
+                return *strategy;
+            }
+            match path.rfind('.') {
+                Some(dot) => path = &path[..dot],
+                None => return self.default,
+            }
+        }
+    }
+
+    /// Merge `source` over `target`.
+    pub fn merge(&self, target: &Value, source: &Value) -> Value {
+        let mut merged = target.clone();
+        self.merge_into(&mut merged, source);
+        merged
+    }
+
+    /// Merge `source` over `target` in place, cloning only what `source` adds.
+    pub fn merge_into(&self, target: &mut Value, source: &Value) {
+        self.merge_into_at("", target, source);
+    }
+
+    /// [`merge_into`](Self::merge_into) for maps of top-level keys, such as
+    /// the per-source configs a [`ConfigManager`](crate::ConfigManager) layers.
+    pub fn merge_map_into(&self, target: &mut HashMap<String, Value>, source: &HashMap<String, Value>) {
+        self.merge_members_into("", target, source);
+    }
+
+    fn merge_into_at(&self, path: &str, target: &mut Value, source: &Value) {
+        if let Some((directive, operand)) = directive(source) {
+            self.apply_directive(path, target, directive, operand);
+            return;
+        }
+        match (target, source) {
+            (Value::Object(target_map), Value::Object(source_map)) => {
+                self.merge_members_into(path, target_map, source_map);
+            }
+            (target, Value::Object(source_map)) => {
+                let mut map = serde_json::Map::new();
+                self.merge_members_into(path, &mut map, source_map);
+                *target = Value::Object(map);
+            }
+            (Value::Array(target_items), Value::Array(source_items)) => match self.strategy_at(path) {
+                MergeStrategy::Concat => target_items.extend(source_items.iter().cloned()),
+                MergeStrategy::UniqueAppend => {
+                    for item in source_items {
+                        if !target_items.contains(item) {
+                            target_items.push(item.clone());
+                        }
+                    }
+                }
+                MergeStrategy::MergeByKey(field) => {
+                    for item in source_items {
+                        let existing = item.get(field).and_then(|id| {
+                            target_items
+                                .iter()
+                                .position(|candidate| candidate.is_object() && candidate.get(field) == Some(id))
+                        });
+                        match existing {
+                            Some(index) => self.merge_into_at(path, &mut target_items[index], item),
+                            None => target_items.push(item.clone()),
+                        }
+                    }
+                }
+                MergeStrategy::ReplaceArrays | MergeStrategy::JsonMergePatch => *target_items = source_items.clone(),
+            },
+            (target, _) => *target = source.clone(),
+        }
+    }
+
+    fn merge_members_into<'a>(
+        &self,
+        path: &str,
+        target: &mut impl Members,
+        source: impl IntoIterator<Item = (&'a String, &'a Value)>,
+    ) {
+        for (key, value) in source {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            let deleted = match directive(value) {
+                Some((Directive::Delete, operand)) => operand.as_bool() != Some(false),
+                _ => value.is_null() && self.strategy_at(&child) == MergeStrategy::JsonMergePatch,
+            };
+            if deleted {
+                target.remove_member(key);
+                continue;
+            }
+            self.merge_into_at(&child, target.member(key), value);
+        }
+    }
+}
+
+/// Object-like containers merged member by member.
+trait Members {
+    /// The member `key`, inserted as `null` if missing.
+    fn member(&mut self, key: &str) -> &mut Value;
+    fn remove_member(&mut self, key: &str);
+}
+
+impl Members for serde_json::Map<String, Value> {
+    fn member(&mut self, key: &str) -> &mut Value {
+        self.entry(key).or_insert(Value::Null)
+    }
+
+    fn remove_member(&mut self, key: &str) {
+        self.remove(key);
+    }
+}
+
+impl Members for HashMap<String, Value> {
+    fn member(&mut self, key: &str) -> &mut Value {
+        self.entry(key.to_string()).or_insert(Value::Null)
+    }
+
+    fn remove_member(&mut self, key: &str) {
+        self.remove(key);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Directive {
+    Delete,
+    Replace,
+    Append,
+    Merge,
+}
+
+/// The directive `value` consists of, if any, and its operand.
+fn directive(value: &Value) -> Option<(Directive, &Value)> {
+    let map = value.as_object().filter(|map| map.len() == 1)?;
+    let (key, operand) = map.iter().next()?;
+    let directive = match key.as_str() {
+        "$delete" => Directive::Delete,
+        "$replace" => Directive::Replace,
+        "$append" => Directive::Append,
+        "$merge" => Directive::Merge,
+        _ => return None,
+    };
+    Some((directive, operand))
+}
+
+impl MergeRules {
+    fn apply_directive(&self, path: &str, target: &mut Value, directive: Directive, operand: &Value) {
+        // Merging onto null strips any directives nested inside the operand.
+        let resolved = |operand: &Value| {
+            let mut value = Value::Null;
+            self.merge_into_at(path, &mut value, operand);
+            value
+        };
+        match directive {
+            // Deletion is handled by the enclosing object; anywhere else it
+            // can only drop the value or, with `false`, keep it.
+            Directive::Delete if operand.as_bool() == Some(false) => {}
+            Directive::Delete => *target = Value::Null,
+            Directive::Replace => *target = resolved(operand),
+            Directive::Append => match (target, resolved(operand)) {
+                (Value::Array(items), Value::Array(appended)) => items.extend(appended),
+                (target, value) => *target = value,
+            },
+            Directive::Merge => match (target, operand) {
+                (Value::Array(target_items), Value::Array(source_items)) => {
+                    for (i, item) in source_items.iter().enumerate() {
+                        match target_items.get_mut(i) {
+                            Some(existing) => self.apply_directive(path, existing, Directive::Merge, item),
+                            None => target_items.push(resolved(item)),
+                        }
+                    }
+                }
+                (target, _) => self.merge_into_at(path, target, operand),
+            },
+        }
+    }
+}
+
+/// Which layer supplied each value of a merge, keyed by JSON pointer.
+///
+/// Holds one entry per leaf of the merged value (arrays count as leaves,
+/// since array strategies may combine several layers' items), naming the last
+/// layer that wrote it. Objects left empty by a deletion have no entry.
+pub type Provenance<S> = BTreeMap<String, S>;
+
+impl MergeRules {
+    /// Merge `layers` lowest-precedence first, also reporting which layer won
+    /// each leaf. See [`merge_with_provenance`].
+    pub fn merge_with_provenance<S: Clone>(&self, layers: &[(S, Value)]) -> (Value, Provenance<S>) {
+        let mut merged = Value::Object(Default::default());
+        let mut provenance = Provenance::new();
+        for (source, values) in layers {
+            self.merge_into(&mut merged, values);
+
+            let mut written = Vec::new();
+            written_paths(values, String::new(), &mut written);
+            for path in written {
+                // Whatever was recorded at, above or below this path is superseded.
+                let nested = format!("{}/", path);
+                provenance.retain(|pointer, _| {
+                    *pointer != path && !pointer.starts_with(&nested) && !path.starts_with(&format!("{}/", pointer))
+                });
+                if let Some(value) = merged.pointer(&path) {
+                    let mut leaves = Vec::new();
+                    leaf_paths(value, path, &mut leaves);
+                    provenance.extend(leaves.into_iter().map(|leaf| (leaf, source.clone())));
+                }
+            }
+            // Drop anything a layer deleted or replaced with a different shape.
+            provenance.retain(|pointer, _| merged.pointer(pointer).is_some());
+        }
+        (merged, provenance)
+    }
+}
+
+/// Merge `layers` (lowest precedence first) with the default strategy and
+/// return the result alongside the layer that supplied each leaf:
+///
+/// ```
+/// use serde_json::json;
+/// use smooai_config::merge::merge_with_provenance;
+///
+/// let (merged, provenance) = merge_with_provenance(&[
+///     ("default.json", json!({"DATABASE": {"host": "db", "port": 5432}})),
+///     ("production.json", json!({"DATABASE": {"host": "prod-db"}})),
+/// ]);
+/// assert_eq!(merged, json!({"DATABASE": {"host": "prod-db", "port": 5432}}));
+/// assert_eq!(provenance["/DATABASE/host"], "production.json");
+/// assert_eq!(provenance["/DATABASE/port"], "default.json");
+/// ```
+pub fn merge_with_provenance<S: Clone>(layers: &[(S, Value)]) -> (Value, Provenance<S>) {
+    MergeRules::default().merge_with_provenance(layers)
+}
+
+/// [`MergeRules::merge_many`] with the default strategy and depth limit.
+pub fn merge_many(layers: &[Value]) -> Result<Value, SmooaiConfigError> {
+    MergeRules::default().merge_many(layers)
+}
+
+/// Pointers a layer writes to: its leaves, with directives, arrays and empty
+/// objects counted as leaves. Deletions are left to the caller's pruning.
+fn written_paths(value: &Value, path: String, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() && directive(value).is_none() => {
+            for (key, member) in map {
+                written_paths(member, format!("{}/{}", path, escape_pointer(key)), out);
+            }
+        }
+        _ if matches!(directive(value), Some((Directive::Delete, _))) => {}
+        _ => out.push(path),
+    }
+}
+
+fn leaf_paths(value: &Value, path: String, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, member) in map {
+                leaf_paths(member, format!("{}/{}", path, escape_pointer(key)), out);
+            }
+        }
+        _ => out.push(path),
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+impl From<MergeStrategy> for MergeRules {
+    fn from(strategy: MergeStrategy) -> Self {
+        Self::new(strategy)
+    }
+}
+
 /// Deep merge where arrays replace entirely, objects recurse, primitives overwrite.
 pub fn merge_replace_arrays(target: &Value, source: &Value) -> Value {
-    match source {
-        // Arrays: replace entirely
-        Value::Array(_) => source.clone(),
+    let mut merged = target.clone();
+    merge_replace_arrays_into(&mut merged, source);
+    merged
+}
 
+/// [`merge_replace_arrays`] in place: `target` is updated without being
+/// copied, and only values that `source` contributes are cloned.
+pub fn merge_replace_arrays_into(target: &mut Value, source: &Value) {
+    match (target, source) {
         // Objects: recursive merge
-        Value::Object(source_map) => {
-            let mut result = match target {
-                Value::Object(target_map) => target_map.clone(),
-                _ => serde_json::Map::new(),
-            };
+        (Value::Object(target_map), Value::Object(source_map)) => {
             for (key, value) in source_map {
-                let merged = if let Some(target_value) = result.get(key) {
-                    merge_replace_arrays(target_value, value)
-                } else {
-                    value.clone()
-                };
-                result.insert(key.clone(), merged);
+                match target_map.get_mut(key) {
+                    Some(existing) => merge_replace_arrays_into(existing, value),
+                    None => {
+                        target_map.insert(key.clone(), value.clone());
+                    }
+                }
             }
-            Value::Object(result)
         }
 
-        // Primitives: source overwrites
-        _ => source.clone(),
+        // Arrays replace entirely; primitives and mismatched types overwrite
+        (target, _) => *target = source.clone(),
     }
 }
 
+/// Apply `patch` to `target` per RFC 7386 (JSON Merge Patch).
+///
+/// Objects merge recursively and `null` members delete the key; any other
+/// patch value (arrays included) replaces the target outright.
+pub fn json_merge_patch(target: &Value, patch: &Value) -> Value {
+    let Value::Object(patch_map) = patch else {
+        return patch.clone();
+    };
+    let mut result = match target {
+        Value::Object(target_map) => target_map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    for (key, value) in patch_map {
+        if value.is_null() {
+            result.remove(key);
+        } else {
+            let merged = json_merge_patch(result.get(key).unwrap_or(&Value::Null), value);
+            result.insert(key.clone(), merged);
+        }
+    }
+    Value::Object(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,4 +599,275 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_merge_patch_rfc7386_examples() {
+        // Appendix A of RFC 7386.
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "b"}), json!({"b": "c"}), json!({"a": "b", "b": "c"})),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (json!({"a": "b", "b": "c"}), json!({"a": null}), json!({"b": "c"})),
+            (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "c"}), json!({"a": ["b"]}), json!({"a": ["b"]})),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (json!({"a": [{"b": "c"}]}), json!({"a": [1]}), json!({"a": [1]})),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+            (json!({"a": "foo"}), json!(null), json!(null)),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (json!({"e": null}), json!({"a": 1}), json!({"e": null, "a": 1})),
+            (json!([1, 2]), json!({"a": "b", "c": null}), json!({"a": "b"})),
+            (json!({}), json!({"a": {"bb": {"ccc": null}}}), json!({"a": {"bb": {}}})),
+        ];
+        for (target, patch, expected) in cases {
+            assert_eq!(
+                json_merge_patch(&target, &patch),
+                expected,
+                "patch {} onto {}",
+                patch,
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn test_strategy_null_handling_differs() {
+        let target = json!({"a": 1, "b": 2});
+        let source = json!({"a": null});
+        assert_eq!(
+            MergeStrategy::ReplaceArrays.merge(&target, &source),
+            json!({"a": null, "b": 2})
+        );
+        assert_eq!(MergeStrategy::JsonMergePatch.merge(&target, &source), json!({"b": 2}));
+        assert_eq!(MergeStrategy::default(), MergeStrategy::ReplaceArrays);
+    }
+
+    #[test]
+    fn test_array_strategies() {
+        let target = json!({"CORS_ORIGINS": ["https://a.com", "https://b.com"]});
+        let source = json!({"CORS_ORIGINS": ["https://b.com", "https://c.com"]});
+        assert_eq!(
+            MergeStrategy::Concat.merge(&target, &source),
+            json!({"CORS_ORIGINS": ["https://a.com", "https://b.com", "https://b.com", "https://c.com"]})
+        );
+        assert_eq!(
+            MergeStrategy::UniqueAppend.merge(&target, &source),
+            json!({"CORS_ORIGINS": ["https://a.com", "https://b.com", "https://c.com"]})
+        );
+        // Non-array sources still replace.
+        assert_eq!(
+            MergeStrategy::Concat.merge(&target, &json!({"CORS_ORIGINS": "*"})),
+            json!({"CORS_ORIGINS": "*"})
+        );
+    }
+
+    #[test]
+    fn test_merge_by_key() {
+        let target = json!({"SERVICES": [
+            {"id": "api", "port": 80, "replicas": 2},
+            {"id": "worker", "replicas": 1},
+            "sidecar"
+        ]});
+        let source = json!({"SERVICES": [
+            {"id": "api", "replicas": 4},
+            {"id": "cron", "replicas": 1},
+            {"name": "no-id"}
+        ]});
+        assert_eq!(
+            MergeStrategy::MergeByKey("id").merge(&target, &source),
+            json!({"SERVICES": [
+                {"id": "api", "port": 80, "replicas": 4},
+                {"id": "worker", "replicas": 1},
+                "sidecar",
+                {"id": "cron", "replicas": 1},
+                {"name": "no-id"}
+            ]})
+        );
+    }
+
+    #[test]
+    fn test_per_path_rules() {
+        let rules = MergeRules::new(MergeStrategy::ReplaceArrays)
+            .with_path("CORS_ORIGINS", MergeStrategy::UniqueAppend)
+            .with_path("API", MergeStrategy::JsonMergePatch);
+        assert_eq!(rules.strategy_at("API.timeouts.read"), MergeStrategy::JsonMergePatch);
+        assert_eq!(rules.strategy_at("TAGS"), MergeStrategy::ReplaceArrays);
+
+        let target = json!({"CORS_ORIGINS": ["a"], "TAGS": ["x"], "API": {"url": "u", "legacy": true}, "OLD": 1});
+        let source = json!({"CORS_ORIGINS": ["b"], "TAGS": ["y"], "API": {"legacy": null}, "OLD": null});
+        assert_eq!(
+            rules.merge(&target, &source),
+            json!({"CORS_ORIGINS": ["a", "b"], "TAGS": ["y"], "API": {"url": "u"}, "OLD": null})
+        );
+    }
+
+    #[test]
+    fn test_rules_match_plain_strategies() {
+        let target = json!({"a": [1], "b": {"c": 1, "d": 2}});
+        let source = json!({"a": [2], "b": {"c": null}, "e": {"f": null}});
+        for strategy in [MergeStrategy::ReplaceArrays, MergeStrategy::JsonMergePatch] {
+            assert_eq!(
+                MergeRules::new(strategy)
+                    .with_path("unused", strategy)
+                    .merge(&target, &source),
+                strategy.merge(&target, &source)
+            );
+        }
+    }
+
+    #[test]
+    fn test_directives() {
+        let target = json!({
+            "CORS_ORIGINS": ["https://a.com"],
+            "DATABASE": {"host": "db", "port": 5432, "ssl": true},
+            "LEGACY": "x",
+            "SERVICES": [{"id": "api", "port": 80}, {"id": "worker"}]
+        });
+        let source = json!({
+            "CORS_ORIGINS": {"$append": ["https://b.com"]},
+            "DATABASE": {"$replace": {"url": "postgres://db"}},
+            "LEGACY": {"$delete": true},
+            "MISSING": {"$delete": true},
+            "SERVICES": {"$merge": [{"port": 8080}]}
+        });
+        assert_eq!(
+            MergeStrategy::ReplaceArrays.merge(&target, &source),
+            json!({
+                "CORS_ORIGINS": ["https://a.com", "https://b.com"],
+                "DATABASE": {"url": "postgres://db"},
+                "SERVICES": [{"id": "api", "port": 8080}, {"id": "worker"}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_directives_are_stripped_from_new_values() {
+        let source = json!({
+            "NEW": {"a": {"$delete": true}, "b": {"$append": [1]}, "c": {"$replace": {"d": {"$delete": true}}}},
+            "KEEP": {"$delete": false}
+        });
+        assert_eq!(
+            MergeStrategy::ReplaceArrays.merge(&json!({"KEEP": 1}), &source),
+            json!({"NEW": {"b": [1], "c": {}}, "KEEP": 1})
+        );
+        // Only single-key objects with a known directive are directives.
+        let data = json!({"X": {"$append": [1], "other": 2}, "Y": {"$ref": "#/a"}});
+        assert_eq!(MergeStrategy::ReplaceArrays.merge(&json!({}), &data), data);
+    }
+
+    #[test]
+    fn test_merge_replace_arrays_into_matches_copying_merge() {
+        let target = json!({"a": {"x": 1, "items": [1, 2]}, "b": "keep", "c": 1});
+        let source = json!({"a": {"y": 2, "items": [3]}, "c": {"nested": true}, "d": null});
+        let mut in_place = target.clone();
+        merge_replace_arrays_into(&mut in_place, &source);
+        assert_eq!(in_place, merge_replace_arrays(&target, &source));
+        assert_eq!(
+            in_place,
+            json!({"a": {"x": 1, "y": 2, "items": [3]}, "b": "keep", "c": {"nested": true}, "d": null})
+        );
+    }
+
+    #[test]
+    fn test_merge_map_into() {
+        let rules = MergeRules::new(MergeStrategy::JsonMergePatch).with_path("ORIGINS", MergeStrategy::UniqueAppend);
+        let mut target: HashMap<String, Value> = [
+            ("ORIGINS".to_string(), json!(["a"])),
+            ("DB".to_string(), json!({"host": "h", "port": 1})),
+            ("OLD".to_string(), json!(true)),
+        ]
+        .into_iter()
+        .collect();
+        let source: HashMap<String, Value> = [
+            ("ORIGINS".to_string(), json!(["a", "b"])),
+            ("DB".to_string(), json!({"port": 2})),
+            ("OLD".to_string(), Value::Null),
+            ("NEW".to_string(), json!({"$replace": [1]})),
+        ]
+        .into_iter()
+        .collect();
+        rules.merge_map_into(&mut target, &source);
+        assert_eq!(target.len(), 3);
+        assert_eq!(target["ORIGINS"], json!(["a", "b"]));
+        assert_eq!(target["DB"], json!({"host": "h", "port": 2}));
+        assert_eq!(target["NEW"], json!([1]));
+    }
+
+    #[test]
+    fn test_merge_with_provenance() {
+        let (merged, provenance) = merge_with_provenance(&[
+            (
+                "default",
+                json!({"DB": {"host": "db", "port": 5432}, "MODE": {"a": 1}, "TAGS": ["x"], "GONE": 1}),
+            ),
+            (
+                "prod",
+                json!({"DB": {"host": "prod-db"}, "MODE": "simple", "a/b": {"~c": true}}),
+            ),
+            ("env", json!({"TAGS": {"$append": ["y"]}, "GONE": {"$delete": true}})),
+        ]);
+        assert_eq!(
+            merged,
+            json!({"DB": {"host": "prod-db", "port": 5432}, "MODE": "simple", "TAGS": ["x", "y"], "a/b": {"~c": true}})
+        );
+        let expected: Provenance<&str> = [
+            ("/DB/host", "prod"),
+            ("/DB/port", "default"),
+            ("/MODE", "prod"),
+            ("/TAGS", "env"),
+            ("/a~1b/~0c", "prod"),
+        ]
+        .into_iter()
+        .map(|(pointer, source)| (pointer.to_string(), source))
+        .collect();
+        assert_eq!(provenance, expected);
+    }
+
+    #[test]
+    fn test_provenance_follows_replaced_objects() {
+        let rules = MergeRules::new(MergeStrategy::JsonMergePatch);
+        let (merged, provenance) = rules.merge_with_provenance(&[
+            (1, json!({"A": "scalar", "B": {"x": 1, "y": 2}})),
+            (2, json!({"A": {"nested": true}, "B": {"$replace": {"x": 3}}})),
+            (3, json!({"B": {"x": null}})),
+        ]);
+        assert_eq!(merged, json!({"A": {"nested": true}, "B": {}}));
+        assert_eq!(provenance.get("/A/nested"), Some(&2));
+        assert_eq!(provenance.get("/A"), None);
+        assert_eq!(provenance.get("/B/x"), None);
+        assert_eq!(provenance.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_many() {
+        let merged = merge_many(&[json!({"a": 1, "b": [1]}), json!({"b": [2]}), json!({"c": {"d": true}})]).unwrap();
+        assert_eq!(merged, json!({"a": 1, "b": [2], "c": {"d": true}}));
+        assert_eq!(merge_many(&[]).unwrap(), json!({}));
+    }
+
+    #[test]
+    fn test_merge_many_rejects_deep_documents() {
+        let mut deep = json!("leaf");
+        for _ in 0..10_000 {
+            deep = Value::Object([("n".to_string(), deep)].into_iter().collect());
+        }
+        let mut layers = vec![json!({"a": 1}), deep];
+        let err = merge_many(&layers).unwrap_err();
+        assert!(err.to_string().contains("maximum merge depth of 64"), "{}", err);
+
+        let rules = MergeRules::default().with_max_depth(2);
+        assert!(rules.merge_many(&[json!({"a": {"b": 1}})]).is_ok());
+        assert!(rules.merge_many(&[json!({"a": {"b": [1]}})]).is_err());
+
+        // serde_json drops values recursively; unwind the chain by hand.
+        let mut deep = layers.pop().unwrap();
+        while let Value::Object(mut map) = deep {
+            deep = map.remove("n").unwrap_or(Value::Null);
+        }
+    }
 }
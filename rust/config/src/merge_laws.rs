@@ -0,0 +1,110 @@
+//! Laws a layer merge should obey, as predicates for property tests.
+//!
+//! Each check takes the merge as a function `(lower, higher) -> merged`, so
+//! it works for a [`MergeStrategy`](crate::merge::MergeStrategy), a
+//! [`MergeRules`](crate::merge::MergeRules) or a hand-written merge alike:
+//!
+//! ```rust
+//! use smooai_config::merge::MergeStrategy;
+//! use smooai_config::merge_laws::reapplying_layer_is_noop;
+//! use serde_json::json;
+//!
+//! let merge = |lower: &_, higher: &_| MergeStrategy::ReplaceArrays.merge(lower, higher);
+//! assert!(reapplying_layer_is_noop(merge, &json!({"A": [1]}), &json!({"A": [2], "B": 1})));
+//! ```
+//!
+//! Not every strategy satisfies every law:
+//!
+//! | law | `ReplaceArrays` | `JsonMergePatch` | `Concat` | `UniqueAppend` |
+//! |---|---|---|---|---|
+//! | [`empty_layer_is_identity`] | yes | yes | yes | yes |
+//! | [`reapplying_layer_is_noop`] | yes | yes | no | yes |
+//! | [`layering_is_associative`] | yes¹ | no | yes¹ | no |
+//! | [`arrays_are_replaced`] | yes | yes | no | no |
+//!
+//! ¹ When the layers agree on shape: a path that holds an object in one
+//! layer holds an object (or nothing) in the others, and likewise for arrays.
+//! Merging an object over a scalar and back is inherently order-dependent.
+//!
+//! The crate's own `tests/merge_laws.rs` checks the table above with
+//! [proptest](https://docs.rs/proptest) strategies that generate
+//! shape-consistent layers. Copy its `layer` strategy to check a custom
+//! merge the same way.
+
+use serde_json::Value;
+
+/// `merge(base, {}) == base`: an empty layer changes nothing.
+pub fn empty_layer_is_identity(merge: impl Fn(&Value, &Value) -> Value, base: &Value) -> bool {
+    merge(base, &Value::Object(Default::default())) == *base
+}
+
+/// `merge(merge(base, layer), layer) == merge(base, layer)`: applying the
+/// same layer twice, e.g. on a reload, is the same as applying it once.
+pub fn reapplying_layer_is_noop(merge: impl Fn(&Value, &Value) -> Value, base: &Value, layer: &Value) -> bool {
+    let once = merge(base, layer);
+    merge(&once, layer) == once
+}
+
+/// `merge(merge(a, b), c) == merge(a, merge(b, c))`: layers can be
+/// pre-merged in groups (e.g. files first) without changing the result.
+pub fn layering_is_associative(merge: impl Fn(&Value, &Value) -> Value, a: &Value, b: &Value, c: &Value) -> bool {
+    merge(&merge(a, b), c) == merge(a, &merge(b, c))
+}
+
+/// Every array in `layer` appears unchanged at the same path of
+/// `merge(base, layer)`: higher layers replace arrays instead of combining them.
+pub fn arrays_are_replaced(merge: impl Fn(&Value, &Value) -> Value, base: &Value, layer: &Value) -> bool {
+    arrays_kept(&merge(base, layer), layer)
+}
+
+fn arrays_kept(merged: &Value, layer: &Value) -> bool {
+    match layer {
+        Value::Array(_) => merged == layer,
+        Value::Object(entries) => entries.iter().all(|(key, value)| match value {
+            Value::Array(_) | Value::Object(_) => merged.get(key).is_some_and(|m| arrays_kept(m, value)),
+            _ => true,
+        }),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge::MergeStrategy;
+    use serde_json::json;
+
+    fn strategy(strategy: MergeStrategy) -> impl Fn(&Value, &Value) -> Value {
+        move |lower, higher| strategy.merge(lower, higher)
+    }
+
+    #[test]
+    fn test_laws_hold_for_replace_arrays() {
+        let merge = strategy(MergeStrategy::ReplaceArrays);
+        let (a, b, c) = (
+            json!({"HOSTS": ["a"], "DB": {"PORT": 1}}),
+            json!({"HOSTS": ["b"], "DB": {"USER": "x"}}),
+            json!({"DB": {"PORT": 2}}),
+        );
+        assert!(empty_layer_is_identity(&merge, &a));
+        assert!(reapplying_layer_is_noop(&merge, &a, &b));
+        assert!(layering_is_associative(&merge, &a, &b, &c));
+        assert!(arrays_are_replaced(&merge, &a, &b));
+    }
+
+    #[test]
+    fn test_laws_catch_violations() {
+        let concat = strategy(MergeStrategy::Concat);
+        let (base, layer) = (json!({"HOSTS": ["a"]}), json!({"HOSTS": ["b"]}));
+        assert!(!reapplying_layer_is_noop(&concat, &base, &layer));
+        assert!(!arrays_are_replaced(&concat, &base, &layer));
+
+        let patch = strategy(MergeStrategy::JsonMergePatch);
+        assert!(!layering_is_associative(
+            &patch,
+            &json!({"A": 1}),
+            &json!({"A": 2}),
+            &json!({"A": null})
+        ));
+    }
+}
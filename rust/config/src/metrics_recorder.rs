@@ -0,0 +1,84 @@
+//! Cache and fetch metrics.
+//!
+//! [`ConfigManager::with_metrics`](crate::ConfigManager::with_metrics) takes
+//! any [`MetricsRecorder`]. Implement the trait to forward events into your
+//! own telemetry, or enable the `metrics` feature and use
+//! [`MetricsCrateRecorder`] to emit through the `metrics` crate facade:
+//!
+//! | metric | kind | labels |
+//! |---|---|---|
+//! | `smooai_config_cache_hits_total` | counter | `tier` |
+//! | `smooai_config_cache_misses_total` | counter | `tier` |
+//! | `smooai_config_fetch_duration_seconds` | histogram | `source` |
+//! | `smooai_config_fetch_failures_total` | counter | `source` |
+//!
+//! Fetch events cover the remote API (`source="remote"`) and every
+//! registered source, on first load and on each refresh.
+//!
+//! With the `otel` feature, [`OtelRecorder`](crate::otel::OtelRecorder)
+//! records the same events as OpenTelemetry metrics and fetch spans.
+
+use std::time::Duration;
+
+use crate::schema::ConfigTier;
+
+/// Receives metric events from a [`ConfigManager`](crate::ConfigManager).
+///
+/// Every method defaults to a no-op. Calls happen on the reading thread
+/// while the manager holds its lock, so keep them cheap.
+pub trait MetricsRecorder: Send + Sync {
+    /// A getter was answered from the per-key cache.
+    fn cache_hit(&self, _tier: ConfigTier) {}
+
+    /// A getter missed the per-key cache and read the merged config.
+    fn cache_miss(&self, _tier: ConfigTier) {}
+
+    /// A source (or the remote API, as `"remote"`) finished loading.
+    fn fetch(&self, _source: &str, _duration: Duration, _success: bool) {}
+
+    /// [`fetch`](Self::fetch) with the environment and org it was for. The
+    /// manager calls this; the default forwards to `fetch`.
+    fn fetch_in(&self, source: &str, duration: Duration, success: bool, _context: &FetchContext<'_>) {
+        self.fetch(source, duration, success);
+    }
+}
+
+/// What a fetch was for, as resolved by the manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchContext<'a> {
+    pub environment: &'a str,
+    pub org_id: Option<&'a str>,
+}
+
+/// Label value for a tier, e.g. `feature_flag`.
+pub fn tier_label(tier: ConfigTier) -> &'static str {
+    match tier {
+        ConfigTier::Public => "public",
+        ConfigTier::Secret => "secret",
+        ConfigTier::FeatureFlag => "feature_flag",
+    }
+}
+
+/// Emits events through the global `metrics` recorder (feature `metrics`).
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsCrateRecorder;
+
+#[cfg(feature = "metrics")]
+impl MetricsRecorder for MetricsCrateRecorder {
+    fn cache_hit(&self, tier: ConfigTier) {
+        metrics::counter!("smooai_config_cache_hits_total", "tier" => tier_label(tier)).increment(1);
+    }
+
+    fn cache_miss(&self, tier: ConfigTier) {
+        metrics::counter!("smooai_config_cache_misses_total", "tier" => tier_label(tier)).increment(1);
+    }
+
+    fn fetch(&self, source: &str, duration: Duration, success: bool) {
+        metrics::histogram!("smooai_config_fetch_duration_seconds", "source" => source.to_string())
+            .record(duration.as_secs_f64());
+        if !success {
+            metrics::counter!("smooai_config_fetch_failures_total", "source" => source.to_string()).increment(1);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! OpenTelemetry metrics and spans (feature `otel`).
+//!
+//! [`OtelRecorder`] is a [`MetricsRecorder`] that records through the
+//! OpenTelemetry API:
+//!
+//! | instrument | kind | attributes |
+//! |---|---|---|
+//! | `smooai_config.fetch.duration` | histogram (s) | `source`, `success` |
+//! | `smooai_config.fetch.errors` | counter | `source` |
+//! | `smooai_config.cache.requests` | counter | `tier`, `result` (`hit`/`miss`) |
+//! | `smooai_config.cache.hit_ratio` | gauge | — |
+//!
+//! Each fetch is also a `smooai_config.fetch` client span, parented to the
+//! current context and annotated with `smooai.config.environment`,
+//! `smooai.config.org_id` and `smooai.config.source`; failures set an error
+//! status. Fetch metrics carry the environment too.
+//!
+//! ```rust,ignore
+//! let manager = ConfigManager::new().with_metrics(OtelRecorder::new());
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use opentelemetry::global::{self, BoxedTracer};
+use opentelemetry::metrics::{Counter, Histogram, Meter, MeterProvider, ObservableGauge};
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer, TracerProvider};
+use opentelemetry::KeyValue;
+
+use crate::metrics_recorder::{tier_label, FetchContext, MetricsRecorder};
+use crate::schema::ConfigTier;
+
+/// Instrumentation scope name for the meter and tracer.
+pub const INSTRUMENTATION_NAME: &str = "smooai-config";
+
+/// Records manager events as OpenTelemetry metrics and spans. See [`crate::otel`].
+pub struct OtelRecorder {
+    tracer: BoxedTracer,
+    fetch_duration: Histogram<f64>,
+    fetch_errors: Counter<u64>,
+    cache_requests: Counter<u64>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+    _hit_ratio: ObservableGauge<f64>,
+}
+
+impl OtelRecorder {
+    /// Record through the global meter and tracer providers.
+    pub fn new() -> Self {
+        Self::build(
+            global::meter(INSTRUMENTATION_NAME),
+            global::tracer(INSTRUMENTATION_NAME),
+        )
+    }
+
+    /// Record through specific providers instead of the global ones.
+    pub fn with_providers<T>(meter_provider: &impl MeterProvider, tracer_provider: &T) -> Self
+    where
+        T: TracerProvider,
+        T::Tracer: Send + Sync + 'static,
+        <T::Tracer as Tracer>::Span: Send + Sync + 'static,
+    {
+        Self::build(
+            meter_provider.meter(INSTRUMENTATION_NAME),
+            BoxedTracer::new(Box::new(tracer_provider.tracer(INSTRUMENTATION_NAME))),
+        )
+    }
+
+    fn build(meter: Meter, tracer: BoxedTracer) -> Self {
+        let hits = Arc::new(AtomicU64::new(0));
+        let misses = Arc::new(AtomicU64::new(0));
+        let (h, m) = (Arc::clone(&hits), Arc::clone(&misses));
+        let hit_ratio = meter
+            .f64_observable_gauge("smooai_config.cache.hit_ratio")
+            .with_description("Share of config reads answered from the per-key cache")
+            .with_callback(move |observer| {
+                let (hits, misses) = (h.load(Ordering::Relaxed), m.load(Ordering::Relaxed));
+                if hits + misses > 0 {
+                    observer.observe(hits as f64 / (hits + misses) as f64, &[]);
+                }
+            })
+            .build();
+        OtelRecorder {
+            tracer,
+            fetch_duration: meter
+                .f64_histogram("smooai_config.fetch.duration")
+                .with_unit("s")
+                .with_description("Time to load a config source or the remote API")
+                .build(),
+            fetch_errors: meter
+                .u64_counter("smooai_config.fetch.errors")
+                .with_description("Failed config source or remote API loads")
+                .build(),
+            cache_requests: meter
+                .u64_counter("smooai_config.cache.requests")
+                .with_description("Config reads by tier and cache result")
+                .build(),
+            hits,
+            misses,
+            _hit_ratio: hit_ratio,
+        }
+    }
+
+    fn cache_request(&self, tier: ConfigTier, result: &'static str) {
+        self.cache_requests.add(
+            1,
+            &[KeyValue::new("tier", tier_label(tier)), KeyValue::new("result", result)],
+        );
+    }
+}
+
+impl Default for OtelRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsRecorder for OtelRecorder {
+    fn cache_hit(&self, tier: ConfigTier) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.cache_request(tier, "hit");
+    }
+
+    fn cache_miss(&self, tier: ConfigTier) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.cache_request(tier, "miss");
+    }
+
+    fn fetch(&self, source: &str, duration: Duration, success: bool) {
+        self.fetch_in(
+            source,
+            duration,
+            success,
+            &FetchContext {
+                environment: "",
+                org_id: None,
+            },
+        );
+    }
+
+    fn fetch_in(&self, source: &str, duration: Duration, success: bool, context: &FetchContext<'_>) {
+        let mut attributes = vec![
+            KeyValue::new("source", source.to_string()),
+            KeyValue::new("success", success),
+        ];
+        if !context.environment.is_empty() {
+            attributes.push(KeyValue::new("environment", context.environment.to_string()));
+        }
+        self.fetch_duration.record(duration.as_secs_f64(), &attributes);
+        if !success {
+            self.fetch_errors.add(1, &[KeyValue::new("source", source.to_string())]);
+        }
+
+        // The fetch already happened; record its span after the fact.
+        let mut span_attributes = vec![KeyValue::new("smooai.config.source", source.to_string())];
+        if !context.environment.is_empty() {
+            span_attributes.push(KeyValue::new(
+                "smooai.config.environment",
+                context.environment.to_string(),
+            ));
+        }
+        if let Some(org_id) = context.org_id {
+            span_attributes.push(KeyValue::new("smooai.config.org_id", org_id.to_string()));
+        }
+        let mut span = self
+            .tracer
+            .span_builder("smooai_config.fetch")
+            .with_kind(SpanKind::Client)
+            .with_start_time(SystemTime::now() - duration)
+            .with_attributes(span_attributes)
+            .start(&self.tracer);
+        if !success {
+            span.set_status(Status::error(format!("failed to load {}", source)));
+        }
+        span.end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_manager::ConfigManager;
+    use crate::source::ConfigSource;
+    use crate::utils::SmooaiConfigError;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+    use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use serde_json::Value as JsonValue;
+    use std::collections::HashMap;
+
+    struct FailingSource;
+
+    impl ConfigSource for FailingSource {
+        fn name(&self) -> String {
+            "vault".to_string()
+        }
+
+        fn load(&self, _env: &HashMap<String, String>) -> Result<HashMap<String, JsonValue>, SmooaiConfigError> {
+            Err(SmooaiConfigError::new("sealed"))
+        }
+    }
+
+    #[test]
+    fn test_records_metrics_and_spans() {
+        let metric_exporter = InMemoryMetricExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(metric_exporter.clone()).build())
+            .build();
+        let span_exporter = InMemorySpanExporter::default();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_simple_exporter(span_exporter.clone())
+            .build();
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".smooai-config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(config_dir.join("default.json"), r#"{"A":"file"}"#).unwrap();
+        let manager = ConfigManager::new()
+            .with_env(HashMap::from([
+                (
+                    "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                    config_dir.to_string_lossy().to_string(),
+                ),
+                ("SMOOAI_CONFIG_ENV".to_string(), "production".to_string()),
+                ("SMOOAI_CONFIG_ORG_ID".to_string(), "org-123".to_string()),
+            ]))
+            .with_source(FailingSource)
+            .with_metrics(OtelRecorder::with_providers(&meter_provider, &tracer_provider));
+
+        manager.get_public_config("A").unwrap();
+        manager.get_public_config("A").unwrap();
+        manager.get_public_config("A").unwrap();
+        manager.get_public_config("A").unwrap();
+
+        let spans = span_exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.name, "smooai_config.fetch");
+        assert_eq!(span.span_kind, SpanKind::Client);
+        assert!(matches!(span.status, Status::Error { .. }));
+        let attribute = |key: &str| {
+            span.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.clone())
+        };
+        assert_eq!(attribute("smooai.config.environment"), Some(Value::from("production")));
+        assert_eq!(attribute("smooai.config.org_id"), Some(Value::from("org-123")));
+        assert_eq!(attribute("smooai.config.source"), Some(Value::from("vault")));
+
+        meter_provider.force_flush().unwrap();
+        let exported = metric_exporter.get_finished_metrics().unwrap();
+        let metrics: HashMap<String, &AggregatedMetrics> = exported
+            .iter()
+            .flat_map(|rm| rm.scope_metrics())
+            .flat_map(|sm| sm.metrics())
+            .map(|m| (m.name().to_string(), m.data()))
+            .collect();
+
+        let Some(AggregatedMetrics::F64(MetricData::Gauge(ratio))) = metrics.get("smooai_config.cache.hit_ratio")
+        else {
+            panic!("missing hit ratio gauge");
+        };
+        assert_eq!(ratio.data_points().next().unwrap().value(), 0.75);
+
+        let Some(AggregatedMetrics::U64(MetricData::Sum(errors))) = metrics.get("smooai_config.fetch.errors") else {
+            panic!("missing fetch errors counter");
+        };
+        assert_eq!(errors.data_points().map(|p| p.value()).sum::<u64>(), 1);
+
+        let Some(AggregatedMetrics::U64(MetricData::Sum(requests))) = metrics.get("smooai_config.cache.requests")
+        else {
+            panic!("missing cache requests counter");
+        };
+        assert_eq!(requests.data_points().map(|p| p.value()).sum::<u64>(), 4);
+
+        let Some(AggregatedMetrics::F64(MetricData::Histogram(duration))) = metrics.get("smooai_config.fetch.duration")
+        else {
+            panic!("missing fetch duration histogram");
+        };
+        let point = duration.data_points().next().unwrap();
+        assert_eq!(point.count(), 1);
+        assert!(point
+            .attributes()
+            .any(|kv| kv.key.as_str() == "environment" && kv.value == Value::from("production")));
+    }
+}
@@ -0,0 +1,147 @@
+//! Limiting which keys a manager will hand out.
+//!
+//! A [`ConfigManager`](crate::ConfigManager) reference given to a plugin or
+//! third-party module can otherwise read every key in every tier. Built with
+//! [`with_access_policy`](crate::ConfigManager::with_access_policy), it
+//! answers reads the policy doesn't permit with
+//! [`SmooaiConfigErrorKind::AccessDenied`](crate::SmooaiConfigErrorKind::AccessDenied),
+//! and leaves those keys out of `get_all`, `export` and namespace listings:
+//!
+//! ```rust
+//! use smooai_config::policy::AccessPolicy;
+//! use smooai_config::schema::ConfigTier;
+//!
+//! let policy = AccessPolicy::deny_by_default()
+//!     .allow(ConfigTier::Public, "*")
+//!     .allow(ConfigTier::Secret, "STRIPE_*")
+//!     .deny(ConfigTier::Secret, "STRIPE_WEBHOOK_SECRET");
+//!
+//! assert!(policy.permits(ConfigTier::Public, "API_URL"));
+//! assert!(policy.permits(ConfigTier::Secret, "STRIPE_API_KEY"));
+//! assert!(!policy.permits(ConfigTier::Secret, "STRIPE_WEBHOOK_SECRET"));
+//! assert!(!policy.permits(ConfigTier::Secret, "DB_PASSWORD"));
+//! ```
+//!
+//! Patterns are a key as passed to the getters, or a prefix ending in `*`.
+//! The most specific matching rule decides: an exact key beats any prefix and
+//! a longer prefix beats a shorter one; between equally specific rules, deny
+//! wins. Keys no rule matches get the policy's default.
+//!
+//! Keys the schema doesn't declare can be read through any tier, so they're
+//! readable whenever some tier's rules permit them.
+
+use crate::schema::ConfigTier;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Rule {
+    tier: ConfigTier,
+    pattern: String,
+    allow: bool,
+}
+
+impl Rule {
+    /// How specifically the rule matches `key`, if it does: exact matches
+    /// rank above every prefix.
+    fn specificity(&self, tier: ConfigTier, key: &str) -> Option<usize> {
+        if self.tier != tier {
+            return None;
+        }
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix).then_some(prefix.len()),
+            None => (key == self.pattern).then_some(usize::MAX),
+        }
+    }
+}
+
+/// Which keys may be read, per tier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccessPolicy {
+    default_allow: bool,
+    rules: Vec<Rule>,
+}
+
+impl AccessPolicy {
+    /// Everything is readable unless a [`deny`](Self::deny) rule says otherwise.
+    pub fn allow_by_default() -> Self {
+        Self {
+            default_allow: true,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Nothing is readable unless an [`allow`](Self::allow) rule says otherwise.
+    pub fn deny_by_default() -> Self {
+        Self {
+            default_allow: false,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Permit reading `pattern` (a key, or a prefix ending in `*`) from `tier`.
+    pub fn allow(self, tier: ConfigTier, pattern: &str) -> Self {
+        self.rule(tier, pattern, true)
+    }
+
+    /// Refuse reading `pattern` (a key, or a prefix ending in `*`) from `tier`.
+    pub fn deny(self, tier: ConfigTier, pattern: &str) -> Self {
+        self.rule(tier, pattern, false)
+    }
+
+    fn rule(mut self, tier: ConfigTier, pattern: &str, allow: bool) -> Self {
+        self.rules.push(Rule {
+            tier,
+            pattern: pattern.to_string(),
+            allow,
+        });
+        self
+    }
+
+    /// Whether `key` may be read from `tier`.
+    pub fn permits(&self, tier: ConfigTier, key: &str) -> bool {
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.specificity(tier, key).map(|rank| (rank, !rule.allow)))
+            .max()
+            .map_or(self.default_allow, |(_, deny)| !deny)
+    }
+
+    /// Whether `key` may be read from any tier, for keys whose tier isn't known.
+    pub fn permits_any(&self, key: &str) -> bool {
+        [ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag]
+            .into_iter()
+            .any(|tier| self.permits(tier, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_specific_rule_wins() {
+        let policy = AccessPolicy::allow_by_default()
+            .deny(ConfigTier::Secret, "*")
+            .allow(ConfigTier::Secret, "PLUGIN_*")
+            .deny(ConfigTier::Secret, "PLUGIN_ADMIN_*")
+            .allow(ConfigTier::Secret, "PLUGIN_ADMIN_NAME");
+
+        assert!(policy.permits(ConfigTier::Public, "ANYTHING"));
+        assert!(!policy.permits(ConfigTier::Secret, "DB_PASSWORD"));
+        assert!(policy.permits(ConfigTier::Secret, "PLUGIN_TOKEN"));
+        assert!(!policy.permits(ConfigTier::Secret, "PLUGIN_ADMIN_TOKEN"));
+        assert!(policy.permits(ConfigTier::Secret, "PLUGIN_ADMIN_NAME"));
+    }
+
+    #[test]
+    fn test_deny_wins_ties_and_default_applies() {
+        let policy = AccessPolicy::deny_by_default()
+            .allow(ConfigTier::FeatureFlag, "checkout/*")
+            .deny(ConfigTier::FeatureFlag, "checkout/*");
+        assert!(!policy.permits(ConfigTier::FeatureFlag, "checkout/new"));
+        assert!(!policy.permits(ConfigTier::Public, "API_URL"));
+        assert!(!policy.permits_any("API_URL"));
+        assert!(AccessPolicy::deny_by_default()
+            .allow(ConfigTier::Secret, "API_URL")
+            .permits_any("API_URL"));
+    }
+}
@@ -0,0 +1,246 @@
+//! Prometheus collector for the config subsystem (feature `prometheus`).
+//!
+//! [`PrometheusCollector`] reads [`ConfigManager::status`] on every scrape,
+//! so nothing has to be wired into the manager itself:
+//!
+//! ```rust,ignore
+//! let manager = Arc::new(ConfigManager::new());
+//! PrometheusCollector::register(&manager, prometheus::default_registry())?;
+//! ```
+//!
+//! | metric | kind | labels |
+//! |---|---|---|
+//! | `smooai_config_source_up` | gauge (0/1) | `source` |
+//! | `smooai_config_source_last_success_timestamp_seconds` | gauge | `source` |
+//! | `smooai_config_source_failures_total` | counter | `source` |
+//! | `smooai_config_source_keys` | gauge | `source` |
+//! | `smooai_config_cached_keys` | gauge | `tier` |
+//! | `smooai_config_merged_keys` | gauge | — |
+//! | `smooai_config_cache_hits_total` | counter | — |
+//! | `smooai_config_cache_misses_total` | counter | — |
+//!
+//! Sources are the config files (`files`), each registered source, the
+//! remote API (`remote`) and the last-known-good snapshot, as in
+//! [`ConfigStatus`](crate::ConfigStatus). The collector holds a weak
+//! reference and reports nothing once the manager is dropped.
+
+use std::sync::{Arc, Weak};
+
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{GaugeVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry};
+
+use crate::config_manager::ConfigManager;
+use crate::status::SourceState;
+
+/// Scrapes a [`ConfigManager`]'s status into Prometheus metrics. See [`crate::prometheus_collector`].
+pub struct PrometheusCollector {
+    manager: Weak<ConfigManager>,
+    source_up: IntGaugeVec,
+    last_success: GaugeVec,
+    failures: IntCounterVec,
+    source_keys: IntGaugeVec,
+    cached_keys: IntGaugeVec,
+    merged_keys: IntGauge,
+    cache_hits: IntCounter,
+    cache_misses: IntCounter,
+}
+
+impl PrometheusCollector {
+    pub fn new(manager: &Arc<ConfigManager>) -> Self {
+        let opts = |name: &str, help: &str| Opts::new(format!("smooai_config_{}", name), help);
+        // Names and label sets are static, so construction can't fail.
+        PrometheusCollector {
+            manager: Arc::downgrade(manager),
+            source_up: IntGaugeVec::new(
+                opts("source_up", "Whether the source's last load succeeded"),
+                &["source"],
+            )
+            .expect("valid metric"),
+            last_success: GaugeVec::new(
+                opts(
+                    "source_last_success_timestamp_seconds",
+                    "Unix time of the source's last successful load",
+                ),
+                &["source"],
+            )
+            .expect("valid metric"),
+            failures: IntCounterVec::new(opts("source_failures_total", "Failed loads per source"), &["source"])
+                .expect("valid metric"),
+            source_keys: IntGaugeVec::new(
+                opts("source_keys", "Keys supplied by the source's last successful load"),
+                &["source"],
+            )
+            .expect("valid metric"),
+            cached_keys: IntGaugeVec::new(opts("cached_keys", "Entries in each tier's per-key cache"), &["tier"])
+                .expect("valid metric"),
+            merged_keys: IntGauge::with_opts(opts("merged_keys", "Keys in the merged config")).expect("valid metric"),
+            cache_hits: IntCounter::with_opts(opts("cache_hits_total", "Reads answered from the per-key cache"))
+                .expect("valid metric"),
+            cache_misses: IntCounter::with_opts(opts("cache_misses_total", "Reads that missed the per-key cache"))
+                .expect("valid metric"),
+        }
+    }
+
+    /// Register a collector for `manager` with `registry`.
+    pub fn register(manager: &Arc<ConfigManager>, registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(Self::new(manager)))
+    }
+}
+
+/// Advance `counter` to `total`; the manager keeps absolute counts.
+fn catch_up(counter: &IntCounter, total: u64) {
+    let current = counter.get();
+    if total > current {
+        counter.inc_by(total - current);
+    }
+}
+
+impl Collector for PrometheusCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        let mut descs = Vec::new();
+        descs.extend(self.source_up.desc());
+        descs.extend(self.last_success.desc());
+        descs.extend(self.failures.desc());
+        descs.extend(self.source_keys.desc());
+        descs.extend(self.cached_keys.desc());
+        descs.extend(self.merged_keys.desc());
+        descs.extend(self.cache_hits.desc());
+        descs.extend(self.cache_misses.desc());
+        descs
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let Some(manager) = self.manager.upgrade() else {
+            return Vec::new();
+        };
+        let status = manager.status();
+
+        for source in &status.sources {
+            let labels = [source.name.as_str()];
+            self.source_up
+                .with_label_values(&labels)
+                .set(i64::from(source.state == SourceState::Loaded));
+            if let Some(last_success) = source.last_success {
+                self.last_success.with_label_values(&labels).set(last_success as f64);
+            }
+            catch_up(&self.failures.with_label_values(&labels), source.failures);
+            self.source_keys.with_label_values(&labels).set(source.key_count as i64);
+        }
+        for (tier, entries) in [
+            ("public", status.cache.public_entries),
+            ("secret", status.cache.secret_entries),
+            ("feature_flag", status.cache.feature_flag_entries),
+        ] {
+            self.cached_keys.with_label_values(&[tier]).set(entries as i64);
+        }
+        self.merged_keys.set(status.merged_keys as i64);
+        catch_up(&self.cache_hits, status.cache.hits);
+        catch_up(&self.cache_misses, status.cache.misses);
+
+        let mut families = Vec::new();
+        families.extend(self.source_up.collect());
+        families.extend(self.last_success.collect());
+        families.extend(self.failures.collect());
+        families.extend(self.source_keys.collect());
+        families.extend(self.cached_keys.collect());
+        families.extend(self.merged_keys.collect());
+        families.extend(self.cache_hits.collect());
+        families.extend(self.cache_misses.collect());
+        families
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::ConfigSource;
+    use crate::utils::SmooaiConfigError;
+    use prometheus::{Encoder, TextEncoder};
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    struct FailingSource;
+
+    impl ConfigSource for FailingSource {
+        fn name(&self) -> String {
+            "vault".to_string()
+        }
+
+        fn load(&self, _env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+            Err(SmooaiConfigError::new("sealed"))
+        }
+    }
+
+    fn scrape(registry: &Registry) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&registry.gather(), &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_exports_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".smooai-config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(config_dir.join("default.json"), r#"{"A":"file","B":2}"#).unwrap();
+        let manager = Arc::new(
+            ConfigManager::new()
+                .with_env(HashMap::from([
+                    (
+                        "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                        config_dir.to_string_lossy().to_string(),
+                    ),
+                    ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+                ]))
+                .with_source(FailingSource),
+        );
+        let registry = Registry::new();
+        PrometheusCollector::register(&manager, &registry).unwrap();
+
+        manager.get_public_config("A").unwrap();
+        manager.get_public_config("A").unwrap();
+        manager.get_feature_flag("B").unwrap();
+        let text = scrape(&registry);
+        assert!(
+            text.contains("smooai_config_source_up{source=\"files\"} 1\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("smooai_config_source_up{source=\"vault\"} 0\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("smooai_config_source_failures_total{source=\"vault\"} 1\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("smooai_config_source_last_success_timestamp_seconds{source=\"files\"} "));
+        assert!(!text.contains("smooai_config_source_last_success_timestamp_seconds{source=\"vault\"}"));
+        assert!(
+            text.contains("smooai_config_cached_keys{tier=\"public\"} 1\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("smooai_config_cached_keys{tier=\"feature_flag\"} 1\n"));
+        assert!(text.contains("smooai_config_cache_hits_total 1\n"), "{}", text);
+        assert!(text.contains("smooai_config_cache_misses_total 2\n"));
+        assert!(text.contains("# TYPE smooai_config_source_failures_total counter\n"));
+
+        // Counters follow the manager across reloads and scrapes.
+        manager.invalidate();
+        manager.get_public_config("A").unwrap();
+        let text = scrape(&registry);
+        assert!(
+            text.contains("smooai_config_source_failures_total{source=\"vault\"} 2\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("smooai_config_cache_misses_total 3\n"), "{}", text);
+
+        drop(manager);
+        assert_eq!(scrape(&registry), "");
+    }
+}
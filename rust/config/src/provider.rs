@@ -0,0 +1,218 @@
+//! The [`ConfigProvider`] trait, for code that reads config without caring
+//! where it comes from.
+//!
+//! [`ConfigManager`] and [`LocalConfigManager`] implement it, and so does
+//! [`StaticConfigProvider`], a fixed set of values for unit tests:
+//!
+//! ```rust
+//! use smooai_config::{ConfigProvider, StaticConfigProvider};
+//! use serde_json::json;
+//!
+//! fn checkout_url(config: &dyn ConfigProvider) -> String {
+//!     config.get_public_config("CHECKOUT_URL").unwrap().unwrap().as_str().unwrap().to_string()
+//! }
+//!
+//! let config = StaticConfigProvider::new().with_public("CHECKOUT_URL", json!("https://pay.example.com"));
+//! assert_eq!(checkout_url(&config), "https://pay.example.com");
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::config_manager::ConfigManager;
+use crate::flags;
+#[allow(deprecated)]
+use crate::local::LocalConfigManager;
+use crate::secret::SecretValue;
+use crate::utils::SmooaiConfigError;
+
+/// Read access to the three config tiers.
+pub trait ConfigProvider: Send + Sync {
+    /// Retrieve a public config value.
+    fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError>;
+
+    /// Retrieve a secret config value.
+    fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError>;
+
+    /// Retrieve a feature flag value.
+    fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError>;
+
+    /// Whether the feature flag `key` is on; `false` when it's unset, not
+    /// boolean-like, or can't be read. See [`ConfigManager::is_enabled`].
+    fn is_enabled(&self, key: &str) -> bool {
+        match self.get_feature_flag(key) {
+            Ok(Some(value)) => flags::flag_enabled(&value).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+impl ConfigProvider for ConfigManager {
+    fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        ConfigManager::get_public_config(self, key)
+    }
+
+    fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        ConfigManager::get_secret_config(self, key)
+    }
+
+    fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        ConfigManager::get_feature_flag(self, key)
+    }
+}
+
+#[allow(deprecated)]
+impl ConfigProvider for LocalConfigManager {
+    fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        LocalConfigManager::get_public_config(self, key)
+    }
+
+    fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        LocalConfigManager::get_secret_config(self, key)
+    }
+
+    fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        LocalConfigManager::get_feature_flag(self, key)
+    }
+}
+
+impl<P: ConfigProvider + ?Sized> ConfigProvider for Arc<P> {
+    fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        (**self).get_public_config(key)
+    }
+
+    fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        (**self).get_secret_config(key)
+    }
+
+    fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        (**self).get_feature_flag(key)
+    }
+
+    fn is_enabled(&self, key: &str) -> bool {
+        (**self).is_enabled(key)
+    }
+}
+
+/// A [`ConfigProvider`] over fixed values, for tests and examples.
+///
+/// Keys are matched exactly; anything not set reads as `None`.
+#[derive(Debug, Clone, Default)]
+pub struct StaticConfigProvider {
+    public: HashMap<String, Value>,
+    secret: HashMap<String, Value>,
+    feature_flags: HashMap<String, Value>,
+}
+
+impl StaticConfigProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `values` from every tier, like a merged config whose keys
+    /// aren't split by schema.
+    pub fn from_values(values: HashMap<String, Value>) -> Self {
+        StaticConfigProvider {
+            public: values.clone(),
+            secret: values.clone(),
+            feature_flags: values,
+        }
+    }
+
+    /// Set a public config value.
+    pub fn with_public(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.public.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Set a secret config value.
+    pub fn with_secret(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.secret.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Set a feature flag value.
+    pub fn with_feature_flag(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.feature_flags.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+impl From<HashMap<String, Value>> for StaticConfigProvider {
+    fn from(values: HashMap<String, Value>) -> Self {
+        Self::from_values(values)
+    }
+}
+
+impl ConfigProvider for StaticConfigProvider {
+    fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        Ok(self.public.get(key).cloned())
+    }
+
+    fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        Ok(self.secret.get(key).cloned().map(SecretValue::new))
+    }
+
+    fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        Ok(self.feature_flags.get(key).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ConfigFixture;
+    use serde_json::json;
+
+    fn describe(config: &dyn ConfigProvider) -> (Option<Value>, Option<String>, bool) {
+        (
+            config.get_public_config("API_URL").unwrap(),
+            config
+                .get_secret_config("DB_PASSWORD")
+                .unwrap()
+                .and_then(|secret| secret.expose_str().map(str::to_string)),
+            config.is_enabled("NEW_CHECKOUT"),
+        )
+    }
+
+    #[test]
+    fn test_static_provider() {
+        let config = StaticConfigProvider::new()
+            .with_public("API_URL", "https://api.example.com")
+            .with_secret("DB_PASSWORD", "hunter2")
+            .with_feature_flag("NEW_CHECKOUT", true);
+        assert_eq!(
+            describe(&config),
+            (
+                Some(json!("https://api.example.com")),
+                Some("hunter2".to_string()),
+                true
+            )
+        );
+        assert_eq!(config.get_public_config("DB_PASSWORD").unwrap(), None);
+        assert!(!config.is_enabled("MISSING"));
+
+        let shared: Arc<dyn ConfigProvider> = Arc::new(StaticConfigProvider::from_values(HashMap::from([(
+            "NEW_CHECKOUT".to_string(),
+            json!("true"),
+        )])));
+        assert!(shared.is_enabled("NEW_CHECKOUT"));
+    }
+
+    #[test]
+    fn test_managers_implement_provider() {
+        let fixture = ConfigFixture::new().with_file(
+            "default.json",
+            json!({"API_URL": "https://api.example.com", "DB_PASSWORD": "hunter2", "NEW_CHECKOUT": true}),
+        );
+        let expected = (
+            Some(json!("https://api.example.com")),
+            Some("hunter2".to_string()),
+            true,
+        );
+        assert_eq!(describe(&fixture.manager()), expected);
+        assert_eq!(describe(&fixture.local_manager()), expected);
+    }
+}
@@ -0,0 +1,336 @@
+//! Redis source (feature `redis`).
+//!
+//! Reads config from either a single hash or every string key under a
+//! prefix. Values are parsed as JSON, so `3`, `true` and `{"host": "db"}`
+//! arrive typed; anything that isn't valid JSON is kept as a plain string.
+//!
+//! ```text
+//! HSET myapp:production API_URL '"https://api"' MAX_RETRIES 3   -> hash("myapp:production")
+//! SET  myapp:production:API_URL '"https://api"'                 -> prefix("myapp:production:")
+//! ```
+//!
+//! Publishers announce changes on an invalidation channel; [`RedisSource::watch`]
+//! subscribes and calls back on every message:
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//! use smooai_config::{ConfigManager, RedisSource};
+//!
+//! let redis = RedisSource::hash("myapp:production").with_invalidation_channel("config:invalidate");
+//! let manager = Arc::new(ConfigManager::new().with_source(redis.clone()));
+//! let watched = Arc::clone(&manager);
+//! let _watch = redis.watch(move || watched.invalidate());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use redis::Commands;
+use serde_json::Value;
+
+use crate::redact;
+use crate::source::{ConfigSource, SourceWatch};
+use crate::utils::SmooaiConfigError;
+
+const DEFAULT_URL: &str = "redis://127.0.0.1:6379";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often a blocked subscriber checks whether it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Pause before reconnecting a failed subscription.
+const WATCH_RETRY_DELAY: Duration = Duration::from_secs(5);
+/// Keys fetched per `MGET` when reading a prefix.
+const MGET_BATCH: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Layout {
+    Hash(String),
+    Prefix(String),
+}
+
+/// Config source backed by a Redis hash or key prefix.
+#[derive(Clone)]
+pub struct RedisSource {
+    layout: Layout,
+    url: Option<String>,
+    channel: Option<String>,
+}
+
+impl std::fmt::Debug for RedisSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The URL may embed a password.
+        f.debug_struct("RedisSource")
+            .field("layout", &self.layout)
+            .field("url", &self.url.as_ref().map(|_| "<redacted>"))
+            .field("channel", &self.channel)
+            .finish()
+    }
+}
+
+impl RedisSource {
+    /// Read every field of the hash at `key`.
+    pub fn hash(key: &str) -> Self {
+        Self {
+            layout: Layout::Hash(key.to_string()),
+            url: None,
+            channel: None,
+        }
+    }
+
+    /// Read every string key starting with `prefix`, keyed by the remainder.
+    pub fn prefix(prefix: &str) -> Self {
+        Self {
+            layout: Layout::Prefix(prefix.to_string()),
+            url: None,
+            channel: None,
+        }
+    }
+
+    /// Connection URL (default: `REDIS_URL`, then `redis://127.0.0.1:6379`).
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Pub/sub channel that announces config changes, used by [`watch`](Self::watch).
+    pub fn with_invalidation_channel(mut self, channel: &str) -> Self {
+        self.channel = Some(channel.to_string());
+        self
+    }
+
+    fn client(&self, env: &HashMap<String, String>) -> Result<redis::Client, SmooaiConfigError> {
+        let url = self
+            .url
+            .clone()
+            .or_else(|| env.get("REDIS_URL").cloned())
+            .unwrap_or_else(|| DEFAULT_URL.to_string());
+        redis::Client::open(url).map_err(|e| SmooaiConfigError::new(&format!("Invalid Redis URL: {}", e)))
+    }
+
+    fn read(&self, con: &mut redis::Connection) -> redis::RedisResult<HashMap<String, String>> {
+        match &self.layout {
+            Layout::Hash(key) => redis::cmd("HGETALL").arg(key).query(con),
+            Layout::Prefix(prefix) => {
+                let pattern = format!("{}*", escape_glob(prefix));
+                let keys: Vec<String> = con.scan_match::<_, String>(&pattern)?.collect();
+                let mut values = HashMap::new();
+                for batch in keys.chunks(MGET_BATCH) {
+                    // A single key MGET still replies with an array.
+                    let fetched: Vec<Option<String>> = redis::cmd("MGET").arg(batch).query(con)?;
+                    for (key, value) in batch.iter().zip(fetched) {
+                        // Keys deleted between SCAN and MGET come back nil.
+                        if let Some(value) = value {
+                            values.insert(key[prefix.len()..].to_string(), value);
+                        }
+                    }
+                }
+                Ok(values)
+            }
+        }
+    }
+
+    /// Subscribe to the invalidation channel on a background thread and call
+    /// `on_change` for every message — typically to invalidate a manager.
+    ///
+    /// Uses `REDIS_URL` from the process env unless [`with_url`](Self::with_url)
+    /// is set. Dropped connections are retried after a short pause. Without an
+    /// invalidation channel the watch does nothing.
+    pub fn watch<F>(&self, on_change: F) -> SourceWatch
+    where
+        F: Fn() + Send + 'static,
+    {
+        let source = self.clone();
+        SourceWatch::spawn(move |stopped| {
+            let Some(channel) = source.channel.clone() else {
+                return;
+            };
+            let env: HashMap<String, String> = std::env::vars().collect();
+            while !stopped.load(Ordering::SeqCst) {
+                let result = (|| -> Result<(), SmooaiConfigError> {
+                    let client = source.client(&env)?;
+                    let mut con = client
+                        .get_connection_with_timeout(CONNECT_TIMEOUT)
+                        .map_err(redis_error)?;
+                    let mut pubsub = con.as_pubsub();
+                    pubsub.subscribe(&channel).map_err(redis_error)?;
+                    pubsub.set_read_timeout(Some(POLL_INTERVAL)).map_err(redis_error)?;
+                    while !stopped.load(Ordering::SeqCst) {
+                        match pubsub.get_message() {
+                            Ok(_) => on_change(),
+                            Err(e) if e.is_timeout() => continue,
+                            Err(e) => return Err(redis_error(e)),
+                        }
+                    }
+                    Ok(())
+                })();
+                if let Err(e) = result {
+                    redact::warn(format_args!("Redis subscription to {} failed: {}", channel, e));
+                    std::thread::sleep(WATCH_RETRY_DELAY);
+                }
+            }
+        })
+    }
+}
+
+impl ConfigSource for RedisSource {
+    fn name(&self) -> String {
+        match &self.layout {
+            Layout::Hash(key) => format!("redis:{}", key),
+            Layout::Prefix(prefix) => format!("redis:{}*", prefix),
+        }
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        let client = self.client(env)?;
+        let mut con = client
+            .get_connection_with_timeout(CONNECT_TIMEOUT)
+            .map_err(redis_error)?;
+        let raw = self.read(&mut con).map_err(redis_error)?;
+        Ok(raw
+            .into_iter()
+            .filter(|(key, _)| !key.is_empty())
+            .map(|(key, raw)| {
+                let value = serde_json::from_str(&raw).unwrap_or(Value::String(raw));
+                (key, value)
+            })
+            .collect())
+    }
+}
+
+fn redis_error(e: redis::RedisError) -> SmooaiConfigError {
+    SmooaiConfigError::remote_fetch(None, &format!("Redis request failed: {}", e)).with_source(e)
+}
+
+/// Escape glob metacharacters so the prefix matches literally in `SCAN MATCH`.
+fn escape_glob(prefix: &str) -> String {
+    let mut escaped = String::with_capacity(prefix.len());
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    /// Read one RESP command (array of bulk strings).
+    fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok().filter(|n| *n > 0)?;
+        let count: usize = line.trim().trim_start_matches('*').parse().ok()?;
+        let mut parts = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).ok()?;
+            line.clear();
+            reader.read_line(&mut line).ok()?;
+            parts.push(line.trim_end_matches("\r\n").to_string());
+        }
+        Some(parts)
+    }
+
+    fn bulk_array(items: &[&str]) -> String {
+        let mut out = format!("*{}\r\n", items.len());
+        for item in items {
+            out.push_str(&format!("${}\r\n{}\r\n", item.len(), item));
+        }
+        out
+    }
+
+    /// A single-connection fake Redis answering from `respond`.
+    fn fake_redis<F>(respond: F) -> String
+    where
+        F: Fn(&[String]) -> String + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { return };
+                let mut writer = stream.try_clone().unwrap();
+                let mut reader = BufReader::new(stream);
+                while let Some(command) = read_command(&mut reader) {
+                    if writer.write_all(respond(&command).as_bytes()).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_hash_values_parse_as_json() {
+        let url = fake_redis(|command| match command[0].to_uppercase().as_str() {
+            "HGETALL" if command[1] == "app:prod" => bulk_array(&[
+                "API_URL",
+                "https://api",
+                "MAX_RETRIES",
+                "3",
+                "DATABASE",
+                r#"{"host":"db"}"#,
+            ]),
+            _ => "+OK\r\n".to_string(),
+        });
+
+        let values = RedisSource::hash("app:prod")
+            .with_url(&url)
+            .load(&HashMap::new())
+            .unwrap();
+        assert_eq!(values["API_URL"], json!("https://api"));
+        assert_eq!(values["MAX_RETRIES"], json!(3));
+        assert_eq!(values["DATABASE"], json!({"host": "db"}));
+    }
+
+    #[test]
+    fn test_prefix_scans_and_strips() {
+        let url = fake_redis(|command| match command[0].to_uppercase().as_str() {
+            "SCAN" => {
+                assert_eq!(command[3], r"app\*:*");
+                format!("*2\r\n$1\r\n0\r\n{}", bulk_array(&[r"app*:A", r"app*:B"]))
+            }
+            "MGET" => "*2\r\n$4\r\ntrue\r\n$-1\r\n".to_string(),
+            _ => "+OK\r\n".to_string(),
+        });
+
+        let env = HashMap::from([("REDIS_URL".to_string(), url)]);
+        let values = RedisSource::prefix("app*:").load(&env).unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["A"], json!(true));
+    }
+
+    #[test]
+    fn test_watch_calls_back_on_message() {
+        let url = fake_redis(|command| match command[0].to_uppercase().as_str() {
+            "SUBSCRIBE" => format!(
+                "*3\r\n$9\r\nsubscribe\r\n${}\r\n{}\r\n:1\r\n{}",
+                command[1].len(),
+                command[1],
+                bulk_array(&["message", &command[1], "API_URL"])
+            ),
+            _ => "+OK\r\n".to_string(),
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let watch = RedisSource::hash("app:prod")
+            .with_url(&url)
+            .with_invalidation_channel("config:invalidate")
+            .watch(move || {
+                let _ = tx.send(());
+            });
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        watch.stop();
+    }
+
+    #[test]
+    fn test_escape_glob() {
+        assert_eq!(escape_glob("a*b?[c]\\"), r"a\*b\?\[c\]\\");
+    }
+}
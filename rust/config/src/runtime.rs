@@ -307,7 +307,7 @@ mod tests {
         );
         assert_eq!(
             manager.get_secret_config("tavilyApiKey").unwrap(),
-            Some(serde_json::json!("tvly-abc"))
+            Some(serde_json::json!("tvly-abc").into())
         );
     }
 
@@ -466,7 +466,7 @@ mod tests {
         );
         assert_eq!(
             manager.get_secret_config("dbPassword").unwrap(),
-            Some(serde_json::json!("super-secret"))
+            Some(serde_json::json!("super-secret").into())
         );
         // Feature flag was dropped — not in the seeded config.
         assert_eq!(manager.get_feature_flag("newFlow").unwrap(), None);
@@ -0,0 +1,481 @@
+//! Typed schedules read from config (feature `schedule`).
+//!
+//! Workers keep their schedules in (remote) config; [`ConfigManager::get_schedule`]
+//! parses and validates them at read time so a typo fails loudly instead of
+//! silently never firing. Two syntaxes are accepted:
+//!
+//! - **cron** — 5 fields (`*/15 * * * *`), or the 6/7-field form with seconds
+//!   (and year) understood by the `cron` crate. Evaluated in UTC.
+//! - **RRULE** (RFC 5545) — `FREQ=DAILY;BYHOUR=9`, optionally prefixed with
+//!   `RRULE:` and preceded by a `DTSTART:20240101T090000Z` line. The supported
+//!   subset is `FREQ` (`MINUTELY`, `HOURLY`, `DAILY`, `WEEKLY`), `INTERVAL`,
+//!   `COUNT`, `UNTIL`, `BYDAY` (plain weekdays), `BYHOUR` and `BYMINUTE`, all in
+//!   UTC. Anything else is rejected rather than misinterpreted.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Timelike, Utc, Weekday};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config_manager::ConfigManager;
+use crate::utils::SmooaiConfigError;
+
+/// Give up looking for an RRULE occurrence after this many empty periods
+/// (e.g. `FREQ=MINUTELY;BYHOUR=25` can never match).
+const MAX_EMPTY_PERIODS: i64 = 100_000;
+
+/// Errors produced by [`ConfigManager::get_schedule`] and [`Schedule::parse`].
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    /// Resolving the key through the [`ConfigManager`] failed.
+    #[error(transparent)]
+    Config(#[from] SmooaiConfigError),
+    /// The key has no value.
+    #[error("config key '{key}' is not set")]
+    Missing { key: String },
+    /// The value is not a string.
+    #[error("config key '{key}' must be a cron expression or RRULE string")]
+    NotAString { key: String },
+    /// The value is not a valid cron expression.
+    #[error("invalid cron expression in '{key}': {message}")]
+    InvalidCron { key: String, message: String },
+    /// The value is not a valid (or supported) RRULE.
+    #[error("invalid RRULE in '{key}': {message}")]
+    InvalidRRule { key: String, message: String },
+}
+
+/// A parsed schedule.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Cron(Box<cron::Schedule>),
+    RRule(RRule),
+}
+
+impl Schedule {
+    /// Parse a cron expression or RRULE. `key` is only used in error messages.
+    pub fn parse(key: &str, input: &str) -> Result<Self, ScheduleError> {
+        let trimmed = input.trim();
+        if trimmed.to_ascii_uppercase().contains("FREQ=") {
+            return RRule::parse(trimmed)
+                .map(Schedule::RRule)
+                .map_err(|message| ScheduleError::InvalidRRule {
+                    key: key.to_string(),
+                    message,
+                });
+        }
+        // The cron crate wants a seconds field; accept the classic 5-field form.
+        let expr = if trimmed.split_whitespace().count() == 5 {
+            format!("0 {}", trimmed)
+        } else {
+            trimmed.to_string()
+        };
+        cron::Schedule::from_str(&expr)
+            .map(|s| Schedule::Cron(Box::new(s)))
+            .map_err(|e| ScheduleError::InvalidCron {
+                key: key.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    /// The first occurrence strictly after `after`, if any.
+    pub fn next_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Cron(schedule) => schedule.after(after).next(),
+            Schedule::RRule(rule) => rule.next_after(after),
+        }
+    }
+
+    /// Up to `n` occurrences strictly after `after`.
+    pub fn upcoming(&self, after: &DateTime<Utc>, n: usize) -> Vec<DateTime<Utc>> {
+        let mut out = Vec::with_capacity(n);
+        let mut cursor = *after;
+        while out.len() < n {
+            match self.next_after(&cursor) {
+                Some(next) => {
+                    out.push(next);
+                    cursor = next;
+                }
+                None => break,
+            }
+        }
+        out
+    }
+}
+
+/// RRULE recurrence frequency (supported subset).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+impl Frequency {
+    fn seconds(self) -> i64 {
+        match self {
+            Frequency::Minutely => 60,
+            Frequency::Hourly => 3_600,
+            Frequency::Daily => 86_400,
+            Frequency::Weekly => 604_800,
+        }
+    }
+}
+
+/// A parsed RFC 5545 recurrence rule (see the module docs for the subset).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+    /// Anchor for `INTERVAL` / `COUNT`; the Unix epoch when no `DTSTART` is given.
+    pub dtstart: DateTime<Utc>,
+    pub by_day: Vec<Weekday>,
+    pub by_hour: Vec<u32>,
+    pub by_minute: Vec<u32>,
+}
+
+fn parse_utc(value: &str) -> Result<DateTime<Utc>, String> {
+    let naive = value
+        .strip_suffix('Z')
+        .ok_or_else(|| format!("'{}' must be a UTC timestamp (YYYYMMDDTHHMMSSZ)", value))?;
+    NaiveDateTime::parse_from_str(naive, "%Y%m%dT%H%M%S")
+        .map(|dt| Utc.from_utc_datetime(&dt))
+        .map_err(|e| format!("invalid timestamp '{}': {}", value, e))
+}
+
+fn parse_list(name: &str, value: &str, max: u32) -> Result<Vec<u32>, String> {
+    let mut out: Vec<u32> = value
+        .split(',')
+        .map(|v| {
+            v.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|n| *n <= max)
+                .ok_or_else(|| format!("{} value '{}' must be 0-{}", name, v, max))
+        })
+        .collect::<Result<_, _>>()?;
+    out.sort_unstable();
+    out.dedup();
+    Ok(out)
+}
+
+impl RRule {
+    /// Parse an RRULE, optionally with `RRULE:` prefix and a `DTSTART:` line.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut dtstart = None;
+        let mut rule_line = None;
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let upper = line.to_ascii_uppercase();
+            if let Some(value) = upper.strip_prefix("DTSTART:") {
+                dtstart = Some(parse_utc(value)?);
+            } else if upper.starts_with("DTSTART") {
+                return Err("only UTC DTSTART values (DTSTART:YYYYMMDDTHHMMSSZ) are supported".to_string());
+            } else {
+                rule_line = Some(upper.strip_prefix("RRULE:").unwrap_or(&upper).to_string());
+            }
+        }
+        let rule_line = rule_line.ok_or_else(|| "missing RRULE".to_string())?;
+
+        let mut freq = None;
+        let mut rule = RRule {
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            dtstart: dtstart.unwrap_or(DateTime::UNIX_EPOCH),
+            by_day: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+        };
+        for part in rule_line.split(';').filter(|p| !p.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("malformed part '{}'", part))?;
+            match name {
+                "FREQ" => {
+                    freq = Some(match value {
+                        "MINUTELY" => Frequency::Minutely,
+                        "HOURLY" => Frequency::Hourly,
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        other => return Err(format!("unsupported FREQ '{}'", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("INTERVAL '{}' must be a positive integer", value))?
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("COUNT '{}' must be a non-negative integer", value))?,
+                    )
+                }
+                "UNTIL" => rule.until = Some(parse_utc(value)?),
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(|d| match d.trim() {
+                            "MO" => Ok(Weekday::Mon),
+                            "TU" => Ok(Weekday::Tue),
+                            "WE" => Ok(Weekday::Wed),
+                            "TH" => Ok(Weekday::Thu),
+                            "FR" => Ok(Weekday::Fri),
+                            "SA" => Ok(Weekday::Sat),
+                            "SU" => Ok(Weekday::Sun),
+                            other => Err(format!("unsupported BYDAY value '{}'", other)),
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "BYHOUR" => rule.by_hour = parse_list("BYHOUR", value, 23)?,
+                "BYMINUTE" => rule.by_minute = parse_list("BYMINUTE", value, 59)?,
+                "WKST" if value == "MO" => {}
+                other => return Err(format!("unsupported RRULE part '{}'", other)),
+            }
+        }
+        rule.freq = freq.ok_or_else(|| "FREQ is required".to_string())?;
+        if rule.count.is_some() && dtstart.is_none() {
+            return Err("COUNT requires a DTSTART".to_string());
+        }
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("COUNT and UNTIL are mutually exclusive".to_string());
+        }
+        Ok(rule)
+    }
+
+    /// Start of the period containing `dtstart`, truncated to the frequency unit.
+    fn base(&self) -> DateTime<Utc> {
+        let start = self.dtstart;
+        let truncated = match self.freq {
+            Frequency::Minutely => start.with_second(0),
+            Frequency::Hourly => start.with_second(0).and_then(|t| t.with_minute(0)),
+            Frequency::Daily => start
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .map(|t| Utc.from_utc_datetime(&t)),
+            Frequency::Weekly => {
+                let monday = start.date_naive() - Duration::days(start.weekday().num_days_from_monday() as i64);
+                monday.and_hms_opt(0, 0, 0).map(|t| Utc.from_utc_datetime(&t))
+            }
+        };
+        truncated.unwrap_or(start)
+    }
+
+    /// Occurrences within period `k`, sorted, before COUNT/UNTIL/DTSTART filtering.
+    fn candidates(&self, base: DateTime<Utc>, k: i64) -> Vec<DateTime<Utc>> {
+        let period_start = base + Duration::seconds(k * self.interval as i64 * self.freq.seconds());
+        let second = self.dtstart.second() as i64;
+        let or_default = |list: &Vec<u32>, default: u32| if list.is_empty() { vec![default] } else { list.clone() };
+
+        let mut out = Vec::new();
+        match self.freq {
+            Frequency::Minutely => out.push(period_start + Duration::seconds(second)),
+            Frequency::Hourly => {
+                for minute in or_default(&self.by_minute, self.dtstart.minute()) {
+                    out.push(period_start + Duration::seconds(minute as i64 * 60 + second));
+                }
+            }
+            Frequency::Daily | Frequency::Weekly => {
+                let days: Vec<i64> = if self.freq == Frequency::Daily {
+                    vec![0]
+                } else if self.by_day.is_empty() {
+                    vec![self.dtstart.weekday().num_days_from_monday() as i64]
+                } else {
+                    self.by_day.iter().map(|d| d.num_days_from_monday() as i64).collect()
+                };
+                for day in days {
+                    for hour in or_default(&self.by_hour, self.dtstart.hour()) {
+                        for minute in or_default(&self.by_minute, self.dtstart.minute()) {
+                            out.push(
+                                period_start
+                                    + Duration::seconds(
+                                        day * 86_400 + hour as i64 * 3_600 + minute as i64 * 60 + second,
+                                    ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        out.retain(|t| {
+            (self.by_day.is_empty() || self.by_day.contains(&t.weekday()))
+                && (self.by_hour.is_empty() || self.by_hour.contains(&t.hour()))
+                && (self.by_minute.is_empty() || self.by_minute.contains(&t.minute()))
+        });
+        out.sort();
+        out.dedup();
+        out
+    }
+
+    /// The first occurrence strictly after `after`, honoring COUNT and UNTIL.
+    pub fn next_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let base = self.base();
+        let period = self.interval as i64 * self.freq.seconds();
+        // COUNT needs every occurrence from the start; otherwise jump close to `after`.
+        let mut k = if self.count.is_some() {
+            0
+        } else {
+            ((*after - base).num_seconds() / period - 1).max(0)
+        };
+        let mut seen: u32 = 0;
+        let mut empty = 0;
+        while empty < MAX_EMPTY_PERIODS {
+            let candidates = self.candidates(base, k);
+            let mut produced = false;
+            for candidate in candidates.into_iter().filter(|t| *t >= self.dtstart) {
+                produced = true;
+                if self.until.is_some_and(|until| candidate > until) {
+                    return None;
+                }
+                if let Some(count) = self.count {
+                    if seen >= count {
+                        return None;
+                    }
+                    seen += 1;
+                }
+                if candidate > *after {
+                    return Some(candidate);
+                }
+            }
+            empty = if produced { 0 } else { empty + 1 };
+            k += 1;
+        }
+        None
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl ConfigManager {
+    /// Read and validate a schedule (cron or RRULE) from the public tier.
+    pub fn get_schedule(&self, key: &str) -> Result<Schedule, ScheduleError> {
+        let value = self
+            .get_public_config(key)?
+            .filter(|v| !v.is_null())
+            .ok_or_else(|| ScheduleError::Missing { key: key.to_string() })?;
+        match value {
+            Value::String(s) => Schedule::parse(key, &s),
+            _ => Err(ScheduleError::NotAString { key: key.to_string() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        parse_utc(s).unwrap()
+    }
+
+    #[test]
+    fn test_five_field_cron() {
+        let schedule = Schedule::parse("JOB", "*/15 * * * *").unwrap();
+        assert!(matches!(schedule, Schedule::Cron(_)));
+        assert_eq!(
+            schedule.next_after(&at("20240101T090700Z")),
+            Some(at("20240101T091500Z"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_cron_is_typed() {
+        let err = Schedule::parse("JOB", "not a cron").unwrap_err();
+        assert!(matches!(err, ScheduleError::InvalidCron { ref key, .. } if key == "JOB"));
+    }
+
+    #[test]
+    fn test_rrule_weekly_byday() {
+        let schedule = Schedule::parse("JOB", "RRULE:FREQ=WEEKLY;BYDAY=MO,FR;BYHOUR=9;BYMINUTE=30").unwrap();
+        // 2024-01-03 is a Wednesday.
+        assert_eq!(
+            schedule.upcoming(&at("20240103T000000Z"), 3),
+            vec![at("20240105T093000Z"), at("20240108T093000Z"), at("20240112T093000Z")]
+        );
+    }
+
+    #[test]
+    fn test_rrule_count_and_interval_anchor_on_dtstart() {
+        let schedule = Schedule::parse("JOB", "DTSTART:20240101T080000Z\nRRULE:FREQ=DAILY;INTERVAL=2;COUNT=3").unwrap();
+        assert_eq!(
+            schedule.upcoming(&at("20231231T000000Z"), 10),
+            vec![at("20240101T080000Z"), at("20240103T080000Z"), at("20240105T080000Z")]
+        );
+    }
+
+    #[test]
+    fn test_rrule_until_and_hourly_filter() {
+        let schedule = Schedule::parse("JOB", "FREQ=HOURLY;BYHOUR=10,11;BYMINUTE=0;UNTIL=20240101T110000Z").unwrap();
+        assert_eq!(
+            schedule.upcoming(&at("20240101T000000Z"), 10),
+            vec![at("20240101T100000Z"), at("20240101T110000Z")]
+        );
+    }
+
+    #[test]
+    fn test_rrule_rejects_unsupported_parts() {
+        for rule in [
+            "FREQ=MONTHLY",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=DAILY;COUNT=3",
+            "FREQ=DAILY;BYHOUR=24",
+        ] {
+            assert!(
+                matches!(Schedule::parse("JOB", rule), Err(ScheduleError::InvalidRRule { .. })),
+                "{} should be rejected",
+                rule
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_schedule_from_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(".smooai-config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(
+            config_dir.join("default.json"),
+            r#"{"CLEANUP_SCHEDULE":"0 3 * * *","BROKEN":"61 * * * *","NUM":5}"#,
+        )
+        .unwrap();
+        let env = std::collections::HashMap::from([
+            (
+                "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                config_dir.to_string_lossy().to_string(),
+            ),
+            ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+        ]);
+        let mgr = ConfigManager::new().with_env(env);
+
+        let schedule = mgr.get_schedule("CLEANUP_SCHEDULE").unwrap();
+        assert_eq!(
+            schedule.next_after(&at("20240101T120000Z")),
+            Some(at("20240102T030000Z"))
+        );
+        assert!(matches!(
+            mgr.get_schedule("BROKEN"),
+            Err(ScheduleError::InvalidCron { .. })
+        ));
+        assert!(matches!(mgr.get_schedule("NUM"), Err(ScheduleError::NotAString { .. })));
+        assert!(matches!(mgr.get_schedule("NOPE"), Err(ScheduleError::Missing { .. })));
+    }
+}
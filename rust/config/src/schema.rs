@@ -1,12 +1,17 @@
 //! Configuration schema definition using serde.
 
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::schema_validator::validate_smooai_schema;
+use crate::flatten::KeyFlattening;
+use crate::redact;
+use crate::schema_validator::{validate_smooai_schema, SchemaValidationError};
+use crate::utils::{camel_to_upper_snake, enum_type_hint, parse_enum_type_hint, KeyCase};
 
 /// Configuration value tiers.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ConfigTier {
     Public,
@@ -14,6 +19,17 @@ pub enum ConfigTier {
     FeatureFlag,
 }
 
+impl ConfigTier {
+    /// Human-readable tier name used in error messages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigTier::Public => "public",
+            ConfigTier::Secret => "secret",
+            ConfigTier::FeatureFlag => "feature flag",
+        }
+    }
+}
+
 /// Result of defining a configuration schema.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ConfigDefinition {
@@ -23,10 +39,359 @@ pub struct ConfigDefinition {
     pub json_schema: serde_json::Value,
 }
 
+impl ConfigDefinition {
+    /// Rebuild a definition from its combined [`json_schema`](Self::json_schema),
+    /// e.g. one fetched from the config server. Tiers missing from it are empty.
+    pub fn from_json_schema(json_schema: serde_json::Value) -> Self {
+        let tier = |name: &str| {
+            json_schema
+                .pointer(&format!("/properties/{}", name))
+                .cloned()
+                .unwrap_or_default()
+        };
+        Self {
+            public_schema: tier("public"),
+            secret_schema: tier("secret"),
+            feature_flag_schema: tier("feature_flags"),
+            json_schema,
+        }
+    }
+
+    /// Keys listed in the `required` arrays of each tier's schema, in tier order
+    /// (public, secret, feature flags) with duplicates removed.
+    ///
+    /// Names are returned as declared in the schema; [`ConfigManager`](crate::ConfigManager)
+    /// accepts either that spelling or its UPPER_SNAKE_CASE form when checking presence.
+    pub fn required_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        for schema in [&self.public_schema, &self.secret_schema, &self.feature_flag_schema] {
+            if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+                for key in required.iter().filter_map(|k| k.as_str()) {
+                    if !keys.iter().any(|k| k == key) {
+                        keys.push(key.to_string());
+                    }
+                }
+            }
+        }
+        keys
+    }
+
+    /// The schema for a single tier.
+    pub fn tier_schema(&self, tier: ConfigTier) -> &serde_json::Value {
+        match tier {
+            ConfigTier::Public => &self.public_schema,
+            ConfigTier::Secret => &self.secret_schema,
+            ConfigTier::FeatureFlag => &self.feature_flag_schema,
+        }
+    }
+
+    /// Whether `tier`'s schema declares `key` as a property.
+    ///
+    /// Matches the property name as written, its UPPER_SNAKE_CASE form
+    /// (`apiUrl` → `API_URL`), or its uppercased form (`api_url` → `API_URL`)
+    /// so both camelCase and serde-derived snake_case schemas line up with
+    /// environment-style keys.
+    pub fn declares(&self, tier: ConfigTier, key: &str) -> bool {
+        self.tier_schema(tier)
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .is_some_and(|props| {
+                props
+                    .keys()
+                    .any(|prop| prop == key || camel_to_upper_snake(prop) == key || prop.to_uppercase() == key)
+            })
+    }
+
+    /// Every declared property across all tiers, as the UPPER_SNAKE_CASE keys
+    /// that [`ConfigManager::with_schema_keys`](crate::ConfigManager::with_schema_keys) expects.
+    ///
+    /// `apiUrl` and `api_url` both become `API_URL`.
+    pub fn schema_keys(&self) -> HashSet<String> {
+        self.properties().map(|(name, _)| property_key(name)).collect()
+    }
+
+    /// Env coercion hints for every declared property, keyed like
+    /// [`schema_keys`](Self::schema_keys), in the form
+    /// [`ConfigManager::with_schema_types`](crate::ConfigManager::with_schema_types) expects:
+    /// `"boolean"`, `"number"`, `"json"` (objects and arrays), `"bytes"`
+    /// (base64 strings) or `"string"`.
+    ///
+    /// Properties with an `enum` (inline or behind a `$ref`, as schemars
+    /// emits for Rust enums) get an [`enum_type_hint`] instead, so env values
+    /// outside the set are rejected.
+    ///
+    /// When a key is declared in several tiers the first tier wins.
+    pub fn schema_types(&self) -> HashMap<String, String> {
+        let mut types = HashMap::new();
+        for root in [&self.public_schema, &self.secret_schema, &self.feature_flag_schema] {
+            let Some(properties) = root.get("properties").and_then(|p| p.as_object()) else {
+                continue;
+            };
+            for (name, schema) in properties {
+                types.entry(property_key(name)).or_insert_with(|| {
+                    let schema = resolve_ref(root, schema);
+                    match schema.get("enum").and_then(|e| e.as_array()) {
+                        Some(values) => enum_type_hint(values),
+                        None => type_hint(schema).to_string(),
+                    }
+                });
+            }
+        }
+        types
+    }
+
+    /// The values `key` is restricted to by an `enum` in the schema, if any.
+    pub fn enum_values(&self, key: &str) -> Option<Vec<serde_json::Value>> {
+        parse_enum_type_hint(self.schema_types().get(&property_key(key))?)
+    }
+
+    /// Keys whose values are redacted when config is listed, explained or
+    /// diffed: every secret-tier property, plus any property in another tier
+    /// annotated `"x-secret": true`. Keyed like [`schema_keys`](Self::schema_keys).
+    pub fn secret_keys(&self) -> HashSet<String> {
+        let secret_tier = self
+            .secret_schema
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .into_iter()
+            .flatten()
+            .map(|(name, _)| property_key(name));
+        let marked = self
+            .properties()
+            .filter(|(_, schema)| schema.get("x-secret") == Some(&serde_json::Value::Bool(true)))
+            .map(|(name, _)| property_key(name));
+        secret_tier.chain(marked).collect()
+    }
+
+    /// `(name, schema)` for every property, tier by tier.
+    fn properties(&self) -> impl Iterator<Item = (&String, &serde_json::Value)> {
+        [&self.public_schema, &self.secret_schema, &self.feature_flag_schema]
+            .into_iter()
+            .filter_map(|schema| schema.get("properties").and_then(|p| p.as_object()))
+            .flatten()
+    }
+
+    /// The first tier (public, secret, feature flags) declaring `key`, if any.
+    pub fn declared_tier(&self, key: &str) -> Option<ConfigTier> {
+        [ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag]
+            .into_iter()
+            .find(|tier| self.declares(*tier, key))
+    }
+}
+
+/// A key whose declared type changed between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RetypedKey {
+    pub key: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Changes to one tier between two schemas. Keys are UPPER_SNAKE_CASE, so
+/// renaming `apiUrl` to `api_url` is not a change.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TierDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub retyped: Vec<RetypedKey>,
+    /// Keys required in the new schema but not the old one (new or existing).
+    pub newly_required: Vec<String>,
+}
+
+impl TierDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.retyped.is_empty() && self.newly_required.is_empty()
+    }
+
+    /// Whether deployed config or readers could break: a key was removed,
+    /// changed type, or became required.
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.retyped.is_empty() || !self.newly_required.is_empty()
+    }
+}
+
+/// Per-tier differences between two schemas, from [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaDiff {
+    pub public: TierDiff,
+    pub secret: TierDiff,
+    pub feature_flags: TierDiff,
+}
+
+impl SchemaDiff {
+    pub fn tier(&self, tier: ConfigTier) -> &TierDiff {
+        match tier {
+            ConfigTier::Public => &self.public,
+            ConfigTier::Secret => &self.secret,
+            ConfigTier::FeatureFlag => &self.feature_flags,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.public.is_empty() && self.secret.is_empty() && self.feature_flags.is_empty()
+    }
+
+    /// Whether any tier has a breaking change; see [`TierDiff::is_breaking`].
+    pub fn is_breaking(&self) -> bool {
+        self.public.is_breaking() || self.secret.is_breaking() || self.feature_flags.is_breaking()
+    }
+}
+
+/// Compare two schemas tier by tier, e.g. in CI to flag breaking config
+/// changes before deploy:
+///
+/// ```rust,ignore
+/// let changes = schema::diff(&deployed, &proposed);
+/// if changes.is_breaking() {
+///     eprintln!("{}", serde_json::to_string_pretty(&changes)?);
+///     std::process::exit(1);
+/// }
+/// ```
+///
+/// Types are compared on the property's `type` (nullability included),
+/// `$ref` target, or `enum` values.
+pub fn diff(old: &ConfigDefinition, new: &ConfigDefinition) -> SchemaDiff {
+    let tier = |tier| diff_tier(old.tier_schema(tier), new.tier_schema(tier));
+    SchemaDiff {
+        public: tier(ConfigTier::Public),
+        secret: tier(ConfigTier::Secret),
+        feature_flags: tier(ConfigTier::FeatureFlag),
+    }
+}
+
+fn diff_tier(old: &serde_json::Value, new: &serde_json::Value) -> TierDiff {
+    let types = |schema: &serde_json::Value| -> BTreeMap<String, String> {
+        schema
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .into_iter()
+            .flatten()
+            .map(|(name, prop)| (property_key(name), type_descriptor(prop)))
+            .collect()
+    };
+    let required = |schema: &serde_json::Value| -> BTreeSet<String> {
+        schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|k| k.as_str())
+            .map(property_key)
+            .collect()
+    };
+    let (old_types, new_types) = (types(old), types(new));
+    let old_required = required(old);
+
+    TierDiff {
+        added: new_types
+            .keys()
+            .filter(|k| !old_types.contains_key(*k))
+            .cloned()
+            .collect(),
+        removed: old_types
+            .keys()
+            .filter(|k| !new_types.contains_key(*k))
+            .cloned()
+            .collect(),
+        retyped: new_types
+            .iter()
+            .filter_map(|(key, new_type)| {
+                let old_type = old_types.get(key)?;
+                (old_type != new_type).then(|| RetypedKey {
+                    key: key.clone(),
+                    old: old_type.clone(),
+                    new: new_type.clone(),
+                })
+            })
+            .collect(),
+        newly_required: required(new).difference(&old_required).cloned().collect(),
+    }
+}
+
+/// A comparable description of a property's type: `integer`,
+/// `integer|null`, `ref:Database`, `enum["a","b"]` or `any`.
+pub(crate) fn type_descriptor(schema: &serde_json::Value) -> String {
+    match schema.get("type") {
+        Some(serde_json::Value::String(ty)) => return ty.clone(),
+        Some(serde_json::Value::Array(types)) => {
+            let mut types: Vec<&str> = types.iter().filter_map(|t| t.as_str()).collect();
+            types.sort_unstable();
+            return types.join("|");
+        }
+        _ => {}
+    }
+    if let Some(target) = schema.get("$ref").and_then(|r| r.as_str()) {
+        return format!("ref:{}", target.rsplit('/').next().unwrap_or(target));
+    }
+    if let Some(values) = schema.get("enum") {
+        return format!("enum{}", values);
+    }
+    // schemars wraps references with extra keywords in `allOf`
+    if let Some(first) = schema.get("allOf").and_then(|a| a.get(0)) {
+        return type_descriptor(first);
+    }
+    "any".to_string()
+}
+
+/// The env-style key for a schema property: snake_case is uppercased,
+/// camelCase is split (`apiUrl` → `API_URL`).
+pub(crate) fn property_key(name: &str) -> String {
+    KeyCase::UpperSnake.convert(name)
+}
+
+/// Follow a local `$ref` (directly, or wrapped in a single-element `allOf`
+/// or a nullable `anyOf`) to its definition in `root`. Unresolvable
+/// references are returned as-is.
+pub(crate) fn resolve_ref<'a>(root: &'a serde_json::Value, schema: &'a serde_json::Value) -> &'a serde_json::Value {
+    if let Some(target) = schema
+        .get("$ref")
+        .and_then(|r| r.as_str())
+        .and_then(|r| r.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+    {
+        return target;
+    }
+    for key in ["allOf", "anyOf"] {
+        let branches: Vec<&serde_json::Value> = schema
+            .get(key)
+            .and_then(|b| b.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|b| b.get("type").and_then(|t| t.as_str()) != Some("null"))
+            .collect();
+        if let [only] = branches[..] {
+            if only.get("$ref").is_some() {
+                return resolve_ref(root, only);
+            }
+        }
+    }
+    schema
+}
+
+/// Coercion hint for a property schema. Nullable types (`["integer", "null"]`)
+/// use their non-null type; references to other definitions are objects.
+fn type_hint(schema: &serde_json::Value) -> &'static str {
+    let ty = match schema.get("type") {
+        Some(serde_json::Value::String(ty)) => Some(ty.as_str()),
+        Some(serde_json::Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).find(|t| *t != "null"),
+        _ => None,
+    };
+    match ty {
+        Some("boolean") => "boolean",
+        Some("integer") | Some("number") => "number",
+        Some("object") | Some("array") => "json",
+        Some("string") if schema.get("contentEncoding").and_then(|e| e.as_str()) == Some("base64") => "bytes",
+        Some(_) => "string",
+        None if schema.get("$ref").is_some() || schema.get("allOf").is_some() => "json",
+        None => "string",
+    }
+}
+
 /// Define a configuration schema from JSON schema components.
 ///
-/// Validates each tier's schema for cross-language compatibility. Returns
-/// an error if any schema uses unsupported JSON Schema features.
+/// Validates each tier's schema for cross-language compatibility and prints
+/// any unsupported features to stderr. Use [`define_config_checked`] to fail
+/// on them instead.
 pub fn define_config(
     public_schema: Option<serde_json::Value>,
     secret_schema: Option<serde_json::Value>,
@@ -46,15 +411,57 @@ pub fn define_config(
                     .iter()
                     .map(|e| format!("  {}: {} Suggestion: {}", e.path, e.message, e.suggestion))
                     .collect();
-                eprintln!(
-                    "[Smooai Config] Warning: [{}] Schema uses unsupported features:\n{}",
+                redact::warn(format_args!(
+                    "[{}] Schema uses unsupported features:\n{}",
                     tier,
                     msgs.join("\n")
-                );
+                ));
             }
         }
     }
 
+    build_definition(public_schema, secret_schema, feature_flag_schema)
+}
+
+/// Like [`define_config`], but returns the cross-language compatibility
+/// violations instead of printing them.
+///
+/// Each error's `path` is prefixed with its tier (`/public/properties/...`,
+/// `/secret/...`, `/feature_flags/...`).
+pub fn define_config_checked(
+    public_schema: Option<serde_json::Value>,
+    secret_schema: Option<serde_json::Value>,
+    feature_flag_schema: Option<serde_json::Value>,
+) -> Result<ConfigDefinition, Vec<SchemaValidationError>> {
+    let mut errors = Vec::new();
+    for (tier, schema) in [
+        ("public", &public_schema),
+        ("secret", &secret_schema),
+        ("feature_flags", &feature_flag_schema),
+    ] {
+        let Some(schema) = schema else { continue };
+        errors.extend(validate_smooai_schema(schema).errors.into_iter().map(|mut e| {
+            e.path = if e.path == "/" {
+                format!("/{}", tier)
+            } else {
+                format!("/{}{}", tier, e.path)
+            };
+            e
+        }));
+    }
+
+    if errors.is_empty() {
+        Ok(build_definition(public_schema, secret_schema, feature_flag_schema))
+    } else {
+        Err(errors)
+    }
+}
+
+pub(crate) fn build_definition(
+    public_schema: Option<serde_json::Value>,
+    secret_schema: Option<serde_json::Value>,
+    feature_flag_schema: Option<serde_json::Value>,
+) -> ConfigDefinition {
     let empty_obj = serde_json::json!({"type": "object", "properties": {}});
 
     let public = public_schema.clone().unwrap_or_default();
@@ -119,6 +526,34 @@ where
     define_config(public_schema, secret_schema, feature_flag_schema)
 }
 
+/// Like [`define_config_typed`], but returns the cross-language compatibility
+/// violations instead of printing them. See [`define_config_checked`].
+pub fn define_config_typed_checked<P, S, F>() -> Result<ConfigDefinition, Vec<SchemaValidationError>>
+where
+    P: JsonSchema + Default,
+    S: JsonSchema + Default,
+    F: JsonSchema + Default,
+{
+    define_config_checked(schema_or_none::<P>(), schema_or_none::<S>(), schema_or_none::<F>())
+}
+
+/// Like [`define_config_typed`], choosing how nested structs become keys.
+///
+/// [`KeyFlattening::Flatten`] turns `database.host` into a `DATABASE_HOST`
+/// key; see [`crate::flatten`].
+pub fn define_config_typed_with<P, S, F>(flattening: KeyFlattening) -> ConfigDefinition
+where
+    P: JsonSchema + Default,
+    S: JsonSchema + Default,
+    F: JsonSchema + Default,
+{
+    let definition = define_config_typed::<P, S, F>();
+    match flattening {
+        KeyFlattening::Nested => definition,
+        KeyFlattening::Flatten => definition.flattened(),
+    }
+}
+
 /// Convert a `JsonSchema` type to a `serde_json::Value`, returning `None` for `EmptySchema`.
 fn schema_or_none<T: JsonSchema>() -> Option<serde_json::Value> {
     // Check if this is EmptySchema by looking at the generated schema
@@ -341,10 +776,163 @@ mod tests {
         assert!(flags["properties"]["enable_beta"].is_object());
     }
 
+    #[test]
+    fn test_required_keys_collects_all_tiers() {
+        let public = serde_json::json!({"type": "object", "required": ["apiUrl", "shared"]});
+        let secret = serde_json::json!({"type": "object", "required": ["apiKey", "shared"]});
+        let result = define_config(Some(public), Some(secret), None);
+        assert_eq!(result.required_keys(), vec!["apiUrl", "shared", "apiKey"]);
+    }
+
+    #[test]
+    fn test_required_keys_from_typed_schema() {
+        let result = define_config_typed::<TestPublicConfig, TestSecretConfig, EmptySchema>();
+        let required = result.required_keys();
+        assert!(required.contains(&"api_url".to_string()));
+        assert!(required.contains(&"api_key".to_string()));
+    }
+
+    #[test]
+    fn test_declared_tier_matches_key_spellings() {
+        let public = serde_json::json!({"type": "object", "properties": {"apiUrl": {"type": "string"}}});
+        let secret = serde_json::json!({"type": "object", "properties": {"db_password": {"type": "string"}}});
+        let result = define_config(Some(public), Some(secret), None);
+        assert_eq!(result.declared_tier("apiUrl"), Some(ConfigTier::Public));
+        assert_eq!(result.declared_tier("API_URL"), Some(ConfigTier::Public));
+        assert_eq!(result.declared_tier("DB_PASSWORD"), Some(ConfigTier::Secret));
+        assert!(result.declares(ConfigTier::Secret, "db_password"));
+        assert!(!result.declares(ConfigTier::Public, "DB_PASSWORD"));
+        assert_eq!(result.declared_tier("UNKNOWN"), None);
+    }
+
+    #[test]
+    fn test_schema_keys_and_types() {
+        let public = serde_json::json!({"type": "object", "properties": {
+            "apiUrl": {"type": "string"},
+            "max_retries": {"type": ["integer", "null"]},
+            "database": {"$ref": "#/definitions/Database"},
+            "cert": {"type": "string", "contentEncoding": "base64"},
+            "logLevel": {"type": "string", "enum": ["debug", "info"]},
+        }});
+        let flags = serde_json::json!({"type": "object", "properties": {"newUi": {"type": "boolean"}}});
+        let result = define_config(Some(public), None, Some(flags));
+
+        assert_eq!(
+            result.schema_keys(),
+            HashSet::from(["API_URL", "MAX_RETRIES", "DATABASE", "CERT", "LOG_LEVEL", "NEW_UI"].map(String::from))
+        );
+        let types = result.schema_types();
+        assert_eq!(types["API_URL"], "string");
+        assert_eq!(types["MAX_RETRIES"], "number");
+        assert_eq!(types["DATABASE"], "json");
+        assert_eq!(types["CERT"], "bytes");
+        assert_eq!(types["NEW_UI"], "boolean");
+        assert_eq!(types["LOG_LEVEL"], r#"enum:["debug","info"]"#);
+        assert_eq!(
+            result.enum_values("logLevel"),
+            Some(vec![serde_json::json!("debug"), serde_json::json!("info")])
+        );
+        assert_eq!(result.enum_values("API_URL"), None);
+    }
+
+    #[test]
+    fn test_secret_keys_include_marked_properties() {
+        let public = serde_json::json!({"type": "object", "properties": {
+            "apiUrl": {"type": "string"},
+            "webhookToken": {"type": "string", "x-secret": true},
+        }});
+        let secret = serde_json::json!({"type": "object", "properties": {"db_password": {"type": "string"}}});
+        let result = define_config(Some(public), Some(secret), None);
+        assert_eq!(
+            result.secret_keys(),
+            HashSet::from(["WEBHOOK_TOKEN", "DB_PASSWORD"].map(String::from))
+        );
+    }
+
+    #[test]
+    fn test_schema_keys_from_typed_schema() {
+        let result = define_config_typed::<TestPublicConfig, TestSecretConfig, EmptySchema>();
+        let keys = result.schema_keys();
+        assert!(keys.contains("API_URL") && keys.contains("MAX_RETRIES") && keys.contains("API_KEY"));
+        assert_eq!(result.schema_types()["DEBUG"], "boolean");
+    }
+
+    #[test]
+    fn test_diff_reports_tier_changes() {
+        let old = define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "apiUrl": {"type": "string"},
+                "maxRetries": {"type": "integer"},
+                "legacy": {"type": "boolean"},
+            }})),
+            None,
+            None,
+        );
+        let new = define_config(
+            Some(serde_json::json!({"type": "object", "properties": {
+                "api_url": {"type": "string"},
+                "maxRetries": {"type": ["string", "null"]},
+                "timeoutMs": {"type": "integer"},
+            }, "required": ["apiUrl"]})),
+            None,
+            Some(serde_json::json!({"type": "object", "properties": {"newUi": {"type": "boolean"}}})),
+        );
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.public.added, ["TIMEOUT_MS"]);
+        assert_eq!(changes.public.removed, ["LEGACY"]);
+        assert_eq!(
+            changes.public.retyped,
+            [RetypedKey {
+                key: "MAX_RETRIES".to_string(),
+                old: "integer".to_string(),
+                new: "null|string".to_string(),
+            }]
+        );
+        assert_eq!(changes.public.newly_required, ["API_URL"]);
+        assert!(changes.secret.is_empty());
+        assert_eq!(changes.feature_flags.added, ["NEW_UI"]);
+        assert!(!changes.feature_flags.is_breaking());
+        assert!(changes.is_breaking());
+        assert!(diff(&new, &new).is_empty());
+    }
+
     #[test]
     fn test_define_config_typed_empty_schema() {
         let result = define_config_typed::<EmptySchema, EmptySchema, EmptySchema>();
         // Should produce valid config with empty tiers
         assert_eq!(result.json_schema["type"], "object");
     }
+
+    #[test]
+    fn test_define_config_checked_reports_violations_per_tier() {
+        let public = serde_json::json!({
+            "type": "object",
+            "properties": {"region": {"type": "string", "if": {"const": "eu"}}}
+        });
+        let flags = serde_json::json!({"type": "object", "patternProperties": {"^x": {}}});
+        let errors = define_config_checked(Some(public), None, Some(flags)).unwrap_err();
+        let paths: Vec<(&str, &str)> = errors.iter().map(|e| (e.path.as_str(), e.keyword.as_str())).collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("/public/properties/region", "if"),
+                ("/feature_flags", "patternProperties")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_define_config_checked_accepts_supported_schema() {
+        let public = serde_json::json!({"type": "object", "properties": {"apiUrl": {"type": "string"}}});
+        let result = define_config_checked(Some(public.clone()), None, None).unwrap();
+        assert_eq!(result.public_schema, public);
+        assert_eq!(result.json_schema, define_config(Some(public), None, None).json_schema);
+    }
+
+    #[test]
+    fn test_define_config_typed_checked() {
+        let result = define_config_typed_checked::<EmptySchema, TestSecretConfig, TestFeatureFlags>().unwrap();
+        assert!(result.json_schema["properties"]["secret"]["properties"]["api_key"].is_object());
+    }
 }
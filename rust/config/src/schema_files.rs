@@ -0,0 +1,169 @@
+//! Schema files on disk, for checking into a repo.
+//!
+//! [`ConfigDefinition::write_schemas`] writes one JSON Schema per tier plus
+//! the combined schema, with object keys sorted and a trailing newline so
+//! regenerating an unchanged definition is a no-op diff. CI can then fail
+//! when the checked-in files drift from the code:
+//!
+//! ```rust,ignore
+//! let stale = definition.check_schemas("schemas")?;
+//! assert!(stale.is_empty(), "schema files out of date, regenerate them: {:?}", stale);
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::schema::{ConfigDefinition, ConfigTier};
+use crate::utils::{SmooaiConfigError, SmooaiConfigErrorKind};
+
+/// File names written by [`ConfigDefinition::write_schemas`]; the last one is the combined schema.
+pub const SCHEMA_FILE_NAMES: [&str; 4] = [
+    "public.schema.json",
+    "secret.schema.json",
+    "feature_flags.schema.json",
+    "schema.json",
+];
+
+impl ConfigDefinition {
+    /// Write each tier's schema and the combined schema to `dir` (created if
+    /// missing), returning the paths written. Empty tiers are written as an
+    /// empty object schema.
+    pub fn write_schemas(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, SmooaiConfigError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(|e| io_error("create", dir, e))?;
+        let mut written = Vec::new();
+        for (name, contents) in self.schema_files() {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).map_err(|e| io_error("write", &path, e))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// The files under `dir` that are missing or differ from what
+    /// [`write_schemas`](Self::write_schemas) would write. Empty when up to date.
+    pub fn check_schemas(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, SmooaiConfigError> {
+        let dir = dir.as_ref();
+        let mut stale = Vec::new();
+        for (name, contents) in self.schema_files() {
+            let path = dir.join(name);
+            match std::fs::read_to_string(&path) {
+                Ok(existing) if existing == contents => {}
+                Ok(_) => stale.push(path),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => stale.push(path),
+                Err(e) => {
+                    return Err(SmooaiConfigError::new(&format!(
+                        "Failed to read {}: {}",
+                        path.display(),
+                        e
+                    )));
+                }
+            }
+        }
+        Ok(stale)
+    }
+
+    pub(crate) fn schema_files(&self) -> Vec<(&'static str, String)> {
+        let empty = serde_json::json!({"type": "object", "properties": {}});
+        let tiers = [ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag].map(|tier| {
+            let schema = self.tier_schema(tier);
+            if schema.is_null() {
+                &empty
+            } else {
+                schema
+            }
+        });
+        tiers
+            .into_iter()
+            .chain([&self.json_schema])
+            .zip(SCHEMA_FILE_NAMES)
+            .map(|(schema, name)| (name, render(schema)))
+            .collect()
+    }
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> SmooaiConfigError {
+    SmooaiConfigError::new(&format!("Failed to {} {}: {}", action, path.display(), e))
+        .with_kind(SmooaiConfigErrorKind::Io {
+            path: path.display().to_string(),
+        })
+        .with_source(e)
+}
+
+fn render(schema: &Value) -> String {
+    let mut out = serde_json::to_string_pretty(&sorted(schema)).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// `value` with object keys in sorted order, whatever map type serde_json uses.
+fn sorted(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), sorted(v)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::define_config;
+    use serde_json::json;
+
+    fn definition() -> ConfigDefinition {
+        define_config(
+            Some(json!({"type": "object", "properties": {"zeta": {"type": "string"}, "alpha": {"type": "integer"}}})),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_write_schemas_is_stable_and_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = definition().write_schemas(dir.path().join("schemas")).unwrap();
+        assert_eq!(written.len(), 4);
+
+        let public = std::fs::read_to_string(&written[0]).unwrap();
+        assert!(public.ends_with("}\n"));
+        assert!(public.find("\"alpha\"").unwrap() < public.find("\"zeta\"").unwrap());
+        let secret: Value = serde_json::from_str(&std::fs::read_to_string(&written[1]).unwrap()).unwrap();
+        assert_eq!(secret, json!({"type": "object", "properties": {}}));
+        let combined: Value = serde_json::from_str(&std::fs::read_to_string(&written[3]).unwrap()).unwrap();
+        assert_eq!(combined, definition().json_schema);
+
+        assert!(definition()
+            .check_schemas(dir.path().join("schemas"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_check_schemas_reports_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        definition().write_schemas(dir.path()).unwrap();
+        std::fs::remove_file(dir.path().join("secret.schema.json")).unwrap();
+
+        let changed = define_config(
+            Some(json!({"type": "object", "properties": {"alpha": {"type": "string"}}})),
+            None,
+            None,
+        );
+        let stale = changed.check_schemas(dir.path()).unwrap();
+        assert_eq!(
+            stale,
+            ["public.schema.json", "secret.schema.json", "schema.json"].map(|n| dir.path().join(n))
+        );
+    }
+}
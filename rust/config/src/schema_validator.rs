@@ -3,10 +3,13 @@
 //! Validates that a JSON Schema uses only the subset of keywords that all
 //! four language SDKs (TypeScript, Python, Rust, Go) can reliably support.
 
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// A single validation error with actionable context.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SchemaValidationError {
     pub path: String,
     pub keyword: String,
@@ -15,10 +18,116 @@ pub struct SchemaValidationError {
 }
 
 /// Result of schema validation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SchemaValidationResult {
     pub valid: bool,
     pub errors: Vec<SchemaValidationError>,
+    /// Unknown keywords, when [`SchemaValidationOptions::report_unknown_keywords`]
+    /// is set. Warnings don't make a schema invalid.
+    #[serde(default)]
+    pub warnings: Vec<SchemaValidationError>,
+}
+
+/// Options for [`validate_smooai_schema_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchemaValidationOptions {
+    /// Warn about keywords that are neither supported nor rejected (usually
+    /// typos like `minLenght`), with a did-you-mean suggestion. Standard
+    /// annotations (`examples`, `deprecated`, ...) and `x-` extensions are
+    /// never reported.
+    pub report_unknown_keywords: bool,
+}
+
+/// Machine-readable validation output shared by the SDKs' CI tooling:
+///
+/// ```json
+/// {
+///   "valid": false,
+///   "summary": { "errorCount": 2, "warningCount": 0, "byKeyword": { "if": 1, "patternProperties": 1 } },
+///   "errors": [{ "path": "/properties/region", "keyword": "if", "message": "...", "suggestion": "..." }],
+///   "warnings": []
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaValidationReport {
+    pub valid: bool,
+    pub summary: SchemaValidationSummary,
+    pub errors: Vec<SchemaValidationError>,
+    #[serde(default)]
+    pub warnings: Vec<SchemaValidationError>,
+}
+
+/// Counts for a [`SchemaValidationReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaValidationSummary {
+    pub error_count: usize,
+    #[serde(default)]
+    pub warning_count: usize,
+    /// Errors per rejected keyword, sorted by keyword.
+    pub by_keyword: BTreeMap<String, usize>,
+}
+
+impl SchemaValidationResult {
+    /// This result with summary counts.
+    pub fn report(&self) -> SchemaValidationReport {
+        let mut by_keyword = BTreeMap::new();
+        for error in &self.errors {
+            *by_keyword.entry(error.keyword.clone()).or_insert(0) += 1;
+        }
+        SchemaValidationReport {
+            valid: self.valid,
+            summary: SchemaValidationSummary {
+                error_count: self.errors.len(),
+                warning_count: self.warnings.len(),
+                by_keyword,
+            },
+            errors: self.errors.clone(),
+            warnings: self.warnings.clone(),
+        }
+    }
+
+    /// The [`report`](Self::report) as pretty-printed JSON.
+    pub fn to_json_report(&self) -> String {
+        serde_json::to_string_pretty(&self.report()).unwrap_or_default()
+    }
+}
+
+/// The JSON Schema dialect a schema declares with `$schema`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SchemaDialect {
+    Draft04,
+    Draft06,
+    Draft07,
+    Draft2019_09,
+    Draft2020_12,
+}
+
+impl SchemaDialect {
+    /// The dialect named by `schema`'s root `$schema`, if it's one we know.
+    pub fn detect(schema: &Value) -> Option<SchemaDialect> {
+        let uri = schema.get("$schema")?.as_str()?;
+        let uri = uri.trim_end_matches('#').trim_end_matches('/');
+        [
+            ("draft-04/schema", SchemaDialect::Draft04),
+            ("draft-06/schema", SchemaDialect::Draft06),
+            ("draft-07/schema", SchemaDialect::Draft07),
+            ("draft/2019-09/schema", SchemaDialect::Draft2019_09),
+            ("draft/2020-12/schema", SchemaDialect::Draft2020_12),
+        ]
+        .into_iter()
+        .find(|(suffix, _)| uri.ends_with(suffix))
+        .map(|(_, dialect)| dialect)
+    }
+
+    /// Drafts before 2019-09, where keywords next to `$ref` are ignored.
+    fn ignores_ref_siblings(self) -> bool {
+        matches!(
+            self,
+            SchemaDialect::Draft04 | SchemaDialect::Draft06 | SchemaDialect::Draft07
+        )
+    }
 }
 
 /// Keywords supported across all four SDK languages.
@@ -62,6 +171,19 @@ const SUPPORTED_KEYWORDS: &[&str] = &[
     "definitions",
 ];
 
+/// Standard annotations that carry no validation semantics; never reported as unknown.
+const ANNOTATION_KEYWORDS: &[&str] = &[
+    "$id",
+    "$comment",
+    "examples",
+    "deprecated",
+    "deprecatedSince",
+    "readOnly",
+    "writeOnly",
+    "contentEncoding",
+    "contentMediaType",
+];
+
 /// Formats supported across all four SDKs.
 const SUPPORTED_FORMATS: &[&str] = &["email", "uri", "uuid", "date-time", "ipv4", "ipv6"];
 
@@ -113,6 +235,11 @@ const REJECTED_KEYWORDS: &[RejectedKeyword] = &[
         message: "\"not\" is not supported across all SDK languages.",
         suggestion: "Express the constraint positively using \"enum\", \"oneOf\", or validation in application code.",
     },
+    RejectedKeyword {
+        keyword: "additionalItems",
+        message: "\"additionalItems\" only applies to array-form \"items\" before 2020-12 and is not supported across all SDK languages.",
+        suggestion: "Use a single schema for \"items\" instead of a positional tuple.",
+    },
     RejectedKeyword {
         keyword: "prefixItems",
         message: "\"prefixItems\" (tuple validation) is not supported across all SDK languages.",
@@ -132,24 +259,139 @@ const REJECTED_KEYWORDS: &[RejectedKeyword] = &[
 
 /// Validate that a JSON Schema uses only the cross-language-compatible subset.
 pub fn validate_smooai_schema(schema: &Value) -> SchemaValidationResult {
+    validate_smooai_schema_with(schema, SchemaValidationOptions::default())
+}
+
+/// [`validate_smooai_schema`] with options, e.g. to warn about unknown keywords.
+pub fn validate_smooai_schema_with(schema: &Value, options: SchemaValidationOptions) -> SchemaValidationResult {
     let mut errors = Vec::new();
-    walk_schema(schema, "", &mut errors);
+    let mut warnings = Vec::new();
+    let mut unknown = options.report_unknown_keywords.then_some(&mut warnings);
+    let dialect = SchemaDialect::detect(schema);
+    walk_schema(schema, "", dialect, &mut errors, &mut unknown);
     SchemaValidationResult {
         valid: errors.is_empty(),
         errors,
+        warnings,
+    }
+}
+
+/// Flag constructs whose meaning differs between draft-07 (and earlier) and
+/// 2020-12, which the SDKs implement.
+fn check_draft_differences(
+    obj: &serde_json::Map<String, Value>,
+    path: &str,
+    dialect: Option<SchemaDialect>,
+    errors: &mut Vec<SchemaValidationError>,
+) {
+    let mut flag = |keyword: &str, message: String, suggestion: &str| {
+        errors.push(SchemaValidationError {
+            path: path.to_string(),
+            keyword: keyword.to_string(),
+            message,
+            suggestion: suggestion.to_string(),
+        })
+    };
+
+    if obj.get("items").is_some_and(|items| items.is_array()) {
+        flag(
+            "items",
+            "Array-form \"items\" is tuple validation in draft-07 but invalid in 2020-12, where tuples use \"prefixItems\"."
+                .to_string(),
+            "Use a single schema for \"items\", or an \"object\" with named fields instead of a positional tuple.",
+        );
+    }
+    for keyword in ["exclusiveMinimum", "exclusiveMaximum"] {
+        if obj.get(keyword).is_some_and(|v| v.is_boolean()) {
+            let bound = if keyword == "exclusiveMinimum" {
+                "minimum"
+            } else {
+                "maximum"
+            };
+            flag(
+                keyword,
+                format!(
+                    "Boolean \"{}\" is draft-04 syntax; since draft-06 it is a number.",
+                    keyword
+                ),
+                &format!(
+                    "Replace \"{}\": N with \"{}\": true by \"{}\": N.",
+                    bound, keyword, keyword
+                ),
+            );
+        }
     }
+    if dialect.is_some_and(SchemaDialect::ignores_ref_siblings) && obj.contains_key("$ref") {
+        let siblings: Vec<&str> = obj
+            .keys()
+            .map(String::as_str)
+            .filter(|k| {
+                !matches!(
+                    *k,
+                    "$ref" | "$schema" | "title" | "description" | "$comment" | "definitions" | "$defs"
+                )
+            })
+            .collect();
+        if !siblings.is_empty() {
+            flag(
+                "$ref",
+                format!(
+                    "Keywords next to \"$ref\" ({}) are ignored before 2019-09 but applied in 2020-12.",
+                    siblings.join(", ")
+                ),
+                "Wrap the reference in \"allOf\": [{\"$ref\": ...}] to keep the draft-07 meaning, or move the keywords into the referenced definition.",
+            );
+        }
+    }
+}
+
+/// The closest known keyword to `keyword`, if it's a plausible typo.
+fn did_you_mean(keyword: &str) -> Option<&'static str> {
+    SUPPORTED_KEYWORDS
+        .iter()
+        .copied()
+        .chain(REJECTED_KEYWORDS.iter().map(|r| r.keyword))
+        .map(|known| (edit_distance(keyword, known), known))
+        .filter(|(distance, known)| *distance <= (known.len() / 3).clamp(1, 3))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance, compared case-insensitively.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 fn find_rejected(keyword: &str) -> Option<&'static RejectedKeyword> {
     REJECTED_KEYWORDS.iter().find(|r| r.keyword == keyword)
 }
 
-fn walk_schema(node: &Value, path: &str, errors: &mut Vec<SchemaValidationError>) {
+fn walk_schema(
+    node: &Value,
+    path: &str,
+    dialect: Option<SchemaDialect>,
+    errors: &mut Vec<SchemaValidationError>,
+    unknown: &mut Option<&mut Vec<SchemaValidationError>>,
+) {
     let obj = match node.as_object() {
         Some(o) => o,
         None => return,
     };
 
+    let node_path = if path.is_empty() { "/" } else { path };
+    check_draft_differences(obj, node_path, dialect, errors);
+
     for key in obj.keys() {
         // Check for rejected keywords first
         if let Some(rejected) = find_rejected(key) {
@@ -190,24 +432,54 @@ fn walk_schema(node: &Value, path: &str, errors: &mut Vec<SchemaValidationError>
             }
             continue;
         }
+
+        if let Some(warnings) = unknown.as_deref_mut() {
+            if !ANNOTATION_KEYWORDS.contains(&key.as_str()) && !key.starts_with("x-") {
+                warnings.push(SchemaValidationError {
+                    path: if path.is_empty() {
+                        "/".to_string()
+                    } else {
+                        path.to_string()
+                    },
+                    keyword: key.clone(),
+                    message: format!("Unknown keyword \"{}\" is ignored by every SDK.", key),
+                    suggestion: match did_you_mean(key) {
+                        Some(known) => format!("Did you mean \"{}\"?", known),
+                        None => "Remove it, or prefix custom annotations with \"x-\".".to_string(),
+                    },
+                });
+            }
+        }
     }
 
     // Recurse into sub-schemas
     if let Some(props) = obj.get("properties").and_then(|v| v.as_object()) {
         for (prop_name, prop_schema) in props {
-            walk_schema(prop_schema, &format!("{}/properties/{}", path, prop_name), errors);
+            walk_schema(
+                prop_schema,
+                &format!("{}/properties/{}", path, prop_name),
+                dialect,
+                errors,
+                unknown,
+            );
         }
     }
 
     if let Some(items) = obj.get("items") {
         if items.is_object() {
-            walk_schema(items, &format!("{}/items", path), errors);
+            walk_schema(items, &format!("{}/items", path), dialect, errors, unknown);
         }
     }
 
     if let Some(additional) = obj.get("additionalProperties") {
         if additional.is_object() && !additional.is_boolean() {
-            walk_schema(additional, &format!("{}/additionalProperties", path), errors);
+            walk_schema(
+                additional,
+                &format!("{}/additionalProperties", path),
+                dialect,
+                errors,
+                unknown,
+            );
         }
     }
 
@@ -215,7 +487,13 @@ fn walk_schema(node: &Value, path: &str, errors: &mut Vec<SchemaValidationError>
     for comp_key in &["anyOf", "oneOf", "allOf"] {
         if let Some(arr) = obj.get(*comp_key).and_then(|v| v.as_array()) {
             for (i, sub_schema) in arr.iter().enumerate() {
-                walk_schema(sub_schema, &format!("{}/{}/{}", path, comp_key, i), errors);
+                walk_schema(
+                    sub_schema,
+                    &format!("{}/{}/{}", path, comp_key, i),
+                    dialect,
+                    errors,
+                    unknown,
+                );
             }
         }
     }
@@ -224,7 +502,13 @@ fn walk_schema(node: &Value, path: &str, errors: &mut Vec<SchemaValidationError>
     for defs_key in &["$defs", "definitions"] {
         if let Some(defs) = obj.get(*defs_key).and_then(|v| v.as_object()) {
             for (def_name, def_schema) in defs {
-                walk_schema(def_schema, &format!("{}/{}/{}", path, defs_key, def_name), errors);
+                walk_schema(
+                    def_schema,
+                    &format!("{}/{}/{}", path, defs_key, def_name),
+                    dialect,
+                    errors,
+                    unknown,
+                );
             }
         }
     }
@@ -328,6 +612,133 @@ mod tests {
         assert!(result.errors[0].message.contains("hostname"));
     }
 
+    #[test]
+    fn test_json_report() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "string", "format": "hostname" },
+                "b": { "type": "string", "format": "idn-email" },
+                "c": { "not": { "type": "string" } }
+            }
+        });
+        let result = validate_smooai_schema(&schema);
+        let report: Value = serde_json::from_str(&result.to_json_report()).unwrap();
+        assert_eq!(report["valid"], json!(false));
+        assert_eq!(
+            report["summary"],
+            json!({"errorCount": 3, "warningCount": 0, "byKeyword": {"format": 2, "not": 1}})
+        );
+        assert_eq!(report["errors"][2]["path"], json!("/properties/c"));
+
+        let round_trip: SchemaValidationReport = serde_json::from_value(report).unwrap();
+        assert_eq!(round_trip.errors, result.errors);
+        assert_eq!(
+            validate_smooai_schema(&json!({})).report().summary,
+            SchemaValidationSummary {
+                error_count: 0,
+                warning_count: 0,
+                by_keyword: BTreeMap::new()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_keywords_reported_with_suggestion() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLenght": 2, "examples": ["a"], "x-secret": true },
+                "tags": { "type": "array", "itemz": { "type": "string" }, "frobnicate": true }
+            }
+        });
+        assert!(validate_smooai_schema(&schema).warnings.is_empty());
+
+        let result = validate_smooai_schema_with(
+            &schema,
+            SchemaValidationOptions {
+                report_unknown_keywords: true,
+            },
+        );
+        assert!(result.valid);
+        let warnings: Vec<(&str, &str, &str)> = result
+            .warnings
+            .iter()
+            .map(|w| (w.path.as_str(), w.keyword.as_str(), w.suggestion.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                ("/properties/name", "minLenght", "Did you mean \"minLength\"?"),
+                (
+                    "/properties/tags",
+                    "frobnicate",
+                    "Remove it, or prefix custom annotations with \"x-\"."
+                ),
+                ("/properties/tags", "itemz", "Did you mean \"items\"?"),
+            ]
+        );
+        assert_eq!(result.report().summary.warning_count, 3);
+    }
+
+    #[test]
+    fn test_detect_dialect() {
+        let detect = |uri: &str| SchemaDialect::detect(&json!({ "$schema": uri }));
+        assert_eq!(
+            detect("http://json-schema.org/draft-07/schema#"),
+            Some(SchemaDialect::Draft07)
+        );
+        assert_eq!(
+            detect("http://json-schema.org/draft-04/schema"),
+            Some(SchemaDialect::Draft04)
+        );
+        assert_eq!(
+            detect("https://json-schema.org/draft/2020-12/schema"),
+            Some(SchemaDialect::Draft2020_12)
+        );
+        assert_eq!(detect("https://example.com/custom"), None);
+        assert_eq!(SchemaDialect::detect(&json!({})), None);
+    }
+
+    #[test]
+    fn test_flags_draft_07_constructs() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {
+                "pair": { "type": "array", "items": [{ "type": "string" }, { "type": "integer" }], "additionalItems": false },
+                "port": { "type": "integer", "minimum": 1, "exclusiveMinimum": true },
+                "db": { "$ref": "#/definitions/Db", "default": {} },
+                "cache": { "$ref": "#/definitions/Db", "description": "annotations are fine" }
+            },
+            "definitions": { "Db": { "type": "object" } }
+        });
+        let result = validate_smooai_schema(&schema);
+        let found: Vec<(&str, &str)> = result
+            .errors
+            .iter()
+            .map(|e| (e.path.as_str(), e.keyword.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("/properties/db", "$ref"),
+                ("/properties/pair", "items"),
+                ("/properties/pair", "additionalItems"),
+                ("/properties/port", "exclusiveMinimum"),
+            ]
+        );
+        assert!(result.errors[0].message.contains("(default)"));
+
+        // In 2020-12, keywords next to $ref apply as written.
+        let mut modern = schema.clone();
+        modern["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+        assert!(!validate_smooai_schema(&modern)
+            .errors
+            .iter()
+            .any(|e| e.keyword == "$ref"));
+    }
+
     #[test]
     fn test_empty_schema() {
         let result = validate_smooai_schema(&json!({}));
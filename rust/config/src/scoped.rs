@@ -0,0 +1,179 @@
+//! Prefix-scoped views of a [`ConfigManager`].
+//!
+//! A shared library that owns a config namespace reads through
+//! [`ConfigManager::scoped`] instead of hard-coding the prefix at every call
+//! site:
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use smooai_config::ConfigManager;
+//! use serde_json::json;
+//!
+//! let manager = ConfigManager::from_values(HashMap::from([
+//!     ("PAYMENTS_API_URL".to_string(), json!("https://pay.example.com")),
+//!     ("PAYMENTS_RETRIES".to_string(), json!(3)),
+//!     ("SEARCH_API_URL".to_string(), json!("https://search.example.com")),
+//! ]));
+//! let payments = manager.scoped("PAYMENTS_");
+//! assert_eq!(payments.get_public_config("API_URL").unwrap(), Some(json!("https://pay.example.com")));
+//! assert_eq!(payments.keys().unwrap(), ["API_URL", "RETRIES"]);
+//! ```
+//!
+//! Errors, access recording and access policies all see the full key.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::config_manager::ConfigManager;
+use crate::provider::ConfigProvider;
+use crate::secret::SecretValue;
+use crate::utils::SmooaiConfigError;
+
+/// A [`ConfigManager`] view whose getters prepend a fixed prefix.
+#[derive(Clone, Copy)]
+pub struct ScopedConfig<'a> {
+    manager: &'a ConfigManager,
+    prefix: &'a str,
+}
+
+impl<'a> ScopedConfig<'a> {
+    pub(crate) fn new(manager: &'a ConfigManager, prefix: &'a str) -> Self {
+        Self { manager, prefix }
+    }
+
+    /// The prefix prepended to every key.
+    pub fn prefix(&self) -> &str {
+        self.prefix
+    }
+
+    /// The manager this view reads from.
+    pub fn manager(&self) -> &'a ConfigManager {
+        self.manager
+    }
+
+    /// The full key for `key` in this scope.
+    pub fn full_key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    /// Retrieve a public config value.
+    pub fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        self.manager.get_public_config(&self.full_key(key))
+    }
+
+    /// Retrieve a public config value deserialized into `T`.
+    pub fn get_public_config_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SmooaiConfigError> {
+        self.manager.get_public_config_as(&self.full_key(key))
+    }
+
+    /// Retrieve a secret config value.
+    pub fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        self.manager.get_secret_config(&self.full_key(key))
+    }
+
+    /// Retrieve a feature flag value.
+    pub fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        self.manager.get_feature_flag(&self.full_key(key))
+    }
+
+    /// Retrieve a feature flag value deserialized into `T`.
+    pub fn get_feature_flag_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SmooaiConfigError> {
+        self.manager.get_feature_flag_as(&self.full_key(key))
+    }
+
+    /// Whether the feature flag `key` is on. See [`ConfigManager::is_enabled`].
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.manager.is_enabled(&self.full_key(key))
+    }
+
+    /// Every key under the prefix, with the prefix stripped, sorted. Keys the
+    /// manager's access policy hides are left out.
+    pub fn keys(&self) -> Result<Vec<String>, SmooaiConfigError> {
+        Ok(self
+            .manager
+            .keys_with_prefix(self.prefix)?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(self.prefix).map(str::to_string))
+            .filter(|key| !key.is_empty())
+            .collect())
+    }
+}
+
+impl std::fmt::Debug for ScopedConfig<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScopedConfig").field("prefix", &self.prefix).finish()
+    }
+}
+
+impl ConfigProvider for ScopedConfig<'_> {
+    fn get_public_config(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        ScopedConfig::get_public_config(self, key)
+    }
+
+    fn get_secret_config(&self, key: &str) -> Result<Option<SecretValue>, SmooaiConfigError> {
+        ScopedConfig::get_secret_config(self, key)
+    }
+
+    fn get_feature_flag(&self, key: &str) -> Result<Option<Value>, SmooaiConfigError> {
+        ScopedConfig::get_feature_flag(self, key)
+    }
+
+    fn is_enabled(&self, key: &str) -> bool {
+        ScopedConfig::is_enabled(self, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn manager() -> ConfigManager {
+        ConfigManager::from_values(HashMap::from([
+            ("PAYMENTS_API_URL".to_string(), json!("https://pay")),
+            ("PAYMENTS_API_KEY".to_string(), json!("pk_live")),
+            ("PAYMENTS_NEW_CHECKOUT".to_string(), json!(true)),
+            ("PAYMENTS_".to_string(), json!("bare prefix")),
+            ("SEARCH_API_URL".to_string(), json!("https://search")),
+        ]))
+    }
+
+    #[test]
+    fn test_scoped_getters_prepend_prefix() {
+        let manager = manager();
+        let payments = manager.scoped("PAYMENTS_");
+        assert_eq!(
+            payments.get_public_config("API_URL").unwrap(),
+            Some(json!("https://pay"))
+        );
+        assert_eq!(
+            payments.get_secret_config("API_KEY").unwrap().unwrap().expose(),
+            &json!("pk_live")
+        );
+        assert!(payments.is_enabled("NEW_CHECKOUT"));
+        assert_eq!(payments.get_public_config("SEARCH_API_URL").unwrap(), None);
+        assert_eq!(
+            payments.get_public_config_as::<String>("API_URL").unwrap().as_deref(),
+            Some("https://pay")
+        );
+
+        let provider: &dyn ConfigProvider = &payments;
+        assert_eq!(
+            provider.get_public_config("API_URL").unwrap(),
+            Some(json!("https://pay"))
+        );
+    }
+
+    #[test]
+    fn test_scoped_keys_strip_prefix() {
+        let manager = manager();
+        assert_eq!(
+            manager.scoped("PAYMENTS_").keys().unwrap(),
+            ["API_KEY", "API_URL", "NEW_CHECKOUT"]
+        );
+        assert!(manager.scoped("BILLING_").keys().unwrap().is_empty());
+    }
+}
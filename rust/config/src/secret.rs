@@ -0,0 +1,173 @@
+//! Redacting wrappers for secret config values.
+//!
+//! [`ConfigManager::get_secret_config`](crate::ConfigManager::get_secret_config)
+//! returns a [`SecretValue`] rather than a bare `serde_json::Value`, so a stray
+//! `{:?}` of a config struct prints `<redacted>` instead of the secret:
+//!
+//! ```rust,ignore
+//! let password = manager.get_secret_config("DB_PASSWORD")?.expect("declared");
+//! println!("{:?}", password);                 // Secret(<redacted>)
+//! connect(password.expose_str().unwrap());    // explicit opt-in to the plaintext
+//! ```
+//!
+//! The wrapped strings are zeroized when the wrapper is dropped. This covers
+//! the copy handed to the caller; anything the caller copies out with
+//! `expose` is theirs to manage.
+
+use std::fmt;
+
+use serde_json::Value;
+use zeroize::Zeroize;
+
+/// Placeholder that replaces secret values in [`ConfigManager::get_all`](crate::ConfigManager::get_all),
+/// [`ConfigManager::explain`](crate::ConfigManager::explain) and change events.
+pub const REDACTED: &str = "<redacted>";
+
+/// Types whose contents can be wiped in place.
+pub trait Wipe {
+    /// Overwrite any secret material held by `self`.
+    fn wipe(&mut self);
+}
+
+impl Wipe for String {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for Value {
+    fn wipe(&mut self) {
+        match self {
+            Value::String(s) => s.zeroize(),
+            Value::Array(items) => items.iter_mut().for_each(Wipe::wipe),
+            Value::Object(map) => map.values_mut().for_each(Wipe::wipe),
+            // Numbers and booleans can't be wiped in place; drop them normally.
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+        *self = Value::Null;
+    }
+}
+
+/// A secret that redacts itself in `Debug` / `Display` and is wiped on drop.
+///
+/// The plaintext is only reachable through [`expose`](Self::expose) and
+/// friends, which makes every use easy to find in review.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret<T: Wipe>(T);
+
+/// A secret config value of any JSON type.
+pub type SecretValue = Secret<Value>;
+
+/// A secret string.
+pub type SecretString = Secret<String>;
+
+impl<T: Wipe> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Borrow the plaintext.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Take the plaintext out of the wrapper. The caller becomes responsible
+    /// for not logging it.
+    pub fn into_exposed(mut self) -> T
+    where
+        T: Default,
+    {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl SecretValue {
+    /// Borrow the plaintext as a string, if the secret is a JSON string.
+    pub fn expose_str(&self) -> Option<&str> {
+        self.0.as_str()
+    }
+
+    /// Whether the secret is JSON `null`.
+    pub fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Convert a string secret into a [`SecretString`], keeping it wrapped.
+    pub fn into_secret_string(mut self) -> Option<SecretString> {
+        match std::mem::take(&mut self.0) {
+            Value::String(s) => Some(Secret(s)),
+            mut other => {
+                other.wipe();
+                None
+            }
+        }
+    }
+}
+
+impl<T: Wipe> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(<redacted>)")
+    }
+}
+
+impl<T: Wipe> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_debug_and_display_are_redacted() {
+        let value = SecretValue::new(json!({"password": "hunter2"}));
+        let string = SecretString::new("hunter2".to_string());
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Config {
+            db: SecretValue,
+        }
+
+        for rendered in [
+            format!("{:?}", value),
+            format!("{}", value),
+            format!("{:?}", string),
+            format!("{:#?}", Config { db: value.clone() }),
+        ] {
+            assert!(!rendered.contains("hunter2"), "{}", rendered);
+            assert!(rendered.contains("<redacted>"));
+        }
+        assert_eq!(format!("{:?}", string), "Secret(<redacted>)");
+    }
+
+    #[test]
+    fn test_expose_and_conversions() {
+        let value = SecretValue::new(json!("sk_live"));
+        assert_eq!(value.expose_str(), Some("sk_live"));
+        assert_eq!(value.clone().into_exposed(), json!("sk_live"));
+        assert_eq!(value.into_secret_string().unwrap().expose(), "sk_live");
+        assert!(SecretValue::new(json!(3)).into_secret_string().is_none());
+    }
+
+    #[test]
+    fn test_wipe_clears_nested_strings() {
+        let mut value = json!({"a": ["x", {"b": "y"}], "n": 1});
+        value.wipe();
+        assert_eq!(value, Value::Null);
+    }
+}
@@ -0,0 +1,293 @@
+//! AWS Secrets Manager source for the secret tier (feature `secrets-manager`).
+//!
+//! Resolves each configured secret (name or ARN) with `GetSecretValue` and
+//! merges the result into the config:
+//!
+//! - a `SecretString` holding a JSON object contributes each of its fields as
+//!   a top-level key (the usual RDS / key-value secret shape);
+//! - anything else (plain strings, arrays, `SecretBinary`) needs a target key
+//!   via [`SecretsManagerSource::with_secret_as`]; binary secrets are stored
+//!   base64-encoded, ready for `get_bytes_decoded`.
+//!
+//! Credentials come from the default AWS chain, so IAM roles (ECS task role,
+//! EC2 instance profile) work without any secrets in env vars or files.
+//! Values default to a 5 minute TTL so rotated secrets are picked up well
+//! before the regular config cache would expire.
+//!
+//! ```rust,ignore
+//! use smooai_config::{ConfigManager, SecretsManagerSource};
+//!
+//! let manager = ConfigManager::new().with_source(
+//!     SecretsManagerSource::new()
+//!         .with_secret("prod/myapp/database")
+//!         .with_secret_as("prod/myapp/stripe-key", "STRIPE_SECRET_KEY"),
+//! );
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::aws::{json_rpc, resolve_region, AwsCredentials};
+use crate::source::ConfigSource;
+use crate::utils::SmooaiConfigError;
+
+/// Default freshness for loaded secrets.
+pub const DEFAULT_SECRETS_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Hash)]
+struct SecretRef {
+    secret_id: String,
+    /// Store the whole secret under this key instead of spreading its fields.
+    key: Option<String>,
+}
+
+/// Config source backed by AWS Secrets Manager.
+#[derive(Debug, Clone)]
+pub struct SecretsManagerSource {
+    secrets: Vec<SecretRef>,
+    region: Option<String>,
+    endpoint: Option<String>,
+    credentials: Option<AwsCredentials>,
+    ttl: Duration,
+}
+
+impl SecretsManagerSource {
+    pub fn new() -> Self {
+        Self {
+            secrets: Vec::new(),
+            region: None,
+            endpoint: None,
+            credentials: None,
+            ttl: DEFAULT_SECRETS_TTL,
+        }
+    }
+
+    /// Resolve a JSON-object secret and merge its fields as top-level keys.
+    pub fn with_secret(mut self, secret_id: &str) -> Self {
+        self.secrets.push(SecretRef {
+            secret_id: secret_id.to_string(),
+            key: None,
+        });
+        self
+    }
+
+    /// Resolve a secret and store its whole value under `key`.
+    ///
+    /// A `SecretString` is kept as the exact string unless it holds a JSON
+    /// object or array, which is stored parsed.
+    pub fn with_secret_as(mut self, secret_id: &str, key: &str) -> Self {
+        self.secrets.push(SecretRef {
+            secret_id: secret_id.to_string(),
+            key: Some(key.to_string()),
+        });
+        self
+    }
+
+    /// Set the region (default: `AWS_REGION` / `AWS_DEFAULT_REGION`).
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Override the endpoint URL (VPC endpoints, LocalStack, tests).
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Use explicit credentials instead of the default chain.
+    pub fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// How long resolved secrets stay fresh (default 5 minutes).
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl Default for SecretsManagerSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigSource for SecretsManagerSource {
+    fn name(&self) -> String {
+        "secrets-manager".to_string()
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        (&self.secrets, &self.region, &self.endpoint, &self.credentials, self.ttl).hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        if self.secrets.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let region = resolve_region(self.region.as_deref(), env)?;
+        let credentials = AwsCredentials::resolve(self.credentials.as_ref(), env)?;
+        let endpoint = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://secretsmanager.{}.amazonaws.com/", region));
+
+        let mut values = HashMap::new();
+        for secret in &self.secrets {
+            let response = json_rpc(
+                &endpoint,
+                &region,
+                "secretsmanager",
+                "secretsmanager.GetSecretValue",
+                &json!({ "SecretId": secret.secret_id }),
+                &credentials,
+            )
+            .map_err(|e| {
+                let message = format!(
+                    "Secret '{}': {}",
+                    secret.secret_id,
+                    e.message.trim_start_matches("[Smooai Config] ")
+                );
+                SmooaiConfigError::new(&message)
+                    .with_kind(e.kind.clone())
+                    .with_source(e)
+            })?;
+
+            let value = if let Some(text) = response.get("SecretString").and_then(|s| s.as_str()) {
+                // A keyed secret stays a string unless it holds a JSON object
+                // or array, so "0123" or "true" aren't turned into numbers.
+                match serde_json::from_str::<Value>(text) {
+                    Ok(parsed @ (Value::Object(_) | Value::Array(_))) => parsed,
+                    _ => Value::String(text.to_string()),
+                }
+            } else if let Some(binary) = response.get("SecretBinary").and_then(|b| b.as_str()) {
+                // The JSON protocol already delivers binary secrets base64-encoded.
+                Value::String(binary.to_string())
+            } else {
+                return Err(SmooaiConfigError::new(&format!(
+                    "Secret '{}' has no SecretString or SecretBinary",
+                    secret.secret_id
+                )));
+            };
+
+            match (&secret.key, value) {
+                (Some(key), value) => {
+                    values.insert(key.clone(), value);
+                }
+                (None, Value::Object(fields)) => values.extend(fields),
+                (None, _) => {
+                    return Err(SmooaiConfigError::new(&format!(
+                        "Secret '{}' is not a JSON object; register it with with_secret_as(id, key)",
+                        secret.secret_id
+                    )));
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    fn ttl(&self) -> Option<Duration> {
+        Some(self.ttl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_secret(server: &MockServer, id: &str, response: Value) {
+        Mock::given(method("POST"))
+            .and(header("x-amz-target", "secretsmanager.GetSecretValue"))
+            .and(body_json(json!({ "SecretId": id })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(server)
+            .await;
+    }
+
+    fn source(uri: &str) -> SecretsManagerSource {
+        SecretsManagerSource::new()
+            .with_region("us-east-1")
+            .with_endpoint(uri)
+            .with_credentials(AwsCredentials::new("AKID", "secret", Some("session")))
+    }
+
+    #[tokio::test]
+    async fn test_load_spreads_objects_and_keys_scalars() {
+        let server = MockServer::start().await;
+        mock_secret(
+            &server,
+            "prod/db",
+            json!({"SecretString": r#"{"DB_USER":"app","DB_PASSWORD":"pw"}"#}),
+        )
+        .await;
+        mock_secret(&server, "prod/stripe", json!({"SecretString": "sk_live_123"})).await;
+        mock_secret(&server, "prod/cert", json!({"SecretBinary": "AQID"})).await;
+        mock_secret(&server, "prod/pin", json!({"SecretString": "0123"})).await;
+        mock_secret(&server, "prod/flag", json!({"SecretString": "true"})).await;
+        mock_secret(&server, "prod/hosts", json!({"SecretString": r#"["a","b"]"#})).await;
+
+        let uri = server.uri();
+        let values = tokio::task::spawn_blocking(move || {
+            source(&uri)
+                .with_secret("prod/db")
+                .with_secret_as("prod/stripe", "STRIPE_KEY")
+                .with_secret_as("prod/cert", "CERT")
+                .with_secret_as("prod/pin", "PIN")
+                .with_secret_as("prod/flag", "FLAG")
+                .with_secret_as("prod/hosts", "HOSTS")
+                .load(&HashMap::new())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(values["DB_USER"], json!("app"));
+        assert_eq!(values["DB_PASSWORD"], json!("pw"));
+        assert_eq!(values["STRIPE_KEY"], json!("sk_live_123"));
+        assert_eq!(values["CERT"], json!("AQID"));
+        // Keyed scalars keep their exact text; JSON structures are parsed.
+        assert_eq!(values["PIN"], json!("0123"));
+        assert_eq!(values["FLAG"], json!("true"));
+        assert_eq!(values["HOSTS"], json!(["a", "b"]));
+    }
+
+    #[tokio::test]
+    async fn test_scalar_secret_without_key_is_an_error() {
+        let server = MockServer::start().await;
+        mock_secret(&server, "plain", json!({"SecretString": "just-a-token"})).await;
+
+        let uri = server.uri();
+        let err = tokio::task::spawn_blocking(move || source(&uri).with_secret("plain").load(&HashMap::new()))
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(err.message.contains("with_secret_as"));
+        assert!(!err.message.contains("just-a-token"));
+    }
+
+    #[test]
+    fn test_fingerprint_covers_secrets_and_region() {
+        let base = || SecretsManagerSource::new().with_secret("app/db");
+        assert_eq!(base().fingerprint(), base().fingerprint());
+        assert_ne!(base().fingerprint(), base().with_region("eu-west-1").fingerprint());
+        assert_ne!(
+            base().fingerprint(),
+            SecretsManagerSource::new().with_secret("app/other").fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_default_ttl_is_shorter_than_cache() {
+        assert_eq!(SecretsManagerSource::new().ttl(), Some(DEFAULT_SECRETS_TTL));
+    }
+}
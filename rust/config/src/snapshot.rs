@@ -0,0 +1,214 @@
+//! Binary snapshots of the merged config for fast cold starts.
+//!
+//! With [`ConfigManager::with_snapshot`](crate::ConfigManager::with_snapshot)
+//! every successful load writes the merged config to disk in bincode. On the
+//! next start,
+//! [`init_from_snapshot`](crate::ConfigManager::init_from_snapshot) serves
+//! that snapshot straight away, skipping the JSON parsing and the remote
+//! fetch, and reloads from the real sources on a background thread.
+//!
+//! A snapshot is only used while it is younger than its max age and was
+//! written by a manager configured the same way (environment, API
+//! credentials, sources, overrides, ...); anything else falls back to a
+//! normal load. Like [`LastKnownGood`](crate::LastKnownGood), it is created
+//! with mode `0600` on Unix and holds secret values in plaintext unless the
+//! manager has [disk encryption](crate::at_rest).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+use crate::at_rest::{AtRestEncryption, SealedSecrets};
+use crate::last_known_good::{io_error, open_secrets, write_private};
+use crate::utils::SmooaiConfigError;
+
+/// Bumped whenever the on-disk layout changes; older snapshots are ignored.
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    /// Seconds since the Unix epoch when the config was loaded.
+    saved_at: u64,
+    /// Fingerprint of the manager that wrote it.
+    fingerprint: u64,
+    values: Vec<(String, SnapshotValue)>,
+    /// Secret values, when saved with encryption; `values` then holds the rest.
+    secrets: Option<SealedSecrets>,
+}
+
+/// `serde_json::Value` in a shape bincode can read back: bincode isn't
+/// self-describing, so `Value`'s own `Deserialize` can't be used.
+#[derive(Serialize, Deserialize)]
+enum SnapshotValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<SnapshotValue>),
+    Object(Vec<(String, SnapshotValue)>),
+}
+
+impl From<&Value> for SnapshotValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Null => SnapshotValue::Null,
+            Value::Bool(b) => SnapshotValue::Bool(*b),
+            Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => SnapshotValue::Int(i),
+                (None, Some(u)) => SnapshotValue::UInt(u),
+                _ => SnapshotValue::Float(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => SnapshotValue::String(s.clone()),
+            Value::Array(items) => SnapshotValue::Array(items.iter().map(Into::into).collect()),
+            Value::Object(map) => SnapshotValue::Object(map.iter().map(|(k, v)| (k.clone(), v.into())).collect()),
+        }
+    }
+}
+
+impl From<SnapshotValue> for Value {
+    fn from(value: SnapshotValue) -> Self {
+        match value {
+            SnapshotValue::Null => Value::Null,
+            SnapshotValue::Bool(b) => Value::Bool(b),
+            SnapshotValue::Int(i) => Value::from(i),
+            SnapshotValue::UInt(u) => Value::from(u),
+            SnapshotValue::Float(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+            SnapshotValue::String(s) => Value::String(s),
+            SnapshotValue::Array(items) => Value::Array(items.into_iter().map(Into::into).collect()),
+            SnapshotValue::Object(entries) => Value::Object(entries.into_iter().map(|(k, v)| (k, v.into())).collect()),
+        }
+    }
+}
+
+/// Where the snapshot lives and how old it may be before it's ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigSnapshot {
+    path: PathBuf,
+    max_age: Duration,
+}
+
+impl ConfigSnapshot {
+    pub fn new(path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        Self {
+            path: path.into(),
+            max_age,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Persist `values` for a manager with `fingerprint`, replacing any
+    /// previous snapshot atomically. Secret values are sealed with
+    /// `encryption` if given.
+    pub fn save(
+        &self,
+        fingerprint: u64,
+        values: &HashMap<String, Value>,
+        encryption: Option<&AtRestEncryption>,
+    ) -> Result<(), SmooaiConfigError> {
+        let (values, secrets) = match encryption {
+            Some(encryption) => encryption.seal(values).map(|(public, sealed)| (public, Some(sealed)))?,
+            None => (values.clone(), None),
+        };
+        let snapshot = Snapshot {
+            version: FORMAT_VERSION,
+            saved_at: now_secs(),
+            fingerprint,
+            values: values.iter().map(|(k, v)| (k.clone(), v.into())).collect(),
+            secrets,
+        };
+        let body = bincode::serialize(&snapshot)
+            .map_err(|e| SmooaiConfigError::new(&format!("Failed to serialize snapshot: {}", e)))?;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        write_private(&tmp, &body).map_err(|e| io_error(&tmp, e))?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| io_error(&self.path, e))
+    }
+
+    /// The saved values, unless the snapshot is missing, unreadable, older
+    /// than `max_age`, was written by a manager with another fingerprint or
+    /// has secrets `encryption` can't open.
+    pub fn load(&self, fingerprint: u64, encryption: Option<&AtRestEncryption>) -> Option<HashMap<String, Value>> {
+        let body = std::fs::read(&self.path).ok()?;
+        let snapshot: Snapshot = bincode::deserialize(&body).ok()?;
+        let age = Duration::from_secs(now_secs().saturating_sub(snapshot.saved_at));
+        if snapshot.version != FORMAT_VERSION || snapshot.fingerprint != fingerprint || age > self.max_age {
+            return None;
+        }
+        let values = snapshot.values.into_iter().map(|(k, v)| (k, v.into())).collect();
+        open_secrets(values, snapshot.secrets.as_ref(), encryption)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = ConfigSnapshot::new(dir.path().join("nested/config.bin"), Duration::from_secs(60));
+        assert!(snapshot.load(1, None).is_none());
+
+        let values = HashMap::from([
+            ("API_URL".to_string(), json!("https://api")),
+            (
+                "LIMITS".to_string(),
+                json!({"max": 18446744073709551615u64, "min": -1, "ratio": 0.5, "tags": ["a", null, true]}),
+            ),
+        ]);
+        snapshot.save(1, &values, None).unwrap();
+        assert_eq!(snapshot.load(1, None), Some(values));
+        assert!(
+            snapshot.load(2, None).is_none(),
+            "written by a differently configured manager"
+        );
+    }
+
+    #[test]
+    fn test_stale_or_corrupt_snapshot_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.bin");
+        let stale = Snapshot {
+            version: FORMAT_VERSION,
+            saved_at: now_secs() - 120,
+            fingerprint: 1,
+            values: Vec::new(),
+            secrets: None,
+        };
+        std::fs::write(&path, bincode::serialize(&stale).unwrap()).unwrap();
+        assert!(ConfigSnapshot::new(&path, Duration::from_secs(60))
+            .load(1, None)
+            .is_none());
+        assert!(ConfigSnapshot::new(&path, Duration::from_secs(600))
+            .load(1, None)
+            .is_some());
+
+        std::fs::write(&path, b"{\"saved_at\": 0}").unwrap();
+        assert!(ConfigSnapshot::new(&path, Duration::from_secs(600))
+            .load(1, None)
+            .is_none());
+    }
+}
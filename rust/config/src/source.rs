@@ -0,0 +1,117 @@
+//! Pluggable config sources merged by [`ConfigManager`](crate::ConfigManager).
+//!
+//! Sources registered with `ConfigManager::with_source` are loaded during
+//! initialization and merged in registration order between the config files
+//! and the remote API:
+//!
+//! ```text
+//! files < sources (in order) < remote API < env vars
+//! ```
+//!
+//! Sources with a [`ttl`](ConfigSource::ttl) (e.g. secrets) trigger a full
+//! reload once it lapses, independent of the value cache TTL.
+//!
+//! A source that fails to load is skipped with a warning, the same way the
+//! remote API is, so an unreachable backend degrades to file/env values
+//! rather than taking the service down.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+
+use crate::utils::SmooaiConfigError;
+
+/// A backend that contributes values to the merged config.
+pub trait ConfigSource: Send + Sync {
+    /// Short label used in warnings and [`explain`](crate::ConfigManager::explain)
+    /// output, e.g. `ssm:/myapp/production/`.
+    fn name(&self) -> String;
+
+    /// Load this source's values.
+    ///
+    /// `env` is the manager's effective environment (the real process env or
+    /// the `with_env` override), so credentials and settings resolve the same
+    /// way everywhere.
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError>;
+
+    /// How long loaded values stay fresh. When the shortest source TTL lapses
+    /// the manager reloads on the next read; `None` (the default) means values
+    /// live until `invalidate()`. Called after each `load`, so lease-based
+    /// sources can report the lease they were just granted.
+    fn ttl(&self) -> Option<Duration> {
+        None
+    }
+
+    /// A hash of everything that decides what this source loads (location,
+    /// region, credentials, key lists, ...), so
+    /// [`ConfigManager::into_shared`](crate::ConfigManager::into_shared) can
+    /// tell two managers' sources apart. `None` (the default) means the source
+    /// can't be compared, and a manager using it is never shared.
+    fn fingerprint(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Place `value` at a `delimiter`-separated `path` in `out`, creating
+/// intermediate objects (`DATABASE/HOST` -> `{"DATABASE": {"HOST": ...}}`).
+/// Empty paths are ignored.
+#[allow(dead_code)] // only used by feature-gated sources
+pub(crate) fn insert_nested(out: &mut Map<String, Value>, path: &str, delimiter: char, value: Value) {
+    let mut segments: Vec<&str> = path.split(delimiter).filter(|s| !s.is_empty()).collect();
+    let Some(last) = segments.pop() else {
+        return;
+    };
+    let mut target = out;
+    for segment in segments {
+        let entry = target
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(Map::new());
+        }
+        target = entry.as_object_mut().expect("just ensured object");
+    }
+    target.insert(last.to_string(), value);
+}
+
+/// Handle for a background watch started by a source (e.g. Consul or etcd).
+/// Dropping it stops the watch.
+pub struct SourceWatch {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SourceWatch {
+    /// Run `watch` on a new thread. It should return soon after the flag it
+    /// is handed becomes `true`.
+    #[allow(dead_code)] // only used by feature-gated sources
+    pub(crate) fn spawn<F>(watch: F) -> Self
+    where
+        F: FnOnce(Arc<AtomicBool>) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || watch(stopped));
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop watching. The background thread exits after its current request;
+    /// an in-flight request is abandoned, not awaited.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.take();
+    }
+}
+
+impl Drop for SourceWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
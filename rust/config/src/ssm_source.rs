@@ -0,0 +1,266 @@
+//! AWS SSM Parameter Store source (feature `ssm`).
+//!
+//! Loads every parameter under a path prefix with `GetParametersByPath`
+//! (SecureString values decrypted) and exposes them as config keys relative
+//! to that prefix:
+//!
+//! ```text
+//! /myapp/production/API_URL        -> API_URL
+//! /myapp/production/DATABASE/HOST  -> DATABASE.HOST (nested object)
+//! ```
+//!
+//! `StringList` parameters become JSON arrays; everything else stays a string,
+//! matching how env vars are read.
+//!
+//! ```rust,ignore
+//! use smooai_config::{ConfigManager, SsmSource};
+//!
+//! let manager = ConfigManager::new().with_source(SsmSource::new("/myapp/production/"));
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use serde_json::{json, Map, Value};
+
+use crate::aws::{json_rpc, resolve_region, AwsCredentials};
+use crate::source::{insert_nested, ConfigSource};
+use crate::utils::SmooaiConfigError;
+
+/// Guard against a misbehaving endpoint returning the same token forever.
+const MAX_PAGES: usize = 1_000;
+
+/// Config source backed by SSM Parameter Store.
+#[derive(Debug, Clone)]
+pub struct SsmSource {
+    path: String,
+    region: Option<String>,
+    endpoint: Option<String>,
+    credentials: Option<AwsCredentials>,
+    recursive: bool,
+    with_decryption: bool,
+}
+
+impl SsmSource {
+    /// Load parameters under `path` (e.g. `/myapp/production/`).
+    pub fn new(path: &str) -> Self {
+        let mut path = path.to_string();
+        if !path.starts_with('/') {
+            path.insert(0, '/');
+        }
+        Self {
+            path,
+            region: None,
+            endpoint: None,
+            credentials: None,
+            recursive: true,
+            with_decryption: true,
+        }
+    }
+
+    /// Set the region (default: `AWS_REGION` / `AWS_DEFAULT_REGION`).
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Override the endpoint URL (VPC endpoints, LocalStack, tests).
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Use explicit credentials instead of the default chain.
+    pub fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Include parameters in nested paths (default: true).
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Decrypt SecureString parameters (default: true).
+    pub fn with_decryption(mut self, with_decryption: bool) -> Self {
+        self.with_decryption = with_decryption;
+        self
+    }
+
+    /// Place one parameter into `out`, nesting on `/` below the prefix.
+    fn insert_parameter(&self, out: &mut Map<String, Value>, name: &str, value: Value) {
+        let relative = name
+            .strip_prefix(&self.path)
+            .or_else(|| name.strip_prefix(self.path.trim_end_matches('/')))
+            .unwrap_or(name)
+            .trim_start_matches('/');
+        insert_nested(out, relative, '/', value);
+    }
+}
+
+impl ConfigSource for SsmSource {
+    fn name(&self) -> String {
+        format!("ssm:{}", self.path)
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        (
+            &self.path,
+            &self.region,
+            &self.endpoint,
+            &self.credentials,
+            self.recursive,
+            self.with_decryption,
+        )
+            .hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        let region = resolve_region(self.region.as_deref(), env)?;
+        let credentials = AwsCredentials::resolve(self.credentials.as_ref(), env)?;
+        let endpoint = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://ssm.{}.amazonaws.com/", region));
+
+        let mut values = Map::new();
+        let mut next_token: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let mut request = json!({
+                "Path": self.path,
+                "Recursive": self.recursive,
+                "WithDecryption": self.with_decryption,
+            });
+            if let Some(ref token) = next_token {
+                request["NextToken"] = Value::String(token.clone());
+            }
+            let response = json_rpc(
+                &endpoint,
+                &region,
+                "ssm",
+                "AmazonSSM.GetParametersByPath",
+                &request,
+                &credentials,
+            )?;
+
+            for param in response
+                .get("Parameters")
+                .and_then(|p| p.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let (Some(name), Some(raw)) = (
+                    param.get("Name").and_then(|n| n.as_str()),
+                    param.get("Value").and_then(|v| v.as_str()),
+                ) else {
+                    continue;
+                };
+                let value = match param.get("Type").and_then(|t| t.as_str()) {
+                    Some("StringList") => Value::Array(raw.split(',').map(|s| Value::String(s.to_string())).collect()),
+                    _ => Value::String(raw.to_string()),
+                };
+                self.insert_parameter(&mut values, name, value);
+            }
+
+            next_token = response
+                .get("NextToken")
+                .and_then(|t| t.as_str())
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string());
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(values.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, header_regex, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_insert_parameter_nests_below_prefix() {
+        let source = SsmSource::new("/myapp/production/");
+        let mut out = Map::new();
+        source.insert_parameter(&mut out, "/myapp/production/API_URL", json!("https://api"));
+        source.insert_parameter(&mut out, "/myapp/production/DATABASE/HOST", json!("db"));
+        source.insert_parameter(&mut out, "/myapp/production/DATABASE/PORT", json!("5432"));
+        assert_eq!(
+            Value::Object(out),
+            json!({"API_URL": "https://api", "DATABASE": {"HOST": "db", "PORT": "5432"}})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_paginates_and_signs() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("x-amz-target", "AmazonSSM.GetParametersByPath"))
+            .and(header_regex(
+                "authorization",
+                r"^AWS4-HMAC-SHA256 Credential=AKID/\d{8}/us-east-1/ssm/aws4_request",
+            ))
+            .and(body_partial_json(json!({"NextToken": "page2"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "Parameters": [{"Name": "/app/prod/HOSTS", "Type": "StringList", "Value": "a,b"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"Path": "/app/prod/", "WithDecryption": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "Parameters": [{"Name": "/app/prod/API_KEY", "Type": "SecureString", "Value": "sk-123"}],
+                "NextToken": "page2"
+            })))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let values = tokio::task::spawn_blocking(move || {
+            SsmSource::new("/app/prod/")
+                .with_region("us-east-1")
+                .with_endpoint(&uri)
+                .with_credentials(AwsCredentials::new("AKID", "secret", None))
+                .load(&HashMap::new())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(values["API_KEY"], json!("sk-123"));
+        assert_eq!(values["HOSTS"], json!(["a", "b"]));
+    }
+
+    #[tokio::test]
+    async fn test_load_surfaces_aws_error_type() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "__type": "com.amazonaws#AccessDeniedException",
+                "message": "nope"
+            })))
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let err = tokio::task::spawn_blocking(move || {
+            SsmSource::new("/app/prod")
+                .with_region("us-east-1")
+                .with_endpoint(&uri)
+                .with_credentials(AwsCredentials::new("AKID", "secret", None))
+                .load(&HashMap::new())
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+        assert!(err.message.contains("AccessDeniedException"));
+    }
+}
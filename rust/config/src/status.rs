@@ -0,0 +1,126 @@
+//! Health and cache introspection for [`ConfigManager`](crate::ConfigManager).
+//!
+//! [`ConfigManager::status`](crate::ConfigManager::status) returns a
+//! [`ConfigStatus`] that serializes straight into a `/healthz` or
+//! `/debug/config` response. It never contains config values, only names,
+//! counts and error messages.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// Outcome of the most recent load attempt for one source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceState {
+    Loaded,
+    Failed,
+}
+
+/// Health of a single layer: the config files, a registered source, the
+/// remote API or the last-known-good snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceStatus {
+    pub name: String,
+    pub state: SourceState,
+    /// Unix seconds of the last successful load, kept across later failures.
+    pub last_success: Option<u64>,
+    /// Error from the most recent load, cleared on success.
+    pub last_error: Option<String>,
+    /// Keys supplied by the last successful load.
+    pub key_count: usize,
+    /// Failed load attempts since the manager was built.
+    pub failures: u64,
+}
+
+/// Per-tier cache sizes and lookup counters since the manager was built.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub public_entries: usize,
+    pub secret_entries: usize,
+    pub feature_flag_entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Snapshot of a manager's health.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigStatus {
+    /// Whether merged config is currently loaded.
+    pub initialized: bool,
+    /// Keys in the merged config.
+    pub merged_keys: usize,
+    /// Sources in load order. A source appears once it has been tried.
+    pub sources: Vec<SourceStatus>,
+    pub cache: CacheStats,
+}
+
+impl ConfigStatus {
+    /// True when no source failed on its most recent attempt.
+    pub fn is_healthy(&self) -> bool {
+        self.sources.iter().all(|s| s.state == SourceState::Loaded)
+    }
+}
+
+/// Record a load attempt for `name`, keeping its previous success time.
+pub(crate) fn record(sources: &mut Vec<SourceStatus>, name: &str, result: Result<usize, String>) {
+    let index = match sources.iter().position(|s| s.name == name) {
+        Some(index) => index,
+        None => {
+            sources.push(SourceStatus {
+                name: name.to_string(),
+                state: SourceState::Failed,
+                last_success: None,
+                last_error: None,
+                key_count: 0,
+                failures: 0,
+            });
+            sources.len() - 1
+        }
+    };
+    let status = &mut sources[index];
+    match result {
+        Ok(key_count) => {
+            status.state = SourceState::Loaded;
+            status.last_success = Some(now_secs());
+            status.last_error = None;
+            status.key_count = key_count;
+        }
+        Err(error) => {
+            status.state = SourceState::Failed;
+            status.last_error = Some(error);
+            status.failures += 1;
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_keeps_last_success() {
+        let mut sources = Vec::new();
+        record(&mut sources, "vault", Ok(3));
+        record(&mut sources, "vault", Err("connection refused".to_string()));
+
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].state, SourceState::Failed);
+        assert!(sources[0].last_success.is_some());
+        assert_eq!(sources[0].last_error.as_deref(), Some("connection refused"));
+        assert_eq!(sources[0].key_count, 3);
+        assert_eq!(sources[0].failures, 1);
+
+        record(&mut sources, "vault", Ok(4));
+        assert_eq!(sources[0].last_error, None);
+        assert_eq!(sources[0].key_count, 4);
+        assert_eq!(sources[0].failures, 1);
+    }
+}
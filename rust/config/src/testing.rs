@@ -0,0 +1,376 @@
+//! Test fixtures and a fake config API (feature `testing`).
+//!
+//! [`ConfigFixture`] replaces the usual temp-dir boilerplate: it writes
+//! config files into a fresh `.smooai-config` directory and hands out
+//! managers whose env map points at it.
+//!
+//! ```rust,ignore
+//! let fixture = ConfigFixture::new()
+//!     .with_file("default.json", json!({"API_URL": "http://localhost"}))
+//!     .with_file("production.json", json!({"API_URL": "https://api.example.com"}))
+//!     .with_environment("production");
+//! let manager = fixture.manager();
+//! ```
+//!
+//! The env map contains only what the fixture sets, so tests never see the
+//! real process environment. Keep the fixture alive while the manager is in
+//! use; dropping it deletes the directory.
+//!
+//! [`FakeConfigServer`] is a wiremock server that speaks the config API:
+//! the OAuth `/token` exchange plus the bulk and per-key value routes.
+//!
+//! ```rust,ignore
+//! let server = FakeConfigServer::start()
+//!     .await
+//!     .with_values("production", [("API_URL", json!("https://api.example.com"))])
+//!     .await;
+//! let mut client = server.client("production");
+//! // or: let manager = server.connect(fixture.manager(), "production");
+//! server.assert_all_authorized().await;
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use wiremock::matchers::{header, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+use crate::client::ConfigClient;
+use crate::config_manager::ConfigManager;
+#[allow(deprecated)]
+use crate::local::LocalConfigManager;
+use crate::token_provider::TokenProvider;
+
+/// A temporary config directory plus the env map that selects it.
+pub struct ConfigFixture {
+    dir: TempDir,
+    config_dir: PathBuf,
+    env: HashMap<String, String>,
+}
+
+impl ConfigFixture {
+    /// An empty config directory, with `SMOOAI_CONFIG_ENV` set to `test`.
+    ///
+    /// Panics if the directory can't be created.
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let config_dir = dir.path().join(".smooai-config");
+        fs::create_dir_all(&config_dir).expect("create config dir");
+        let env = HashMap::from([
+            (
+                "SMOOAI_ENV_CONFIG_DIR".to_string(),
+                config_dir.to_string_lossy().to_string(),
+            ),
+            ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+        ]);
+        ConfigFixture { dir, config_dir, env }
+    }
+
+    /// Write `name` (e.g. `default.json`) with `values` as its contents.
+    pub fn with_file(self, name: &str, values: Value) -> Self {
+        let text = serde_json::to_string_pretty(&values).expect("serialize config file");
+        self.with_raw_file(name, &text)
+    }
+
+    /// Write `name` verbatim, e.g. malformed JSON or a `schema.json`.
+    pub fn with_raw_file(self, name: &str, contents: &str) -> Self {
+        let path = self.config_dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create config subdirectory");
+        }
+        fs::write(&path, contents).expect("write config file");
+        self
+    }
+
+    /// Set an env var in the managers' env map.
+    pub fn with_env_var(mut self, key: &str, value: &str) -> Self {
+        self.env.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Select the environment whose files are layered (`SMOOAI_CONFIG_ENV`).
+    pub fn with_environment(self, environment: &str) -> Self {
+        self.with_env_var("SMOOAI_CONFIG_ENV", environment)
+    }
+
+    /// A [`ConfigManager`] over this fixture. Chain further `with_*` calls as usual.
+    pub fn manager(&self) -> ConfigManager {
+        ConfigManager::new().with_env(self.env())
+    }
+
+    /// A [`LocalConfigManager`] over this fixture.
+    #[allow(deprecated)]
+    pub fn local_manager(&self) -> LocalConfigManager {
+        LocalConfigManager::new().with_env(self.env())
+    }
+
+    /// The env map the managers are given.
+    pub fn env(&self) -> HashMap<String, String> {
+        self.env.clone()
+    }
+
+    /// The `.smooai-config` directory.
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+
+    /// The temp directory containing `.smooai-config`.
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl Default for ConfigFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Organization id the fake server serves.
+pub const FAKE_ORG_ID: &str = "00000000-0000-4000-8000-000000000000";
+/// Bearer token the fake server issues and expects.
+pub const FAKE_TOKEN: &str = "fake-config-token";
+
+const VALUES_ROUTE: &str = r"^/organizations/[^/]+/config/values(/[^/]+)?$";
+
+/// An in-process config API for integration tests. See [`crate::testing`].
+pub struct FakeConfigServer {
+    server: MockServer,
+}
+
+impl FakeConfigServer {
+    /// Start a server that answers the token exchange and nothing else yet.
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": FAKE_TOKEN,
+                "expires_in": 3600
+            })))
+            .mount(&server)
+            .await;
+        FakeConfigServer { server }
+    }
+
+    /// Serve `values` for `environment`, in bulk and per key, to requests
+    /// carrying [`FAKE_TOKEN`]. Keys not listed are 404s.
+    pub async fn with_values<K: Into<String>>(
+        self,
+        environment: &str,
+        values: impl IntoIterator<Item = (K, Value)>,
+    ) -> Self {
+        let values: HashMap<String, Value> = values.into_iter().map(|(k, v)| (k.into(), v)).collect();
+        let base = format!("/organizations/{}/config/values", FAKE_ORG_ID);
+        for (key, value) in &values {
+            self.authorized("GET", &format!("{}/{}", base, key), environment)
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "value": value })))
+                .mount(&self.server)
+                .await;
+        }
+        self.authorized("GET", &base, environment)
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "values": values })))
+            .mount(&self.server)
+            .await;
+        self
+    }
+
+    /// Answer every value request with `status`, ahead of any values.
+    pub async fn with_error(self, status: u16) -> Self {
+        Mock::given(path_regex(VALUES_ROUTE))
+            .respond_with(ResponseTemplate::new(status).set_body_json(json!({ "error": "fake error" })))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+        self
+    }
+
+    fn authorized(&self, http_method: &str, route: &str, environment: &str) -> wiremock::MockBuilder {
+        Mock::given(method(http_method))
+            .and(path(route))
+            .and(query_param("environment", environment))
+            .and(header("Authorization", format!("Bearer {}", FAKE_TOKEN)))
+    }
+
+    /// Base URL for the API and the OAuth issuer.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// A [`ConfigClient`] for `environment` that authenticates against this server.
+    pub fn client(&self, environment: &str) -> ConfigClient {
+        let token_provider = TokenProvider::with_options(
+            &self.uri(),
+            "fake-client-id",
+            "fake-client-secret",
+            Duration::from_secs(60),
+            reqwest::Client::new(),
+        )
+        .expect("valid token provider");
+        ConfigClient::with_token_provider(&self.uri(), Arc::new(token_provider), FAKE_ORG_ID, environment)
+    }
+
+    /// Point `manager`'s remote fetch at this server for `environment`.
+    ///
+    /// The manager fetches with blocking HTTP, so read it from
+    /// `spawn_blocking` (or a plain thread) inside async tests.
+    pub fn connect(&self, manager: ConfigManager, environment: &str) -> ConfigManager {
+        manager
+            .with_api_key(FAKE_TOKEN)
+            .with_base_url(&self.uri())
+            .with_org_id(FAKE_ORG_ID)
+            .with_environment(environment)
+    }
+
+    /// Every request the server has seen, the token exchange included.
+    pub async fn received_requests(&self) -> Vec<Request> {
+        self.server.received_requests().await.unwrap_or_default()
+    }
+
+    /// Value requests (bulk or per key) made for `environment`.
+    pub async fn fetch_count(&self, environment: &str) -> usize {
+        self.value_requests()
+            .await
+            .iter()
+            .filter(|request| {
+                request
+                    .url
+                    .query_pairs()
+                    .any(|(name, value)| name == "environment" && value == environment)
+            })
+            .count()
+    }
+
+    /// Panic unless every value request carried [`FAKE_TOKEN`].
+    pub async fn assert_all_authorized(&self) {
+        let expected = format!("Bearer {}", FAKE_TOKEN);
+        for request in self.value_requests().await {
+            let authorization = request
+                .headers
+                .get("authorization")
+                .and_then(|value| value.to_str().ok());
+            assert_eq!(
+                authorization,
+                Some(expected.as_str()),
+                "unauthorized request to {}",
+                request.url
+            );
+        }
+    }
+
+    async fn value_requests(&self) -> Vec<Request> {
+        self.received_requests()
+            .await
+            .into_iter()
+            .filter(|request| is_value_route(request.url.path()))
+            .collect()
+    }
+}
+
+/// Whether `path` is one of the routes [`VALUES_ROUTE`] matches.
+fn is_value_route(path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    matches!(
+        segments.as_slice(),
+        ["organizations", _, "config", "values"] | ["organizations", _, "config", "values", _]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_layers_files() {
+        let fixture = ConfigFixture::new()
+            .with_file("default.json", json!({"API_URL": "http://localhost", "PORT": 80}))
+            .with_file("production.json", json!({"API_URL": "https://api.example.com"}))
+            .with_environment("production")
+            .with_env_var("PORT", "9090");
+        let manager = fixture
+            .manager()
+            .with_schema_keys(std::collections::HashSet::from(["PORT".to_string()]));
+
+        assert_eq!(
+            manager.get_public_config("API_URL").unwrap(),
+            Some(json!("https://api.example.com"))
+        );
+        assert_eq!(manager.get_public_config("PORT").unwrap(), Some(json!("9090")));
+        assert_eq!(
+            fixture.local_manager().get_public_config("PORT").unwrap(),
+            Some(json!(80))
+        );
+        assert!(fixture.config_dir().starts_with(fixture.root()));
+    }
+
+    #[tokio::test]
+    async fn test_fake_server_serves_client() {
+        let server = FakeConfigServer::start()
+            .await
+            .with_values(
+                "production",
+                [("API_URL", json!("https://api.example.com")), ("PORT", json!(443))],
+            )
+            .await;
+        let mut client = server.client("production");
+
+        assert_eq!(
+            client.get_value("API_URL", None).await.unwrap(),
+            json!("https://api.example.com")
+        );
+        let all = client.get_all_values(None).await.unwrap();
+        assert_eq!(all.get("PORT"), Some(&json!(443)));
+        assert!(client.get_value("MISSING", None).await.is_err());
+        assert_eq!(server.fetch_count("production").await, 3);
+        assert_eq!(server.fetch_count("staging").await, 0);
+        server.assert_all_authorized().await;
+    }
+
+    #[tokio::test]
+    async fn test_fake_server_feeds_manager() {
+        let server = FakeConfigServer::start()
+            .await
+            .with_values("production", [("API_URL", json!("https://remote.example.com"))])
+            .await;
+        let fixture = ConfigFixture::new().with_file("default.json", json!({"API_URL": "http://localhost"}));
+        let manager = server.connect(fixture.manager(), "production");
+
+        let value = tokio::task::spawn_blocking(move || manager.get_public_config("API_URL").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(value, Some(json!("https://remote.example.com")));
+        assert_eq!(server.fetch_count("production").await, 1);
+        server.assert_all_authorized().await;
+    }
+
+    #[tokio::test]
+    async fn test_fake_server_errors() {
+        let server = FakeConfigServer::start()
+            .await
+            .with_values("production", [("API_URL", json!("https://api.example.com"))])
+            .await
+            .with_error(403)
+            .await;
+        let err = server
+            .client("production")
+            .get_value("API_URL", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(403));
+    }
+
+    #[test]
+    fn test_raw_files_and_cleanup() {
+        let fixture = ConfigFixture::new().with_raw_file("default.json", "{not json");
+        assert!(fixture.local_manager().get_public_config("A").is_err());
+
+        let root = fixture.root().to_path_buf();
+        drop(fixture);
+        assert!(!root.exists());
+    }
+}
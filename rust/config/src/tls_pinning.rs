@@ -0,0 +1,225 @@
+//! Certificate pinning for the config API connection.
+//!
+//! Normal TLS validation trusts any certificate a system CA signed, including
+//! a corporate proxy's CA. With pins set, the server's chain must also
+//! contain a certificate matching one of them, so a proxy that can mint
+//! trusted certificates still can't read the secrets the API returns.
+//!
+//! ```rust,no_run
+//! use smooai_config::tls_pinning::CertificatePin;
+//! use smooai_config::ConfigManager;
+//!
+//! // `curl --pinnedpubkey` format: SHA-256 of the DER SubjectPublicKeyInfo.
+//! let pin = CertificatePin::parse("sha256/1czNA+fZzELn94qCGtoXoz0Z+FG8CAsKtTNwlQ1XLzo=").unwrap();
+//! let manager = ConfigManager::new().with_tls_pins([pin]);
+//! ```
+//!
+//! Pin the key of your issuing intermediate as well as the leaf's (or a
+//! backup key) so certificate rotation doesn't lock services out. Pins only
+//! apply to `https://` URLs.
+//!
+//! The chain is validated against the platform trust store, as without
+//! pins, so a server with a private-CA certificate keeps working. The
+//! bundled Mozilla roots are used only where no platform roots can be loaded.
+
+use std::sync::{Arc, OnceLock};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::ring;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::server::ParsedCertificate;
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+use crate::utils::SmooaiConfigError;
+
+/// A SHA-256 hash the server's certificate chain must match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CertificatePin {
+    /// Hash of a certificate's DER SubjectPublicKeyInfo; survives
+    /// re-issuing a certificate for the same key.
+    Spki([u8; 32]),
+    /// Hash of a whole DER certificate.
+    Certificate([u8; 32]),
+}
+
+impl CertificatePin {
+    /// Parse an SPKI pin in `sha256/<base64>` form, as `curl --pinnedpubkey`
+    /// and HPKP use. The `sha256/` prefix is optional.
+    pub fn parse(pin: &str) -> Result<Self, SmooaiConfigError> {
+        let encoded = pin.trim().strip_prefix("sha256/").unwrap_or(pin.trim());
+        let hash = BASE64
+            .decode(encoded)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                SmooaiConfigError::new(&format!(
+                    "Invalid certificate pin '{}': expected sha256/ followed by a base64 SHA-256 hash",
+                    pin
+                ))
+            })?;
+        Ok(Self::Spki(hash))
+    }
+
+    /// Whether `cert` (DER) matches this pin.
+    pub fn matches(&self, cert: &[u8]) -> bool {
+        match self {
+            Self::Certificate(hash) => Sha256::digest(cert).as_slice() == hash,
+            Self::Spki(hash) => {
+                let der = CertificateDer::from(cert);
+                ParsedCertificate::try_from(&der)
+                    .is_ok_and(|parsed| Sha256::digest(parsed.subject_public_key_info()).as_slice() == hash)
+            }
+        }
+    }
+}
+
+/// Validates the chain against the trusted roots as usual, then requires a
+/// pinned certificate somewhere in it.
+#[derive(Debug)]
+struct PinnedVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Vec<CertificatePin>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self
+            .inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+        let pinned = std::iter::once(end_entity)
+            .chain(intermediates)
+            .any(|cert| self.pins.iter().any(|pin| pin.matches(cert)));
+        if !pinned {
+            return Err(rustls::Error::General(format!(
+                "certificate chain for {} matches none of the configured pins",
+                server_name.to_str()
+            )));
+        }
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// The platform's trusted roots, loaded once; the bundled Mozilla roots
+/// when the platform store is empty or unreadable.
+fn trusted_roots() -> Arc<RootCertStore> {
+    static ROOTS: OnceLock<Arc<RootCertStore>> = OnceLock::new();
+    Arc::clone(ROOTS.get_or_init(|| {
+        let mut roots = RootCertStore::empty();
+        roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+        if roots.is_empty() {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+        Arc::new(roots)
+    }))
+}
+
+/// A rustls config that only completes handshakes with servers whose chain
+/// matches one of `pins`. Pass it to `use_preconfigured_tls`.
+pub(crate) fn pinned_tls_config(pins: &[CertificatePin]) -> ClientConfig {
+    let provider = Arc::new(ring::default_provider());
+    let inner = WebPkiServerVerifier::builder_with_provider(trusted_roots(), Arc::clone(&provider))
+        .build()
+        .expect("trusted roots are non-empty");
+    ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .expect("ring supports the default protocol versions")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+            inner,
+            pins: pins.to_vec(),
+        }))
+        .with_no_client_auth()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed P-256 certificate for CN=config.example.test.
+    const CERT: &str = "MIIBkzCCATmgAwIBAgIUXlj69UQRoAwb4djC5pvI3aytG6wwCgYIKoZIzj0EAwIw\
+        HjEcMBoGA1UEAwwTY29uZmlnLmV4YW1wbGUudGVzdDAgFw0yNjEwMTYxNDE2MjFa\
+        GA8yMTI2MDkyMjE0MTYyMVowHjEcMBoGA1UEAwwTY29uZmlnLmV4YW1wbGUudGVz\
+        dDBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABC7HEltKSz/goLGJ0RXsp4eXshFf\
+        GZzITlHH9dotd5oqxYwLaOZrO3ciKFAMowcuY8O4FiEfTSWbGcTHUXMa2JqjUzBR\
+        MB0GA1UdDgQWBBThYumSjN/rxO/m26os2tJemzB+STAfBgNVHSMEGDAWgBThYumS\
+        jN/rxO/m26os2tJemzB+STAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gA\
+        MEUCIQD7Qg0S/2+paKM1jp/fkxNIlZqZiZFzBKvB/Cab1khudAIgJhaoqGomQgrJ\
+        qcoTrav72X+eklMX1MXx9mMOKKVeHHw=";
+    const SPKI_PIN: &str = "sha256/1czNA+fZzELn94qCGtoXoz0Z+FG8CAsKtTNwlQ1XLzo=";
+    const CERT_SHA256: &str = "lnYlX/lZTFHxnIvbf2WBpwG0bGj0pMf2CONyGbfXWvI=";
+
+    fn cert() -> Vec<u8> {
+        BASE64.decode(CERT).unwrap()
+    }
+
+    #[test]
+    fn test_pins_match_certificate() {
+        let cert = cert();
+        assert!(CertificatePin::parse(SPKI_PIN).unwrap().matches(&cert));
+        assert!(CertificatePin::parse(&SPKI_PIN["sha256/".len()..])
+            .unwrap()
+            .matches(&cert));
+        let whole: [u8; 32] = BASE64.decode(CERT_SHA256).unwrap().try_into().unwrap();
+        assert!(CertificatePin::Certificate(whole).matches(&cert));
+
+        assert!(!CertificatePin::Spki(whole).matches(&cert));
+        assert!(!CertificatePin::Certificate([0; 32]).matches(&cert));
+        assert!(!CertificatePin::Spki([0; 32]).matches(b"not a certificate"));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_pins() {
+        assert!(CertificatePin::parse("sha256/not-base64!").is_err());
+        assert!(CertificatePin::parse("sha256/AAAA").is_err());
+        let err = CertificatePin::parse("").unwrap_err();
+        assert!(err.to_string().contains("Invalid certificate pin"));
+    }
+
+    #[test]
+    fn test_trusted_roots_not_empty() {
+        assert!(!trusted_roots().is_empty());
+    }
+
+    #[test]
+    fn test_pinned_config_builds() {
+        let pins = [CertificatePin::parse(SPKI_PIN).unwrap()];
+        let config = pinned_tls_config(&pins);
+        assert!(reqwest::blocking::Client::builder()
+            .use_preconfigured_tls(config)
+            .build()
+            .is_ok());
+    }
+}
@@ -133,6 +133,25 @@ impl TokenProvider {
         })
     }
 
+    /// The issuer base URL; tokens are requested from `{auth_url}/token`.
+    pub fn auth_url(&self) -> &str {
+        &self.auth_url
+    }
+
+    /// A copy of this provider, with an empty token cache, that sends its
+    /// token requests through `http_client`.
+    #[cfg(feature = "tls-pinning")]
+    pub(crate) fn with_http_client(&self, http_client: Client) -> Self {
+        Self {
+            auth_url: self.auth_url.clone(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            refresh_window: self.refresh_window,
+            http_client,
+            cache: Mutex::new(None),
+        }
+    }
+
     /// Return a valid OAuth access token, refreshing from the issuer if
     /// the cache is missing or within the refresh window of expiry.
     pub async fn get_access_token(&self) -> Result<String, TokenProviderError> {
@@ -1,6 +1,19 @@
 //! Utility functions for configuration management.
 
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+use base64::Engine as _;
+use serde_json::Value;
+
+use crate::redact::{redact_patterns, redact_text};
+use crate::schema::ConfigTier;
+use crate::value_validator::ValueViolation;
 
 /// Kind discriminator for [`SmooaiConfigError`].
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -10,23 +23,113 @@ pub enum SmooaiConfigErrorKind {
     /// Caller asked for a key that isn't declared in the active schema.
     /// SMOODEV-958 — friendly, actionable error matching the TS/.NET ports.
     UndefinedKey { key: String, schema_path: String },
+    /// One or more keys declared as required resolved to nothing (absent or
+    /// `null`) after all sources were merged. Lists every missing key at once.
+    MissingRequiredKeys { keys: Vec<String> },
+    /// A key was read through the getter for one tier but the schema declares
+    /// it in another (e.g. `get_secret_config("API_URL")` for a public key).
+    WrongTier {
+        key: String,
+        requested: ConfigTier,
+        declared: ConfigTier,
+    },
+    /// Merged values failed JSON Schema validation (see
+    /// [`ConfigManager::with_value_validation`](crate::ConfigManager)).
+    InvalidValues { violations: Vec<ValueViolation> },
+    /// No config directory was found: `path` is the `SMOOAI_ENV_CONFIG_DIR`
+    /// that doesn't exist, or the directory the upward search started from.
+    ConfigDirNotFound { path: String },
+    /// A config document (file, bundle entry, snapshot) isn't valid JSON or
+    /// has the wrong shape. The parser error is the [`source`](std::error::Error::source).
+    FileParse { path: String },
+    /// Reading or writing a local file failed.
+    Io { path: String },
+    /// A remote backend (config server, AWS, Vault, Consul, etcd, bundle
+    /// host) couldn't be reached or answered with a non-success `status`.
+    RemoteFetch { status: Option<u16> },
+    /// An internal lock was poisoned by a panicking thread.
+    LockPoisoned,
+    /// A config value couldn't be converted to the requested type.
+    Coercion { key: String },
+    /// The manager's [`AccessPolicy`](crate::policy::AccessPolicy) doesn't
+    /// permit reading `key` (from `tier`, when the read names one).
+    AccessDenied { key: String, tier: Option<ConfigTier> },
 }
 
 /// Configuration error with standard prefix.
+///
+/// `message` is what [`Display`](fmt::Display) prints; branch on `kind`
+/// rather than matching on it.
 #[derive(Debug, Clone)]
 pub struct SmooaiConfigError {
     pub message: String,
     pub kind: SmooaiConfigErrorKind,
+    source: Option<Arc<dyn std::error::Error + Send + Sync>>,
 }
 
 impl SmooaiConfigError {
+    /// An error with `message`, credentials in it redacted (see [`crate::redact`]).
     pub fn new(message: &str) -> Self {
         Self {
-            message: format!("[Smooai Config] {}", message),
+            message: format!("[Smooai Config] {}", redact_patterns(message)),
             kind: SmooaiConfigErrorKind::Generic,
+            source: None,
         }
     }
 
+    /// Set the error's kind, keeping its message.
+    pub fn with_kind(mut self, kind: SmooaiConfigErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Also redact the known secret `values` from the message.
+    pub(crate) fn redact_values<'a>(mut self, values: impl IntoIterator<Item = &'a str>) -> Self {
+        self.message = redact_text(&self.message, values);
+        self
+    }
+
+    /// Attach the underlying error, reported by [`std::error::Error::source`].
+    pub fn with_source(mut self, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// A [`SmooaiConfigErrorKind::LockPoisoned`] error.
+    pub fn lock_poisoned(message: &str) -> Self {
+        Self::new(message).with_kind(SmooaiConfigErrorKind::LockPoisoned)
+    }
+
+    /// A [`SmooaiConfigErrorKind::FileParse`] error for `path`, with `source`
+    /// as the cause: "Error parsing {path}: {source}".
+    pub fn file_parse(path: &str, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::new(&format!("Error parsing {}: {}", path, source))
+            .with_kind(SmooaiConfigErrorKind::FileParse { path: path.to_string() })
+            .with_source(source)
+    }
+
+    /// A [`SmooaiConfigErrorKind::RemoteFetch`] error.
+    pub fn remote_fetch(status: Option<u16>, message: &str) -> Self {
+        Self::new(message).with_kind(SmooaiConfigErrorKind::RemoteFetch { status })
+    }
+
+    /// A [`SmooaiConfigErrorKind::Coercion`] error for `key`.
+    pub fn coercion(key: &str, message: &str) -> Self {
+        Self::new(message).with_kind(SmooaiConfigErrorKind::Coercion { key: key.to_string() })
+    }
+
+    /// A [`SmooaiConfigErrorKind::AccessDenied`] error for `key`.
+    pub fn access_denied(key: &str, tier: Option<ConfigTier>) -> Self {
+        let message = match tier {
+            Some(tier) => format!("Access policy does not permit reading {} key '{}'", tier.as_str(), key),
+            None => format!("Access policy does not permit reading key '{}'", key),
+        };
+        Self::new(&message).with_kind(SmooaiConfigErrorKind::AccessDenied {
+            key: key.to_string(),
+            tier,
+        })
+    }
+
     /// Build a friendly error for a key that isn't declared in the schema.
     ///
     /// Mirrors the TS `assertKeyDefined` and .NET `ConfigKey` ctor messages
@@ -49,6 +152,59 @@ impl SmooaiConfigError {
                 key: key.to_string(),
                 schema_path: path.to_string(),
             },
+            source: None,
+        }
+    }
+}
+
+impl SmooaiConfigError {
+    /// Build the error returned when required keys are missing after merge.
+    pub fn missing_required_keys(keys: Vec<String>) -> Self {
+        Self {
+            message: format!(
+                "[Smooai Config] Missing required config keys: {}. \
+                 Set them in .smooai-config/*.json, the config server, or the environment.",
+                keys.join(", ")
+            ),
+            kind: SmooaiConfigErrorKind::MissingRequiredKeys { keys },
+            source: None,
+        }
+    }
+}
+
+impl SmooaiConfigError {
+    /// Build the error returned when a key is read through the wrong tier's getter.
+    pub fn wrong_tier(key: &str, requested: ConfigTier, declared: ConfigTier) -> Self {
+        Self {
+            message: format!(
+                "[Smooai Config] Config key '{}' is declared as a {} key but was requested as {}. \
+                 Read it with the {} getter instead.",
+                key,
+                declared.as_str(),
+                requested.as_str(),
+                declared.as_str()
+            ),
+            kind: SmooaiConfigErrorKind::WrongTier {
+                key: key.to_string(),
+                requested,
+                declared,
+            },
+            source: None,
+        }
+    }
+}
+
+impl SmooaiConfigError {
+    /// Build the error returned when merged values don't match the schema.
+    pub fn invalid_values(violations: Vec<ValueViolation>) -> Self {
+        let listed: Vec<String> = violations.iter().map(|v| format!("  {}", v)).collect();
+        Self {
+            message: format!(
+                "[Smooai Config] Config values don't match the schema:\n{}",
+                redact_patterns(&listed.join("\n"))
+            ),
+            kind: SmooaiConfigErrorKind::InvalidValues { violations },
+            source: None,
         }
     }
 }
@@ -59,7 +215,72 @@ impl fmt::Display for SmooaiConfigError {
     }
 }
 
-impl std::error::Error for SmooaiConfigError {}
+impl std::error::Error for SmooaiConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Binary config value decoded from base64 (certs, keys, other blobs).
+///
+/// `Debug` only reports the length so a stray `{:?}` never prints key
+/// material; there is deliberately no `Display`. Use [`Self::as_bytes`] or
+/// [`Self::into_vec`] to get at the contents.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DecodedBytes(Vec<u8>);
+
+impl DecodedBytes {
+    /// Borrow the decoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Take ownership of the decoded bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Number of decoded bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the decoded value is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for DecodedBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DecodedBytes(<{} bytes redacted>)", self.0.len())
+    }
+}
+
+/// Decode a config value carrying the `"bytes"` type hint.
+///
+/// Accepts standard or URL-safe base64 (padding optional) and ignores
+/// embedded whitespace, so values wrapped across lines survive. Errors name
+/// the key but never echo the value.
+pub fn decode_base64_value(key: &str, value: &Value) -> Result<DecodedBytes, SmooaiConfigError> {
+    let encoded = value.as_str().ok_or_else(|| {
+        SmooaiConfigError::coercion(
+            key,
+            &format!("Config key '{}' has the \"bytes\" type but is not a base64 string", key),
+        )
+    })?;
+    let compact: String = encoded.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    let trimmed = compact.trim_end_matches('=');
+    STANDARD
+        .decode(&compact)
+        .or_else(|_| URL_SAFE.decode(&compact))
+        .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(trimmed))
+        .or_else(|_| base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(trimmed))
+        .map(DecodedBytes)
+        .map_err(|_| SmooaiConfigError::coercion(key, &format!("Config key '{}' is not valid base64", key)))
+}
 
 /// Check if a string is already in UPPER_SNAKE_CASE format.
 /// Pattern: ^[A-Z0-9]+(_[A-Z0-9]+)*$
@@ -139,6 +360,186 @@ pub fn camel_to_upper_snake(input: &str) -> String {
     out
 }
 
+/// Convert UPPER_SNAKE_CASE to camelCase: `API_URL` → `apiUrl`.
+///
+/// Acronyms come back title-cased (`ENABLE_NEW_UI` → `enableNewUi`), which
+/// [`camel_to_upper_snake`] maps back to the same key.
+pub fn upper_snake_to_camel(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for (i, word) in input.split(['_', '-']).filter(|w| !w.is_empty()).enumerate() {
+        let lower = word.to_lowercase();
+        if i == 0 {
+            out.push_str(&lower);
+            continue;
+        }
+        let mut chars = lower.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    out
+}
+
+/// Convert camelCase to kebab-case: `apiUrl` → `api-url`.
+pub fn camel_to_kebab(input: &str) -> String {
+    upper_snake_to_kebab(&camel_to_upper_snake(input))
+}
+
+/// Convert kebab-case to camelCase: `api-url` → `apiUrl`.
+pub fn kebab_to_camel(input: &str) -> String {
+    upper_snake_to_camel(input)
+}
+
+/// Convert UPPER_SNAKE_CASE to kebab-case: `API_URL` → `api-url`.
+pub fn upper_snake_to_kebab(input: &str) -> String {
+    input.to_lowercase().replace('_', "-")
+}
+
+/// Convert kebab-case to UPPER_SNAKE_CASE: `api-url` → `API_URL`.
+pub fn kebab_to_upper_snake(input: &str) -> String {
+    input.to_uppercase().replace('-', "_")
+}
+
+/// A naming convention for config keys. [`convert`](KeyCase::convert)
+/// accepts a key in any of them, so `apiUrl`, `API_URL` and `api-url` all
+/// name the same key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum KeyCase {
+    /// `API_URL`: env vars and the canonical key form.
+    #[default]
+    UpperSnake,
+    /// `apiUrl`: schema property names and the TypeScript SDK.
+    Camel,
+    /// `api-url`: CLI flags and file-based secrets.
+    Kebab,
+}
+
+impl KeyCase {
+    /// Rewrite `key` (in any supported case) in this case.
+    pub fn convert(self, key: &str) -> String {
+        let upper_snake = if key.contains('-') {
+            kebab_to_upper_snake(key)
+        } else if key.contains('_') {
+            key.to_uppercase()
+        } else {
+            camel_to_upper_snake(key)
+        };
+        match self {
+            KeyCase::UpperSnake => upper_snake,
+            KeyCase::Camel => upper_snake_to_camel(&upper_snake),
+            KeyCase::Kebab => upper_snake_to_kebab(&upper_snake),
+        }
+    }
+
+    /// The case `key` is written in, if it's unambiguously one of them.
+    /// Single lowercase words (`database`) count as camelCase.
+    pub fn detect(key: &str) -> Option<KeyCase> {
+        if is_upper_snake_case(key) {
+            Some(KeyCase::UpperSnake)
+        } else if key.starts_with(|c: char| c.is_ascii_lowercase()) && key.chars().all(|c| c.is_ascii_alphanumeric()) {
+            Some(KeyCase::Camel)
+        } else if !key.is_empty()
+            && key
+                .split('-')
+                .all(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+        {
+            Some(KeyCase::Kebab)
+        } else {
+            None
+        }
+    }
+}
+
+/// What [`render_template_with`] does with a placeholder whose key is
+/// missing or `null`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MissingKey {
+    /// Fail, naming the key.
+    #[default]
+    Error,
+    /// Render nothing.
+    Empty,
+    /// Leave the `{{KEY}}` placeholder as written.
+    Keep,
+}
+
+/// Substitute `{{KEY}}` placeholders in `template` with values from `config`,
+/// failing on missing keys. See [`render_template_with`].
+///
+/// ```
+/// use std::collections::HashMap;
+/// use serde_json::json;
+/// use smooai_config::utils::render_template;
+///
+/// let config = HashMap::from([("HOST".to_string(), json!("db")), ("PORT".to_string(), json!(5432))]);
+/// assert_eq!(render_template("{{HOST}}:{{ PORT }}", &config).unwrap(), "db:5432");
+/// ```
+pub fn render_template(template: &str, config: &HashMap<String, Value>) -> Result<String, SmooaiConfigError> {
+    render_template_with(template, config, MissingKey::Error)
+}
+
+/// Substitute `{{KEY}}` placeholders in `template` with values from `config`.
+///
+/// - Keys may be written in any [`KeyCase`] (`{{apiUrl}}` reads `API_URL`)
+///   and may follow a dotted path into objects (`{{DATABASE.host}}`).
+/// - Strings are inserted as-is, other values as compact JSON.
+/// - `\{{` renders a literal `{{`.
+pub fn render_template_with(
+    template: &str,
+    config: &HashMap<String, Value>,
+    missing: MissingKey,
+) -> Result<String, SmooaiConfigError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| SmooaiConfigError::new(&format!("Unterminated placeholder in template: {}", template)))?;
+        let placeholder = after[..end].trim();
+        match lookup_path(config, placeholder) {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(value) if !value.is_null() => out.push_str(&value.to_string()),
+            _ => match missing {
+                MissingKey::Error => {
+                    return Err(SmooaiConfigError::new(&format!(
+                        "Template references missing config key '{}'",
+                        placeholder
+                    )))
+                }
+                MissingKey::Empty => {}
+                MissingKey::Keep => out.push_str(&rest[start..start + 2 + end + 2]),
+            },
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn lookup_path<'a>(config: &'a HashMap<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let key = segments.next().filter(|k| !k.is_empty())?;
+    let mut value = config
+        .get(key)
+        .or_else(|| config.get(&KeyCase::UpperSnake.convert(key)))?;
+    for segment in segments {
+        value = match value {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => value.get(segment)?,
+        };
+    }
+    Some(value)
+}
+
 /// Coerce a string value to boolean.
 /// "true", "1" → true; everything else → false.
 pub fn coerce_boolean(value: &str) -> bool {
@@ -146,9 +547,139 @@ pub fn coerce_boolean(value: &str) -> bool {
     lower == "true" || lower == "1"
 }
 
+/// Prefix of the enum type hint; the rest is a JSON array of the allowed
+/// values, e.g. `enum:["debug","info"]`.
+pub const ENUM_TYPE_HINT_PREFIX: &str = "enum:";
+
+/// The type hint restricting a key to `values`.
+pub fn enum_type_hint(values: &[Value]) -> String {
+    format!("{}{}", ENUM_TYPE_HINT_PREFIX, Value::Array(values.to_vec()))
+}
+
+/// The allowed values of an enum type hint, or `None` for any other hint.
+pub fn parse_enum_type_hint(hint: &str) -> Option<Vec<Value>> {
+    serde_json::from_str(hint.strip_prefix(ENUM_TYPE_HINT_PREFIX)?).ok()
+}
+
+/// Coerce a raw (env) string to one of `allowed`.
+///
+/// An exact string match wins, then a case-insensitive one if it is
+/// unambiguous (`DEBUG` → `"debug"`), then numbers, booleans and `null` by
+/// their JSON spelling. `None` when nothing matches.
+pub fn coerce_enum(raw: &str, allowed: &[Value]) -> Option<Value> {
+    if let Some(exact) = allowed.iter().find(|v| v.as_str() == Some(raw)) {
+        return Some(exact.clone());
+    }
+    let mut folded = allowed
+        .iter()
+        .filter(|v| v.as_str().is_some_and(|s| s.eq_ignore_ascii_case(raw.trim())));
+    if let (Some(only), None) = (folded.next(), folded.next()) {
+        return Some(only.clone());
+    }
+    let parsed: Value = serde_json::from_str(raw.trim()).ok()?;
+    allowed
+        .iter()
+        .find(|v| !v.is_string() && (**v == parsed || v.as_f64().is_some_and(|n| Some(n) == parsed.as_f64())))
+        .cloned()
+}
+
+/// `"debug", "info"` — allowed values for error messages.
+pub fn format_allowed_values(allowed: &[Value]) -> String {
+    allowed.iter().map(Value::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Spread `ttl` uniformly over `ttl * (1 ± jitter)` so that many processes
+/// started together don't all expire at the same instant.
+///
+/// `jitter` is a fraction clamped to `0.0..=1.0` (NaN and infinities count
+/// as `0.0`); `0.0` returns `ttl` unchanged.
+///
+/// The offset is pseudo-random, not cryptographic: std's `RandomState` seeds
+/// its keys from the OS once per thread and steps them for each new instance,
+/// so this hashes nothing under a fresh key. That spreads expiries across
+/// processes and calls, which is all jitter needs.
+pub fn jittered_ttl(ttl: Duration, jitter: f64) -> Duration {
+    let jitter = jitter_fraction(jitter);
+    if jitter == 0.0 {
+        return ttl;
+    }
+    let unit = (RandomState::new().build_hasher().finish() >> 11) as f64 / (1u64 << 53) as f64; // [0, 1)
+    ttl.mul_f64(1.0 - jitter + 2.0 * jitter * unit)
+}
+
+/// Clamp a jitter fraction to `0.0..=1.0`, treating a non-finite value as
+/// `0.0` (off) rather than letting NaN reach `Duration::mul_f64`.
+pub fn jitter_fraction(fraction: f64) -> f64 {
+    if fraction.is_finite() {
+        fraction.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Walk `path` (`options.pool_size`, `hosts.0`) down from `value`: each
+/// segment is an object key, or an index when the value there is an array.
+pub fn value_at_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, segment| match value {
+        Value::Object(map) => map.get(segment),
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// Every value nested below the top-level keys of `config`, by dot path
+/// (`DATABASE.options.pool_size`, `HOSTS.0`). The first of two colliding
+/// paths wins, which only happens when object keys contain dots.
+pub fn index_paths(config: &HashMap<String, Value>) -> HashMap<String, Arc<Value>> {
+    fn walk(path: &str, value: &Value, index: &mut HashMap<String, Arc<Value>>) {
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(k, v)| (format!("{}.{}", path, k), v)).collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (format!("{}.{}", path, i), v))
+                .collect(),
+            _ => return,
+        };
+        for (child_path, child) in children {
+            walk(&child_path, child, index);
+            index.entry(child_path).or_insert_with(|| Arc::new(child.clone()));
+        }
+    }
+
+    let mut index = HashMap::new();
+    for (key, value) in config {
+        walk(key, value, &mut index);
+    }
+    index
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_value_at_path_and_index_agree() {
+        let config = HashMap::from([
+            ("DATABASE".to_string(), json!({"options": {"pool_size": 10}})),
+            ("HOSTS".to_string(), json!(["a", {"port": 1}])),
+            ("API_URL".to_string(), json!("https://api")),
+        ]);
+        let index = index_paths(&config);
+        assert_eq!(index.len(), 5);
+        for (path, expected) in [
+            ("DATABASE.options", Some(json!({"pool_size": 10}))),
+            ("DATABASE.options.pool_size", Some(json!(10))),
+            ("HOSTS.1.port", Some(json!(1))),
+            ("HOSTS.2", None),
+            ("API_URL.length", None),
+        ] {
+            let (key, rest) = path.split_once('.').unwrap();
+            assert_eq!(value_at_path(&config[key], rest).cloned(), expected, "{}", path);
+            assert_eq!(index.get(path).map(|v| (**v).clone()), expected, "{}", path);
+        }
+    }
 
     #[test]
     fn test_api_url() {
@@ -228,6 +759,101 @@ mod tests {
         assert_eq!(camel_to_upper_snake("A"), "A");
     }
 
+    #[test]
+    fn test_enum_type_hint_round_trip() {
+        let values = vec![json!("debug"), json!(3)];
+        let hint = enum_type_hint(&values);
+        assert_eq!(hint, r#"enum:["debug",3]"#);
+        assert_eq!(parse_enum_type_hint(&hint), Some(values));
+        assert_eq!(parse_enum_type_hint("string"), None);
+    }
+
+    #[test]
+    fn test_coerce_enum() {
+        let allowed = vec![json!("debug"), json!("info"), json!(3), json!(true)];
+        assert_eq!(coerce_enum("info", &allowed), Some(json!("info")));
+        assert_eq!(coerce_enum("DEBUG", &allowed), Some(json!("debug")));
+        assert_eq!(coerce_enum("3", &allowed), Some(json!(3)));
+        assert_eq!(coerce_enum("3.0", &allowed), Some(json!(3)));
+        assert_eq!(coerce_enum("true", &allowed), Some(json!(true)));
+        assert_eq!(coerce_enum("verbose", &allowed), None);
+        // Ambiguous case-insensitive matches are rejected.
+        assert_eq!(coerce_enum("info", &[json!("Info"), json!("INFO")]), None);
+    }
+
+    #[test]
+    fn test_reverse_case_conversions() {
+        assert_eq!(upper_snake_to_camel("API_URL"), "apiUrl");
+        assert_eq!(upper_snake_to_camel("ENABLE_NEW_UI"), "enableNewUi");
+        assert_eq!(upper_snake_to_camel("OAUTH2_TOKEN"), "oauth2Token");
+        assert_eq!(upper_snake_to_camel("DATABASE"), "database");
+        assert_eq!(camel_to_kebab("maxRetries"), "max-retries");
+        assert_eq!(camel_to_kebab("apiURL"), "api-url");
+        assert_eq!(kebab_to_camel("max-retries"), "maxRetries");
+        assert_eq!(upper_snake_to_kebab("JWT_SECRET"), "jwt-secret");
+        assert_eq!(kebab_to_upper_snake("jwt-secret"), "JWT_SECRET");
+        for key in ["API_URL", "ENABLE_NEW_UI", "OAUTH2_TOKEN", "DATABASE"] {
+            assert_eq!(camel_to_upper_snake(&upper_snake_to_camel(key)), key);
+            assert_eq!(kebab_to_upper_snake(&upper_snake_to_kebab(key)), key);
+        }
+    }
+
+    #[test]
+    fn test_key_case_convert_and_detect() {
+        for key in ["apiUrl", "API_URL", "api-url", "api_url"] {
+            assert_eq!(KeyCase::UpperSnake.convert(key), "API_URL");
+            assert_eq!(KeyCase::Camel.convert(key), "apiUrl");
+            assert_eq!(KeyCase::Kebab.convert(key), "api-url");
+        }
+        assert_eq!(KeyCase::detect("API_URL"), Some(KeyCase::UpperSnake));
+        assert_eq!(KeyCase::detect("apiUrl"), Some(KeyCase::Camel));
+        assert_eq!(KeyCase::detect("api-url"), Some(KeyCase::Kebab));
+        assert_eq!(KeyCase::detect("Api_url"), None);
+        assert_eq!(KeyCase::detect(""), None);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let config: HashMap<String, Value> = [
+            ("HOST".to_string(), serde_json::json!("db.internal")),
+            ("PORT".to_string(), serde_json::json!(5432)),
+            ("API_URL".to_string(), serde_json::json!("https://api")),
+            (
+                "DATABASE".to_string(),
+                serde_json::json!({"name": "app", "replicas": ["r1", "r2"]}),
+            ),
+            ("UNSET".to_string(), Value::Null),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            render_template("postgres://{{HOST}}:{{ PORT }}/{{DATABASE.name}}", &config).unwrap(),
+            "postgres://db.internal:5432/app"
+        );
+        assert_eq!(
+            render_template("{{apiUrl}}/v1 {{DATABASE.replicas.1}}", &config).unwrap(),
+            "https://api/v1 r2"
+        );
+        assert_eq!(
+            render_template("\\{{HOST}} {{HOST}}", &config).unwrap(),
+            "{{HOST}} db.internal"
+        );
+
+        let err = render_template("{{MISSING}}", &config).unwrap_err();
+        assert!(err.message.contains("'MISSING'"));
+        assert!(render_template("{{UNSET}}", &config).is_err());
+        assert!(render_template("{{HOST", &config).is_err());
+        assert_eq!(
+            render_template_with("a{{MISSING}}b", &config, MissingKey::Empty).unwrap(),
+            "ab"
+        );
+        assert_eq!(
+            render_template_with("a{{ MISSING }}b", &config, MissingKey::Keep).unwrap(),
+            "a{{ MISSING }}b"
+        );
+    }
+
     #[test]
     fn test_coerce_boolean_true() {
         assert!(coerce_boolean("true"));
@@ -244,9 +870,92 @@ mod tests {
         assert!(!coerce_boolean("yes"));
     }
 
+    #[test]
+    fn test_missing_required_keys_lists_all() {
+        let err = SmooaiConfigError::missing_required_keys(vec!["A".to_string(), "B".to_string()]);
+        assert!(err.message.contains("A, B"));
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::MissingRequiredKeys {
+                keys: vec!["A".to_string(), "B".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_value_standard_and_wrapped() {
+        let decoded = decode_base64_value("CERT", &Value::String("aGVs\nbG8=".to_string())).unwrap();
+        assert_eq!(decoded.as_bytes(), b"hello");
+        let unpadded = decode_base64_value("CERT", &Value::String("aGVsbG8".to_string())).unwrap();
+        assert_eq!(unpadded.into_vec(), b"hello".to_vec());
+        let url_safe = decode_base64_value("CERT", &Value::String("-_8=".to_string())).unwrap();
+        assert_eq!(url_safe.as_bytes(), &[0xfb, 0xff]);
+    }
+
+    #[test]
+    fn test_decode_base64_value_errors_without_echoing_value() {
+        let err = decode_base64_value("CERT", &Value::String("not base64!!".to_string())).unwrap_err();
+        assert!(err.message.contains("CERT"));
+        assert!(!err.message.contains("not base64!!"));
+        assert!(decode_base64_value("CERT", &serde_json::json!(42)).is_err());
+    }
+
+    #[test]
+    fn test_decoded_bytes_debug_is_redacted() {
+        let decoded = decode_base64_value("KEY", &Value::String("c2VjcmV0".to_string())).unwrap();
+        let debug = format!("{:?}", decoded);
+        assert_eq!(debug, "DecodedBytes(<6 bytes redacted>)");
+        assert!(!debug.contains("secret"));
+    }
+
     #[test]
     fn test_error_message_format() {
         let err = SmooaiConfigError::new("test error");
         assert_eq!(err.to_string(), "[Smooai Config] test error");
     }
+
+    #[test]
+    fn test_error_kind_and_source() {
+        use std::error::Error as _;
+
+        let parse_error = serde_json::from_str::<Value>("{").unwrap_err();
+        let err = SmooaiConfigError::file_parse("default.json", parse_error);
+        assert!(err
+            .to_string()
+            .starts_with("[Smooai Config] Error parsing default.json: "));
+        assert_eq!(
+            err.kind,
+            SmooaiConfigErrorKind::FileParse {
+                path: "default.json".to_string()
+            }
+        );
+        assert!(err.source().unwrap().is::<serde_json::Error>());
+
+        let err = SmooaiConfigError::lock_poisoned("Failed to acquire write lock");
+        assert_eq!(err.to_string(), "[Smooai Config] Failed to acquire write lock");
+        assert_eq!(err.kind, SmooaiConfigErrorKind::LockPoisoned);
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn test_jittered_ttl_stays_in_range() {
+        let ttl = Duration::from_secs(100);
+        assert_eq!(jittered_ttl(ttl, 0.0), ttl);
+        let samples: Vec<Duration> = (0..200).map(|_| jittered_ttl(ttl, 0.1)).collect();
+        assert!(samples
+            .iter()
+            .all(|d| *d >= Duration::from_secs(90) && *d <= Duration::from_secs(110)));
+        assert!(samples.iter().any(|d| *d != samples[0]));
+    }
+
+    #[test]
+    fn test_non_finite_jitter_is_off() {
+        let ttl = Duration::from_secs(100);
+        for jitter in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(jitter_fraction(jitter), 0.0);
+            assert_eq!(jittered_ttl(ttl, jitter), ttl);
+        }
+        assert_eq!(jitter_fraction(2.0), 1.0);
+        assert_eq!(jitter_fraction(-1.0), 0.0);
+    }
 }
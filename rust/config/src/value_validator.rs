@@ -0,0 +1,163 @@
+//! Validate merged config values against a [`ConfigDefinition`]'s JSON Schema.
+//!
+//! [`ConfigDefinition::validate_values`] (feature `jsonschema`) checks each
+//! tier's schema against the merged config and reports every type mismatch,
+//! out-of-range number and missing required key, with the config key path:
+//!
+//! ```text
+//! public /MAX_RETRIES: -1 is less than the minimum of 0
+//! secret: "apiKey" is a required property
+//! ```
+//!
+//! Schema properties may be camelCase or snake_case; values are looked up
+//! under the property name or its UPPER_SNAKE_CASE form, and paths are
+//! reported with the config key (`/MAX_RETRIES`). Config keys the schema
+//! doesn't declare are ignored.
+
+use std::fmt;
+
+use crate::schema::ConfigTier;
+
+/// One value that doesn't satisfy the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueViolation {
+    pub tier: ConfigTier,
+    /// JSON pointer to the offending value, starting at its config key
+    /// (`/DATABASE/port`); empty for errors about the tier as a whole, such
+    /// as a missing required key.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValueViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}: {}", self.tier.as_str(), self.message)
+        } else {
+            write!(f, "{} {}: {}", self.tier.as_str(), self.path, self.message)
+        }
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+mod validate {
+    use std::collections::HashMap;
+
+    use serde_json::{Map, Value};
+
+    use super::ValueViolation;
+    use crate::schema::{property_key, ConfigDefinition, ConfigTier};
+    use crate::utils::SmooaiConfigError;
+
+    impl ConfigDefinition {
+        /// Check `values` (a merged config map) against every tier's schema.
+        ///
+        /// Returns all violations, or an error if a tier schema itself can't
+        /// be compiled.
+        pub fn validate_values(
+            &self,
+            values: &HashMap<String, Value>,
+        ) -> Result<Vec<ValueViolation>, SmooaiConfigError> {
+            let mut violations = Vec::new();
+            for tier in [ConfigTier::Public, ConfigTier::Secret, ConfigTier::FeatureFlag] {
+                let schema = self.tier_schema(tier);
+                let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+                    continue;
+                };
+                let validator = jsonschema::validator_for(schema)
+                    .map_err(|e| SmooaiConfigError::new(&format!("Invalid {} schema: {}", tier.as_str(), e)))?;
+
+                // Present each declared value under its schema property name.
+                let mut instance = Map::new();
+                for name in properties.keys() {
+                    if let Some(value) = values.get(name).or_else(|| values.get(&property_key(name))) {
+                        instance.insert(name.clone(), value.clone());
+                    }
+                }
+                let instance = Value::Object(instance);
+
+                for error in validator.iter_errors(&instance) {
+                    violations.push(ValueViolation {
+                        tier,
+                        path: config_key_path(error.instance_path.as_str()),
+                        message: error.to_string(),
+                    });
+                }
+            }
+            Ok(violations)
+        }
+    }
+
+    /// Swap the leading property name in a JSON pointer for its config key.
+    fn config_key_path(pointer: &str) -> String {
+        let Some(rest) = pointer.strip_prefix('/') else {
+            return pointer.to_string();
+        };
+        let (name, tail) = rest.split_once('/').map_or((rest, ""), |(n, t)| (n, t));
+        let key = property_key(&name.replace("~1", "/").replace("~0", "~"));
+        if tail.is_empty() {
+            format!("/{}", key)
+        } else {
+            format!("/{}/{}", key, tail)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::schema::define_config;
+        use serde_json::json;
+
+        fn definition() -> ConfigDefinition {
+            define_config(
+                Some(json!({
+                    "type": "object",
+                    "properties": {
+                        "maxRetries": {"type": "integer", "minimum": 0},
+                        "database": {
+                            "type": "object",
+                            "properties": {"port": {"type": "integer"}}
+                        }
+                    },
+                    "required": ["maxRetries"]
+                })),
+                Some(json!({
+                    "type": "object",
+                    "properties": {"api_key": {"type": "string"}},
+                    "required": ["api_key"]
+                })),
+                None,
+            )
+        }
+
+        #[test]
+        fn test_valid_values() {
+            let values = HashMap::from([
+                ("MAX_RETRIES".to_string(), json!(3)),
+                ("API_KEY".to_string(), json!("sk")),
+                ("UNDECLARED".to_string(), json!({"anything": true})),
+            ]);
+            assert!(definition().validate_values(&values).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_reports_paths_ranges_and_missing_keys() {
+            let values = HashMap::from([
+                ("MAX_RETRIES".to_string(), json!(-1)),
+                ("DATABASE".to_string(), json!({"port": "5432"})),
+            ]);
+            let mut violations = definition().validate_values(&values).unwrap();
+            violations.sort_by(|a, b| a.path.cmp(&b.path));
+
+            assert_eq!(violations.len(), 3);
+            assert_eq!(
+                (violations[0].tier, violations[0].path.as_str()),
+                (ConfigTier::Secret, "")
+            );
+            assert!(violations[0].message.contains("api_key"));
+            assert_eq!(violations[1].path, "/DATABASE/port");
+            assert_eq!(violations[2].path, "/MAX_RETRIES");
+            assert!(violations[2].to_string().starts_with("public /MAX_RETRIES: "));
+        }
+    }
+}
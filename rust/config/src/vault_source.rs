@@ -0,0 +1,545 @@
+//! HashiCorp Vault KV v2 source (feature `vault`).
+//!
+//! Reads one or more secrets from a KV v2 mount and merges each secret's
+//! fields as top-level config keys:
+//!
+//! ```text
+//! vault kv put secret/myapp/production DB_PASSWORD=... STRIPE_KEY=...
+//! ```
+//!
+//! ```rust,ignore
+//! use smooai_config::{ConfigManager, VaultAuth, VaultSource};
+//!
+//! let manager = ConfigManager::new().with_source(
+//!     VaultSource::new("secret", "myapp/production")
+//!         .with_auth(VaultAuth::Kubernetes { role: "myapp".into(), jwt_path: None, mount: None }),
+//! );
+//! ```
+//!
+//! Authentication supports a static token (default: `VAULT_TOKEN`), AppRole
+//! and Kubernetes. Login tokens are cached and renewed with `renew-self` once
+//! less than a third of their lease is left, falling back to a fresh login;
+//! a 403 from a KV read drops the cached token and logs in again. The source's TTL is the
+//! shorter of the configured refresh interval and the remaining token lease,
+//! so the manager reloads before the token expires.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::source::ConfigSource;
+use crate::utils::{SmooaiConfigError, SmooaiConfigErrorKind};
+
+const DEFAULT_K8S_JWT_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+/// Default refresh interval for Vault-backed values.
+pub const DEFAULT_VAULT_TTL: Duration = Duration::from_secs(300);
+
+/// How the source authenticates to Vault.
+#[derive(Clone, PartialEq, Eq)]
+pub enum VaultAuth {
+    /// A static token; `None` reads `VAULT_TOKEN`.
+    Token(Option<String>),
+    /// AppRole login. `mount` defaults to `approle`.
+    AppRole {
+        role_id: String,
+        secret_id: String,
+        mount: Option<String>,
+    },
+    /// Kubernetes service-account login. `jwt_path` defaults to the projected
+    /// service-account token; `mount` defaults to `kubernetes`.
+    Kubernetes {
+        role: String,
+        jwt_path: Option<String>,
+        mount: Option<String>,
+    },
+}
+
+impl std::fmt::Debug for VaultAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultAuth::Token(_) => write!(f, "Token(<redacted>)"),
+            VaultAuth::AppRole { role_id, mount, .. } => f
+                .debug_struct("AppRole")
+                .field("role_id", role_id)
+                .field("secret_id", &"<redacted>")
+                .field("mount", mount)
+                .finish(),
+            VaultAuth::Kubernetes { role, jwt_path, mount } => f
+                .debug_struct("Kubernetes")
+                .field("role", role)
+                .field("jwt_path", jwt_path)
+                .field("mount", mount)
+                .finish(),
+        }
+    }
+}
+
+struct CachedToken {
+    token: String,
+    /// `None` for tokens without a lease (root / static tokens).
+    expires_at: Option<Instant>,
+    /// The lease granted at login or last renewal.
+    lease: Duration,
+    renewable: bool,
+}
+
+impl CachedToken {
+    /// Whether the token should be renewed or replaced before use: less than a
+    /// third of its lease (and at least 10 seconds) is left.
+    fn needs_refresh(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|at| {
+            let remaining = at.saturating_duration_since(now);
+            remaining <= (self.lease / 3).max(Duration::from_secs(10))
+        })
+    }
+}
+
+/// Config source backed by a Vault KV v2 mount.
+pub struct VaultSource {
+    address: Option<String>,
+    namespace: Option<String>,
+    mount: String,
+    paths: Vec<String>,
+    auth: VaultAuth,
+    ttl: Duration,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl VaultSource {
+    /// Read the secret at `path` under the KV v2 `mount` (e.g. `"secret"`).
+    pub fn new(mount: &str, path: &str) -> Self {
+        Self {
+            address: None,
+            namespace: None,
+            mount: mount.trim_matches('/').to_string(),
+            paths: vec![path.trim_matches('/').to_string()],
+            auth: VaultAuth::Token(None),
+            ttl: DEFAULT_VAULT_TTL,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Read another secret from the same mount; later paths win on conflicts.
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.paths.push(path.trim_matches('/').to_string());
+        self
+    }
+
+    /// Vault address (default: `VAULT_ADDR`).
+    pub fn with_address(mut self, address: &str) -> Self {
+        self.address = Some(address.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Enterprise namespace (default: `VAULT_NAMESPACE`).
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    pub fn with_auth(mut self, auth: VaultAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Upper bound on how long values stay fresh (default 5 minutes).
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::blocking::Client,
+        method: reqwest::Method,
+        url: &str,
+        namespace: Option<&String>,
+        token: Option<&str>,
+        body: Option<&Value>,
+    ) -> Result<Value, SmooaiConfigError> {
+        let mut request = client.request(method, url);
+        if let Some(ns) = namespace {
+            request = request.header("X-Vault-Namespace", ns);
+        }
+        if let Some(token) = token {
+            request = request.header("X-Vault-Token", token);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().map_err(|e| {
+            SmooaiConfigError::remote_fetch(None, &format!("Vault request failed: {}", e)).with_source(e)
+        })?;
+        let status = response.status();
+        let body: Value = response.json().unwrap_or(Value::Null);
+        if !status.is_success() {
+            let errors = body
+                .get("errors")
+                .and_then(|e| e.as_array())
+                .map(|e| e.iter().filter_map(|m| m.as_str()).collect::<Vec<_>>().join("; "))
+                .unwrap_or_default();
+            return Err(SmooaiConfigError::remote_fetch(
+                Some(status.as_u16()),
+                &format!(
+                    "Vault returned HTTP {} for {}{}",
+                    status,
+                    url.split('?').next().unwrap_or(url),
+                    if errors.is_empty() {
+                        String::new()
+                    } else {
+                        format!(": {}", errors)
+                    }
+                ),
+            ));
+        }
+        Ok(body)
+    }
+
+    /// A usable client token: cached, renewed, or freshly logged in.
+    fn token(
+        &self,
+        client: &reqwest::blocking::Client,
+        address: &str,
+        namespace: Option<&String>,
+        env: &HashMap<String, String>,
+    ) -> Result<String, SmooaiConfigError> {
+        let mut cached = self
+            .token
+            .lock()
+            .map_err(|_| SmooaiConfigError::lock_poisoned("Vault token cache poisoned"))?;
+
+        if let Some(ref current) = *cached {
+            let now = Instant::now();
+            match current.expires_at {
+                _ if !current.needs_refresh(now) => return Ok(current.token.clone()),
+                Some(at) if current.renewable && at > now => {
+                    let url = format!("{}/v1/auth/token/renew-self", address);
+                    if let Ok(body) = self.request(
+                        client,
+                        reqwest::Method::POST,
+                        &url,
+                        namespace,
+                        Some(&current.token),
+                        Some(&json!({})),
+                    ) {
+                        if let Some(renewed) = Self::parse_auth(&body) {
+                            let token = renewed.token.clone();
+                            *cached = Some(renewed);
+                            return Ok(token);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let fresh = match &self.auth {
+            VaultAuth::Token(token) => {
+                let token = token
+                    .clone()
+                    .or_else(|| env.get("VAULT_TOKEN").cloned())
+                    .filter(|t| !t.is_empty())
+                    .ok_or_else(|| SmooaiConfigError::new("No Vault token (set VAULT_TOKEN or configure auth)"))?;
+                CachedToken {
+                    token,
+                    expires_at: None,
+                    lease: Duration::ZERO,
+                    renewable: false,
+                }
+            }
+            VaultAuth::AppRole {
+                role_id,
+                secret_id,
+                mount,
+            } => {
+                let url = format!("{}/v1/auth/{}/login", address, mount.as_deref().unwrap_or("approle"));
+                let body = json!({ "role_id": role_id, "secret_id": secret_id });
+                let response = self.request(client, reqwest::Method::POST, &url, namespace, None, Some(&body))?;
+                Self::parse_auth(&response)
+                    .ok_or_else(|| SmooaiConfigError::new("Vault AppRole login returned no client token"))?
+            }
+            VaultAuth::Kubernetes { role, jwt_path, mount } => {
+                let path = jwt_path.as_deref().unwrap_or(DEFAULT_K8S_JWT_PATH);
+                let jwt = std::fs::read_to_string(path).map_err(|e| {
+                    SmooaiConfigError::new(&format!(
+                        "Failed to read Kubernetes service account token {}: {}",
+                        path, e
+                    ))
+                })?;
+                let url = format!("{}/v1/auth/{}/login", address, mount.as_deref().unwrap_or("kubernetes"));
+                let body = json!({ "role": role, "jwt": jwt.trim() });
+                let response = self.request(client, reqwest::Method::POST, &url, namespace, None, Some(&body))?;
+                Self::parse_auth(&response)
+                    .ok_or_else(|| SmooaiConfigError::new("Vault Kubernetes login returned no client token"))?
+            }
+        };
+        let token = fresh.token.clone();
+        *cached = Some(fresh);
+        Ok(token)
+    }
+
+    /// Read every configured path with `token`; later paths win on conflicts.
+    fn read_paths(
+        &self,
+        client: &reqwest::blocking::Client,
+        address: &str,
+        namespace: Option<&String>,
+        token: &str,
+    ) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        let mut values = HashMap::new();
+        for path in &self.paths {
+            let url = format!("{}/v1/{}/data/{}", address, self.mount, path);
+            let body = self.request(client, reqwest::Method::GET, &url, namespace, Some(token), None)?;
+            match body.get("data").and_then(|d| d.get("data")) {
+                Some(Value::Object(fields)) => values.extend(fields.clone()),
+                _ => {
+                    return Err(SmooaiConfigError::new(&format!(
+                        "Vault secret {}/{} has no KV v2 data",
+                        self.mount, path
+                    )))
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Parse the `auth` block of a login / renew response.
+    fn parse_auth(body: &Value) -> Option<CachedToken> {
+        let auth = body.get("auth")?;
+        let token = auth.get("client_token")?.as_str()?.to_string();
+        let lease = auth.get("lease_duration").and_then(|l| l.as_u64()).unwrap_or(0);
+        Some(CachedToken {
+            token,
+            expires_at: (lease > 0).then(|| Instant::now() + Duration::from_secs(lease)),
+            lease: Duration::from_secs(lease),
+            renewable: auth.get("renewable").and_then(|r| r.as_bool()).unwrap_or(false),
+        })
+    }
+}
+
+impl ConfigSource for VaultSource {
+    fn name(&self) -> String {
+        format!("vault:{}/{}", self.mount, self.paths.join(","))
+    }
+
+    fn load(&self, env: &HashMap<String, String>) -> Result<HashMap<String, Value>, SmooaiConfigError> {
+        let address = self
+            .address
+            .clone()
+            .or_else(|| env.get("VAULT_ADDR").map(|a| a.trim_end_matches('/').to_string()))
+            .ok_or_else(|| SmooaiConfigError::new("No Vault address (set VAULT_ADDR or call with_address)"))?;
+        let namespace = self.namespace.clone().or_else(|| env.get("VAULT_NAMESPACE").cloned());
+        let client = reqwest::blocking::Client::new();
+        let token = self.token(&client, &address, namespace.as_ref(), env)?;
+        match self.read_paths(&client, &address, namespace.as_ref(), &token) {
+            // The token was revoked or its policy changed: log in again once.
+            Err(e) if matches!(e.kind, SmooaiConfigErrorKind::RemoteFetch { status: Some(403) }) => {
+                if let Ok(mut cached) = self.token.lock() {
+                    *cached = None;
+                }
+                let token = self.token(&client, &address, namespace.as_ref(), env)?;
+                self.read_paths(&client, &address, namespace.as_ref(), &token)
+            }
+            result => result,
+        }
+    }
+
+    fn ttl(&self) -> Option<Duration> {
+        let lease_remaining = self
+            .token
+            .lock()
+            .ok()
+            .and_then(|t| t.as_ref().and_then(|t| t.expires_at))
+            .map(|at| at.saturating_duration_since(Instant::now()));
+        Some(match lease_remaining {
+            // Reload at two thirds of the lease so renewal happens before expiry.
+            Some(remaining) => self.ttl.min(remaining * 2 / 3),
+            None => self.ttl,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_kv(server: &MockServer, token: &str) {
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/myapp/production"))
+            .and(header("X-Vault-Token", token))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"data": {"DB_PASSWORD": "pw", "API_KEY": "k"}, "metadata": {"version": 3}}
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_token_auth_from_env() {
+        let server = MockServer::start().await;
+        mock_kv(&server, "s.static").await;
+
+        let env = HashMap::from([
+            ("VAULT_ADDR".to_string(), server.uri()),
+            ("VAULT_TOKEN".to_string(), "s.static".to_string()),
+        ]);
+        let (values, ttl) = tokio::task::spawn_blocking(move || {
+            let source = VaultSource::new("secret", "myapp/production");
+            (source.load(&env), source.ttl())
+        })
+        .await
+        .unwrap();
+        let values = values.unwrap();
+        assert_eq!(values["DB_PASSWORD"], json!("pw"));
+        assert_eq!(ttl, Some(DEFAULT_VAULT_TTL));
+    }
+
+    #[tokio::test]
+    async fn test_approle_login_caches_token_and_bounds_ttl_by_lease() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/auth/approle/login"))
+            .and(body_json(json!({"role_id": "r", "secret_id": "s"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "auth": {"client_token": "s.login", "lease_duration": 60, "renewable": true}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        mock_kv(&server, "s.login").await;
+
+        let uri = server.uri();
+        let (first, second, ttl) = tokio::task::spawn_blocking(move || {
+            let source = VaultSource::new("secret", "/myapp/production/")
+                .with_address(&uri)
+                .with_auth(VaultAuth::AppRole {
+                    role_id: "r".to_string(),
+                    secret_id: "s".to_string(),
+                    mount: None,
+                });
+            let first = source.load(&HashMap::new());
+            let second = source.load(&HashMap::new());
+            (first, second, source.ttl())
+        })
+        .await
+        .unwrap();
+        assert_eq!(first.unwrap()["API_KEY"], json!("k"));
+        assert!(second.is_ok());
+        let ttl = ttl.unwrap();
+        assert!(ttl <= Duration::from_secs(40) && ttl > Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_token_refreshes_in_last_third_of_lease() {
+        let now = Instant::now();
+        let token = |remaining: u64| CachedToken {
+            token: "s.tok".to_string(),
+            expires_at: Some(now + Duration::from_secs(remaining)),
+            lease: Duration::from_secs(90),
+            renewable: true,
+        };
+        assert!(!token(40).needs_refresh(now));
+        assert!(token(30).needs_refresh(now));
+        assert!(token(5).needs_refresh(now));
+        let static_token = CachedToken {
+            expires_at: None,
+            ..token(0)
+        };
+        assert!(!static_token.needs_refresh(now));
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_read_logs_in_again() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/auth/approle/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "auth": {"client_token": "s.revoked", "lease_duration": 3600, "renewable": true}
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/auth/approle/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "auth": {"client_token": "s.fresh", "lease_duration": 3600, "renewable": true}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/myapp/production"))
+            .and(header("X-Vault-Token", "s.revoked"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({"errors": ["permission denied"]})))
+            .mount(&server)
+            .await;
+        mock_kv(&server, "s.fresh").await;
+
+        let uri = server.uri();
+        let values = tokio::task::spawn_blocking(move || {
+            VaultSource::new("secret", "myapp/production")
+                .with_address(&uri)
+                .with_auth(VaultAuth::AppRole {
+                    role_id: "r".to_string(),
+                    secret_id: "s".to_string(),
+                    mount: None,
+                })
+                .load(&HashMap::new())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(values["DB_PASSWORD"], json!("pw"));
+    }
+
+    #[tokio::test]
+    async fn test_kubernetes_login_and_error_messages() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/auth/k8s/login"))
+            .and(body_json(json!({"role": "myapp", "jwt": "jwt-token"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "auth": {"client_token": "s.k8s", "lease_duration": 3600, "renewable": false}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/secret/data/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"errors": []})))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let jwt_path = dir.path().join("token");
+        std::fs::write(&jwt_path, "jwt-token\n").unwrap();
+        let uri = server.uri();
+        let err = tokio::task::spawn_blocking(move || {
+            VaultSource::new("secret", "missing")
+                .with_address(&uri)
+                .with_auth(VaultAuth::Kubernetes {
+                    role: "myapp".to_string(),
+                    jwt_path: Some(jwt_path.to_string_lossy().to_string()),
+                    mount: Some("k8s".to_string()),
+                })
+                .load(&HashMap::new())
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+        assert!(err.message.contains("HTTP 404"));
+    }
+
+    #[test]
+    fn test_auth_debug_is_redacted() {
+        let auth = VaultAuth::AppRole {
+            role_id: "r".to_string(),
+            secret_id: "very-secret".to_string(),
+            mount: None,
+        };
+        assert!(!format!("{:?}", auth).contains("very-secret"));
+        assert!(!format!("{:?}", VaultAuth::Token(Some("s.tok".to_string()))).contains("s.tok"));
+    }
+}
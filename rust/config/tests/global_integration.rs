@@ -0,0 +1,40 @@
+//! The process-wide singleton. Lives in its own test binary because the
+//! global can only be installed once per process.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use serde_json::json;
+use smooai_config::{global, global_init, try_global, ConfigManager};
+
+#[test]
+fn test_global_init_installs_once() {
+    let tmp = tempfile::tempdir().unwrap();
+    let config_dir = tmp.path().join(".smooai-config");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let mut f = std::fs::File::create(config_dir.join("default.json")).unwrap();
+    f.write_all(br#"{"API_URL":"http://global"}"#).unwrap();
+    let env = HashMap::from([
+        (
+            "SMOOAI_ENV_CONFIG_DIR".to_string(),
+            config_dir.to_string_lossy().to_string(),
+        ),
+        ("SMOOAI_CONFIG_ENV".to_string(), "test".to_string()),
+    ]);
+
+    assert!(try_global().is_none());
+    let installed = global_init(ConfigManager::new().with_env(env)).unwrap();
+    assert!(std::ptr::eq(installed, global()));
+    assert_eq!(
+        global().get_public_config("API_URL").unwrap(),
+        Some(json!("http://global"))
+    );
+
+    let err = global_init(ConfigManager::new()).err().expect("second init fails");
+    assert!(err.to_string().contains("already initialized"));
+    // The original manager stays installed.
+    assert_eq!(
+        global().get_public_config("API_URL").unwrap(),
+        Some(json!("http://global"))
+    );
+}
@@ -1,5 +1,7 @@
 //! Full pipeline integration tests mirroring TypeScript integration test suite 2.
 
+#![allow(deprecated)]
+
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
@@ -110,11 +112,11 @@ fn test_default_loads_all_tiers() {
     // Secret config
     assert_eq!(
         mgr.get_secret_config("API_KEY").unwrap(),
-        Some(json!("default-api-key"))
+        Some(json!("default-api-key").into())
     );
     assert_eq!(
         mgr.get_secret_config("DB_PASSWORD").unwrap(),
-        Some(json!("default-db-pass"))
+        Some(json!("default-db-pass").into())
     );
 
     // Feature flags
@@ -192,11 +194,11 @@ fn test_development_inherits_secrets() {
 
     assert_eq!(
         mgr.get_secret_config("API_KEY").unwrap(),
-        Some(json!("default-api-key"))
+        Some(json!("default-api-key").into())
     );
     assert_eq!(
         mgr.get_secret_config("DB_PASSWORD").unwrap(),
-        Some(json!("default-db-pass"))
+        Some(json!("default-db-pass").into())
     );
 }
 
@@ -240,15 +242,15 @@ fn test_production_secrets() {
 
     assert_eq!(
         mgr.get_secret_config("API_KEY").unwrap(),
-        Some(json!("prod-api-key-secret"))
+        Some(json!("prod-api-key-secret").into())
     );
     assert_eq!(
         mgr.get_secret_config("DB_PASSWORD").unwrap(),
-        Some(json!("prod-db-pass-secret"))
+        Some(json!("prod-db-pass-secret").into())
     );
     assert_eq!(
         mgr.get_secret_config("JWT_SECRET").unwrap(),
-        Some(json!("prod-jwt-secret"))
+        Some(json!("prod-jwt-secret").into())
     );
 }
 
@@ -0,0 +1,112 @@
+//! Property tests for the merge laws in `smooai_config::merge_laws`.
+//!
+//! Each built-in strategy is checked against the laws its documentation
+//! claims, over a few thousand generated layers. Layers are "shape
+//! consistent": keys `S*` always hold scalars (null included), `L*` arrays
+//! of scalars and `O*` nested layers, which is the precondition for
+//! associativity. proptest shrinks a failure to a minimal set of layers and
+//! records it under `proptest-regressions/` so it is retried first.
+//!
+//! To check a custom merge, copy `layer` and `layers` and write a
+//! `proptest!` block that passes your merge function to the laws.
+
+use proptest::prelude::*;
+use serde_json::{Map, Value};
+use smooai_config::merge::MergeStrategy;
+use smooai_config::merge_laws::{
+    arrays_are_replaced, empty_layer_is_identity, layering_is_associative, reapplying_layer_is_noop,
+};
+
+const CASES: u32 = 2_000;
+const MAX_DEPTH: u32 = 3;
+
+fn scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        (-1i64..3).prop_map(Value::from),
+        // Few distinct strings, so arrays share items across layers.
+        prop::sample::select(vec!["a", "b", "c"]).prop_map(Value::from),
+        Just(Value::from(String::new())),
+    ]
+}
+
+fn array() -> impl Strategy<Value = Value> {
+    prop::collection::vec(scalar(), 0..4).prop_map(Value::Array)
+}
+
+/// A shape-consistent layer nested at most `depth` objects deep.
+fn layer(depth: u32) -> BoxedStrategy<Value> {
+    let nested = if depth == 0 {
+        Just(Value::Object(Map::new())).boxed()
+    } else {
+        layer(depth - 1)
+    };
+    (
+        prop::option::of(scalar()),
+        prop::option::of(scalar()),
+        prop::option::of(array()),
+        prop::option::of(array()),
+        prop::option::of(nested.clone()),
+        prop::option::of(nested),
+    )
+        .prop_map(|(s1, s2, l1, l2, o1, o2)| {
+            let entries = [("S1", s1), ("S2", s2), ("L1", l1), ("L2", l2), ("O1", o1), ("O2", o2)]
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.to_string(), value?)))
+                .collect::<Map<_, _>>();
+            Value::Object(entries)
+        })
+        .boxed()
+}
+
+fn layers() -> impl Strategy<Value = (Value, Value, Value)> {
+    (layer(MAX_DEPTH), layer(MAX_DEPTH), layer(MAX_DEPTH))
+}
+
+fn merge(strategy: MergeStrategy) -> impl Fn(&Value, &Value) -> Value + Copy {
+    move |lower, higher| strategy.merge(lower, higher)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn every_strategy_has_an_identity(a in layer(MAX_DEPTH)) {
+        for strategy in [
+            MergeStrategy::ReplaceArrays,
+            MergeStrategy::JsonMergePatch,
+            MergeStrategy::Concat,
+            MergeStrategy::UniqueAppend,
+        ] {
+            prop_assert!(empty_layer_is_identity(merge(strategy), &a), "{:?} identity", strategy);
+        }
+    }
+
+    #[test]
+    fn replace_arrays_laws((a, b, c) in layers()) {
+        let m = merge(MergeStrategy::ReplaceArrays);
+        prop_assert!(reapplying_layer_is_noop(m, &a, &b), "reapply");
+        prop_assert!(layering_is_associative(m, &a, &b, &c), "associativity");
+        prop_assert!(arrays_are_replaced(m, &a, &b), "array replacement");
+    }
+
+    #[test]
+    fn json_merge_patch_laws((a, b) in (layer(MAX_DEPTH), layer(MAX_DEPTH))) {
+        let m = merge(MergeStrategy::JsonMergePatch);
+        prop_assert!(reapplying_layer_is_noop(m, &a, &b), "reapply");
+        prop_assert!(arrays_are_replaced(m, &a, &b), "array replacement");
+    }
+
+    #[test]
+    fn concat_laws((a, b, c) in layers()) {
+        let m = merge(MergeStrategy::Concat);
+        prop_assert!(layering_is_associative(m, &a, &b, &c), "associativity");
+    }
+
+    #[test]
+    fn unique_append_laws((a, b) in (layer(MAX_DEPTH), layer(MAX_DEPTH))) {
+        let m = merge(MergeStrategy::UniqueAppend);
+        prop_assert!(reapplying_layer_is_noop(m, &a, &b), "reapply");
+    }
+}
@@ -351,7 +351,7 @@ async fn blob_hydration_resolves_offline() {
     );
     assert_eq!(
         manager.get_secret_config("sendgridApiKey").unwrap(),
-        Some(json!("SG.from-blob"))
+        Some(json!("SG.from-blob").into())
     );
 }
 